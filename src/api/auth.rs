@@ -12,6 +12,7 @@ use axum::{
 use crate::api::handlers::ApiHandlers;
 use crate::api::response::ApiResponse;
 use crate::config::read_config;
+use crate::map_installer::with_audit_actor;
 
 pub async fn require_api_key(
     State(handlers): State<Arc<ApiHandlers>>,
@@ -25,7 +26,7 @@ pub async fn require_api_key(
         .map(str::trim)
         .filter(|key| !key.is_empty())
     else {
-        return next.run(request).await;
+        return with_audit_actor("api", next.run(request)).await;
     };
 
     let authorized = request
@@ -36,7 +37,7 @@ pub async fn require_api_key(
         .is_some_and(|provided| provided == expected);
 
     if authorized {
-        next.run(request).await
+        with_audit_actor("api", next.run(request)).await
     } else {
         (
             StatusCode::UNAUTHORIZED,
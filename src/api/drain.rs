@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::{header::RETRY_AFTER, HeaderValue, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::api::error::ApiError;
+use crate::api::handlers::ApiHandlers;
+
+/// Seconds clients are told to wait before retrying a request refused while draining.
+pub const DRAIN_RETRY_AFTER_SECS: u64 = 30;
+
+/// Once set, the API refuses new mutating requests so in-flight work can finish
+/// before a restart. There is no way back short of restarting the daemon.
+#[derive(Debug, Clone, Default)]
+pub struct DrainState(Arc<AtomicBool>);
+
+impl DrainState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enter drain mode; returns whether it was already draining.
+    pub fn drain(&self) -> bool {
+        self.0.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Answer mutating requests with 503 and `Retry-After` while draining; reads pass.
+pub async fn reject_while_draining(
+    State(handlers): State<Arc<ApiHandlers>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let read_only = matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    if read_only || !handlers.drain.is_draining() {
+        return next.run(request).await;
+    }
+    let mut response = ApiError::service_unavailable(
+        "Daemon is draining before shutdown; not accepting new work",
+    )
+    .with_code("draining")
+    .into_response();
+    response
+        .headers_mut()
+        .insert(RETRY_AFTER, HeaderValue::from(DRAIN_RETRY_AFTER_SECS));
+    response
+}
@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use axum::Json;
+use tracing::info;
+
+use crate::api::error::ApiError;
+use crate::api::response::ApiResponse;
+use crate::api::types::{DaemonStats, DrainStatus};
+use crate::downloader::circuit_breaker::CircuitBreakerStatus;
+
+use super::helpers::{ok_json, registry_internal_err};
+use super::ApiHandlers;
+
+impl ApiHandlers {
+    pub fn drain(&self) -> Json<ApiResponse<DrainStatus>> {
+        if !self.drain.drain() {
+            info!("Entered drain mode; refusing new mutating API requests");
+        }
+        ok_json(DrainStatus { draining: true })
+    }
+
+    pub fn steam_status(&self) -> Json<ApiResponse<CircuitBreakerStatus>> {
+        ok_json(self.installer.steam_breaker_status())
+    }
+
+    pub async fn stats(&self) -> Result<Json<ApiResponse<DaemonStats>>, ApiError> {
+        let footprint = self
+            .installer
+            .map_footprint()
+            .await
+            .map_err(|e| registry_internal_err(e, "Failed to compute map footprint"))?;
+        Ok(ok_json(DaemonStats {
+            map_count: footprint.map_count,
+            total_bytes: footprint.total_bytes,
+            last_sync_ok_at: self.sync_health.last_ok_at(),
+            sync_paused: self.sync_pause.is_paused(),
+            steam: self.installer.steam_breaker_status(),
+            watcher_running: self.watcher_status.is_running(),
+            active_installs: self.installer.install_operations().len(),
+            draining: self.drain.is_draining(),
+        }))
+    }
+}
@@ -1,17 +1,43 @@
 // SPDX-License-Identifier: GPL-3.0-only
-use axum::Json;
+use std::time::Duration;
+
+use axum::{extract::Query, Json};
 use tracing::info;
 
 use crate::api::error::ApiError;
 use crate::api::response::ApiResponse;
 use crate::api::service_error::classify_workshop_error;
-use crate::api::types::{DiscoverRequest, UpdateWorkshopRequest};
-use crate::map_installer::{CompactReport, DiscoveryReport, WorkshopUpdateReport};
+use crate::api::types::{AuditQuery, CleanupQuery, DiscoverRequest, UpdateWorkshopRequest};
+use crate::config::read_config;
+use crate::map_installer::{
+    AuditEntry, CleanupReport, CompactReport, DiscoveryReport, ReconcileReport,
+    WorkshopUpdateReport,
+};
 
 use super::helpers::{installer_internal_err, ok_json};
 use super::ApiHandlers;
 
+const DEFAULT_AUDIT_LIMIT: usize = 100;
+const MAX_AUDIT_LIMIT: usize = 1000;
+
 impl ApiHandlers {
+    pub async fn audit_log(
+        &self,
+        Query(query): Query<AuditQuery>,
+    ) -> Result<Json<ApiResponse<Vec<AuditEntry>>>, ApiError> {
+        let limit = query.limit.unwrap_or(DEFAULT_AUDIT_LIMIT);
+        if !(1..=MAX_AUDIT_LIMIT).contains(&limit) {
+            return Err(ApiError::bad_request(format!(
+                "limit must be between 1 and {MAX_AUDIT_LIMIT}"
+            )));
+        }
+
+        match self.installer.audit_log().recent(query.map_id, limit).await {
+            Ok(entries) => Ok(ok_json(entries)),
+            Err(e) => Err(installer_internal_err(e, "Failed to read audit log")),
+        }
+    }
+
     pub async fn update_workshop_maps(
         &self,
         Json(request): Json<UpdateWorkshopRequest>,
@@ -55,4 +81,39 @@ impl ApiHandlers {
             Err(e) => Err(installer_internal_err(e, "Compact failed")),
         }
     }
+
+    pub async fn reconcile(&self) -> Result<Json<ApiResponse<ReconcileReport>>, ApiError> {
+        info!("Reconcile request received");
+
+        match self.installer.reconcile().await {
+            Ok(report) => Ok(ok_json(report)),
+            Err(e) => Err(installer_internal_err(e, "Reconcile failed")),
+        }
+    }
+
+    pub async fn cleanup_temp(
+        &self,
+        Query(query): Query<CleanupQuery>,
+    ) -> Result<Json<ApiResponse<CleanupReport>>, ApiError> {
+        let config = read_config(&self.config);
+        let older_than_secs = query
+            .older_than_secs
+            .unwrap_or(config.temp_cleanup_max_age_secs);
+        if older_than_secs < config.install_timeout_secs {
+            return Err(ApiError::bad_request(format!(
+                "older_than_secs must be at least install_timeout_secs ({})",
+                config.install_timeout_secs
+            )));
+        }
+        info!(older_than_secs, "Temp cleanup request received");
+
+        match self
+            .installer
+            .cleanup_temp(Duration::from_secs(older_than_secs))
+            .await
+        {
+            Ok(report) => Ok(ok_json(report)),
+            Err(e) => Err(installer_internal_err(e, "Temp cleanup failed")),
+        }
+    }
 }
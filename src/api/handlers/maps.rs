@@ -1,19 +1,36 @@
 // SPDX-License-Identifier: GPL-3.0-only
-use axum::extract::Path;
+use axum::extract::{Path, Query};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
 use axum::Json;
 use tracing::info;
 
 use crate::api::error::ApiError;
 use crate::api::response::ApiResponse;
-use crate::api::service_error::{classify_modify_error, classify_uninstall_error};
-use crate::api::types::{InstallMapRequest, ModifyMapRequest};
-use crate::api::validation::{parse_map_id, validate_install_request, validate_modify_request, InstallSource};
-use crate::map_installer::MapUpdatesStatus;
-use crate::registry::MapEntry;
+use crate::api::service_error::{
+    classify_force_uninstall_error, classify_install_error, classify_modify_error,
+    classify_uninstall_error,
+};
+use crate::api::types::{
+    InstallMapQuery, InstallMapRequest, InstallMapResponse, ListMapsQuery, ModifyMapRequest, RenameMapRequest, SearchMapsQuery,
+    TagsRequest, WorkshopCollectionItem,
+};
+use crate::api::validation::{
+    parse_map_id, validate_expected_checksum, validate_install_request, validate_modify_request,
+    validate_optional_name, InstallSource,
+};
+use crate::map_installer::{
+    capture_operation_id, ForceUninstallReport, InstallOptions, InstalledFileInfo, MapFootprint, MapUpdatesStatus,
+};
+use crate::downloader::steam::steam_time_to_utc;
+use crate::extractor::traits::VpkFileEntry;
+use crate::registry::{MapEntry, sanitize_tag};
 
 use super::helpers::{installer_internal_err, ok_json, registry_internal_err};
 use super::ApiHandlers;
 
+const MAX_TAGS_PER_REQUEST: usize = 32;
+
 impl ApiHandlers {
     pub async fn list_available_updates(
         &self,
@@ -153,66 +170,292 @@ impl ApiHandlers {
 
     pub async fn list_maps(
         &self,
+        Query(query): Query<ListMapsQuery>,
     ) -> Result<Json<ApiResponse<Vec<MapEntry>>>, ApiError> {
-        match self.registry.list_maps().await {
+        let maps = match query.tag.as_deref() {
+            Some(tag) => {
+                let tag = sanitize_tag(tag)
+                    .ok_or_else(|| ApiError::bad_request(format!("Invalid tag '{tag}'")))?;
+                self.registry.list_by_tag(&tag).await
+            }
+            None => self.registry.list_maps().await,
+        };
+        let maps = maps.map(|mut maps| {
+            if let Some(content) = query.content.as_deref().map(str::trim) {
+                maps.retain(|map| map.content_flags.iter().any(|f| f.eq_ignore_ascii_case(content)));
+            }
+            maps
+        });
+        match maps {
             Ok(maps) => Ok(ok_json(self.denylist().filter_visible(maps))),
             Err(e) => Err(registry_internal_err(e, "Failed to list maps")),
         }
     }
 
+    pub async fn search_maps(
+        &self,
+        Query(query): Query<SearchMapsQuery>,
+    ) -> Result<Json<ApiResponse<Vec<MapEntry>>>, ApiError> {
+        let q = query.q.trim();
+        if q.is_empty() {
+            return Err(ApiError::bad_request("Search query 'q' is required"));
+        }
+        if q.len() > 255 {
+            return Err(ApiError::bad_request(
+                "Search query too long (max 255 characters)",
+            ));
+        }
+
+        match self.registry.search_maps(q).await {
+            Ok(maps) => Ok(ok_json(self.denylist().filter_visible(maps))),
+            Err(e) => Err(registry_internal_err(e, "Failed to search maps")),
+        }
+    }
+
+    pub async fn list_files_on_disk(
+        &self,
+    ) -> Result<Json<ApiResponse<Vec<InstalledFileInfo>>>, ApiError> {
+        match self.installer.scan_addons_dir().await {
+            Ok(files) => Ok(ok_json(files)),
+            Err(e) => Err(installer_internal_err(e, "Failed to scan addons directory")),
+        }
+    }
+
+    pub async fn force_uninstall_file(
+        &self,
+        Path(filename): Path<String>,
+    ) -> Result<Json<ApiResponse<ForceUninstallReport>>, ApiError> {
+        match self.installer.force_uninstall_by_path(&filename).await {
+            Ok(report) => Ok(ok_json(report)),
+            Err(e) => Err(classify_force_uninstall_error(e)),
+        }
+    }
+
+    pub async fn map_footprint(&self) -> Result<Json<ApiResponse<MapFootprint>>, ApiError> {
+        match self.installer.map_footprint().await {
+            Ok(footprint) => Ok(ok_json(footprint)),
+            Err(e) => Err(registry_internal_err(e, "Failed to compute map footprint")),
+        }
+    }
+
     pub async fn get_map(
         &self,
         Path(id): Path<String>,
     ) -> Result<Json<ApiResponse<MapEntry>>, ApiError> {
         let map_id = parse_map_id(&id)?;
+        Ok(ok_json(self.visible_map(map_id).await?))
+    }
 
-        match self.registry.get_map(map_id).await {
-            Ok(Some(map)) => {
-                if self.denylist().is_hidden(&map) {
-                    return Err(ApiError::not_found(format!("Map #{map_id} not found")));
-                }
-                Ok(ok_json(map))
+    /// Look a map up by name (compared after sanitizing); 409 when several maps share it.
+    pub async fn get_map_by_name(
+        &self,
+        Path(name): Path<String>,
+    ) -> Result<Json<ApiResponse<MapEntry>>, ApiError> {
+        let maps = self
+            .registry
+            .get_map_by_name(&name)
+            .await
+            .map_err(|e| registry_internal_err(e, "Failed to get map"))?;
+        let denylist = self.denylist();
+        let mut visible: Vec<MapEntry> =
+            maps.into_iter().filter(|map| !denylist.is_hidden(map)).collect();
+        match visible.len() {
+            0 => Err(ApiError::not_found(format!("Map '{name}' not found"))),
+            1 => Ok(ok_json(visible.remove(0))),
+            _ => Err(ApiError::conflict(format!(
+                "{} maps are named '{name}' (IDs {}); look the map up by ID instead",
+                visible.len(),
+                visible.iter().map(|map| map.id.to_string()).collect::<Vec<_>>().join(", ")
+            ))
+            .with_code("ambiguous_name")),
+        }
+    }
+
+    /// Redirect (302) to where the map's original file can be fetched again.
+    ///
+    /// Workshop maps get a freshly resolved Steam URL; HTTP(S) sources redirect to
+    /// `source_url`; local sources (`file:`, `detected:`, `archive:`) are a 409.
+    pub async fn map_download_redirect(&self, Path(id): Path<String>) -> Result<Response, ApiError> {
+        let map_id = parse_map_id(&id)?;
+        let map = self.visible_map(map_id).await?;
+
+        let location = if let Some(workshop_id) = map.workshop_id {
+            self.installer
+                .workshop_download_url(workshop_id)
+                .await
+                .map_err(classify_install_error)?
+        } else if reqwest::Url::parse(&map.source_url)
+            .is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+        {
+            map.source_url
+        } else {
+            return Err(ApiError::conflict(format!(
+                "Map #{map_id} has no downloadable source ({})",
+                map.source_url
+            ))
+            .with_code("not_redirectable"));
+        };
+
+        Ok((StatusCode::FOUND, [(header::LOCATION, location)]).into_response())
+    }
+
+    /// Files inside the map's VPK (the logical tree for split archives).
+    pub async fn map_files(
+        &self,
+        Path(id): Path<String>,
+    ) -> Result<Json<ApiResponse<Vec<VpkFileEntry>>>, ApiError> {
+        let map_id = parse_map_id(&id)?;
+        self.visible_map(map_id).await?;
+
+        match self.installer.list_vpk_contents(map_id).await {
+            Ok(files) => Ok(ok_json(files)),
+            Err(e) if e.to_string().contains("not found") => Err(ApiError::not_found(e.to_string())),
+            Err(e) if e.to_string().contains("is missing") => {
+                Err(ApiError::not_found(e.to_string()).with_code("file_missing"))
+            }
+            Err(e) => Err(ApiError::internal(format!("{e:#}"))),
+        }
+    }
+
+    /// Delete one part file of a split map, keeping the map installed.
+    pub async fn remove_map_file(
+        &self,
+        Path((id, filename)): Path<(String, String)>,
+    ) -> Result<Json<ApiResponse<MapEntry>>, ApiError> {
+        let map_id = parse_map_id(&id)?;
+        self.visible_map(map_id).await?;
+
+        match self.installer.remove_map_file(map_id, &filename).await {
+            Ok(entry) => {
+                info!(map_id, filename = %filename, "Map file removed");
+                Ok(ok_json(entry))
+            }
+            Err(e) if e.to_string().contains("not found") => Err(ApiError::not_found(e.to_string())),
+            Err(e) if e.to_string().contains("is not a file of") || e.to_string().contains("main file") => {
+                Err(ApiError::bad_request(e.to_string()))
             }
-            Ok(None) => Err(ApiError::not_found(format!("Map #{map_id} not found"))),
+            Err(e) => Err(ApiError::internal(format!("{e:#}"))),
+        }
+    }
+
+    /// Maps a Workshop collection would install, with nested collections flattened.
+    pub async fn workshop_collection(
+        &self,
+        Path(id): Path<String>,
+    ) -> Result<Json<ApiResponse<Vec<WorkshopCollectionItem>>>, ApiError> {
+        let collection_id = id.parse::<u64>().map_err(|_| {
+            ApiError::bad_request("Invalid workshop ID format (expected integer)")
+        })?;
+        let items = self
+            .installer
+            .workshop_collection_items(collection_id)
+            .await
+            .map_err(classify_install_error)?;
+        Ok(ok_json(
+            items
+                .into_iter()
+                .map(|item| WorkshopCollectionItem {
+                    workshop_id: item.workshop_id,
+                    title: item.metadata.title,
+                    file_size: item.file_size,
+                    updated_at: steam_time_to_utc(item.time_updated),
+                })
+                .collect(),
+        ))
+    }
+
+    async fn visible_map(&self, map_id: u64) -> Result<MapEntry, ApiError> {
+        match self.registry.get_map(map_id).await {
+            Ok(Some(map)) if !self.denylist().is_hidden(&map) => Ok(map),
+            Ok(_) => Err(ApiError::not_found(format!("Map #{map_id} not found"))),
             Err(e) => Err(registry_internal_err(e, "Failed to get map")),
         }
     }
 
+    /// Install, replaying the stored result when `idempotency_key` was already completed.
+    pub async fn install_map_idempotent(
+        &self,
+        idempotency_key: Option<&str>,
+        query: Query<InstallMapQuery>,
+        request: Json<InstallMapRequest>,
+    ) -> Result<Json<ApiResponse<InstallMapResponse>>, ApiError> {
+        let Some(key) = idempotency_key else {
+            return self.install_map(query, request).await;
+        };
+        self.install_results
+            .run(key, || async {
+                self.install_map(query, request)
+                    .await
+                    .map(|Json(response)| response)
+            })
+            .await
+            .map(Json)
+    }
+
     pub async fn install_map(
         &self,
+        Query(query): Query<InstallMapQuery>,
         Json(request): Json<InstallMapRequest>,
-    ) -> Result<Json<ApiResponse<u64>>, ApiError> {
+    ) -> Result<Json<ApiResponse<InstallMapResponse>>, ApiError> {
         let source = validate_install_request(&request)?;
+        let expected_checksum = validate_expected_checksum(&request)?;
 
-        match source {
-            InstallSource::Url(url) => {
-                info!(url = %url, "Install map request received with URL");
-                match self
-                    .installer
-                    .install_from_url(url, request.name)
-                    .await
-                {
-                    Ok(map_entry) => {
-                        info!(map_id = %map_entry.id, "Map installed successfully");
-                        Ok(ok_json(map_entry.id))
-                    }
-                    Err(e) => Err(installer_internal_err(e, "Failed to install map")),
+        let install = async {
+            match source {
+                InstallSource::Url(url, download) => {
+                    info!(url = %url, "Install map request received with URL");
+                    self.installer
+                        .install_from_url(
+                            url,
+                            request.name,
+                            InstallOptions {
+                                force: query.force,
+                                download,
+                                expected_checksum,
+                                mirror_urls: request.mirror_urls,
+                            },
+                        )
+                        .await
                 }
-            }
-            InstallSource::Workshop(workshop_id) => {
-                info!(workshop_id, "Install map request received with workshop ID");
-                match self
-                    .installer
-                    .install_from_workshop_id(workshop_id, request.name)
-                    .await
-                {
-                    Ok(map_entry) => {
-                        info!(map_id = %map_entry.id, "Map installed successfully");
-                        Ok(ok_json(map_entry.id))
-                    }
-                    Err(e) => Err(installer_internal_err(e, "Failed to install map")),
+                InstallSource::LocalPath(path) => {
+                    info!(path = %path.display(), "Install map request received with local path");
+                    self.installer
+                        .install_from_local_path(
+                            path,
+                            request.name,
+                            InstallOptions {
+                                force: query.force,
+                                expected_checksum,
+                                ..InstallOptions::default()
+                            },
+                        )
+                        .await
+                }
+                InstallSource::Workshop(workshop_id) => {
+                    info!(workshop_id, "Install map request received with workshop ID");
+                    self.installer
+                        .install_from_workshop_id(
+                            workshop_id,
+                            request.name,
+                            InstallOptions {
+                                force: query.force,
+                                expected_checksum,
+                                ..InstallOptions::default()
+                            },
+                        )
+                        .await
                 }
             }
+        };
+        match capture_operation_id(install).await {
+            (Ok(map_entry), operation_id) => {
+                info!(map_id = %map_entry.id, ?operation_id, "Map installed successfully");
+                Ok(ok_json(InstallMapResponse {
+                    map_id: map_entry.id,
+                    operation_id,
+                }))
+            }
+            (Err(e), _) => Err(classify_install_error(e)),
         }
     }
 
@@ -256,4 +499,76 @@ impl ApiHandlers {
             Err(e) => Err(classify_modify_error(e)),
         }
     }
+
+    pub async fn rename_map(
+        &self,
+        Path(id): Path<String>,
+        Json(request): Json<RenameMapRequest>,
+    ) -> Result<Json<ApiResponse<MapEntry>>, ApiError> {
+        let map_id = parse_map_id(&id)?;
+        if request.name.trim().is_empty() {
+            return Err(ApiError::bad_request("Map name cannot be empty"));
+        }
+        validate_optional_name(&Some(request.name.clone()))?;
+        self.visible_map(map_id).await?;
+
+        info!(map_id, name = %request.name, "Rename map request received");
+
+        match self.installer.rename_map(map_id, &request.name).await {
+            Ok(map) => Ok(ok_json(map)),
+            Err(e) => Err(classify_modify_error(e)),
+        }
+    }
+
+    pub async fn add_map_tags(
+        &self,
+        Path(id): Path<String>,
+        Json(request): Json<TagsRequest>,
+    ) -> Result<Json<ApiResponse<MapEntry>>, ApiError> {
+        let map_id = parse_map_id(&id)?;
+        validate_tags(&request.tags)?;
+        self.visible_map(map_id).await?;
+
+        match self.registry.add_tags(map_id, &request.tags).await {
+            Ok(map) => {
+                info!(map_id, tags = ?map.tags, "Map tags added");
+                Ok(ok_json(map))
+            }
+            Err(e) => Err(registry_internal_err(e, "Failed to add tags")),
+        }
+    }
+
+    pub async fn remove_map_tags(
+        &self,
+        Path(id): Path<String>,
+        Json(request): Json<TagsRequest>,
+    ) -> Result<Json<ApiResponse<MapEntry>>, ApiError> {
+        let map_id = parse_map_id(&id)?;
+        validate_tags(&request.tags)?;
+        self.visible_map(map_id).await?;
+
+        match self.registry.remove_tags(map_id, &request.tags).await {
+            Ok(map) => {
+                info!(map_id, tags = ?map.tags, "Map tags removed");
+                Ok(ok_json(map))
+            }
+            Err(e) => Err(registry_internal_err(e, "Failed to remove tags")),
+        }
+    }
+}
+
+/// Every requested tag must survive sanitizing, so typos are reported rather than dropped.
+fn validate_tags(tags: &[String]) -> Result<(), ApiError> {
+    if tags.is_empty() {
+        return Err(ApiError::bad_request("At least one tag is required"));
+    }
+    if tags.len() > MAX_TAGS_PER_REQUEST {
+        return Err(ApiError::bad_request(format!(
+            "Too many tags (max {MAX_TAGS_PER_REQUEST})"
+        )));
+    }
+    if let Some(bad) = tags.iter().find(|t| sanitize_tag(t).is_none()) {
+        return Err(ApiError::bad_request(format!("Invalid tag '{bad}'")));
+    }
+    Ok(())
 }
@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: GPL-3.0-only
+mod admin;
 mod l4d2center;
 mod maintenance;
 mod maps;
+mod operations;
+mod sync;
 
 #[cfg(test)]
 mod tests;
@@ -10,17 +13,32 @@ use std::sync::Arc;
 
 use axum::Json;
 
+use crate::api::drain::DrainState;
 use crate::api::error::ApiError;
+use crate::api::idempotency::{IdempotencyStore, IDEMPOTENCY_TTL};
 use crate::api::response::ApiResponse;
+use crate::api::types::InstallMapResponse;
 use crate::config::{read_config, ConfigHandle};
-use crate::map_installer::MapInstallationService;
+use crate::map_installer::{MapEvent, MapInstallationService};
 use crate::maps_denylist::Mapsdenylist;
 use crate::registry::Registry;
+use crate::sync::{SyncHealth, SyncPause, SyncTrigger};
+use crate::watcher::WatcherStatus;
 
 pub struct ApiHandlers {
     pub(super) registry: Arc<dyn Registry>,
     pub(super) installer: Arc<MapInstallationService>,
     pub(super) config: ConfigHandle,
+    /// Completed install results keyed by `Idempotency-Key`.
+    pub(super) install_results: IdempotencyStore<ApiResponse<InstallMapResponse>>,
+    /// Pause switch of the periodic sync loop.
+    pub(super) sync_pause: SyncPause,
+    /// Wakes the periodic sync loop for `POST /api/sync/now`; none without a loop.
+    pub(super) sync_trigger: Option<SyncTrigger>,
+    pub(super) sync_health: SyncHealth,
+    pub(super) watcher_status: WatcherStatus,
+    /// Set before shutdown to refuse new mutating requests.
+    pub(crate) drain: DrainState,
 }
 
 impl ApiHandlers {
@@ -33,9 +51,43 @@ impl ApiHandlers {
             registry,
             installer,
             config,
+            install_results: IdempotencyStore::new(IDEMPOTENCY_TTL),
+            sync_pause: SyncPause::new(),
+            sync_trigger: None,
+            sync_health: SyncHealth::new(),
+            watcher_status: WatcherStatus::new(),
+            drain: DrainState::new(),
         }
     }
 
+    /// Control the given sync loop's pause switch instead of a detached one.
+    pub fn with_sync_pause(mut self, sync_pause: SyncPause) -> Self {
+        self.sync_pause = sync_pause;
+        self
+    }
+
+    /// Run on-demand sync cycles through the given sync loop.
+    pub fn with_sync_trigger(mut self, sync_trigger: SyncTrigger) -> Self {
+        self.sync_trigger = Some(sync_trigger);
+        self
+    }
+
+    pub fn with_sync_health(mut self, sync_health: SyncHealth) -> Self {
+        self.sync_health = sync_health;
+        self
+    }
+
+    pub fn with_watcher_status(mut self, watcher_status: WatcherStatus) -> Self {
+        self.watcher_status = watcher_status;
+        self
+    }
+
+    /// Share `drain` with the shutdown path so it can stop new work first.
+    pub fn with_drain(mut self, drain: DrainState) -> Self {
+        self.drain = drain;
+        self
+    }
+
     pub(super) fn denylist(&self) -> Mapsdenylist {
         Mapsdenylist::from_config(&read_config(&self.config))
     }
@@ -43,6 +95,10 @@ impl ApiHandlers {
     pub(super) fn l4d2center_index_url(&self) -> String {
         read_config(&self.config).l4d2center_index_url.clone()
     }
+
+    pub fn subscribe_events(&self) -> tokio::sync::broadcast::Receiver<MapEvent> {
+        self.installer.events().subscribe()
+    }
 }
 
 pub(super) mod helpers {
@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use axum::{extract::Path, Json};
+use futures_util::Stream;
+use tracing::info;
+
+use crate::api::error::ApiError;
+use crate::api::response::ApiResponse;
+use crate::map_installer::{InstallOperation, MapEvent};
+
+use super::helpers::ok_json;
+use super::ApiHandlers;
+
+impl ApiHandlers {
+    pub fn list_operations(&self) -> Json<ApiResponse<Vec<InstallOperation>>> {
+        ok_json(self.installer.install_operations())
+    }
+
+    /// Events of running install `id` until it completes or fails.
+    pub fn watch_operation(
+        &self,
+        Path(id): Path<String>,
+    ) -> Result<impl Stream<Item = MapEvent> + use<>, ApiError> {
+        let operation_id = parse_operation_id(&id)?;
+        self.installer.watch_install(operation_id).ok_or_else(|| {
+            ApiError::not_found(format!("Operation {operation_id} not found or already finished"))
+        })
+    }
+
+    pub fn cancel_operation(
+        &self,
+        Path(id): Path<String>,
+    ) -> Result<Json<ApiResponse<u64>>, ApiError> {
+        let operation_id = parse_operation_id(&id)?;
+        if !self.installer.cancel_install(operation_id) {
+            return Err(ApiError::not_found(format!(
+                "Operation {operation_id} not found or already finished"
+            )));
+        }
+        info!(operation_id, "Install cancellation requested");
+        Ok(ok_json(operation_id))
+    }
+}
+
+fn parse_operation_id(id: &str) -> Result<u64, ApiError> {
+    id.parse::<u64>()
+        .map_err(|_| ApiError::bad_request("Invalid operation ID format (expected integer)"))
+}
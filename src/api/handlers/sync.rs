@@ -0,0 +1,52 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use axum::Json;
+use tracing::info;
+
+use crate::api::error::ApiError;
+use crate::api::response::ApiResponse;
+use crate::api::types::SyncStatus;
+use crate::sync::runner::SyncCycleReport;
+
+use super::helpers::ok_json;
+use super::ApiHandlers;
+
+impl ApiHandlers {
+    pub fn pause_sync(&self) -> Json<ApiResponse<SyncStatus>> {
+        if !self.sync_pause.pause() {
+            info!("Backend sync paused");
+        }
+        self.sync_status()
+    }
+
+    pub fn resume_sync(&self) -> Json<ApiResponse<SyncStatus>> {
+        if self.sync_pause.resume() {
+            info!("Backend sync resumed");
+        }
+        self.sync_status()
+    }
+
+    /// Run one fetch-apply-push cycle now, in the sync loop so it never overlaps a
+    /// scheduled one, and return what it did.
+    pub async fn sync_now(&self) -> Result<Json<ApiResponse<SyncCycleReport>>, ApiError> {
+        if self.sync_pause.is_paused() {
+            return Err(ApiError::conflict("Backend sync is paused; resume it first")
+                .with_code("sync_paused"));
+        }
+        let Some(trigger) = &self.sync_trigger else {
+            return Err(ApiError::service_unavailable("Backend sync is not running")
+                .with_code("sync_unavailable"));
+        };
+        info!("Sync cycle requested through the API");
+        let report = trigger.run_now().await.ok_or_else(|| {
+            ApiError::service_unavailable("Sync loop stopped before running the cycle")
+                .with_code("sync_unavailable")
+        })?;
+        Ok(ok_json(report))
+    }
+
+    pub fn sync_status(&self) -> Json<ApiResponse<SyncStatus>> {
+        ok_json(SyncStatus {
+            paused: self.sync_pause.is_paused(),
+        })
+    }
+}
@@ -1,8 +1,12 @@
 // SPDX-License-Identifier: GPL-3.0-only
-use axum::extract::Path;
+use axum::extract::{Path, Query};
 use axum::Json;
+use std::collections::HashMap;
 
-use crate::api::types::{DiscoverRequest, InstallMapRequest, ModifyMapRequest, UpdateWorkshopRequest};
+use crate::api::types::{
+    DiscoverRequest, InstallMapQuery, InstallMapRequest, ListMapsQuery, ModifyMapRequest, RenameMapRequest, TagsRequest,
+    UpdateWorkshopRequest,
+};
 use crate::map_installer::DiscoveryMode;
 use crate::registry::models::SourceKind;
 use crate::registry::MapEntry;
@@ -11,17 +15,9 @@ use crate::api::test_support::setup_api_fixture;
 
 fn sample_map() -> MapEntry {
     MapEntry {
-        id: 0,
-        name: "Test Map".to_string(),
         source_url: "https://example.com/map.zip".to_string(),
-        source_kind: SourceKind::Other,
-        workshop_id: None,
         installed_path: "test_map.vpk".to_string(),
-        installed_at: chrono::Utc::now(),
-        workshop_updated_at: None,
-        version: None,
-        checksum: None,
-        checksum_kind: None,
+        ..crate::test_helpers::test_map_entry("Test Map")
     }
 }
 
@@ -65,6 +61,42 @@ async fn test_modify_map_not_found() {
     );
 }
 
+#[tokio::test]
+async fn test_map_download_redirects_to_http_source() {
+    let (handlers, registry, _dirs) = setup_api_fixture().await;
+    let id = registry.add_map(sample_map()).await.unwrap();
+
+    let response = handlers
+        .map_download_redirect(Path(id.to_string()))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), axum::http::StatusCode::FOUND);
+    assert_eq!(
+        response.headers()[axum::http::header::LOCATION],
+        "https://example.com/map.zip"
+    );
+}
+
+#[tokio::test]
+async fn test_map_download_rejects_local_source() {
+    let (handlers, registry, _dirs) = setup_api_fixture().await;
+    let id = registry
+        .add_map(MapEntry {
+            source_url: "detected:/srv/l4d2/left4dead2/addons/test_map.vpk".to_string(),
+            ..sample_map()
+        })
+        .await
+        .unwrap();
+
+    let result = handlers.map_download_redirect(Path(id.to_string())).await;
+
+    assert_eq!(
+        result.unwrap_err().status_code(),
+        axum::http::StatusCode::CONFLICT
+    );
+}
+
 #[tokio::test]
 async fn test_modify_map_unknown_field() {
     let (handlers, registry, _dirs) = setup_api_fixture().await;
@@ -186,13 +218,127 @@ async fn test_list_maps_returns_entries() {
     let (handlers, registry, _dirs) = setup_api_fixture().await;
     let id = registry.add_map(sample_map()).await.unwrap();
 
-    let response = handlers.list_maps().await.unwrap();
+    let response = handlers.list_maps(Query(ListMapsQuery::default())).await.unwrap();
     assert!(response.0.success);
     let maps = response.0.data.unwrap();
     assert_eq!(maps.len(), 1);
     assert_eq!(maps[0].id, id);
 }
 
+#[tokio::test]
+async fn test_rename_map_collision_is_conflict() {
+    let (handlers, registry, _dirs) = setup_api_fixture().await;
+    let id = registry.add_map(sample_map()).await.unwrap();
+    registry
+        .add_map(MapEntry {
+            name: "other_map".to_string(),
+            installed_path: "other_map.vpk".to_string(),
+            ..sample_map()
+        })
+        .await
+        .unwrap();
+
+    let result = handlers
+        .rename_map(
+            Path(id.to_string()),
+            Json(RenameMapRequest {
+                name: "Other Map".to_string(),
+            }),
+        )
+        .await;
+
+    assert_eq!(
+        result.unwrap_err().status_code(),
+        axum::http::StatusCode::CONFLICT
+    );
+}
+
+#[tokio::test]
+async fn test_tagged_maps_filter_list_by_tag() {
+    let (handlers, registry, _dirs) = setup_api_fixture().await;
+    let tagged = registry.add_map(sample_map()).await.unwrap();
+    let untagged = registry
+        .add_map(MapEntry {
+            name: "Other Map".to_string(),
+            installed_path: "other_map.vpk".to_string(),
+            ..sample_map()
+        })
+        .await
+        .unwrap();
+
+    let tags = |tags: &[&str]| TagsRequest {
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+    };
+    let response = handlers
+        .add_map_tags(Path(tagged.to_string()), Json(tags(&["Survival", "survival ", "Co Op"])))
+        .await
+        .unwrap();
+    assert_eq!(response.0.data.unwrap().tags, vec!["co-op", "survival"]);
+
+    let filtered = handlers
+        .list_maps(Query(ListMapsQuery {
+            tag: Some("survival".to_string()),
+            ..ListMapsQuery::default()
+        }))
+        .await
+        .unwrap();
+    let ids: Vec<u64> = filtered.0.data.unwrap().iter().map(|m| m.id).collect();
+    assert_eq!(ids, vec![tagged]);
+    assert!(!ids.contains(&untagged));
+
+    let invalid = handlers
+        .add_map_tags(Path(tagged.to_string()), Json(tags(&["???"])))
+        .await;
+    assert_eq!(
+        invalid.unwrap_err().status_code(),
+        axum::http::StatusCode::BAD_REQUEST
+    );
+    let missing = handlers
+        .add_map_tags(Path("999".to_string()), Json(tags(&["survival"])))
+        .await;
+    assert_eq!(
+        missing.unwrap_err().status_code(),
+        axum::http::StatusCode::NOT_FOUND
+    );
+
+    let removed = handlers
+        .remove_map_tags(Path(tagged.to_string()), Json(tags(&["SURVIVAL"])))
+        .await
+        .unwrap();
+    assert_eq!(removed.0.data.unwrap().tags, vec!["co-op"]);
+}
+
+#[tokio::test]
+async fn test_list_maps_filters_by_content_flag() {
+    let (handlers, registry, _dirs) = setup_api_fixture().await;
+    let campaign = registry
+        .add_map(MapEntry {
+            content_flags: vec!["campaign".to_string(), "map".to_string()],
+            ..sample_map()
+        })
+        .await
+        .unwrap();
+    registry
+        .add_map(MapEntry {
+            name: "Sound Pack".to_string(),
+            installed_path: "sound_pack.vpk".to_string(),
+            content_flags: vec!["sound".to_string()],
+            ..sample_map()
+        })
+        .await
+        .unwrap();
+
+    let filtered = handlers
+        .list_maps(Query(ListMapsQuery {
+            content: Some("Campaign".to_string()),
+            ..ListMapsQuery::default()
+        }))
+        .await
+        .unwrap();
+    let ids: Vec<u64> = filtered.0.data.unwrap().iter().map(|m| m.id).collect();
+    assert_eq!(ids, vec![campaign]);
+}
+
 #[tokio::test]
 async fn test_list_maps_excludes_denylisted() {
     use std::sync::Arc;
@@ -219,51 +365,31 @@ async fn test_list_maps_excludes_denylisted() {
 
     let visible_id = registry
         .add_map(MapEntry {
-            id: 0,
-            name: "Visible".to_string(),
             source_url: String::new(),
             source_kind: SourceKind::Workshop,
             workshop_id: Some(111),
             installed_path: "visible.vpk".to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..crate::test_helpers::test_map_entry("Visible")
         })
         .await
         .unwrap();
 
     let hidden_workshop_id = registry
         .add_map(MapEntry {
-            id: 0,
-            name: "Hidden Workshop".to_string(),
             source_url: String::new(),
             source_kind: SourceKind::Workshop,
             workshop_id: Some(381419931),
             installed_path: "hidden_workshop.vpk".to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..crate::test_helpers::test_map_entry("Hidden Workshop")
         })
         .await
         .unwrap();
 
     let hidden_internal_id = registry
         .add_map(MapEntry {
-            id: 0,
-            name: "Hidden Internal".to_string(),
             source_url: "https://example.com/map.zip".to_string(),
-            source_kind: SourceKind::Other,
-            workshop_id: None,
             installed_path: "hidden_internal.vpk".to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..crate::test_helpers::test_map_entry("Hidden Internal")
         })
         .await
         .unwrap();
@@ -279,7 +405,7 @@ async fn test_list_maps_excludes_denylisted() {
         config_handle,
     ));
 
-    let response = handlers.list_maps().await.unwrap();
+    let response = handlers.list_maps(Query(ListMapsQuery::default())).await.unwrap();
     let maps = response.0.data.unwrap();
     assert_eq!(maps.len(), 1);
     assert_eq!(maps[0].id, visible_id);
@@ -316,17 +442,11 @@ async fn test_list_maps_reflects_hot_reloaded_denylist() {
 
     registry
         .add_map(MapEntry {
-            id: 0,
-            name: "Workshop Map".to_string(),
             source_url: String::new(),
             source_kind: SourceKind::Workshop,
             workshop_id: Some(381419931),
             installed_path: "workshop.vpk".to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..crate::test_helpers::test_map_entry("Workshop Map")
         })
         .await
         .unwrap();
@@ -353,7 +473,7 @@ hidden_map_ids = []
         config_handle.clone(),
     ));
 
-    let hidden = handlers.list_maps().await.unwrap();
+    let hidden = handlers.list_maps(Query(ListMapsQuery::default())).await.unwrap();
     assert!(hidden.0.data.unwrap().is_empty());
 
     std::fs::write(
@@ -371,10 +491,10 @@ hidden_map_ids = []
     )
     .expect("write updated config");
 
-    let change = apply_reload(&config_handle, &config_path).expect("reload");
+    let change = apply_reload(&config_handle, std::slice::from_ref(&config_path)).expect("reload");
     assert!(change.live_applied.contains(&"hidden_workshop_ids"));
 
-    let visible = handlers.list_maps().await.unwrap();
+    let visible = handlers.list_maps(Query(ListMapsQuery::default())).await.unwrap();
     assert_eq!(visible.0.data.unwrap().len(), 1);
 }
 
@@ -404,10 +524,40 @@ async fn test_install_map_validation_both_sources() {
     let (handlers, _registry, _dirs) = setup_api_fixture().await;
 
     let result = handlers
-        .install_map(Json(InstallMapRequest {
+        .install_map(Query(InstallMapQuery::default()), Json(InstallMapRequest {
             url: Some("https://example.com/map.zip".to_string()),
             workshop_id: Some(123),
+            local_path: None,
+            mirror_urls: Vec::new(),
             name: None,
+            headers: HashMap::new(),
+            basic_auth: None,
+            expected_checksum: None,
+            expected_checksum_kind: None,
+        }))
+        .await;
+
+    assert_eq!(
+        result.unwrap_err().status_code(),
+        axum::http::StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn test_install_map_validation_rejects_local_path_with_url() {
+    let (handlers, _registry, _dirs) = setup_api_fixture().await;
+
+    let result = handlers
+        .install_map(Query(InstallMapQuery::default()), Json(InstallMapRequest {
+            url: Some("https://example.com/map.zip".to_string()),
+            workshop_id: None,
+            local_path: Some("/srv/maps/map.vpk".to_string()),
+            mirror_urls: Vec::new(),
+            name: None,
+            headers: HashMap::new(),
+            basic_auth: None,
+            expected_checksum: None,
+            expected_checksum_kind: None,
         }))
         .await;
 
@@ -422,10 +572,40 @@ async fn test_install_map_validation_neither_source() {
     let (handlers, _registry, _dirs) = setup_api_fixture().await;
 
     let result = handlers
-        .install_map(Json(InstallMapRequest {
+        .install_map(Query(InstallMapQuery::default()), Json(InstallMapRequest {
             url: None,
             workshop_id: None,
+            local_path: None,
+            mirror_urls: Vec::new(),
+            name: None,
+            headers: HashMap::new(),
+            basic_auth: None,
+            expected_checksum: None,
+            expected_checksum_kind: None,
+        }))
+        .await;
+
+    assert_eq!(
+        result.unwrap_err().status_code(),
+        axum::http::StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn test_install_map_validation_rejects_headers_for_workshop() {
+    let (handlers, _registry, _dirs) = setup_api_fixture().await;
+
+    let result = handlers
+        .install_map(Query(InstallMapQuery::default()), Json(InstallMapRequest {
+            url: None,
+            workshop_id: Some(123),
+            local_path: None,
+            mirror_urls: Vec::new(),
             name: None,
+            headers: HashMap::from([("Referer".to_string(), "https://example.com".to_string())]),
+            basic_auth: None,
+            expected_checksum: None,
+            expected_checksum_kind: None,
         }))
         .await;
 
@@ -435,6 +615,95 @@ async fn test_install_map_validation_neither_source() {
     );
 }
 
+#[tokio::test]
+async fn test_install_map_validation_rejects_mirrors_for_workshop() {
+    let (handlers, _registry, _dirs) = setup_api_fixture().await;
+
+    let result = handlers
+        .install_map(Query(InstallMapQuery::default()), Json(InstallMapRequest {
+            url: None,
+            workshop_id: Some(123),
+            local_path: None,
+            mirror_urls: vec!["https://mirror.example.com/map.zip".to_string()],
+            name: None,
+            headers: HashMap::new(),
+            basic_auth: None,
+            expected_checksum: None,
+            expected_checksum_kind: None,
+        }))
+        .await;
+
+    assert_eq!(
+        result.unwrap_err().status_code(),
+        axum::http::StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn test_install_map_validation_rejects_malformed_checksum() {
+    let (handlers, _registry, _dirs) = setup_api_fixture().await;
+
+    let result = handlers
+        .install_map(Query(InstallMapQuery::default()), Json(InstallMapRequest {
+            url: Some("https://example.com/map.zip".to_string()),
+            workshop_id: None,
+            local_path: None,
+            mirror_urls: Vec::new(),
+            name: None,
+            headers: HashMap::new(),
+            basic_auth: None,
+            expected_checksum: Some("not-a-checksum".to_string()),
+            expected_checksum_kind: None,
+        }))
+        .await;
+
+    assert_eq!(
+        result.unwrap_err().status_code(),
+        axum::http::StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+async fn test_install_map_with_same_idempotency_key_replays_response() {
+    let (handlers, registry, _dirs) = setup_api_fixture().await;
+    registry
+        .add_map(MapEntry {
+            source_kind: SourceKind::Workshop,
+            workshop_id: Some(424242),
+            ..sample_map()
+        })
+        .await
+        .unwrap();
+    let request = || InstallMapRequest {
+        url: None,
+        workshop_id: Some(424242),
+        local_path: None,
+        mirror_urls: Vec::new(),
+        name: None,
+        headers: HashMap::new(),
+        basic_auth: None,
+        expected_checksum: None,
+        expected_checksum_kind: None,
+    };
+
+    let first = handlers
+        .install_map_idempotent(Some("retry-1"), Query(InstallMapQuery::default()), Json(request()))
+        .await
+        .unwrap();
+    let second = handlers
+        .install_map_idempotent(Some("retry-1"), Query(InstallMapQuery::default()), Json(request()))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&first.0).unwrap(),
+        serde_json::to_string(&second.0).unwrap()
+    );
+    // Already installed, so no install operation ran.
+    assert_eq!(first.0.data.as_ref().unwrap().operation_id, None);
+    assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+}
+
 #[tokio::test]
 async fn test_uninstall_map_success() {
     let (handlers, registry, _dirs) = setup_api_fixture().await;
@@ -474,7 +743,6 @@ async fn test_get_map_hides_denylisted() {
     use crate::api::handlers::ApiHandlers;
     use crate::config::init_handle;
     use crate::map_installer::MapInstallationService;
-    use crate::registry::models::SourceKind;
 
     let (registry, dirs) = crate::test_helpers::setup_test_dirs().await.unwrap();
     let paths = dirs.service_paths();
@@ -493,17 +761,9 @@ async fn test_get_map_hides_denylisted() {
 
     let hidden_id = registry
         .add_map(MapEntry {
-            id: 0,
-            name: "Hidden".to_string(),
             source_url: String::new(),
-            source_kind: SourceKind::Other,
-            workshop_id: None,
             installed_path: "hidden.vpk".to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..crate::test_helpers::test_map_entry("Hidden")
         })
         .await
         .unwrap();
@@ -518,3 +778,203 @@ async fn test_get_map_hides_denylisted() {
         axum::http::StatusCode::NOT_FOUND
     );
 }
+
+#[tokio::test]
+async fn test_list_files_on_disk_flags_tracked_and_untracked_vpks() {
+    use crate::test_helpers;
+
+    let (handlers, registry, dirs) = setup_api_fixture().await;
+    let addons = dirs.addons_path();
+    test_helpers::write_minimal_test_vpk(&addons.join("test_map.vpk"), "Test Map").unwrap();
+    test_helpers::write_minimal_test_vpk(&addons.join("stray.vpk"), "Stray").unwrap();
+    std::fs::write(addons.join("notes.txt"), b"not a map").unwrap();
+    let id = registry.add_map(sample_map()).await.unwrap();
+
+    let files = handlers.list_files_on_disk().await.unwrap().0.data.unwrap();
+
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].path, "stray.vpk");
+    assert!(!files[0].tracked);
+    assert_eq!(files[0].map_id, None);
+    assert_eq!(files[1].path, "test_map.vpk");
+    assert!(files[1].tracked);
+    assert_eq!(files[1].map_id, Some(id));
+    assert!(files[1].size > 0);
+    assert!(files[1].modified.is_some());
+    assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_get_map_by_name_found() {
+    let (handlers, registry, _dirs) = setup_api_fixture().await;
+    let id = registry.add_map(sample_map()).await.unwrap();
+
+    let response = handlers
+        .get_map_by_name(Path("test map".to_string()))
+        .await
+        .unwrap();
+    assert_eq!(response.0.data.unwrap().id, id);
+}
+
+#[tokio::test]
+async fn test_get_map_by_name_not_found() {
+    let (handlers, registry, _dirs) = setup_api_fixture().await;
+    registry.add_map(sample_map()).await.unwrap();
+
+    let result = handlers.get_map_by_name(Path("Other Map".to_string())).await;
+    assert_eq!(
+        result.unwrap_err().status_code(),
+        axum::http::StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_get_map_by_name_ambiguous() {
+    let (handlers, registry, _dirs) = setup_api_fixture().await;
+    registry.add_map(sample_map()).await.unwrap();
+    registry
+        .add_map(MapEntry {
+            name: "test_map".to_string(),
+            installed_path: "test_map_2.vpk".to_string(),
+            ..sample_map()
+        })
+        .await
+        .unwrap();
+
+    let result = handlers.get_map_by_name(Path("Test Map".to_string())).await;
+    assert_eq!(
+        result.unwrap_err().status_code(),
+        axum::http::StatusCode::CONFLICT
+    );
+}
+
+#[derive(Default)]
+struct CountingSyncService {
+    fetches: std::sync::atomic::AtomicUsize,
+}
+
+#[async_trait::async_trait]
+impl crate::sync::SyncService for CountingSyncService {
+    async fn sync_registry(&self, entries: Vec<MapEntry>) -> anyhow::Result<crate::sync::traits::SyncPushResult> {
+        Ok(crate::sync::traits::SyncPushResult { accepted: entries.len(), rejected: Vec::new() })
+    }
+
+    async fn sync_delta(
+        &self,
+        changed: Vec<MapEntry>,
+        removed: Vec<String>,
+    ) -> anyhow::Result<crate::sync::traits::SyncPushResult> {
+        Ok(crate::sync::traits::SyncPushResult { accepted: changed.len() + removed.len(), rejected: Vec::new() })
+    }
+
+    async fn fetch_updates(&self) -> anyhow::Result<Vec<crate::sync::traits::MapUpdate>> {
+        self.fetches.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Ok(Vec::new())
+    }
+}
+
+#[tokio::test]
+async fn test_sync_now_runs_a_cycle_in_the_sync_loop() {
+    let (handlers, registry, _dirs) = setup_api_fixture().await;
+    let backend = std::sync::Arc::new(CountingSyncService::default());
+    let runner = std::sync::Arc::new(crate::sync::SyncRunner::new(
+        handlers.installer.clone(),
+        backend.clone(),
+        handlers.config.clone(),
+    ));
+    let handlers = super::ApiHandlers::new(registry, handlers.installer.clone(), handlers.config.clone())
+        .with_sync_trigger(runner.trigger_control());
+
+    // Without a loop running the request has nobody to serve it.
+    let detached = super::ApiHandlers::new(
+        handlers.registry.clone(),
+        handlers.installer.clone(),
+        handlers.config.clone(),
+    );
+    assert_eq!(
+        detached.sync_now().await.unwrap_err().status_code(),
+        axum::http::StatusCode::SERVICE_UNAVAILABLE
+    );
+
+    let loop_runner = runner.clone();
+    let sync_loop = tokio::spawn(async move { loop_runner.run_periodic().await });
+
+    let report = tokio::time::timeout(std::time::Duration::from_secs(10), handlers.sync_now())
+        .await
+        .expect("sync_now timed out")
+        .unwrap();
+    assert!(report.0.success);
+    // The loop's initial tick already pushed the registry, so nothing is left to send.
+    assert!(!report.0.data.unwrap().pushed);
+    // The loop's initial tick plus the requested cycle; the 300s interval never elapses.
+    assert_eq!(backend.fetches.load(std::sync::atomic::Ordering::SeqCst), 2);
+
+    sync_loop.abort();
+}
+
+#[tokio::test]
+async fn test_stats_reports_map_count_after_install() {
+    let staging = tempfile::TempDir::new().unwrap();
+    let (mut config, _config_dir) = crate::test_helpers::create_test_config();
+    config.local_install_dirs = vec![staging.path().to_path_buf()];
+    let (handlers, _registry, _dirs, _config) =
+        crate::api::test_support::setup_api_fixture_with_config(config).await;
+
+    let before = handlers.stats().await.unwrap().0.data.unwrap();
+    assert_eq!(before.map_count, 0);
+    assert!(before.last_sync_ok_at.is_none());
+    assert!(!before.watcher_running);
+
+    let vpk_path = staging.path().join("stats_map.vpk");
+    crate::test_helpers::write_minimal_test_vpk(&vpk_path, "Stats Map").unwrap();
+    let installed = handlers
+        .install_map(
+            Query(InstallMapQuery::default()),
+            Json(InstallMapRequest {
+                url: None,
+                workshop_id: None,
+                local_path: Some(vpk_path.to_string_lossy().into_owned()),
+                mirror_urls: Vec::new(),
+                name: None,
+                headers: HashMap::new(),
+                basic_auth: None,
+                expected_checksum: None,
+                expected_checksum_kind: None,
+            }),
+        )
+        .await
+        .unwrap();
+    assert!(installed.0.success);
+    let response = installed.0.data.unwrap();
+    assert!(response.operation_id.is_some());
+    assert_eq!(
+        handlers.registry.get_map(response.map_id).await.unwrap().unwrap().name,
+        "stats_map"
+    );
+
+    let after = handlers.stats().await.unwrap().0.data.unwrap();
+    assert_eq!(after.map_count, 1);
+    assert!(after.total_bytes > 0);
+    assert_eq!(after.active_installs, 0);
+}
+
+#[tokio::test]
+async fn test_map_files_lists_vpk_directory_tree() {
+    use crate::test_helpers;
+
+    let (handlers, registry, dirs) = setup_api_fixture().await;
+    let addons = dirs.addons_path();
+    test_helpers::write_minimal_test_vpk(&addons.join("test_map.vpk"), "Test Map").unwrap();
+    let id = registry.add_map(sample_map()).await.unwrap();
+
+    let files = handlers.map_files(Path(id.to_string())).await.unwrap().0.data.unwrap();
+    let addoninfo = files
+        .iter()
+        .find(|entry| entry.path == "addoninfo.txt")
+        .expect("addoninfo.txt listed");
+    assert!(addoninfo.size > 0);
+
+    std::fs::remove_file(addons.join("test_map.vpk")).unwrap();
+    let missing = handlers.map_files(Path(id.to_string())).await.unwrap_err();
+    assert_eq!(missing.status_code(), axum::http::StatusCode::NOT_FOUND);
+}
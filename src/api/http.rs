@@ -1,18 +1,32 @@
 // SPDX-License-Identifier: GPL-3.0-only
+use anyhow::Context;
 use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::net::TcpListener;
 use tracing::info;
 
+use crate::api::drain::DrainState;
 use crate::api::handlers::ApiHandlers;
 use crate::api::routes;
 use crate::config::ConfigHandle;
 use crate::map_installer::MapInstallationService;
 use crate::registry::Registry;
+use crate::sync::{SyncHealth, SyncPause, SyncTrigger};
+use crate::watcher::WatcherStatus;
 
 pub struct HttpServer {
     handlers: ApiHandlers,
     addr: SocketAddr,
+    tls: Option<TlsFiles>,
+}
+
+/// PEM certificate chain and private key the API is served with over HTTPS.
+struct TlsFiles {
+    cert_path: PathBuf,
+    key_path: PathBuf,
 }
 
 impl HttpServer {
@@ -25,21 +39,79 @@ impl HttpServer {
         Self {
             handlers: ApiHandlers::new(registry, installer, config),
             addr,
+            tls: None,
         }
     }
 
+    /// Expose the sync loop's pause switch through `/api/sync/*`.
+    pub fn with_sync_pause(mut self, sync_pause: SyncPause) -> Self {
+        self.handlers = self.handlers.with_sync_pause(sync_pause);
+        self
+    }
+
+    /// Let `POST /api/sync/now` wake the sync loop for an immediate cycle.
+    pub fn with_sync_trigger(mut self, sync_trigger: SyncTrigger) -> Self {
+        self.handlers = self.handlers.with_sync_trigger(sync_trigger);
+        self
+    }
+
+    /// Report the sync loop's last successful cycle in `/api/stats`.
+    pub fn with_sync_health(mut self, sync_health: SyncHealth) -> Self {
+        self.handlers = self.handlers.with_sync_health(sync_health);
+        self
+    }
+
+    /// Report whether the addons watcher is running in `/api/stats`.
+    pub fn with_watcher_status(mut self, watcher_status: WatcherStatus) -> Self {
+        self.handlers = self.handlers.with_watcher_status(watcher_status);
+        self
+    }
+
+    /// Drain switch flipped by `POST /api/admin/drain` and by shutdown.
+    pub fn with_drain(mut self, drain: DrainState) -> Self {
+        self.handlers = self.handlers.with_drain(drain);
+        self
+    }
+
     pub fn router(handlers: Arc<ApiHandlers>) -> Router {
         routes::routes(handlers)
     }
 
+    /// Serve over HTTPS with the PEM certificate chain and private key at these paths.
+    pub fn with_tls(mut self, cert_path: PathBuf, key_path: PathBuf) -> Self {
+        self.tls = Some(TlsFiles { cert_path, key_path });
+        self
+    }
+
     pub async fn serve(self) -> anyhow::Result<()> {
+        let listener = TcpListener::bind(&self.addr).await?;
+        self.serve_on(listener).await
+    }
+
+    /// Serve on an already bound listener, over HTTPS when TLS files are configured.
+    async fn serve_on(self, listener: TcpListener) -> anyhow::Result<()> {
         let handlers = Arc::new(self.handlers);
         let app = Self::router(handlers);
 
-        info!(addr = %self.addr, "Starting HTTP server");
+        let Some(tls) = self.tls else {
+            info!(addr = %self.addr, "Starting HTTP server");
+            axum::serve(listener, app).await?;
+            return Ok(());
+        };
 
-        let listener = tokio::net::TcpListener::bind(&self.addr).await?;
-        axum::serve(listener, app).await?;
+        let rustls = RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to load TLS certificate {} and key {}",
+                    tls.cert_path.display(),
+                    tls.key_path.display()
+                )
+            })?;
+        info!(addr = %self.addr, "Starting HTTPS server");
+        axum_server::from_tcp_rustls(listener.into_std()?, rustls)?
+            .serve(app.into_make_service())
+            .await?;
 
         Ok(())
     }
@@ -49,7 +121,9 @@ impl HttpServer {
 mod tests {
     use super::*;
     use crate::api::response::ApiResponse;
-    use crate::api::test_support::{setup_api_router, setup_api_router_with_config};
+    use crate::api::test_support::{
+        setup_api_router, setup_api_router_with_config, setup_http_server,
+    };
     use crate::config::{read_config, Config};
     use axum::body::Body;
     use axum::http::{Request, StatusCode};
@@ -65,6 +139,35 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_serves_health_over_https_with_configured_certificate() {
+        let certified = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let tls_dir = tempfile::tempdir().unwrap();
+        let cert_path = tls_dir.path().join("api.crt");
+        let key_path = tls_dir.path().join("api.key");
+        std::fs::write(&cert_path, certified.cert.pem()).unwrap();
+        std::fs::write(&key_path, certified.signing_key.serialize_pem()).unwrap();
+
+        let (server, _dirs) = setup_http_server().await;
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(server.with_tls(cert_path, key_path).serve_on(listener));
+
+        let client = reqwest::Client::builder()
+            .add_root_certificate(reqwest::Certificate::from_pem(certified.cert.pem().as_bytes()).unwrap())
+            .build()
+            .unwrap();
+        let response = client
+            .get(format!("https://localhost:{port}/health"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+        let plain = reqwest::get(format!("http://localhost:{port}/health")).await;
+        assert!(plain.is_err() || !plain.unwrap().status().is_success());
+    }
+
     #[tokio::test]
     async fn test_health_endpoint_does_not_require_api_key() {
         let mut config = Config::default();
@@ -156,6 +259,202 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn test_search_maps_requires_query() {
+        let (app, _dirs) = setup_api_router().await;
+        let response = app
+            .clone()
+            .oneshot(Request::get("/api/maps/search?q=").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(Request::get("/api/maps/search?q=carn").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let parsed: ApiResponse<Vec<crate::registry::MapEntry>> =
+            serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed.data.unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_audit_endpoint_validates_limit() {
+        let (app, _dirs) = setup_api_router().await;
+        let response = app
+            .clone()
+            .oneshot(Request::get("/api/audit?limit=0").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(Request::get("/api/audit?map_id=1").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let parsed: ApiResponse<Vec<crate::map_installer::AuditEntry>> =
+            serde_json::from_slice(&body).unwrap();
+        assert!(parsed.data.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_force_uninstall_endpoint_rejects_traversal() {
+        let (app, _dirs) = setup_api_router().await;
+        let response = app
+            .clone()
+            .oneshot(
+                Request::delete("/api/maps/on-disk/..%2Foutside.vpk")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app
+            .oneshot(
+                Request::delete("/api/maps/on-disk/missing.vpk")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_sync_pause_resume_endpoints() {
+        let (app, _dirs) = setup_api_router().await;
+        for (request, expected) in [
+            (Request::get("/api/sync/status"), false),
+            (Request::post("/api/sync/pause"), true),
+            (Request::get("/api/sync/status"), true),
+            (Request::post("/api/sync/resume"), false),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(request.body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let parsed: ApiResponse<crate::api::types::SyncStatus> =
+                serde_json::from_slice(&body).unwrap();
+            assert_eq!(parsed.data.unwrap().paused, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drain_rejects_mutations_but_serves_reads() {
+        let (app, _dirs) = setup_api_router().await;
+        let drain = app
+            .clone()
+            .oneshot(Request::post("/api/admin/drain").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(drain.status(), StatusCode::OK);
+
+        let install = app
+            .clone()
+            .oneshot(
+                Request::post("/api/maps/install")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"workshop_id":123}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(install.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            install.headers().get("retry-after").unwrap(),
+            &crate::api::drain::DRAIN_RETRY_AFTER_SECS.to_string()
+        );
+        let body = axum::body::to_bytes(install.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed["code"], "draining");
+
+        let list = app
+            .clone()
+            .oneshot(Request::get("/api/maps").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(list.status(), StatusCode::OK);
+
+        let again = app
+            .oneshot(Request::post("/api/admin/drain").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(again.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_mode_refuses_install_but_serves_list() {
+        let config = Config {
+            read_only: true,
+            ..Config::default()
+        };
+        let (app, _handle, _dirs) = setup_api_router_with_config(config).await;
+
+        let install = app
+            .clone()
+            .oneshot(
+                Request::post("/api/maps/install")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"workshop_id":123}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(install.status(), StatusCode::FORBIDDEN);
+        let body = axum::body::to_bytes(install.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed["code"], "read_only");
+
+        let list = app
+            .oneshot(Request::get("/api/maps").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(list.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_openapi_document_lists_install_route() {
+        let config = Config {
+            backend_api_key: Some("secret".to_string()),
+            ..Config::default()
+        };
+        let (app, _handle, _dirs) = setup_api_router_with_config(config).await;
+        let response = app
+            .oneshot(Request::get("/api/openapi.json").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let spec: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+        assert!(spec["paths"]["/api/maps/install"]["post"].is_object());
+        let schemas = &spec["components"]["schemas"];
+        assert!(schemas["InstallMapRequest"].is_object());
+        assert!(schemas["MapEntry"].is_object());
+    }
+
     #[tokio::test]
     async fn test_get_map_not_found_returns_json_error() {
         let (app, _dirs) = setup_api_router().await;
@@ -174,6 +473,28 @@ mod tests {
         let parsed: ApiResponse<()> = serde_json::from_slice(&body).unwrap();
         assert!(!parsed.success);
         assert!(parsed.error.is_some());
+        assert_eq!(parsed.code.as_deref(), Some("not_found"));
+    }
+
+    #[tokio::test]
+    async fn test_install_invalid_url_returns_invalid_url_code() {
+        let (app, _dirs) = setup_api_router().await;
+        let response = app
+            .oneshot(
+                Request::post("/api/maps/install")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"url":"not a url"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let parsed: ApiResponse<()> = serde_json::from_slice(&body).unwrap();
+        assert!(!parsed.success);
+        assert_eq!(parsed.code.as_deref(), Some("invalid_url"));
     }
 
     #[tokio::test]
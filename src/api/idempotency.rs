@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+use crate::api::error::ApiError;
+
+/// Header clients send to make a retried `POST` safe to repeat.
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// How long a completed result is replayed for a repeated key.
+pub const IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const MAX_KEY_LEN: usize = 255;
+
+type Slot<T> = Arc<Mutex<Option<(Instant, T)>>>;
+
+/// Remembers successful results per `Idempotency-Key` so retries replay them.
+///
+/// Requests sharing a key are serialized: a retry that arrives while the first
+/// attempt is still running waits for it. Failures are not stored, so a key whose
+/// request failed can be retried.
+pub struct IdempotencyStore<T> {
+    ttl: Duration,
+    slots: Mutex<HashMap<String, Slot<T>>>,
+}
+
+impl<T: Clone> IdempotencyStore<T> {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            slots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run `op` once per `key` within the TTL, returning the stored result for repeats.
+    pub async fn run<F, Fut>(&self, key: &str, op: F) -> Result<T, ApiError>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, ApiError>>,
+    {
+        validate_key(key)?;
+
+        let slot = {
+            let mut slots = self.slots.lock().await;
+            self.prune(&mut slots);
+            Arc::clone(slots.entry(key.to_string()).or_default())
+        };
+
+        let mut completed = slot.lock().await;
+        if let Some((at, result)) = completed.as_ref()
+            && at.elapsed() < self.ttl
+        {
+            return Ok(result.clone());
+        }
+
+        let result = op().await?;
+        *completed = Some((Instant::now(), result.clone()));
+        Ok(result)
+    }
+
+    /// Drop expired or failed slots nobody else is holding.
+    fn prune(&self, slots: &mut HashMap<String, Slot<T>>) {
+        slots.retain(|_, slot| {
+            if Arc::strong_count(slot) > 1 {
+                return true;
+            }
+            match slot.try_lock() {
+                Ok(completed) => completed
+                    .as_ref()
+                    .is_some_and(|(at, _)| at.elapsed() < self.ttl),
+                Err(_) => true,
+            }
+        });
+    }
+}
+
+fn validate_key(key: &str) -> Result<(), ApiError> {
+    if key.is_empty() || key.len() > MAX_KEY_LEN {
+        return Err(ApiError::bad_request(format!(
+            "Idempotency-Key must be 1-{MAX_KEY_LEN} characters"
+        )));
+    }
+    if !key.chars().all(|c| c.is_ascii_graphic()) {
+        return Err(ApiError::bad_request(
+            "Idempotency-Key must be printable ASCII without spaces",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[tokio::test]
+    async fn repeated_key_replays_first_result() {
+        let store = IdempotencyStore::new(IDEMPOTENCY_TTL);
+        let calls = AtomicU64::new(0);
+        let op = || async { Ok(calls.fetch_add(1, Ordering::SeqCst) + 10) };
+
+        assert_eq!(store.run("key-a", op).await.unwrap(), 10);
+        assert_eq!(store.run("key-a", op).await.unwrap(), 10);
+        assert_eq!(store.run("key-b", op).await.unwrap(), 11);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_result_is_not_stored() {
+        let store = IdempotencyStore::<u64>::new(IDEMPOTENCY_TTL);
+        let failed = store
+            .run("key", || async { Err(ApiError::internal("boom")) })
+            .await;
+        assert!(failed.is_err());
+        assert_eq!(store.run("key", || async { Ok(7) }).await.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn expired_result_runs_again() {
+        let store = IdempotencyStore::new(Duration::ZERO);
+        assert_eq!(store.run("key", || async { Ok(1) }).await.unwrap(), 1);
+        assert_eq!(store.run("key", || async { Ok(2) }).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_malformed_keys() {
+        let store = IdempotencyStore::new(IDEMPOTENCY_TTL);
+        assert!(store.run("", || async { Ok(1) }).await.is_err());
+        assert!(store.run("has space", || async { Ok(1) }).await.is_err());
+        assert!(store.run(&"k".repeat(256), || async { Ok(1) }).await.is_err());
+    }
+}
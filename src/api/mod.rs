@@ -1,8 +1,12 @@
 // SPDX-License-Identifier: GPL-3.0-only
 pub mod auth;
+pub mod drain;
 pub mod error;
 pub mod handlers;
 pub mod http;
+pub mod idempotency;
+pub mod openapi;
+pub mod read_only;
 pub mod response;
 pub mod routes;
 pub mod service_error;
@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! OpenAPI document for the local HTTP API, generated from the route handlers.
+
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi};
+
+use crate::api::routes;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "KetherServerDaemon API",
+        description = "Local map management API. Protected routes expect `Authorization: Bearer <backend_api_key>` when a key is configured."
+    ),
+    paths(
+        routes::health_handler,
+        routes::list_maps_handler,
+        routes::search_maps_handler,
+        routes::list_on_disk_handler,
+        routes::force_uninstall_handler,
+        routes::footprint_handler,
+        routes::get_map_handler,
+        routes::get_map_by_name_handler,
+        routes::modify_map_handler,
+        routes::map_download_handler,
+        routes::map_files_handler,
+        routes::remove_map_file_handler,
+        routes::add_map_tags_handler,
+        routes::remove_map_tags_handler,
+        routes::install_map_handler,
+        routes::uninstall_map_handler,
+        routes::list_available_updates_handler,
+        routes::check_available_updates_handler,
+        routes::update_workshop_handler,
+        routes::workshop_collection_handler,
+        routes::list_l4d2center_handler,
+        routes::install_l4d2center_handler,
+        routes::update_l4d2center_handler,
+        routes::discover_handler,
+        routes::compact_handler,
+        routes::reconcile_handler,
+        routes::cleanup_handler,
+        routes::audit_handler,
+        routes::pause_sync_handler,
+        routes::resume_sync_handler,
+        routes::sync_now_handler,
+        routes::sync_status_handler,
+        routes::drain_handler,
+        routes::steam_status_handler,
+        routes::stats_handler,
+        routes::events_handler,
+        routes::list_operations_handler,
+        routes::operation_stream_handler,
+        routes::cancel_operation_handler,
+    ),
+    modifiers(&BearerAuth)
+)]
+pub struct ApiDoc;
+
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
@@ -0,0 +1,29 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::api::error::ApiError;
+use crate::api::handlers::ApiHandlers;
+use crate::config::read_config;
+
+/// Answer requests that would change the addons directory with 403 while
+/// `read_only` is set; reads on the same routes pass.
+pub async fn reject_in_read_only(
+    State(handlers): State<Arc<ApiHandlers>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let reading = matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    if reading || !read_config(&handlers.config).read_only {
+        return next.run(request).await;
+    }
+    ApiError::forbidden("Daemon is in read-only mode; the addons directory is not modified")
+        .with_code("read_only")
+        .into_response()
+}
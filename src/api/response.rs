@@ -1,11 +1,16 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Envelope returned by every endpoint: `data` on success, `error`/`code` on failure.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub error: Option<String>,
+    /// Machine-readable error code (e.g. `invalid_url`, `not_found`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
 }
 
 impl<T> ApiResponse<T> {
@@ -14,6 +19,7 @@ impl<T> ApiResponse<T> {
             success: true,
             data: Some(data),
             error: None,
+            code: None,
         }
     }
 
@@ -22,6 +28,14 @@ impl<T> ApiResponse<T> {
             success: false,
             data: None,
             error: Some(message),
+            code: None,
+        }
+    }
+
+    pub fn error_with_code(message: String, code: &str) -> ApiResponse<()> {
+        ApiResponse {
+            code: Some(code.to_string()),
+            ..ApiResponse::<()>::error(message)
         }
     }
 }
@@ -1,46 +1,183 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use axum::{
-    extract::Path,
+    extract::{Path, Query},
+    http::HeaderMap,
+    response::{sse::{Event, KeepAlive, Sse}, Response},
     Json, Router,
 };
+use futures_util::stream::Stream;
+use std::convert::Infallible;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+use utoipa::OpenApi;
 
 use crate::api::error::ApiError;
+use crate::api::idempotency::IDEMPOTENCY_KEY_HEADER;
+use crate::api::openapi::ApiDoc;
 use crate::api::handlers::ApiHandlers;
 use crate::api::response::ApiResponse;
 use crate::api::auth::require_api_key;
+use crate::api::drain::reject_while_draining;
+use crate::api::read_only::reject_in_read_only;
 use crate::api::types::{
-    DiscoverRequest, InstallL4d2CenterRequest, InstallMapRequest, ModifyMapRequest,
-    UpdateL4d2CenterRequest, UpdateWorkshopRequest,
+    AuditQuery, CleanupQuery, DaemonStats, DiscoverRequest, DrainStatus, InstallL4d2CenterRequest, InstallMapQuery, InstallMapRequest, InstallMapResponse, ListMapsQuery,
+    PatchMapRequest, SearchMapsQuery, SyncStatus, TagsRequest, UpdateL4d2CenterRequest, UpdateWorkshopRequest,
+    WorkshopCollectionItem,
 };
 use crate::catalog::L4d2CenterCatalogEntry;
+use crate::downloader::circuit_breaker::CircuitBreakerStatus;
+use crate::extractor::traits::VpkFileEntry;
 use crate::map_installer::{
-    CompactReport, DiscoveryReport, L4d2CenterUpdateReport, MapUpdatesStatus, WorkshopUpdateReport,
+    AuditEntry, CleanupReport, CompactReport, ForceUninstallReport, InstallOperation, InstalledFileInfo, DiscoveryReport, L4d2CenterUpdateReport, MapFootprint, MapUpdatesStatus, ReconcileReport,
+    WorkshopUpdateReport,
 };
 use crate::registry::MapEntry;
+use crate::sync::runner::SyncCycleReport;
 
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "health",
+    responses(
+        (status = 200, description = "Daemon is up", body = ApiResponse<String>),
+    ),
+)]
 pub async fn health_handler() -> Json<ApiResponse<&'static str>> {
     Json(ApiResponse::success("ok"))
 }
 
+/// Serve the generated OpenAPI 3 document.
+pub async fn openapi_handler() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/maps",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(ListMapsQuery),
+    responses(
+        (status = 200, description = "Installed maps (denylisted entries hidden)", body = ApiResponse<Vec<MapEntry>>),
+        (status = 400, description = "Invalid tag filter", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn list_maps_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Query(query): Query<ListMapsQuery>,
 ) -> Result<Json<ApiResponse<Vec<MapEntry>>>, ApiError> {
-    handlers.list_maps().await
+    handlers.list_maps(Query(query)).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/maps/on-disk",
+    tag = "maps",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "VPKs present in addons, with whether the registry tracks each", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn list_on_disk_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+) -> Result<Json<ApiResponse<Vec<InstalledFileInfo>>>, ApiError> {
+    handlers.list_files_on_disk().await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/maps/on-disk/{filename}",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(("filename" = String, Path, description = "VPK path relative to the addons directory")),
+    responses(
+        (status = 200, description = "File and any registry entries pointing at it removed", body = ApiResponse<serde_json::Value>),
+        (status = 400, description = "Path is not a VPK inside the addons directory", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Neither a file nor a registry entry exists", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn force_uninstall_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Path(filename): Path<String>,
+) -> Result<Json<ApiResponse<ForceUninstallReport>>, ApiError> {
+    handlers.force_uninstall_file(Path(filename)).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/maps/footprint",
+    tag = "maps",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Total bytes used by registered maps, with a per-source-kind breakdown", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn footprint_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+) -> Result<Json<ApiResponse<MapFootprint>>, ApiError> {
+    handlers.map_footprint().await
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/maps/search",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(SearchMapsQuery),
+    responses(
+        (status = 200, description = "Ranked matches", body = ApiResponse<Vec<MapEntry>>),
+        (status = 400, description = "Missing or too long query", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn search_maps_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Query(query): Query<SearchMapsQuery>,
+) -> Result<Json<ApiResponse<Vec<MapEntry>>>, ApiError> {
+    handlers.search_maps(Query(query)).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/maps/updates/available",
+    tag = "updates",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Pending updates from the last check", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn list_available_updates_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
 ) -> Result<Json<ApiResponse<MapUpdatesStatus>>, ApiError> {
     handlers.list_available_updates().await
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/maps/updates/check",
+    tag = "updates",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Pending updates after a fresh check", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn check_available_updates_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
 ) -> Result<Json<ApiResponse<MapUpdatesStatus>>, ApiError> {
     handlers.check_available_updates().await
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/maps/{id}",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(("id" = u64, Path, description = "Map ID")),
+    responses(
+        (status = 200, description = "Map entry", body = ApiResponse<MapEntry>),
+        (status = 404, description = "Map not found", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn get_map_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
     Path(id): Path<String>,
@@ -48,13 +185,173 @@ pub async fn get_map_handler(
     handlers.get_map(Path(id)).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/maps/by-name/{name}",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(("name" = String, Path, description = "Map name; compared after sanitizing (case and spaces ignored)")),
+    responses(
+        (status = 200, description = "Map details", body = ApiResponse<MapEntry>),
+        (status = 404, description = "No map has this name", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Several maps share this name", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn get_map_by_name_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Path(name): Path<String>,
+) -> Result<Json<ApiResponse<MapEntry>>, ApiError> {
+    handlers.get_map_by_name(Path(name)).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/maps/{id}/download",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(("id" = u64, Path, description = "Map ID")),
+    responses(
+        (status = 302, description = "Redirect to the original file (fresh Steam URL for workshop maps)"),
+        (status = 404, description = "Map not found", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Map source is local and cannot be redirected to", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn map_download_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    handlers.map_download_redirect(Path(id)).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/maps/{id}/files",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(("id" = u64, Path, description = "Map ID")),
+    responses(
+        (status = 200, description = "Files in the map's VPK directory tree, sorted by path", body = ApiResponse<Vec<VpkFileEntry>>),
+        (status = 404, description = "Map not found, or its VPK is missing (`file_missing`)", body = ApiResponse<serde_json::Value>),
+        (status = 500, description = "VPK directory tree could not be read", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn map_files_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<Vec<VpkFileEntry>>>, ApiError> {
+    handlers.map_files(Path(id)).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/maps/{id}/parts/{filename}",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(
+        ("id" = u64, Path, description = "Map ID"),
+        ("filename" = String, Path, description = "Part file name, e.g. `mymap_001.vpk`"),
+    ),
+    responses(
+        (status = 200, description = "Map entry with the recomputed size", body = ApiResponse<MapEntry>),
+        (status = 400, description = "Not a part file of the map", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Map not found", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn remove_map_file_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Path(params): Path<(String, String)>,
+) -> Result<Json<ApiResponse<MapEntry>>, ApiError> {
+    handlers.remove_map_file(Path(params)).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/maps/{id}/tags",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(("id" = u64, Path, description = "Map ID")),
+    request_body = TagsRequest,
+    responses(
+        (status = 200, description = "Map entry with the merged tag list", body = ApiResponse<MapEntry>),
+        (status = 400, description = "Empty or invalid tags", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Map not found", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn add_map_tags_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Path(id): Path<String>,
+    Json(request): Json<TagsRequest>,
+) -> Result<Json<ApiResponse<MapEntry>>, ApiError> {
+    handlers.add_map_tags(Path(id), Json(request)).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/maps/{id}/tags",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(("id" = u64, Path, description = "Map ID")),
+    request_body = TagsRequest,
+    responses(
+        (status = 200, description = "Map entry with the remaining tags", body = ApiResponse<MapEntry>),
+        (status = 400, description = "Empty or invalid tags", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Map not found", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn remove_map_tags_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Path(id): Path<String>,
+    Json(request): Json<TagsRequest>,
+) -> Result<Json<ApiResponse<MapEntry>>, ApiError> {
+    handlers.remove_map_tags(Path(id), Json(request)).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/maps/install",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(
+        InstallMapQuery,
+        ("Idempotency-Key" = Option<String>, Header, description = "Replay the first result for retried requests"),
+    ),
+    request_body = InstallMapRequest,
+    responses(
+        (status = 200, description = "Installed map ID and the install operation that ran", body = ApiResponse<InstallMapResponse>),
+        (status = 400, description = "Invalid request", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Map already installed", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn install_map_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    headers: HeaderMap,
+    Query(query): Query<InstallMapQuery>,
     Json(request): Json<InstallMapRequest>,
-) -> Result<Json<ApiResponse<u64>>, ApiError> {
-    handlers.install_map(Json(request)).await
+) -> Result<Json<ApiResponse<InstallMapResponse>>, ApiError> {
+    let idempotency_key = match headers.get(IDEMPOTENCY_KEY_HEADER) {
+        Some(value) => Some(
+            value
+                .to_str()
+                .map_err(|_| ApiError::bad_request("Idempotency-Key must be printable ASCII"))?,
+        ),
+        None => None,
+    };
+    handlers
+        .install_map_idempotent(idempotency_key, Query(query), Json(request))
+        .await
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/maps/uninstall/{id}",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(("id" = u64, Path, description = "Map ID")),
+    responses(
+        (status = 200, description = "Map removed", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Map not found", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn uninstall_map_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
     Path(id): Path<String>,
@@ -62,6 +359,35 @@ pub async fn uninstall_map_handler(
     handlers.uninstall_map(Path(id)).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/maps/workshop/collections/{id}",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(("id" = u64, Path, description = "Workshop collection ID")),
+    responses(
+        (status = 200, description = "Downloadable items of the collection, nested collections flattened", body = ApiResponse<Vec<WorkshopCollectionItem>>),
+        (status = 400, description = "Invalid workshop ID", body = ApiResponse<serde_json::Value>),
+        (status = 503, description = "Steam temporarily unavailable", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn workshop_collection_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<Vec<WorkshopCollectionItem>>>, ApiError> {
+    handlers.workshop_collection(Path(id)).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/maps/workshop/update",
+    tag = "updates",
+    security(("bearer" = [])),
+    request_body = UpdateWorkshopRequest,
+    responses(
+        (status = 200, description = "Workshop update report", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn update_workshop_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
     Json(request): Json<UpdateWorkshopRequest>,
@@ -69,6 +395,16 @@ pub async fn update_workshop_handler(
     handlers.update_workshop_maps(Json(request)).await
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/maps/discover",
+    tag = "maintenance",
+    security(("bearer" = [])),
+    request_body = DiscoverRequest,
+    responses(
+        (status = 200, description = "Discovery report", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn discover_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
     Json(request): Json<DiscoverRequest>,
@@ -76,12 +412,223 @@ pub async fn discover_handler(
     handlers.discover_maps(Json(request)).await
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/maps/compact",
+    tag = "maintenance",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Compact report", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn compact_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
 ) -> Result<Json<ApiResponse<CompactReport>>, ApiError> {
     handlers.compact_registry().await
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/maintenance/reconcile",
+    tag = "maintenance",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Reconcile report", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn reconcile_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+) -> Result<Json<ApiResponse<ReconcileReport>>, ApiError> {
+    handlers.reconcile().await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/maintenance/cleanup",
+    tag = "maintenance",
+    security(("bearer" = [])),
+    params(CleanupQuery),
+    responses(
+        (status = 200, description = "Removed temp files and how many were kept", body = ApiResponse<serde_json::Value>),
+        (status = 400, description = "Threshold shorter than the install timeout", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn cleanup_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Query(query): Query<CleanupQuery>,
+) -> Result<Json<ApiResponse<CleanupReport>>, ApiError> {
+    handlers.cleanup_temp(Query(query)).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/audit",
+    tag = "maintenance",
+    security(("bearer" = [])),
+    params(AuditQuery),
+    responses(
+        (status = 200, description = "Audit entries, newest first", body = ApiResponse<Vec<AuditEntry>>),
+        (status = 400, description = "Invalid limit", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn audit_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<ApiResponse<Vec<AuditEntry>>>, ApiError> {
+    handlers.audit_log(Query(query)).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sync/pause",
+    tag = "sync",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Periodic backend sync paused", body = ApiResponse<SyncStatus>),
+    ),
+)]
+pub async fn pause_sync_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+) -> Json<ApiResponse<SyncStatus>> {
+    handlers.pause_sync()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sync/resume",
+    tag = "sync",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Periodic backend sync resumed", body = ApiResponse<SyncStatus>),
+    ),
+)]
+pub async fn resume_sync_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+) -> Json<ApiResponse<SyncStatus>> {
+    handlers.resume_sync()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sync/now",
+    tag = "sync",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Report of the sync cycle that was run", body = ApiResponse<SyncCycleReport>),
+        (status = 409, description = "Sync is paused", body = ApiResponse<serde_json::Value>),
+        (status = 503, description = "No sync loop is running", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn sync_now_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+) -> Result<Json<ApiResponse<SyncCycleReport>>, ApiError> {
+    handlers.sync_now().await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/sync/status",
+    tag = "sync",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Whether periodic backend sync is paused", body = ApiResponse<SyncStatus>),
+    ),
+)]
+pub async fn sync_status_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+) -> Json<ApiResponse<SyncStatus>> {
+    handlers.sync_status()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/drain",
+    tag = "admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Drain mode entered; mutating requests now get 503", body = ApiResponse<DrainStatus>),
+    ),
+)]
+pub async fn drain_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+) -> Json<ApiResponse<DrainStatus>> {
+    handlers.drain()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/operations",
+    tag = "maps",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Installs currently running, oldest first", body = ApiResponse<Vec<InstallOperation>>),
+    ),
+)]
+pub async fn list_operations_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+) -> Json<ApiResponse<Vec<InstallOperation>>> {
+    handlers.list_operations()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/operations/{id}/cancel",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(("id" = u64, Path, description = "Operation ID from the `install_started` event")),
+    responses(
+        (status = 200, description = "Cancellation requested; the install fails and cleans up its temp files", body = ApiResponse<u64>),
+        (status = 404, description = "No running install with this ID", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn cancel_operation_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<u64>>, ApiError> {
+    handlers.cancel_operation(Path(id))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/stats",
+    tag = "admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Snapshot of daemon state", body = ApiResponse<DaemonStats>),
+        (status = 500, description = "Registry error", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+pub async fn stats_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+) -> Result<Json<ApiResponse<DaemonStats>>, ApiError> {
+    handlers.stats().await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/steam/status",
+    tag = "admin",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Steam circuit breaker state", body = ApiResponse<CircuitBreakerStatus>),
+    ),
+)]
+pub async fn steam_status_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+) -> Json<ApiResponse<CircuitBreakerStatus>> {
+    handlers.steam_status()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/maps/l4d2center/update",
+    tag = "l4d2center",
+    security(("bearer" = [])),
+    request_body = UpdateL4d2CenterRequest,
+    responses(
+        (status = 200, description = "L4D2Center update report", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn update_l4d2center_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
     Json(request): Json<UpdateL4d2CenterRequest>,
@@ -89,12 +636,31 @@ pub async fn update_l4d2center_handler(
     handlers.update_l4d2center_maps(Json(request)).await
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/maps/l4d2center",
+    tag = "l4d2center",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "L4D2Center catalog with install status", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn list_l4d2center_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
 ) -> Result<Json<ApiResponse<Vec<L4d2CenterCatalogEntry>>>, ApiError> {
     handlers.list_l4d2center_catalog().await
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/maps/l4d2center/install",
+    tag = "l4d2center",
+    security(("bearer" = [])),
+    request_body = InstallL4d2CenterRequest,
+    responses(
+        (status = 200, description = "Installed map", body = ApiResponse<MapEntry>),
+    ),
+)]
 pub async fn install_l4d2center_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
     Json(request): Json<InstallL4d2CenterRequest>,
@@ -102,34 +668,152 @@ pub async fn install_l4d2center_handler(
     handlers.install_l4d2center_map(Json(request)).await
 }
 
+#[utoipa::path(
+    patch,
+    path = "/api/maps/{id}",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(("id" = u64, Path, description = "Map ID")),
+    request_body = PatchMapRequest,
+    responses(
+        (status = 200, description = "Updated map entry", body = ApiResponse<MapEntry>),
+        (status = 400, description = "Invalid field or name", body = ApiResponse<serde_json::Value>),
+        (status = 404, description = "Map not found", body = ApiResponse<serde_json::Value>),
+        (status = 409, description = "Name already used by another map", body = ApiResponse<serde_json::Value>),
+    ),
+)]
 pub async fn modify_map_handler(
     axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
     Path(id): Path<String>,
-    Json(request): Json<ModifyMapRequest>,
+    Json(request): Json<PatchMapRequest>,
 ) -> Result<Json<ApiResponse<MapEntry>>, ApiError> {
-    handlers.modify_map(Path(id), Json(request)).await
+    match request {
+        PatchMapRequest::Rename(rename) => handlers.rename_map(Path(id), Json(rename)).await,
+        PatchMapRequest::Modify(modify) => handlers.modify_map(Path(id), Json(modify)).await,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "events",
+    security(("bearer" = [])),
+    responses(
+        (status = 200, description = "Server-Sent Events stream of install/uninstall/sync activity", content_type = "text/event-stream"),
+    ),
+)]
+/// Stream installer/sync activity as Server-Sent Events until the client disconnects.
+pub async fn events_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = handlers.subscribe_events();
+    let stream = futures_util::stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => match Event::default().event(event.kind()).json_data(&event) {
+                    Ok(sse) => return Some((Ok(sse), receiver)),
+                    Err(e) => warn!(error = %e, "Failed to encode SSE event"),
+                },
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "SSE subscriber lagged; events dropped");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/operations/{id}/stream",
+    tag = "maps",
+    security(("bearer" = [])),
+    params(("id" = u64, Path, description = "Operation ID from the `install_started` event or `GET /api/operations`")),
+    responses(
+        (status = 200, description = "Server-Sent Events of this install (`install_progress`, then `install_completed` or `install_failed`); ends after the last one", content_type = "text/event-stream"),
+        (status = 404, description = "No running install with this ID", body = ApiResponse<serde_json::Value>),
+    ),
+)]
+/// Stream one running install's progress and outcome as Server-Sent Events.
+pub async fn operation_stream_handler(
+    axum::extract::State(handlers): axum::extract::State<Arc<ApiHandlers>>,
+    Path(id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    use futures_util::StreamExt;
+
+    let stream = handlers.watch_operation(Path(id))?.filter_map(|event| async move {
+        match Event::default().event(event.kind()).json_data(&event) {
+            Ok(sse) => Some(Ok(sse)),
+            Err(e) => {
+                warn!(error = %e, "Failed to encode SSE event");
+                None
+            }
+        }
+    });
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
 }
 
 pub fn routes(handlers: Arc<ApiHandlers>) -> Router {
     use axum::middleware;
-    use axum::routing::{get, post};
+    use axum::routing::{delete, get, post};
 
-    let protected = Router::new()
+    // Routes that write to the addons directory, refused in read-only mode.
+    let addons_writes = Router::new()
         .route("/api/maps/install", post(install_map_handler))
         .route("/api/maps/uninstall/{id}", post(uninstall_map_handler))
         .route("/api/maps/workshop/update", post(update_workshop_handler))
-        .route("/api/maps/l4d2center", get(list_l4d2center_handler))
         .route("/api/maps/l4d2center/install", post(install_l4d2center_handler))
         .route("/api/maps/l4d2center/update", post(update_l4d2center_handler))
+        .route("/api/maps/on-disk/{filename}", delete(force_uninstall_handler))
+        .route(
+            "/api/maps/{id}",
+            get(get_map_handler).patch(modify_map_handler),
+        )
+        .route("/api/maps/{id}/parts/{filename}", delete(remove_map_file_handler))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&handlers),
+            reject_in_read_only,
+        ));
+
+    let protected = Router::new()
+        .merge(addons_writes)
+        .route("/api/maps/workshop/collections/{id}", get(workshop_collection_handler))
+        .route("/api/maps/l4d2center", get(list_l4d2center_handler))
         .route("/api/maps/discover", post(discover_handler))
         .route("/api/maps/compact", post(compact_handler))
+        .route("/api/maintenance/reconcile", post(reconcile_handler))
+        .route("/api/maintenance/cleanup", post(cleanup_handler))
+        .route("/api/audit", get(audit_handler))
+        .route("/api/sync/pause", post(pause_sync_handler))
+        .route("/api/sync/resume", post(resume_sync_handler))
+        .route("/api/sync/now", post(sync_now_handler))
+        .route("/api/sync/status", get(sync_status_handler))
+        .route("/api/steam/status", get(steam_status_handler))
+        .route("/api/stats", get(stats_handler))
         .route("/api/maps/updates/available", get(list_available_updates_handler))
         .route("/api/maps/updates/check", post(check_available_updates_handler))
         .route("/api/maps", get(list_maps_handler))
+        .route("/api/maps/search", get(search_maps_handler))
+        .route("/api/maps/on-disk", get(list_on_disk_handler))
+        .route("/api/maps/footprint", get(footprint_handler))
+        .route("/api/events", get(events_handler))
+        .route("/api/operations", get(list_operations_handler))
+        .route("/api/operations/{id}/stream", get(operation_stream_handler))
+        .route("/api/maps/{id}/download", get(map_download_handler))
+        .route("/api/maps/{id}/files", get(map_files_handler))
+        .route("/api/maps/by-name/{name}", get(get_map_by_name_handler))
         .route(
-            "/api/maps/{id}",
-            get(get_map_handler).patch(modify_map_handler),
+            "/api/maps/{id}/tags",
+            post(add_map_tags_handler).delete(remove_map_tags_handler),
         )
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&handlers),
+            reject_while_draining,
+        ))
+        // Added after the drain layer so draining again, or cancelling work, is not refused.
+        .route("/api/admin/drain", post(drain_handler))
+        .route("/api/operations/{id}/cancel", post(cancel_operation_handler))
         .route_layer(middleware::from_fn_with_state(
             Arc::clone(&handlers),
             require_api_key,
@@ -138,6 +822,7 @@ pub fn routes(handlers: Arc<ApiHandlers>) -> Router {
 
     Router::new()
         .route("/health", get(health_handler))
+        .route("/api/openapi.json", get(openapi_handler))
         .merge(protected)
         .with_state(handlers)
 }
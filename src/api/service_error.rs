@@ -2,6 +2,11 @@
 use tracing::error;
 
 use crate::api::error::ApiError;
+use crate::downloader::circuit_breaker::CircuitOpen;
+use crate::downloader::steam::SteamRetry;
+use crate::downloader::workshop::SteamRequestError;
+use crate::map_installer::InstallTimedOut;
+use crate::utils::InsufficientDiskSpace;
 
 pub fn classify_workshop_error(err: anyhow::Error) -> ApiError {
     let message = err.to_string();
@@ -36,6 +41,14 @@ pub fn classify_modify_error(err: anyhow::Error) -> ApiError {
         error!(error = %message, "Map not found for modify");
         return ApiError::not_found(message);
     }
+    if message.contains("is already used by map") {
+        error!(error = %message, "Map name collision");
+        return ApiError::conflict(message).with_code("map_exists");
+    }
+    if message.contains("Invalid map name") {
+        error!(error = %message, "Invalid map name");
+        return ApiError::bad_request(message);
+    }
     if message.contains("Unknown or read-only field")
         || message.contains("Invalid source_kind")
         || message.contains("Invalid workshop_id")
@@ -52,6 +65,89 @@ pub fn classify_modify_error(err: anyhow::Error) -> ApiError {
     ApiError::internal(err.to_string())
 }
 
+pub fn classify_install_error(err: anyhow::Error) -> ApiError {
+    let message = err.to_string();
+    let detail = format!("{err:#}");
+    if message.ends_with("was cancelled") {
+        error!(error = %detail, "Install cancelled");
+        return ApiError::conflict(message).with_code("cancelled");
+    }
+    if err.chain().any(|e| e.is::<InstallTimedOut>()) {
+        error!(error = %detail, "Install timed out");
+        return ApiError::gateway_timeout(message).with_code("timeout");
+    }
+    if let Some(space) = err.chain().find_map(|e| e.downcast_ref::<InsufficientDiskSpace>()) {
+        error!(error = %detail, "Install rejected: insufficient disk space");
+        return ApiError::insufficient_storage(space.to_string());
+    }
+    if detail.contains("Invalid URL")
+        || detail.contains("SSRF")
+        || detail.contains("not in allowed_download_hosts")
+    {
+        error!(error = %detail, "Install rejected: invalid URL");
+        return ApiError::bad_request(message).with_code("invalid_url");
+    }
+    if detail.contains("blocked by policy") {
+        error!(error = %detail, "Install rejected: blocked by policy");
+        return ApiError::forbidden(message).with_code("blocked");
+    }
+    if detail.contains("VPK signature check failed")
+        || detail.contains("VPK signature does not match")
+        || detail.contains("require_signed_vpk is enabled")
+    {
+        error!(error = %detail, "Install rejected: VPK signature invalid");
+        return ApiError::unprocessable_entity(message).with_code("signature_invalid");
+    }
+    if detail.contains("has a denied extension") || detail.contains("is not in archive_extension_allowlist") {
+        error!(error = %detail, "Install rejected: archive entry not permitted");
+        return ApiError::unprocessable_entity(message).with_code("archive_entry_denied");
+    }
+    if detail.contains("max_vpks_per_archive allows") {
+        error!(error = %detail, "Install rejected: archive holds too many VPK files");
+        return ApiError::unprocessable_entity(message).with_code("too_many_vpks");
+    }
+    if message.starts_with("Invalid local path") {
+        error!(error = %detail, "Install rejected: invalid local path");
+        return ApiError::bad_request(message).with_code("invalid_local_path");
+    }
+    if detail.contains("already installed") {
+        error!(error = %detail, "Install rejected: map already installed");
+        return ApiError::conflict(message).with_code("map_exists");
+    }
+    if detail.contains("not found on Steam") {
+        error!(error = %detail, "Install target not found");
+        return ApiError::not_found(message);
+    }
+    if err.chain().any(|e| e.is::<CircuitOpen>()) {
+        error!(error = %detail, "Install rejected: Steam circuit breaker open");
+        return ApiError::service_unavailable(detail).with_code("steam_unavailable");
+    }
+    if let Some(steam) = err.chain().find_map(|e| e.downcast_ref::<SteamRequestError>())
+        && steam.source.retry_action() != SteamRetry::FailFast
+    {
+        error!(error = %detail, "Steam unreachable after retries");
+        return ApiError::internal(message).with_code("steam_unavailable");
+    }
+    if err.chain().any(|e| {
+        let message = e.to_string();
+        message.starts_with("Request failed with") || message.starts_with("Failed to download")
+    }) {
+        error!(error = %detail, "Map download failed");
+        return ApiError::internal(message).with_code("download_failed");
+    }
+    error!(error = %detail, "Failed to install map");
+    ApiError::internal("Internal server error")
+}
+
+pub fn classify_force_uninstall_error(err: anyhow::Error) -> ApiError {
+    let message = format!("{err:#}");
+    if message.starts_with("Invalid path") {
+        error!(error = %message, "Rejected force uninstall path");
+        return ApiError::bad_request(message);
+    }
+    classify_uninstall_error(err)
+}
+
 pub fn classify_uninstall_error(err: anyhow::Error) -> ApiError {
     let message = err.to_string();
     if message.contains("not found") {
@@ -26,11 +26,14 @@ pub async fn setup_api_fixture() -> (Arc<ApiHandlers>, Arc<dyn Registry>, TestDi
 }
 
 #[cfg(test)]
-async fn setup_api_fixture_with_config(
+pub async fn setup_api_fixture_with_config(
     mut config: Config,
 ) -> (Arc<ApiHandlers>, Arc<dyn Registry>, TestDirs, ConfigHandle) {
     let (registry, dirs) = test_helpers::setup_test_dirs().await.unwrap();
     let paths = dirs.service_paths();
+    config.l4d2center_index_url = "https://l4d2center.com/maps/servers/index.json".to_string();
+    let config_handle = init_handle(config);
+    // Handlers and installer share one handle, as in the daemon.
     let installer = Arc::new(
         MapInstallationService::new(
             Arc::clone(&registry),
@@ -41,10 +44,9 @@ async fn setup_api_fixture_with_config(
             10000,
         )
         .await
-        .unwrap(),
+        .unwrap()
+        .with_config(config_handle.clone()),
     );
-    config.l4d2center_index_url = "https://l4d2center.com/maps/servers/index.json".to_string();
-    let config_handle = init_handle(config);
     (
         Arc::new(ApiHandlers::new(
             Arc::clone(&registry),
@@ -57,6 +59,32 @@ async fn setup_api_fixture_with_config(
     )
 }
 
+/// An `HttpServer` over a fresh test registry, for tests that serve on a real socket.
+#[cfg(test)]
+pub async fn setup_http_server() -> (HttpServer, TestDirs) {
+    let (registry, dirs) = test_helpers::setup_test_dirs().await.unwrap();
+    let paths = dirs.service_paths();
+    let installer = Arc::new(
+        MapInstallationService::new(
+            Arc::clone(&registry),
+            paths.addons_dir,
+            paths.download_dir,
+            100 * 1024 * 1024,
+            1024 * 1024 * 1024,
+            10000,
+        )
+        .await
+        .unwrap(),
+    );
+    let server = HttpServer::new(
+        registry,
+        installer,
+        "127.0.0.1:0".parse().unwrap(),
+        init_handle(Config::default()),
+    );
+    (server, dirs)
+}
+
 #[cfg(test)]
 pub async fn setup_api_router() -> (Router, TestDirs) {
     let (handlers, _registry, dirs) = setup_api_fixture().await;
@@ -1,21 +1,115 @@
 // SPDX-License-Identifier: GPL-3.0-only
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use std::collections::HashMap;
 
 use crate::map_installer::DiscoveryMode;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct InstallMapRequest {
-    /// HTTP/HTTPS URL for ZIP file download (only used when workshop_id is not provided)
+    /// HTTP/HTTPS URL for ZIP file download (only used when workshop_id and local_path are not provided)
     pub url: Option<String>,
 
-    /// Steam Workshop ID (only used when url is not provided)
+    /// Steam Workshop ID (only used when url and local_path are not provided)
     pub workshop_id: Option<u64>,
 
+    /// Absolute path of a VPK/ZIP/7z on the host, inside `local_install_dirs`
+    /// (only used when neither url nor workshop_id is provided)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_path: Option<String>,
+
+    /// Fallback download URLs tried in order when `url` fails
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mirror_urls: Vec<String>,
+
     /// Optional map name override
     pub name: Option<String>,
+
+    /// Extra request headers for the `url` download (e.g. Referer, Cookie)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+
+    /// Basic auth credentials for the `url` download
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub basic_auth: Option<BasicAuth>,
+
+    /// Reject the install unless the map VPK has this checksum
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_checksum: Option<String>,
+
+    /// Algorithm for `expected_checksum` (only "md5"; default "md5")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_checksum_kind: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Response for `POST /api/maps/install`.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InstallMapResponse {
+    pub map_id: u64,
+    /// Install operation that ran (see `GET /api/operations`); absent when the map
+    /// was already installed from this source
+    pub operation_id: Option<u64>,
+}
+
+/// Username/password pair for mirrors behind HTTP basic auth.
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct BasicAuth {
+    pub username: String,
+    pub password: String,
+}
+
+impl std::fmt::Debug for BasicAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BasicAuth")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Query parameters for `GET /api/audit`.
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+pub struct AuditQuery {
+    /// Only entries for this map
+    pub map_id: Option<u64>,
+    /// Maximum entries returned, newest first (default 100, max 1000)
+    pub limit: Option<usize>,
+}
+
+/// Query parameters for `POST /api/maintenance/cleanup`.
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+pub struct CleanupQuery {
+    /// Remove temp files older than this many seconds (default `temp_cleanup_max_age_secs`)
+    pub older_than_secs: Option<u64>,
+}
+
+/// Query parameters for `GET /api/maps/search`.
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+pub struct SearchMapsQuery {
+    /// Case-insensitive text matched against name, version and installed path
+    #[serde(default)]
+    pub q: String,
+}
+
+/// Query parameters for `GET /api/maps`.
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+pub struct ListMapsQuery {
+    /// Only maps carrying this tag
+    pub tag: Option<String>,
+    /// Only maps whose `addoninfo.txt` sets this content flag (e.g. `campaign`)
+    pub content: Option<String>,
+}
+
+/// Query parameters for `POST /api/maps/install`.
+#[derive(Debug, Default, Serialize, Deserialize, IntoParams)]
+pub struct InstallMapQuery {
+    /// Replace an existing map with the same name instead of failing
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateWorkshopRequest {
     pub map_id: Option<u64>,
     #[serde(default)]
@@ -24,24 +118,45 @@ pub struct UpdateWorkshopRequest {
     pub check_only: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct DiscoverRequest {
     #[serde(default)]
     pub mode: DiscoveryMode,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ModifyMapRequest {
     pub field: String,
     pub value: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Body for renaming a map (and its VPK file) via `PATCH /api/maps/{id}`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RenameMapRequest {
+    pub name: String,
+}
+
+/// `PATCH /api/maps/{id}` accepts either `{ name }` or `{ field, value }`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(untagged)]
+pub enum PatchMapRequest {
+    Rename(RenameMapRequest),
+    Modify(ModifyMapRequest),
+}
+
+/// Body for `POST`/`DELETE /api/maps/{id}/tags`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TagsRequest {
+    /// Tags to add or remove; sanitized to lowercase `a-z0-9-_`
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct InstallL4d2CenterRequest {
     pub name: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UpdateL4d2CenterRequest {
     pub map_id: Option<u64>,
     pub name: Option<String>,
@@ -50,3 +165,48 @@ pub struct UpdateL4d2CenterRequest {
     #[serde(default)]
     pub check_only: bool,
 }
+
+/// Response for `POST /api/admin/drain`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DrainStatus {
+    /// Whether the daemon refuses new mutating requests
+    pub draining: bool,
+}
+
+/// Response for `GET /api/stats`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DaemonStats {
+    /// Registered maps
+    pub map_count: usize,
+    /// Recorded size of all registered maps in bytes
+    pub total_bytes: u64,
+    /// End of the last sync cycle whose fetch and push both succeeded
+    pub last_sync_ok_at: Option<DateTime<Utc>>,
+    pub sync_paused: bool,
+    /// Steam circuit breaker state
+    pub steam: crate::downloader::circuit_breaker::CircuitBreakerStatus,
+    /// Whether the addons directory watcher is running
+    pub watcher_running: bool,
+    /// Installs currently in progress
+    pub active_installs: usize,
+    /// Whether the daemon refuses new mutating requests
+    pub draining: bool,
+}
+
+/// Response for the `/api/sync/*` control endpoints.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SyncStatus {
+    /// Whether periodic backend sync is paused
+    pub paused: bool,
+}
+
+/// A downloadable item of a Workshop collection, from
+/// `GET /api/maps/workshop/collections/{id}`.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct WorkshopCollectionItem {
+    pub workshop_id: u64,
+    pub title: Option<String>,
+    /// Size in bytes as reported by Steam (0 when unknown)
+    pub file_size: u64,
+    pub updated_at: DateTime<Utc>,
+}
@@ -3,11 +3,17 @@ use tracing::error;
 
 use crate::api::error::ApiError;
 use crate::api::types::{InstallMapRequest, ModifyMapRequest};
+use crate::downloader::client::DownloadOptions;
+use crate::map_installer::ExpectedChecksum;
+
+const MAX_DOWNLOAD_HEADERS: usize = 32;
+const MAX_MIRROR_URLS: usize = 8;
 
 #[derive(Debug, Clone)]
 pub enum InstallSource {
-    Url(String),
+    Url(String, DownloadOptions),
     Workshop(u64),
+    LocalPath(std::path::PathBuf),
 }
 
 pub fn parse_map_id(id: &str) -> Result<u64, ApiError> {
@@ -30,32 +36,111 @@ pub fn validate_optional_name(name: &Option<String>) -> Result<(), ApiError> {
 }
 
 pub fn validate_install_request(req: &InstallMapRequest) -> Result<InstallSource, ApiError> {
-    match (req.url.as_ref(), req.workshop_id) {
-        (Some(_), Some(_)) => {
-            error!("Both url and workshop_id provided, but only one is allowed");
-            Err(ApiError::bad_request(
-                "Both url and workshop_id provided, but only one is allowed",
-            ))
+    let provided = [req.url.is_some(), req.workshop_id.is_some(), req.local_path.is_some()]
+        .into_iter()
+        .filter(|set| *set)
+        .count();
+    if provided > 1 {
+        error!("More than one of url, workshop_id and local_path provided");
+        return Err(ApiError::bad_request(
+            "Only one of url, workshop_id and local_path may be provided",
+        ));
+    }
+
+    validate_optional_name(&req.name)?;
+    if let Some(url) = &req.url {
+        if url.len() > 2048 {
+            error!("URL too long: {} characters", url.len());
+            return Err(ApiError::bad_request("URL too long (max 2048 characters)"));
+        }
+        if req.mirror_urls.len() > MAX_MIRROR_URLS {
+            error!("Too many mirror URLs: {}", req.mirror_urls.len());
+            return Err(ApiError::bad_request(format!(
+                "Too many mirror_urls (max {MAX_MIRROR_URLS})"
+            )));
         }
-        (None, None) => {
-            error!("Neither url nor workshop_id provided, one is required");
-            Err(ApiError::bad_request(
-                "Neither url nor workshop_id provided, one is required",
-            ))
+        if req.mirror_urls.iter().any(|mirror| mirror.len() > 2048) {
+            error!("Mirror URL too long");
+            return Err(ApiError::bad_request("Mirror URL too long (max 2048 characters)"));
         }
-        (Some(url), None) => {
-            if url.len() > 2048 {
-                error!("URL too long: {} characters", url.len());
-                return Err(ApiError::bad_request("URL too long (max 2048 characters)"));
-            }
-            validate_optional_name(&req.name)?;
-            Ok(InstallSource::Url(url.clone()))
+        return Ok(InstallSource::Url(url.clone(), validate_download_options(req)?));
+    }
+
+    if !req.mirror_urls.is_empty() {
+        error!("Mirror URLs provided for a non-url install");
+        return Err(ApiError::bad_request("mirror_urls are only supported for url installs"));
+    }
+
+    if !req.headers.is_empty() || req.basic_auth.is_some() {
+        error!("Download headers provided for a non-url install");
+        return Err(ApiError::bad_request(
+            "headers and basic_auth are only supported for url installs",
+        ));
+    }
+    if let Some(workshop_id) = req.workshop_id {
+        return Ok(InstallSource::Workshop(workshop_id));
+    }
+    if let Some(local_path) = &req.local_path {
+        let path = std::path::PathBuf::from(local_path);
+        if !path.is_absolute() {
+            error!(path = %local_path, "Relative local_path provided");
+            return Err(ApiError::bad_request("local_path must be an absolute path"));
         }
-        (None, Some(workshop_id)) => {
-            validate_optional_name(&req.name)?;
-            Ok(InstallSource::Workshop(workshop_id))
+        return Ok(InstallSource::LocalPath(path));
+    }
+
+    error!("Neither url, workshop_id nor local_path provided, one is required");
+    Err(ApiError::bad_request(
+        "Neither url, workshop_id nor local_path provided, one is required",
+    ))
+}
+
+pub fn validate_expected_checksum(
+    req: &InstallMapRequest,
+) -> Result<Option<ExpectedChecksum>, ApiError> {
+    let Some(value) = req.expected_checksum.as_deref().map(str::trim) else {
+        if req.expected_checksum_kind.is_some() {
+            return Err(ApiError::bad_request(
+                "expected_checksum_kind requires expected_checksum",
+            ));
         }
+        return Ok(None);
+    };
+    let kind = req.expected_checksum_kind.as_deref().unwrap_or("md5");
+    if !kind.eq_ignore_ascii_case("md5") {
+        error!(kind = %kind, "Unsupported expected checksum kind");
+        return Err(ApiError::bad_request(format!(
+            "Unsupported expected_checksum_kind '{kind}' (expected md5)"
+        )));
+    }
+    if value.len() != 32 || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+        error!("Invalid expected MD5 checksum");
+        return Err(ApiError::bad_request(
+            "expected_checksum must be a 32-character hex MD5",
+        ));
+    }
+    Ok(Some(ExpectedChecksum::md5(value)))
+}
+
+fn validate_download_options(req: &InstallMapRequest) -> Result<DownloadOptions, ApiError> {
+    if req.headers.len() > MAX_DOWNLOAD_HEADERS {
+        error!("Too many download headers: {}", req.headers.len());
+        return Err(ApiError::bad_request(format!(
+            "Too many headers (max {MAX_DOWNLOAD_HEADERS})"
+        )));
     }
+    let options = DownloadOptions {
+        headers: req.headers.clone(),
+        basic_auth: req
+            .basic_auth
+            .as_ref()
+            .map(|auth| (auth.username.clone(), auth.password.clone())),
+    };
+    options.validate().map_err(|e| {
+        error!(error = %e, "Invalid download headers");
+        ApiError::bad_request(e.to_string())
+    })?;
+    Ok(options)
 }
 
 pub fn validate_modify_request(req: &ModifyMapRequest) -> Result<(), ApiError> {
@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use serde::{Deserialize, Serialize};
 
-use crate::downloader::client::HttpClient;
+use crate::downloader::client::{HttpClient, HttpSettings};
 use crate::registry::models::SourceKind;
 use crate::registry::traits::Registry;
 use crate::utils::{md5_matches, validate_url_resolved};
@@ -34,13 +34,16 @@ pub struct L4d2CenterCatalogEntry {
     pub status: CatalogMapStatus,
 }
 
-pub async fn fetch_index(url: &str) -> anyhow::Result<Vec<L4d2CenterIndexEntry>> {
+pub async fn fetch_index(
+    url: &str,
+    http: &HttpSettings,
+) -> anyhow::Result<Vec<L4d2CenterIndexEntry>> {
     validate_url_resolved(url)
         .await
         .map_err(|e| anyhow::anyhow!("Invalid L4D2Center index URL: {e}"))?;
 
     // Index JSON is small; reuse SSRF-hardened client with a modest size cap.
-    let client = HttpClient::new(16 * 1024 * 1024)?;
+    let client = HttpClient::new(16 * 1024 * 1024, http)?;
     let body = client.get_text(url).await?;
     let entries: Vec<L4d2CenterIndexEntry> = serde_json::from_str(&body)
         .map_err(|e| anyhow::anyhow!("Failed to parse L4D2Center index JSON: {e}"))?;
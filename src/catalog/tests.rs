@@ -20,17 +20,12 @@ async fn enrich_with_registry_marks_installed_and_outdated() {
     let registry = Arc::new(JsonRegistry::new(&registry_path).await.unwrap()) as Arc<dyn Registry>;
 
     let entry = crate::registry::models::MapEntry {
-        id: 0,
-        name: "Widebox".to_string(),
         source_url: "https://l4d2center.com/maps/servers/widebox1.7z".to_string(),
         source_kind: crate::registry::models::SourceKind::L4d2Center,
-        workshop_id: None,
         installed_path: "widebox1.vpk".to_string(),
-        installed_at: chrono::Utc::now(),
-        workshop_updated_at: None,
-        version: None,
         checksum: Some("deadbeef".to_string()),
         checksum_kind: Some("md5".to_string()),
+        ..crate::test_helpers::test_map_entry("Widebox")
     };
     registry.add_map(entry).await.unwrap();
 
@@ -52,17 +47,13 @@ async fn enrich_with_registry_marks_other_source_when_not_l4d2center() {
     let registry = Arc::new(JsonRegistry::new(&registry_path).await.unwrap()) as Arc<dyn Registry>;
 
     let entry = crate::registry::models::MapEntry {
-        id: 0,
-        name: "No Echo".to_string(),
         source_url: "https://steamcommunity.com/sharedfiles/filedetails/?id=12345".to_string(),
         source_kind: crate::registry::models::SourceKind::Workshop,
         workshop_id: Some(12345),
         installed_path: "widebox1.vpk".to_string(),
-        installed_at: chrono::Utc::now(),
-        workshop_updated_at: None,
-        version: None,
         checksum: Some("deadbeef".to_string()),
         checksum_kind: Some("md5".to_string()),
+        ..crate::test_helpers::test_map_entry("No Echo")
     };
     registry.add_map(entry).await.unwrap();
 
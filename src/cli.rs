@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Command-line arguments.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+
+use crate::config::Config;
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Left 4 Dead 2 map management daemon")]
+pub struct Cli {
+    /// Config file(s), comma-separated with later files overriding earlier ones.
+    /// Takes precedence over KETHER_CONFIG.
+    #[arg(long, value_name = "PATH")]
+    pub config: Option<String>,
+
+    /// Load and validate the config, print a summary and exit without starting services.
+    #[arg(long)]
+    pub check_config: bool,
+
+    /// Fail instead of writing a default config when the config file is missing;
+    /// an environment-only setup must then set the required KETHER_* variables.
+    /// Same as KETHER_STRICT_CONFIG=1.
+    #[arg(long)]
+    pub strict_config: bool,
+}
+
+/// Load and validate `config_paths` (primary first) and describe the result.
+///
+/// Unlike a normal start, a missing primary file is an error rather than being
+/// created from the default template.
+pub fn check_config(config_paths: &[PathBuf]) -> anyhow::Result<String> {
+    let primary = &config_paths[0];
+    if !primary.exists() {
+        anyhow::bail!("Config file {} does not exist", primary.display());
+    }
+    let (config, path) = Config::load_with_paths(config_paths, false)?;
+    config.validate()?;
+    Ok(summarize(&config, &path))
+}
+
+fn summarize(config: &Config, path: &std::path::Path) -> String {
+    [
+        format!("Config OK: {}", path.display()),
+        format!("  addons_dir:         {}", config.addons_dir().display()),
+        format!("  registry_path:      {}", config.registry_path.display()),
+        format!("  backend_api_url:    {}", config.backend_api_url),
+        format!(
+            "  backend_api_key:    {}",
+            if config.backend_api_key.is_some() { "set" } else { "not set" }
+        ),
+        format!("  local_api_bind:     {}", config.local_api_bind),
+        format!("  sync_interval_secs: {}", config.sync_interval_secs),
+        format!("  log_level:          {}", config.log_level),
+    ]
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    const VALID_CONFIG: &str = r#"
+l4d2_server_dir = "/srv/l4d2"
+registry_path = "registry.json"
+backend_api_url = "http://127.0.0.1:3001/api"
+local_api_bind = "127.0.0.1:8080"
+sync_interval_secs = 300
+log_level = "info"
+"#;
+
+    #[test]
+    #[serial]
+    fn check_config_accepts_valid_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        std::fs::write(&path, VALID_CONFIG).unwrap();
+
+        let summary = check_config(&[path]).unwrap();
+        assert!(summary.starts_with("Config OK"));
+        assert!(summary.contains("/srv/l4d2/left4dead2/addons"));
+    }
+
+    #[test]
+    #[serial]
+    fn check_config_rejects_invalid_or_missing_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        std::fs::write(&path, VALID_CONFIG.replace("\"info\"", "\"loud\"")).unwrap();
+        let error = check_config(&[path]).unwrap_err();
+        assert!(error.to_string().contains("log_level"), "{error}");
+
+        let missing = temp_dir.path().join("missing.toml");
+        assert!(check_config(std::slice::from_ref(&missing)).is_err());
+        assert!(!missing.exists(), "check mode must not write a default config");
+    }
+
+    #[test]
+    fn parses_config_and_check_flags() {
+        let cli = Cli::try_parse_from(["KetherServerDaemon", "--config", "a.toml", "--check-config"])
+            .unwrap();
+        assert_eq!(cli.config.as_deref(), Some("a.toml"));
+        assert!(cli.check_config);
+    }
+}
@@ -40,6 +40,12 @@ impl Config {
         if self.hidden_map_ids != new.hidden_map_ids {
             change.live_applied.push("hidden_map_ids");
         }
+        if self.blocked_workshop_ids != new.blocked_workshop_ids {
+            change.live_applied.push("blocked_workshop_ids");
+        }
+        if self.blocked_url_patterns != new.blocked_url_patterns {
+            change.live_applied.push("blocked_url_patterns");
+        }
         if self.sync_interval_secs != new.sync_interval_secs {
             change.live_applied.push("sync_interval_secs");
         }
@@ -67,24 +73,156 @@ impl Config {
         if self.l4d2center_update_auto_apply != new.l4d2center_update_auto_apply {
             change.live_applied.push("l4d2center_update_auto_apply");
         }
+        if self.detect_require_checksum != new.detect_require_checksum {
+            change.live_applied.push("detect_require_checksum");
+        }
+        if self.default_checksum_kind != new.default_checksum_kind {
+            change.live_applied.push("default_checksum_kind");
+        }
+        if self.require_addoninfo != new.require_addoninfo {
+            change.live_applied.push("require_addoninfo");
+        }
+        if self.read_only != new.read_only {
+            change.live_applied.push("read_only");
+        }
+        if self.require_signed_vpk != new.require_signed_vpk {
+            change.live_applied.push("require_signed_vpk");
+        }
+        if self.vpk_signing_public_key != new.vpk_signing_public_key {
+            change.live_applied.push("vpk_signing_public_key");
+        }
+        if self.allowed_download_hosts != new.allowed_download_hosts {
+            change.live_applied.push("allowed_download_hosts");
+        }
+        if self.archive_extension_allowlist != new.archive_extension_allowlist {
+            change.live_applied.push("archive_extension_allowlist");
+        }
+        if self.archive_extension_denylist != new.archive_extension_denylist {
+            change.live_applied.push("archive_extension_denylist");
+        }
+        if self.max_vpks_per_archive != new.max_vpks_per_archive {
+            change.live_applied.push("max_vpks_per_archive");
+        }
+        if self.local_install_dirs != new.local_install_dirs {
+            change.live_applied.push("local_install_dirs");
+        }
+        if self.backend_sync_gzip != new.backend_sync_gzip {
+            change.live_applied.push("backend_sync_gzip");
+        }
+        if self.max_sync_response_bytes != new.max_sync_response_bytes {
+            change.live_applied.push("max_sync_response_bytes");
+        }
+        if self.install_timeout_secs != new.install_timeout_secs {
+            change.live_applied.push("install_timeout_secs");
+        }
+        if self.temp_cleanup_interval_secs != new.temp_cleanup_interval_secs {
+            change.live_applied.push("temp_cleanup_interval_secs");
+        }
+        if self.temp_cleanup_max_age_secs != new.temp_cleanup_max_age_secs {
+            change.live_applied.push("temp_cleanup_max_age_secs");
+        }
+        if self.integrity_scan_interval_secs != new.integrity_scan_interval_secs {
+            change.live_applied.push("integrity_scan_interval_secs");
+        }
+        if self.shutdown_grace_secs != new.shutdown_grace_secs {
+            change.live_applied.push("shutdown_grace_secs");
+        }
+        if self.post_install_command != new.post_install_command {
+            change.live_applied.push("post_install_command");
+        }
+        if self.post_uninstall_command != new.post_uninstall_command {
+            change.live_applied.push("post_uninstall_command");
+        }
+        if self.event_webhook_url != new.event_webhook_url {
+            change.live_applied.push("event_webhook_url");
+        }
+        if self.install_filename_template != new.install_filename_template {
+            change.live_applied.push("install_filename_template");
+        }
+        if self.installed_file_mode != new.installed_file_mode {
+            change.live_applied.push("installed_file_mode");
+        }
+        if self.installed_file_owner != new.installed_file_owner {
+            change.live_applied.push("installed_file_owner");
+        }
+        if self.installed_file_group != new.installed_file_group {
+            change.live_applied.push("installed_file_group");
+        }
+        if self.quarantine_dir != new.quarantine_dir {
+            change.live_applied.push("quarantine_dir");
+        }
+        if self.quarantine_max_bytes != new.quarantine_max_bytes {
+            change.live_applied.push("quarantine_max_bytes");
+        }
+        if self.workshop_collection_max_depth != new.workshop_collection_max_depth {
+            change.live_applied.push("workshop_collection_max_depth");
+        }
         if self.l4d2_server_dir != new.l4d2_server_dir {
             change.requires_restart.push("l4d2_server_dir");
         }
+        if self.addons_subpath != new.addons_subpath {
+            change.requires_restart.push("addons_subpath");
+        }
+        if self.server_dirs != new.server_dirs {
+            change.requires_restart.push("server_dirs");
+        }
         if self.registry_path != new.registry_path {
             change.requires_restart.push("registry_path");
         }
         if self.local_api_bind != new.local_api_bind {
             change.requires_restart.push("local_api_bind");
         }
+        if self.tls_cert_path != new.tls_cert_path {
+            change.requires_restart.push("tls_cert_path");
+        }
+        if self.tls_key_path != new.tls_key_path {
+            change.requires_restart.push("tls_key_path");
+        }
         if self.max_download_size_bytes != new.max_download_size_bytes {
             change.requires_restart.push("max_download_size_bytes");
         }
+        if self.http_proxy != new.http_proxy {
+            change.requires_restart.push("http_proxy");
+        }
+        if self.https_proxy != new.https_proxy {
+            change.requires_restart.push("https_proxy");
+        }
+        if self.http_user_agent != new.http_user_agent {
+            change.requires_restart.push("http_user_agent");
+        }
+        if self.http_connect_timeout_secs != new.http_connect_timeout_secs {
+            change.requires_restart.push("http_connect_timeout_secs");
+        }
+        if self.http_read_timeout_secs != new.http_read_timeout_secs {
+            change.requires_restart.push("http_read_timeout_secs");
+        }
+        if self.steam_breaker_failure_threshold != new.steam_breaker_failure_threshold {
+            change.requires_restart.push("steam_breaker_failure_threshold");
+        }
+        if self.steam_breaker_cooldown_secs != new.steam_breaker_cooldown_secs {
+            change.requires_restart.push("steam_breaker_cooldown_secs");
+        }
+        if self.download_temp_dir != new.download_temp_dir {
+            change.requires_restart.push("download_temp_dir");
+        }
+        if self.max_concurrent_downloads != new.max_concurrent_downloads {
+            change.requires_restart.push("max_concurrent_downloads");
+        }
+        if self.max_download_bytes_per_sec != new.max_download_bytes_per_sec {
+            change.requires_restart.push("max_download_bytes_per_sec");
+        }
         if self.max_extraction_size_bytes != new.max_extraction_size_bytes {
             change.requires_restart.push("max_extraction_size_bytes");
         }
         if self.max_extraction_file_count != new.max_extraction_file_count {
             change.requires_restart.push("max_extraction_file_count");
         }
+        if self.import_existing_on_start != new.import_existing_on_start {
+            change.requires_restart.push("import_existing_on_start");
+        }
+        if self.recompute_checksums_on_start != new.recompute_checksums_on_start {
+            change.requires_restart.push("recompute_checksums_on_start");
+        }
         if self.log_level != new.log_level {
             change.requires_restart.push("log_level");
         }
@@ -98,6 +236,8 @@ impl Config {
         let mut merged = self.clone();
         merged.hidden_workshop_ids = new.hidden_workshop_ids.clone();
         merged.hidden_map_ids = new.hidden_map_ids.clone();
+        merged.blocked_workshop_ids = new.blocked_workshop_ids.clone();
+        merged.blocked_url_patterns = new.blocked_url_patterns.clone();
         merged.sync_interval_secs = new.sync_interval_secs;
         merged.l4d2center_index_url = new.l4d2center_index_url.clone();
         merged.backend_api_url = new.backend_api_url.clone();
@@ -107,6 +247,34 @@ impl Config {
         merged.workshop_update_auto_apply = new.workshop_update_auto_apply;
         merged.l4d2center_update_check_enabled = new.l4d2center_update_check_enabled;
         merged.l4d2center_update_auto_apply = new.l4d2center_update_auto_apply;
+        merged.detect_require_checksum = new.detect_require_checksum;
+        merged.default_checksum_kind = new.default_checksum_kind.clone();
+        merged.require_addoninfo = new.require_addoninfo;
+        merged.read_only = new.read_only;
+        merged.require_signed_vpk = new.require_signed_vpk;
+        merged.vpk_signing_public_key = new.vpk_signing_public_key.clone();
+        merged.allowed_download_hosts = new.allowed_download_hosts.clone();
+        merged.archive_extension_allowlist = new.archive_extension_allowlist.clone();
+        merged.archive_extension_denylist = new.archive_extension_denylist.clone();
+        merged.max_vpks_per_archive = new.max_vpks_per_archive;
+        merged.local_install_dirs = new.local_install_dirs.clone();
+        merged.backend_sync_gzip = new.backend_sync_gzip;
+        merged.max_sync_response_bytes = new.max_sync_response_bytes;
+        merged.install_timeout_secs = new.install_timeout_secs;
+        merged.temp_cleanup_interval_secs = new.temp_cleanup_interval_secs;
+        merged.temp_cleanup_max_age_secs = new.temp_cleanup_max_age_secs;
+        merged.integrity_scan_interval_secs = new.integrity_scan_interval_secs;
+        merged.shutdown_grace_secs = new.shutdown_grace_secs;
+        merged.post_install_command = new.post_install_command.clone();
+        merged.post_uninstall_command = new.post_uninstall_command.clone();
+        merged.event_webhook_url = new.event_webhook_url.clone();
+        merged.install_filename_template = new.install_filename_template.clone();
+        merged.installed_file_mode = new.installed_file_mode.clone();
+        merged.installed_file_owner = new.installed_file_owner.clone();
+        merged.installed_file_group = new.installed_file_group.clone();
+        merged.quarantine_dir = new.quarantine_dir.clone();
+        merged.quarantine_max_bytes = new.quarantine_max_bytes;
+        merged.workshop_collection_max_depth = new.workshop_collection_max_depth;
         merged
     }
 }
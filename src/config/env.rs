@@ -7,22 +7,67 @@ use crate::config::model::Config;
 
 pub mod keys {
     pub const CONFIG: &str = "KETHER_CONFIG";
+    pub const STRICT_CONFIG: &str = "KETHER_STRICT_CONFIG";
     pub const L4D2_SERVER_DIR: &str = "KETHER_L4D2_SERVER_DIR";
+    pub const SERVER_DIRS: &str = "KETHER_SERVER_DIRS";
+    pub const READ_ONLY: &str = "KETHER_READ_ONLY";
+    pub const ADDONS_SUBPATH: &str = "KETHER_ADDONS_SUBPATH";
     pub const REGISTRY_PATH: &str = "KETHER_REGISTRY_PATH";
     pub const BACKEND_API_URL: &str = "KETHER_BACKEND_API_URL";
     pub const BACKEND_API_KEY: &str = "KETHER_BACKEND_API_KEY";
     pub const LOCAL_API_BIND: &str = "KETHER_LOCAL_API_BIND";
+    pub const TLS_CERT_PATH: &str = "KETHER_TLS_CERT_PATH";
+    pub const TLS_KEY_PATH: &str = "KETHER_TLS_KEY_PATH";
     pub const SYNC_INTERVAL_SECS: &str = "KETHER_SYNC_INTERVAL_SECS";
     pub const LOG_LEVEL: &str = "KETHER_LOG_LEVEL";
     pub const MAX_DOWNLOAD_SIZE_BYTES: &str = "KETHER_MAX_DOWNLOAD_SIZE_BYTES";
     pub const MAX_EXTRACTION_SIZE_BYTES: &str = "KETHER_MAX_EXTRACTION_SIZE_BYTES";
     pub const MAX_EXTRACTION_FILE_COUNT: &str = "KETHER_MAX_EXTRACTION_FILE_COUNT";
+    pub const MAX_VPKS_PER_ARCHIVE: &str = "KETHER_MAX_VPKS_PER_ARCHIVE";
+    pub const MAX_CONCURRENT_DOWNLOADS: &str = "KETHER_MAX_CONCURRENT_DOWNLOADS";
+    pub const MAX_DOWNLOAD_BYTES_PER_SEC: &str = "KETHER_MAX_DOWNLOAD_BYTES_PER_SEC";
+    pub const INSTALL_TIMEOUT_SECS: &str = "KETHER_INSTALL_TIMEOUT_SECS";
+    pub const HTTP_CONNECT_TIMEOUT_SECS: &str = "KETHER_HTTP_CONNECT_TIMEOUT_SECS";
+    pub const HTTP_READ_TIMEOUT_SECS: &str = "KETHER_HTTP_READ_TIMEOUT_SECS";
+    pub const STEAM_BREAKER_FAILURE_THRESHOLD: &str = "KETHER_STEAM_BREAKER_FAILURE_THRESHOLD";
+    pub const STEAM_BREAKER_COOLDOWN_SECS: &str = "KETHER_STEAM_BREAKER_COOLDOWN_SECS";
+    pub const WORKSHOP_COLLECTION_MAX_DEPTH: &str = "KETHER_WORKSHOP_COLLECTION_MAX_DEPTH";
+    pub const DOWNLOAD_TEMP_DIR: &str = "KETHER_DOWNLOAD_TEMP_DIR";
+    pub const TEMP_CLEANUP_INTERVAL_SECS: &str = "KETHER_TEMP_CLEANUP_INTERVAL_SECS";
+    pub const TEMP_CLEANUP_MAX_AGE_SECS: &str = "KETHER_TEMP_CLEANUP_MAX_AGE_SECS";
+    pub const INTEGRITY_SCAN_INTERVAL_SECS: &str = "KETHER_INTEGRITY_SCAN_INTERVAL_SECS";
+    pub const SHUTDOWN_GRACE_SECS: &str = "KETHER_SHUTDOWN_GRACE_SECS";
     pub const L4D2CENTER_INDEX_URL: &str = "KETHER_L4D2CENTER_INDEX_URL";
     pub const MAP_UPDATE_CHECK_INTERVAL_DAYS: &str = "KETHER_MAP_UPDATE_CHECK_INTERVAL_DAYS";
     pub const WORKSHOP_UPDATE_CHECK_ENABLED: &str = "KETHER_WORKSHOP_UPDATE_CHECK_ENABLED";
     pub const WORKSHOP_UPDATE_AUTO_APPLY: &str = "KETHER_WORKSHOP_UPDATE_AUTO_APPLY";
     pub const L4D2CENTER_UPDATE_CHECK_ENABLED: &str = "KETHER_L4D2CENTER_UPDATE_CHECK_ENABLED";
     pub const L4D2CENTER_UPDATE_AUTO_APPLY: &str = "KETHER_L4D2CENTER_UPDATE_AUTO_APPLY";
+    pub const DETECT_REQUIRE_CHECKSUM: &str = "KETHER_DETECT_REQUIRE_CHECKSUM";
+    pub const IMPORT_EXISTING_ON_START: &str = "KETHER_IMPORT_EXISTING_ON_START";
+    pub const DEFAULT_CHECKSUM_KIND: &str = "KETHER_DEFAULT_CHECKSUM_KIND";
+    pub const RECOMPUTE_CHECKSUMS_ON_START: &str = "KETHER_RECOMPUTE_CHECKSUMS_ON_START";
+    pub const REQUIRE_ADDONINFO: &str = "KETHER_REQUIRE_ADDONINFO";
+    pub const REQUIRE_SIGNED_VPK: &str = "KETHER_REQUIRE_SIGNED_VPK";
+    pub const VPK_SIGNING_PUBLIC_KEY: &str = "KETHER_VPK_SIGNING_PUBLIC_KEY";
+    pub const ALLOWED_DOWNLOAD_HOSTS: &str = "KETHER_ALLOWED_DOWNLOAD_HOSTS";
+    pub const ARCHIVE_EXTENSION_ALLOWLIST: &str = "KETHER_ARCHIVE_EXTENSION_ALLOWLIST";
+    pub const ARCHIVE_EXTENSION_DENYLIST: &str = "KETHER_ARCHIVE_EXTENSION_DENYLIST";
+    pub const LOCAL_INSTALL_DIRS: &str = "KETHER_LOCAL_INSTALL_DIRS";
+    pub const BACKEND_SYNC_GZIP: &str = "KETHER_BACKEND_SYNC_GZIP";
+    pub const MAX_SYNC_RESPONSE_BYTES: &str = "KETHER_MAX_SYNC_RESPONSE_BYTES";
+    pub const HTTP_PROXY: &str = "KETHER_HTTP_PROXY";
+    pub const HTTPS_PROXY: &str = "KETHER_HTTPS_PROXY";
+    pub const HTTP_USER_AGENT: &str = "KETHER_HTTP_USER_AGENT";
+    pub const POST_INSTALL_COMMAND: &str = "KETHER_POST_INSTALL_COMMAND";
+    pub const POST_UNINSTALL_COMMAND: &str = "KETHER_POST_UNINSTALL_COMMAND";
+    pub const EVENT_WEBHOOK_URL: &str = "KETHER_EVENT_WEBHOOK_URL";
+    pub const INSTALL_FILENAME_TEMPLATE: &str = "KETHER_INSTALL_FILENAME_TEMPLATE";
+    pub const INSTALLED_FILE_MODE: &str = "KETHER_INSTALLED_FILE_MODE";
+    pub const INSTALLED_FILE_OWNER: &str = "KETHER_INSTALLED_FILE_OWNER";
+    pub const INSTALLED_FILE_GROUP: &str = "KETHER_INSTALLED_FILE_GROUP";
+    pub const QUARANTINE_DIR: &str = "KETHER_QUARANTINE_DIR";
+    pub const QUARANTINE_MAX_BYTES: &str = "KETHER_QUARANTINE_MAX_BYTES";
 }
 
 /// Apply environment variable overrides on top of file/default configuration.
@@ -30,6 +75,15 @@ pub fn apply_env_overrides(config: &mut Config) -> anyhow::Result<()> {
     if let Ok(val) = std::env::var(keys::L4D2_SERVER_DIR) {
         config.l4d2_server_dir = PathBuf::from(val);
     }
+    if let Ok(val) = std::env::var(keys::SERVER_DIRS) {
+        config.server_dirs = parse_list_env(&val).into_iter().map(PathBuf::from).collect();
+    }
+    if let Ok(val) = std::env::var(keys::READ_ONLY) {
+        config.read_only = parse_bool_env(&val)?;
+    }
+    if let Ok(val) = std::env::var(keys::ADDONS_SUBPATH) {
+        config.addons_subpath = PathBuf::from(val);
+    }
     if let Ok(val) = std::env::var(keys::REGISTRY_PATH) {
         config.registry_path = PathBuf::from(val);
     }
@@ -42,6 +96,12 @@ pub fn apply_env_overrides(config: &mut Config) -> anyhow::Result<()> {
     if let Ok(val) = std::env::var(keys::LOCAL_API_BIND) {
         config.local_api_bind = SocketAddr::from_str(&val)?;
     }
+    if let Ok(val) = std::env::var(keys::TLS_CERT_PATH) {
+        config.tls_cert_path = Some(PathBuf::from(val));
+    }
+    if let Ok(val) = std::env::var(keys::TLS_KEY_PATH) {
+        config.tls_key_path = Some(PathBuf::from(val));
+    }
     if let Ok(val) = std::env::var(keys::SYNC_INTERVAL_SECS) {
         config.sync_interval_secs = val.parse()?;
     }
@@ -57,6 +117,48 @@ pub fn apply_env_overrides(config: &mut Config) -> anyhow::Result<()> {
     if let Ok(val) = std::env::var(keys::MAX_EXTRACTION_FILE_COUNT) {
         config.max_extraction_file_count = val.parse()?;
     }
+    if let Ok(val) = std::env::var(keys::MAX_VPKS_PER_ARCHIVE) {
+        config.max_vpks_per_archive = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::MAX_CONCURRENT_DOWNLOADS) {
+        config.max_concurrent_downloads = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::MAX_DOWNLOAD_BYTES_PER_SEC) {
+        config.max_download_bytes_per_sec = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::INSTALL_TIMEOUT_SECS) {
+        config.install_timeout_secs = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::HTTP_CONNECT_TIMEOUT_SECS) {
+        config.http_connect_timeout_secs = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::HTTP_READ_TIMEOUT_SECS) {
+        config.http_read_timeout_secs = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::STEAM_BREAKER_FAILURE_THRESHOLD) {
+        config.steam_breaker_failure_threshold = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::STEAM_BREAKER_COOLDOWN_SECS) {
+        config.steam_breaker_cooldown_secs = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::WORKSHOP_COLLECTION_MAX_DEPTH) {
+        config.workshop_collection_max_depth = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::DOWNLOAD_TEMP_DIR) {
+        config.download_temp_dir = Some(PathBuf::from(val));
+    }
+    if let Ok(val) = std::env::var(keys::TEMP_CLEANUP_INTERVAL_SECS) {
+        config.temp_cleanup_interval_secs = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::TEMP_CLEANUP_MAX_AGE_SECS) {
+        config.temp_cleanup_max_age_secs = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::INTEGRITY_SCAN_INTERVAL_SECS) {
+        config.integrity_scan_interval_secs = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::SHUTDOWN_GRACE_SECS) {
+        config.shutdown_grace_secs = val.parse()?;
+    }
     if let Ok(val) = std::env::var(keys::L4D2CENTER_INDEX_URL) {
         config.l4d2center_index_url = val;
     }
@@ -75,11 +177,94 @@ pub fn apply_env_overrides(config: &mut Config) -> anyhow::Result<()> {
     if let Ok(val) = std::env::var(keys::L4D2CENTER_UPDATE_AUTO_APPLY) {
         config.l4d2center_update_auto_apply = parse_bool_env(&val)?;
     }
+    if let Ok(val) = std::env::var(keys::DETECT_REQUIRE_CHECKSUM) {
+        config.detect_require_checksum = parse_bool_env(&val)?;
+    }
+    if let Ok(val) = std::env::var(keys::IMPORT_EXISTING_ON_START) {
+        config.import_existing_on_start = parse_bool_env(&val)?;
+    }
+    if let Ok(val) = std::env::var(keys::DEFAULT_CHECKSUM_KIND) {
+        config.default_checksum_kind = val;
+    }
+    if let Ok(val) = std::env::var(keys::RECOMPUTE_CHECKSUMS_ON_START) {
+        config.recompute_checksums_on_start = parse_bool_env(&val)?;
+    }
+    if let Ok(val) = std::env::var(keys::REQUIRE_ADDONINFO) {
+        config.require_addoninfo = parse_bool_env(&val)?;
+    }
+    if let Ok(val) = std::env::var(keys::REQUIRE_SIGNED_VPK) {
+        config.require_signed_vpk = parse_bool_env(&val)?;
+    }
+    if let Ok(val) = std::env::var(keys::VPK_SIGNING_PUBLIC_KEY) {
+        config.vpk_signing_public_key = Some(PathBuf::from(val));
+    }
+    if let Ok(val) = std::env::var(keys::ALLOWED_DOWNLOAD_HOSTS) {
+        config.allowed_download_hosts = parse_list_env(&val);
+    }
+    if let Ok(val) = std::env::var(keys::ARCHIVE_EXTENSION_ALLOWLIST) {
+        config.archive_extension_allowlist = parse_list_env(&val);
+    }
+    if let Ok(val) = std::env::var(keys::ARCHIVE_EXTENSION_DENYLIST) {
+        config.archive_extension_denylist = parse_list_env(&val);
+    }
+    if let Ok(val) = std::env::var(keys::LOCAL_INSTALL_DIRS) {
+        config.local_install_dirs = parse_list_env(&val).into_iter().map(PathBuf::from).collect();
+    }
+    if let Ok(val) = std::env::var(keys::BACKEND_SYNC_GZIP) {
+        config.backend_sync_gzip = parse_bool_env(&val)?;
+    }
+    if let Ok(val) = std::env::var(keys::MAX_SYNC_RESPONSE_BYTES) {
+        config.max_sync_response_bytes = val.parse()?;
+    }
+    if let Ok(val) = std::env::var(keys::HTTP_PROXY) {
+        config.http_proxy = Some(val);
+    }
+    if let Ok(val) = std::env::var(keys::HTTPS_PROXY) {
+        config.https_proxy = Some(val);
+    }
+    if let Ok(val) = std::env::var(keys::HTTP_USER_AGENT) {
+        config.http_user_agent = Some(val);
+    }
+    if let Ok(val) = std::env::var(keys::POST_INSTALL_COMMAND) {
+        config.post_install_command = Some(PathBuf::from(val));
+    }
+    if let Ok(val) = std::env::var(keys::POST_UNINSTALL_COMMAND) {
+        config.post_uninstall_command = Some(PathBuf::from(val));
+    }
+    if let Ok(val) = std::env::var(keys::EVENT_WEBHOOK_URL) {
+        config.event_webhook_url = Some(val);
+    }
+    if let Ok(val) = std::env::var(keys::INSTALL_FILENAME_TEMPLATE) {
+        config.install_filename_template = Some(val);
+    }
+    if let Ok(val) = std::env::var(keys::INSTALLED_FILE_MODE) {
+        config.installed_file_mode = Some(val);
+    }
+    if let Ok(val) = std::env::var(keys::INSTALLED_FILE_OWNER) {
+        config.installed_file_owner = Some(val);
+    }
+    if let Ok(val) = std::env::var(keys::INSTALLED_FILE_GROUP) {
+        config.installed_file_group = Some(val);
+    }
+    if let Ok(val) = std::env::var(keys::QUARANTINE_DIR) {
+        config.quarantine_dir = Some(PathBuf::from(val));
+    }
+    if let Ok(val) = std::env::var(keys::QUARANTINE_MAX_BYTES) {
+        config.quarantine_max_bytes = val.parse()?;
+    }
 
     Ok(())
 }
 
-fn parse_bool_env(val: &str) -> anyhow::Result<bool> {
+fn parse_list_env(val: &str) -> Vec<String> {
+    val.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+pub(super) fn parse_bool_env(val: &str) -> anyhow::Result<bool> {
     match val.trim().to_lowercase().as_str() {
         "1" | "true" | "yes" | "on" => Ok(true),
         "0" | "false" | "no" | "off" => Ok(false),
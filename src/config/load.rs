@@ -6,6 +6,22 @@ use crate::config::model::Config;
 
 pub const CONF_FILE_NAME: &str = "config.toml";
 
+/// Environment variables a strict, file-less start cannot do without: the defaults
+/// (a placeholder server path and a localhost backend) would only mask a misconfiguration.
+const STRICT_REQUIRED_ENV: &[&str] = &[keys::L4D2_SERVER_DIR, keys::BACKEND_API_URL];
+
+/// `--strict-config` (`cli_strict`) or `KETHER_STRICT_CONFIG`.
+fn strict_config_enabled(cli_strict: bool) -> anyhow::Result<bool> {
+    if cli_strict {
+        return Ok(true);
+    }
+    match std::env::var(keys::STRICT_CONFIG) {
+        Ok(val) => env::parse_bool_env(&val)
+            .map_err(|e| anyhow::anyhow!("Invalid {}: {}", keys::STRICT_CONFIG, e)),
+        Err(_) => Ok(false),
+    }
+}
+
 impl Config {
     /// Load configuration from TOML file with environment variable overrides
     pub fn load() -> anyhow::Result<Self> {
@@ -13,18 +29,34 @@ impl Config {
         Ok(config)
     }
 
-    /// Load configuration and return the resolved config file path for hot reload.
+    /// Load configuration and return the primary config file path for hot reload.
+    ///
+    /// `KETHER_CONFIG` may list several comma-separated files; later files override
+    /// earlier ones and environment variables are applied last.
     pub fn load_with_path() -> anyhow::Result<(Self, PathBuf)> {
-        let config_path = resolve_config_path();
-        let mut config = load_from_path(&config_path)?;
+        Self::load_with_paths(&resolve_config_paths(None), false)
+    }
+
+    /// Like `load_with_path`, for an explicit primary file followed by override layers.
+    ///
+    /// With `strict` (`--strict-config`, or `KETHER_STRICT_CONFIG`) a missing primary
+    /// file is not created from the default template; the config then comes from
+    /// defaults and environment variables alone.
+    pub fn load_with_paths(config_paths: &[PathBuf], strict: bool) -> anyhow::Result<(Self, PathBuf)> {
+        let primary = &config_paths[0];
+        if !primary.exists() && strict_config_enabled(strict)? {
+            let config = load_env_only(primary, &config_paths[1..])?;
+            return Ok((config, primary.clone()));
+        }
+        let mut config = load_layers(primary, &config_paths[1..])?;
         env::apply_env_overrides(&mut config)?;
-        let watched_path = canonicalize_config_path(&config_path);
+        let watched_path = canonicalize_config_path(primary);
         Ok((config, watched_path))
     }
 
-    /// Parse configuration from a TOML string (used by hot reload).
-    pub fn from_toml_str(content: &str) -> anyhow::Result<Self> {
-        Ok(toml::from_str(content)?)
+    /// Reload `config_paths` (primary first, then override layers) without env overrides.
+    pub fn reload_from(config_paths: &[PathBuf]) -> anyhow::Result<Self> {
+        load_layers(&config_paths[0], &config_paths[1..])
     }
 
     /// Load configuration from a specific path without environment overrides.
@@ -33,16 +65,104 @@ impl Config {
     }
 }
 
-fn resolve_config_path() -> PathBuf {
-    std::env::var(keys::CONFIG)
+/// Config files to load, primary first: the comma-separated `--config` value
+/// (`cli_paths`), then `KETHER_CONFIG`, then `config.toml`.
+pub fn resolve_config_paths(cli_paths: Option<&str>) -> Vec<PathBuf> {
+    let paths = match cli_paths.map(split_config_paths) {
+        Some(paths) if !paths.is_empty() => paths,
+        _ => split_config_paths(&std::env::var(keys::CONFIG).unwrap_or_default()),
+    };
+    if paths.is_empty() {
+        vec![PathBuf::from(CONF_FILE_NAME)]
+    } else {
+        paths
+    }
+}
+
+fn split_config_paths(value: &str) -> Vec<PathBuf> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
         .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from(CONF_FILE_NAME))
+        .collect()
 }
 
 fn canonicalize_config_path(path: &Path) -> PathBuf {
     std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
 }
 
+/// Strict mode without a config file: defaults plus environment overrides, provided
+/// every key in `STRICT_REQUIRED_ENV` is set. Nothing is written to disk.
+fn load_env_only(primary: &Path, overrides: &[PathBuf]) -> anyhow::Result<Config> {
+    if !overrides.is_empty() {
+        anyhow::bail!(
+            "Config file {} does not exist; override layers need a primary file (strict config mode)",
+            primary.display()
+        );
+    }
+    let missing: Vec<&str> = STRICT_REQUIRED_ENV
+        .iter()
+        .copied()
+        .filter(|key| std::env::var(key).map_or(true, |val| val.trim().is_empty()))
+        .collect();
+    if !missing.is_empty() {
+        anyhow::bail!(
+            "Config file {} does not exist and strict config mode requires: {}",
+            primary.display(),
+            missing.join(", ")
+        );
+    }
+    let mut config = Config::default();
+    env::apply_env_overrides(&mut config)?;
+    Ok(config)
+}
+
+/// Load the primary config (created if missing) and merge override layers on top.
+fn load_layers(primary: &Path, overrides: &[PathBuf]) -> anyhow::Result<Config> {
+    if overrides.is_empty() {
+        return load_from_path(primary);
+    }
+
+    ensure_config_file(primary)?;
+    let mut merged = read_table(primary)?;
+    for path in overrides {
+        if !path.exists() {
+            println!("Skipping missing config layer: {}", path.display());
+            continue;
+        }
+        merge_tables(&mut merged, read_table(path)?);
+    }
+    Ok(toml::Value::Table(merged).try_into()?)
+}
+
+fn read_table(path: &Path) -> anyhow::Result<toml::Table> {
+    let contents = std::fs::read_to_string(path)?;
+    toml::from_str(&contents)
+        .map_err(|e| anyhow::anyhow!("Failed to parse config layer {}: {}", path.display(), e))
+}
+
+/// Recursively merge `overlay` into `base`; nested tables merge, other values replace.
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn ensure_config_file(path: &Path) -> anyhow::Result<()> {
+    if !path.exists() {
+        load_from_path(path)?;
+    }
+    Ok(())
+}
+
 fn load_from_path(path: &Path) -> anyhow::Result<Config> {
     if path.exists() {
         let contents = std::fs::read_to_string(path)?;
@@ -14,5 +14,5 @@ mod tests;
 pub use change::ConfigChange;
 pub use env::apply_env_overrides;
 pub use handle::{init_handle, read_config, ConfigHandle};
-pub use load::CONF_FILE_NAME;
+pub use load::{resolve_config_paths, CONF_FILE_NAME};
 pub use model::Config;
@@ -9,6 +9,20 @@ pub struct Config {
     /// Base Left 4 Dead 2 server directory
     pub l4d2_server_dir: PathBuf,
 
+    /// Addons directory relative to `l4d2_server_dir` (default `left4dead2/addons`)
+    #[serde(default = "default_addons_subpath")]
+    pub addons_subpath: PathBuf,
+
+    /// Further server instances on this host; every installed VPK is also copied into
+    /// `<dir>/<addons_subpath>` of each, under one registry entry
+    #[serde(default)]
+    pub server_dirs: Vec<PathBuf>,
+
+    /// Never modify the addons directories: installs, uninstalls and updates are refused
+    /// by the API and skipped by sync and update checks; listing and registry push still work
+    #[serde(default = "default_false")]
+    pub read_only: bool,
+
     /// JSON registry file path
     pub registry_path: PathBuf,
 
@@ -22,6 +36,14 @@ pub struct Config {
     /// Local API bind address (e.g., "127.0.0.1:8080")
     pub local_api_bind: SocketAddr,
 
+    /// PEM certificate chain for serving the local API over HTTPS (with `tls_key_path`)
+    #[serde(default)]
+    pub tls_cert_path: Option<PathBuf>,
+
+    /// PEM private key for `tls_cert_path`
+    #[serde(default)]
+    pub tls_key_path: Option<PathBuf>,
+
     /// Backend sync interval in seconds
     pub sync_interval_secs: u64,
 
@@ -40,6 +62,63 @@ pub struct Config {
     #[serde(default = "default_max_extraction_file_count")]
     pub max_extraction_file_count: u64,
 
+    /// Most VPK files a single ZIP/7z archive may hold; more rejects it before
+    /// extraction. 0 means no limit (default: 0)
+    #[serde(default = "default_max_vpks_per_archive")]
+    pub max_vpks_per_archive: u64,
+
+    /// Maximum downloads/Steam jobs in flight across API, sync and update checks (default: 2)
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: usize,
+
+    /// Throttle each download to this many bytes per second; 0 means unlimited
+    #[serde(default)]
+    pub max_download_bytes_per_sec: u64,
+
+    /// Abort a single install (download + extract + register) after this many seconds
+    #[serde(default = "default_install_timeout_secs")]
+    pub install_timeout_secs: u64,
+
+    /// Give up on establishing an outbound HTTP connection after this many seconds
+    #[serde(default = "default_http_connect_timeout_secs")]
+    pub http_connect_timeout_secs: u64,
+
+    /// Abort a download when no bytes arrive for this many seconds
+    #[serde(default = "default_http_read_timeout_secs")]
+    pub http_read_timeout_secs: u64,
+
+    /// Failed Steam connection attempts in a row before Steam work is refused; 0 disables the breaker
+    #[serde(default = "default_steam_breaker_failure_threshold")]
+    pub steam_breaker_failure_threshold: u32,
+
+    /// Seconds Steam work is refused once the breaker has opened
+    #[serde(default = "default_steam_breaker_cooldown_secs")]
+    pub steam_breaker_cooldown_secs: u64,
+
+    /// How many levels of nested Workshop collections are followed when expanding a collection
+    #[serde(default = "default_workshop_collection_max_depth")]
+    pub workshop_collection_max_depth: u32,
+
+    /// Base directory for download staging (default: system temp); `kether-downloads` is created inside
+    #[serde(default)]
+    pub download_temp_dir: Option<PathBuf>,
+
+    /// Seconds between sweeps of stale download temp files; 0 disables the sweep
+    #[serde(default = "default_temp_cleanup_interval_secs")]
+    pub temp_cleanup_interval_secs: u64,
+
+    /// Temp files older than this many seconds are treated as orphaned
+    #[serde(default = "default_temp_cleanup_max_age_secs")]
+    pub temp_cleanup_max_age_secs: u64,
+
+    /// Seconds between background re-hashes of installed maps; 0 disables the scan
+    #[serde(default)]
+    pub integrity_scan_interval_secs: u64,
+
+    /// Longest wait for background tasks to stop on shutdown before exiting anyway
+    #[serde(default = "default_shutdown_grace_secs")]
+    pub shutdown_grace_secs: u64,
+
     /// L4D2Center server map catalog index URL
     #[serde(default = "default_l4d2center_index_url")]
     pub l4d2center_index_url: String,
@@ -52,6 +131,14 @@ pub struct Config {
     #[serde(default)]
     pub hidden_map_ids: Vec<u64>,
 
+    /// Steam Workshop addon IDs that are never installed, even when the backend asks
+    #[serde(default)]
+    pub blocked_workshop_ids: Vec<u64>,
+
+    /// URL patterns (`*` wildcard, whole URL, case-insensitive) that are never downloaded
+    #[serde(default)]
+    pub blocked_url_patterns: Vec<String>,
+
     /// Shared interval (days) between periodic workshop / L4D2Center update checks
     #[serde(default = "default_map_update_check_interval_days")]
     pub map_update_check_interval_days: u64,
@@ -71,6 +158,110 @@ pub struct Config {
     /// Automatically download and apply L4D2Center updates found by periodic checks
     #[serde(default = "default_false")]
     pub l4d2center_update_auto_apply: bool,
+
+    /// Skip registering detected maps whose checksum cannot be computed
+    #[serde(default = "default_false")]
+    pub detect_require_checksum: bool,
+
+    /// Register untracked VPKs already in the addons directory before the watcher starts
+    #[serde(default = "default_false")]
+    pub import_existing_on_start: bool,
+
+    /// Checksum algorithm recorded for new installs: `md5` or `sha256`
+    #[serde(default = "default_checksum_kind")]
+    pub default_checksum_kind: String,
+
+    /// At startup, re-hash entries whose `checksum_kind` differs from `default_checksum_kind`
+    #[serde(default = "default_false")]
+    pub recompute_checksums_on_start: bool,
+
+    /// Reject VPKs without `addoninfo.txt`; when false they install under their filename
+    #[serde(default = "default_true")]
+    pub require_addoninfo: bool,
+
+    /// Reject VPKs that are unsigned or fail verification against `vpk_signing_public_key`
+    #[serde(default = "default_false")]
+    pub require_signed_vpk: bool,
+
+    /// RSA public key (PEM or DER file) that VPK v2 signature sections are checked against
+    #[serde(default)]
+    pub vpk_signing_public_key: Option<PathBuf>,
+
+    /// Hostnames allowed for URL downloads (exact or `*.example.com`); empty allows any
+    #[serde(default)]
+    pub allowed_download_hosts: Vec<String>,
+
+    /// When non-empty, archives may only contain files with these extensions
+    #[serde(default)]
+    pub archive_extension_allowlist: Vec<String>,
+
+    /// File extensions that get a whole archive rejected before extraction (e.g. `sh`, `exe`)
+    #[serde(default)]
+    pub archive_extension_denylist: Vec<String>,
+
+    /// Host directories `local_path` installs may read from; empty disables local installs
+    #[serde(default)]
+    pub local_install_dirs: Vec<PathBuf>,
+
+    /// Gzip backend sync request bodies and accept gzip-encoded responses
+    #[serde(default = "default_false")]
+    pub backend_sync_gzip: bool,
+
+    /// Largest backend sync response accepted, in bytes (after gzip decoding)
+    #[serde(default = "default_max_sync_response_bytes")]
+    pub max_sync_response_bytes: u64,
+
+    /// Proxy for plain-HTTP downloads and backend sync (falls back to `HTTP_PROXY`)
+    #[serde(default)]
+    pub http_proxy: Option<String>,
+
+    /// Proxy for HTTPS downloads and backend sync (falls back to `HTTPS_PROXY`)
+    #[serde(default)]
+    pub https_proxy: Option<String>,
+
+    /// `User-Agent` for downloads and backend sync (default `KetherServerDaemon/<version>`)
+    #[serde(default)]
+    pub http_user_agent: Option<String>,
+
+    /// Executable run (without a shell) after a map is installed; gets `KETHER_MAP_*` env vars
+    #[serde(default)]
+    pub post_install_command: Option<PathBuf>,
+
+    /// Executable run (without a shell) after a map is uninstalled; gets `KETHER_MAP_*` env vars
+    #[serde(default)]
+    pub post_uninstall_command: Option<PathBuf>,
+
+    /// URL that install/uninstall/update events are POSTed to as JSON (fire-and-forget)
+    #[serde(default)]
+    pub event_webhook_url: Option<String>,
+
+    /// Name installed VPKs get, e.g. `{map_id}_{original}`; unset keeps the VPK's own name
+    #[serde(default)]
+    pub install_filename_template: Option<String>,
+
+    /// Octal mode (e.g. `"0644"`) set on VPKs installed into the addons directory
+    #[serde(default)]
+    pub installed_file_mode: Option<String>,
+
+    /// User (name or UID) that installed VPKs are chowned to
+    #[serde(default)]
+    pub installed_file_owner: Option<String>,
+
+    /// Group (name or GID) that installed VPKs are chowned to
+    #[serde(default)]
+    pub installed_file_group: Option<String>,
+
+    /// Keep rejected downloads here (with a `.reason` file) instead of deleting them
+    #[serde(default)]
+    pub quarantine_dir: Option<PathBuf>,
+
+    /// Oldest quarantined files are removed once the directory exceeds this many bytes
+    #[serde(default = "default_quarantine_max_bytes")]
+    pub quarantine_max_bytes: u64,
+}
+
+fn default_addons_subpath() -> PathBuf {
+    PathBuf::from("left4dead2").join("addons")
 }
 
 fn default_max_download_size() -> u64 {
@@ -85,10 +276,66 @@ fn default_max_extraction_file_count() -> u64 {
     10000
 }
 
+fn default_max_vpks_per_archive() -> u64 {
+    0
+}
+
+fn default_max_sync_response_bytes() -> u64 {
+    16 * 1024 * 1024 // 16MB
+}
+
+fn default_max_concurrent_downloads() -> usize {
+    2
+}
+
+fn default_install_timeout_secs() -> u64 {
+    30 * 60
+}
+
+fn default_http_connect_timeout_secs() -> u64 {
+    10
+}
+
+fn default_http_read_timeout_secs() -> u64 {
+    120
+}
+
+fn default_steam_breaker_failure_threshold() -> u32 {
+    5
+}
+
+fn default_steam_breaker_cooldown_secs() -> u64 {
+    5 * 60
+}
+
+fn default_workshop_collection_max_depth() -> u32 {
+    3
+}
+
+fn default_quarantine_max_bytes() -> u64 {
+    1024 * 1024 * 1024 // 1GB
+}
+
+fn default_temp_cleanup_interval_secs() -> u64 {
+    60 * 60
+}
+
+fn default_temp_cleanup_max_age_secs() -> u64 {
+    24 * 60 * 60
+}
+
+fn default_shutdown_grace_secs() -> u64 {
+    2
+}
+
 fn default_l4d2center_index_url() -> String {
     "https://l4d2center.com/maps/servers/index.json".to_string()
 }
 
+fn default_checksum_kind() -> String {
+    "md5".to_string()
+}
+
 fn default_map_update_check_interval_days() -> u64 {
     3
 }
@@ -105,23 +352,69 @@ impl Default for Config {
     fn default() -> Self {
         Self {
             l4d2_server_dir: PathBuf::from("/home/steam/l4d2"),
+            addons_subpath: default_addons_subpath(),
+            server_dirs: Vec::new(),
+            read_only: false,
             registry_path: PathBuf::from("registry.json"),
             backend_api_url: String::from("http://127.0.0.1:3001/api"),
             backend_api_key: None,
             local_api_bind: SocketAddr::from_str("127.0.0.1:8080").unwrap(),
+            tls_cert_path: None,
+            tls_key_path: None,
             sync_interval_secs: 300, // 5 minutes
             log_level: String::from("info"),
             max_download_size_bytes: default_max_download_size(),
             max_extraction_size_bytes: default_max_extraction_size(),
             max_extraction_file_count: default_max_extraction_file_count(),
+            max_vpks_per_archive: default_max_vpks_per_archive(),
+            max_concurrent_downloads: default_max_concurrent_downloads(),
+            max_download_bytes_per_sec: 0,
+            install_timeout_secs: default_install_timeout_secs(),
+            http_connect_timeout_secs: default_http_connect_timeout_secs(),
+            http_read_timeout_secs: default_http_read_timeout_secs(),
+            steam_breaker_failure_threshold: default_steam_breaker_failure_threshold(),
+            steam_breaker_cooldown_secs: default_steam_breaker_cooldown_secs(),
+            workshop_collection_max_depth: default_workshop_collection_max_depth(),
+            download_temp_dir: None,
+            temp_cleanup_interval_secs: default_temp_cleanup_interval_secs(),
+            temp_cleanup_max_age_secs: default_temp_cleanup_max_age_secs(),
+            integrity_scan_interval_secs: 0,
+            shutdown_grace_secs: default_shutdown_grace_secs(),
             l4d2center_index_url: default_l4d2center_index_url(),
             hidden_workshop_ids: Vec::new(),
             hidden_map_ids: Vec::new(),
+            blocked_workshop_ids: Vec::new(),
+            blocked_url_patterns: Vec::new(),
             map_update_check_interval_days: default_map_update_check_interval_days(),
             workshop_update_check_enabled: true,
             workshop_update_auto_apply: true,
             l4d2center_update_check_enabled: true,
             l4d2center_update_auto_apply: false,
+            detect_require_checksum: false,
+            import_existing_on_start: false,
+            default_checksum_kind: default_checksum_kind(),
+            recompute_checksums_on_start: false,
+            require_addoninfo: true,
+            require_signed_vpk: false,
+            vpk_signing_public_key: None,
+            allowed_download_hosts: Vec::new(),
+            archive_extension_allowlist: Vec::new(),
+            archive_extension_denylist: Vec::new(),
+            local_install_dirs: Vec::new(),
+            backend_sync_gzip: false,
+            max_sync_response_bytes: default_max_sync_response_bytes(),
+            http_proxy: None,
+            https_proxy: None,
+            http_user_agent: None,
+            post_install_command: None,
+            post_uninstall_command: None,
+            event_webhook_url: None,
+            install_filename_template: None,
+            installed_file_mode: None,
+            installed_file_owner: None,
+            installed_file_group: None,
+            quarantine_dir: None,
+            quarantine_max_bytes: default_quarantine_max_bytes(),
         }
     }
 }
@@ -136,15 +429,31 @@ impl Config {
 # Saving this file reloads live fields without restart (denylist, sync interval, backend URL, etc.).
 # Restart is required for paths, bind address, download limits, and log level.
 
-# Base Left 4 Dead 2 server directory (addons at {{dir}}/left4dead2/addons)
+# Base Left 4 Dead 2 server directory (addons at {{dir}}/<addons_subpath>)
 l4d2_server_dir = "{}"
 
+# Addons directory relative to l4d2_server_dir; must stay inside it
+addons_subpath = "{}"
+
+# Further L4D2 server directories on this host; installed VPKs are copied into
+# <dir>/<addons_subpath> of each (the daemon only watches l4d2_server_dir)
+server_dirs = []
+
+# Observe and push the registry only: refuse installs, uninstalls and updates
+read_only = false
+
 # JSON map registry file path
 registry_path = "{}"
 
 # Website-server registry sync API (port 3001)
 backend_api_url = "{}"
 
+# Gzip registry sync uploads and accept gzip responses (backend must support it)
+backend_sync_gzip = {}
+
+# Reject backend sync responses larger than this many bytes (after gzip decoding)
+max_sync_response_bytes = {}
+
 # Shared bearer token for backend sync and inbound API requests
 # (must match website-server [server_daemon].sync_api_key)
 # backend_api_key = "your-shared-secret"
@@ -152,6 +461,11 @@ backend_api_url = "{}"
 # Local HTTP API bind address. A non-loopback address requires backend_api_key.
 local_api_bind = "{}"
 
+# Serve the local API over HTTPS with this PEM certificate chain and private key.
+# Both must be set together; when unset the API is served over plain HTTP.
+# tls_cert_path = "/etc/kether/api.crt"
+# tls_key_path = "/etc/kether/api.key"
+
 # Backend sync interval in seconds
 sync_interval_secs = {}
 
@@ -167,6 +481,52 @@ max_extraction_size_bytes = {}
 # Maximum number of files extracted from a single archive
 max_extraction_file_count = {}
 
+# Maximum number of VPK files in a single ZIP/7z archive (0 = no limit)
+max_vpks_per_archive = {}
+
+# Maximum concurrent downloads shared by API installs, sync and update checks
+max_concurrent_downloads = {}
+
+# Throttle each download to this many bytes per second so one large map cannot
+# saturate a shared uplink. 0 means unlimited.
+max_download_bytes_per_sec = {}
+
+# Abort an install (download, extraction and registration) after this many seconds
+install_timeout_secs = {}
+
+# Outbound HTTP timeouts: connection setup, and the longest stall between received
+# bytes before a download attempt is abandoned and retried
+http_connect_timeout_secs = {}
+http_read_timeout_secs = {}
+
+# Stop reconnecting to Steam after this many failed connection attempts in a row
+# (e.g. during Steam maintenance); workshop installs then fail fast until the
+# cooldown has passed. Set the threshold to 0 to always reconnect.
+steam_breaker_failure_threshold = {}
+steam_breaker_cooldown_secs = {}
+
+# Workshop collections may contain other collections; follow at most this many
+# levels when expanding one into its maps. Collections that contain each other
+# are only visited once.
+workshop_collection_max_depth = {}
+
+# Base directory for download staging; "kether-downloads" is created inside and
+# cleared on startup. Defaults to the system temp dir, which may be a small tmpfs.
+# download_temp_dir = "/srv/kether/tmp"
+
+# Periodically remove temp files older than temp_cleanup_max_age_secs (left behind
+# by crashed or killed installs). Set the interval to 0 to disable the sweep.
+temp_cleanup_interval_secs = {}
+temp_cleanup_max_age_secs = {}
+
+# Re-hash every installed map this often and log files that are missing or no longer
+# match their recorded checksum. Reads every VPK, so 0 (disabled) is the default.
+integrity_scan_interval_secs = {}
+
+# On shutdown, wait at most this long for background tasks to stop; shutdown
+# finishes as soon as they have, so this only bounds a slow unwind.
+shutdown_grace_secs = {}
+
 # L4D2Center server map catalog index URL
 l4d2center_index_url = "{}"
 
@@ -174,6 +534,11 @@ l4d2center_index_url = "{}"
 hidden_workshop_ids = []
 hidden_map_ids = []
 
+# Maps that are never installed, whether requested via the API, sync or the REPL.
+# URL patterns match the whole URL; "*" matches any run of characters.
+blocked_workshop_ids = []
+blocked_url_patterns = []   # e.g. ["https://bad-mirror.example.com/*"]
+
 # Periodic map update checks (first check runs after one full interval from startup)
 # Shared cadence in days for workshop and L4D2Center checks
 map_update_check_interval_days = {}
@@ -185,22 +550,124 @@ workshop_update_auto_apply = {}
 # L4D2Center: check catalog MD5; auto-apply is off by default
 l4d2center_update_check_enabled = {}
 l4d2center_update_auto_apply = {}
+
+# Detected maps (watcher/discovery): skip registration when the checksum
+# cannot be computed instead of registering without a checksum
+detect_require_checksum = {}
+
+# On startup, register VPKs already in the addons directory that the registry does
+# not track yet (useful on first run against a server that already has maps)
+import_existing_on_start = {}
+
+# Checksum algorithm for new installs: "md5" or "sha256". L4D2Center maps always
+# use MD5, which is what the catalog publishes.
+default_checksum_kind = "{}"
+# On startup, re-hash maps recorded with another algorithm (after verifying the
+# old checksum) so the whole registry uses default_checksum_kind
+recompute_checksums_on_start = {}
+
+# Reject VPKs without addoninfo.txt. Set to false to accept structurally valid
+# content packs, which are then named after their filename.
+require_addoninfo = {}
+
+# Check the signature section of VPK v2 files against this RSA public key (PEM or
+# DER). A failed check is logged; with require_signed_vpk = true the VPK is rejected,
+# as are unsigned VPKs (including every v1 VPK).
+# vpk_signing_public_key = "/srv/kether/vpk-signing.pem"
+require_signed_vpk = {}
+
+# Restrict URL downloads to these hosts (exact or "*.example.com" for subdomains).
+# Empty allows any public host; private/loopback addresses are always rejected.
+allowed_download_hosts = []
+
+# Reject ZIP/7z archives holding files with a denied extension, or (when the
+# allowlist is non-empty) any file whose extension is not listed, before extracting.
+# Extensionless files only pass an allowlist containing "".
+archive_extension_allowlist = []
+archive_extension_denylist = []
+
+# Directories operators may install maps from by local path (POST /api/maps/install
+# with "local_path"). Empty disables local-path installs.
+local_install_dirs = []
+
+# Outbound proxies for map downloads (including Steam CDN) and backend sync.
+# When unset, the standard HTTP_PROXY / HTTPS_PROXY / NO_PROXY env vars are used.
+# http_proxy = "http://proxy.example:3128"
+# https_proxy = "http://proxy.example:3128"
+
+# User-Agent for map downloads and backend sync (default "KetherServerDaemon/<version>").
+# http_user_agent = "KetherServerDaemon/custom"
+
+# Executables run after a map is installed / uninstalled (e.g. to reload the map
+# cycle or notify a chat channel). They are started directly, not through a shell,
+# with only KETHER_MAP_ID, KETHER_MAP_NAME and KETHER_MAP_PATH in their environment,
+# and killed after 5 minutes. A failing hook is logged and does not undo the install.
+# Paths must be absolute.
+# post_install_command = "/srv/kether/hooks/map-installed.sh"
+# post_uninstall_command = "/srv/kether/hooks/map-uninstalled.sh"
+
+# URL that receives a POST with {{"event", "map_id", "name", "timestamp"}} after each
+# install, uninstall and update ("event" is "install", "uninstall" or "update").
+# Delivered in the background with a 5s timeout and up to 3 attempts; the URL must
+# pass the same checks as download URLs (no localhost or private addresses).
+# event_webhook_url = "https://hooks.example.com/kether"
+
+# Filename for newly installed VPKs. Placeholders: {{map_id}}, {{original}} (the name
+# the daemon would otherwise pick, without .vpk) and {{sanitized_name}} (the map name).
+# ".vpk" is appended when missing. Unset keeps the default naming.
+# install_filename_template = "{{map_id}}_{{original}}"
+
+# Mode and ownership for VPKs placed in the addons directory, for game servers that
+# run as a different user. Unset leaves the daemon's defaults; changing the owner
+# usually requires running the daemon as root.
+# installed_file_mode = "0644"
+# installed_file_owner = "steam"
+# installed_file_group = "steam"
+
+# Move downloads rejected by VPK validation or checksum verification here, each with a
+# "<file>.reason" note, instead of deleting them. The oldest files are removed once
+# the directory grows past quarantine_max_bytes.
+# quarantine_dir = "/srv/kether/quarantine"
+quarantine_max_bytes = {}
 "#,
             defaults.l4d2_server_dir.display(),
+            defaults.addons_subpath.display(),
             defaults.registry_path.display(),
             defaults.backend_api_url,
+            defaults.backend_sync_gzip,
+            defaults.max_sync_response_bytes,
             defaults.local_api_bind,
             defaults.sync_interval_secs,
             defaults.log_level,
             defaults.max_download_size_bytes,
             defaults.max_extraction_size_bytes,
             defaults.max_extraction_file_count,
+            defaults.max_vpks_per_archive,
+            defaults.max_concurrent_downloads,
+            defaults.max_download_bytes_per_sec,
+            defaults.install_timeout_secs,
+            defaults.http_connect_timeout_secs,
+            defaults.http_read_timeout_secs,
+            defaults.steam_breaker_failure_threshold,
+            defaults.steam_breaker_cooldown_secs,
+            defaults.workshop_collection_max_depth,
+            defaults.temp_cleanup_interval_secs,
+            defaults.temp_cleanup_max_age_secs,
+            defaults.integrity_scan_interval_secs,
+            defaults.shutdown_grace_secs,
             defaults.l4d2center_index_url,
             defaults.map_update_check_interval_days,
             defaults.workshop_update_check_enabled,
             defaults.workshop_update_auto_apply,
             defaults.l4d2center_update_check_enabled,
             defaults.l4d2center_update_auto_apply,
+            defaults.detect_require_checksum,
+            defaults.import_existing_on_start,
+            defaults.default_checksum_kind,
+            defaults.recompute_checksums_on_start,
+            defaults.require_addoninfo,
+            defaults.require_signed_vpk,
+            defaults.quarantine_max_bytes,
         )
     }
 }
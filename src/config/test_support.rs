@@ -18,22 +18,67 @@ pub fn remove_env_var(key: &str) {
 
 pub fn clear_kether_env_vars() {
     remove_env_var(keys::CONFIG);
+    remove_env_var(keys::STRICT_CONFIG);
     remove_env_var(keys::L4D2_SERVER_DIR);
+    remove_env_var(keys::SERVER_DIRS);
+    remove_env_var(keys::READ_ONLY);
+    remove_env_var(keys::ADDONS_SUBPATH);
     remove_env_var(keys::REGISTRY_PATH);
     remove_env_var(keys::BACKEND_API_URL);
     remove_env_var(keys::BACKEND_API_KEY);
     remove_env_var(keys::LOCAL_API_BIND);
+    remove_env_var(keys::TLS_CERT_PATH);
+    remove_env_var(keys::TLS_KEY_PATH);
     remove_env_var(keys::SYNC_INTERVAL_SECS);
     remove_env_var(keys::LOG_LEVEL);
     remove_env_var(keys::MAX_DOWNLOAD_SIZE_BYTES);
     remove_env_var(keys::MAX_EXTRACTION_SIZE_BYTES);
     remove_env_var(keys::MAX_EXTRACTION_FILE_COUNT);
+    remove_env_var(keys::MAX_VPKS_PER_ARCHIVE);
+    remove_env_var(keys::MAX_CONCURRENT_DOWNLOADS);
+    remove_env_var(keys::MAX_DOWNLOAD_BYTES_PER_SEC);
+    remove_env_var(keys::INSTALL_TIMEOUT_SECS);
+    remove_env_var(keys::HTTP_CONNECT_TIMEOUT_SECS);
+    remove_env_var(keys::HTTP_READ_TIMEOUT_SECS);
+    remove_env_var(keys::STEAM_BREAKER_FAILURE_THRESHOLD);
+    remove_env_var(keys::STEAM_BREAKER_COOLDOWN_SECS);
+    remove_env_var(keys::WORKSHOP_COLLECTION_MAX_DEPTH);
+    remove_env_var(keys::TEMP_CLEANUP_INTERVAL_SECS);
+    remove_env_var(keys::TEMP_CLEANUP_MAX_AGE_SECS);
+    remove_env_var(keys::INTEGRITY_SCAN_INTERVAL_SECS);
+    remove_env_var(keys::SHUTDOWN_GRACE_SECS);
+    remove_env_var(keys::DOWNLOAD_TEMP_DIR);
     remove_env_var(keys::L4D2CENTER_INDEX_URL);
     remove_env_var(keys::MAP_UPDATE_CHECK_INTERVAL_DAYS);
     remove_env_var(keys::WORKSHOP_UPDATE_CHECK_ENABLED);
     remove_env_var(keys::WORKSHOP_UPDATE_AUTO_APPLY);
     remove_env_var(keys::L4D2CENTER_UPDATE_CHECK_ENABLED);
     remove_env_var(keys::L4D2CENTER_UPDATE_AUTO_APPLY);
+    remove_env_var(keys::DETECT_REQUIRE_CHECKSUM);
+    remove_env_var(keys::IMPORT_EXISTING_ON_START);
+    remove_env_var(keys::DEFAULT_CHECKSUM_KIND);
+    remove_env_var(keys::RECOMPUTE_CHECKSUMS_ON_START);
+    remove_env_var(keys::REQUIRE_ADDONINFO);
+    remove_env_var(keys::REQUIRE_SIGNED_VPK);
+    remove_env_var(keys::VPK_SIGNING_PUBLIC_KEY);
+    remove_env_var(keys::ALLOWED_DOWNLOAD_HOSTS);
+    remove_env_var(keys::ARCHIVE_EXTENSION_ALLOWLIST);
+    remove_env_var(keys::ARCHIVE_EXTENSION_DENYLIST);
+    remove_env_var(keys::LOCAL_INSTALL_DIRS);
+    remove_env_var(keys::BACKEND_SYNC_GZIP);
+    remove_env_var(keys::MAX_SYNC_RESPONSE_BYTES);
+    remove_env_var(keys::HTTP_PROXY);
+    remove_env_var(keys::HTTPS_PROXY);
+    remove_env_var(keys::HTTP_USER_AGENT);
+    remove_env_var(keys::POST_INSTALL_COMMAND);
+    remove_env_var(keys::POST_UNINSTALL_COMMAND);
+    remove_env_var(keys::EVENT_WEBHOOK_URL);
+    remove_env_var(keys::INSTALL_FILENAME_TEMPLATE);
+    remove_env_var(keys::INSTALLED_FILE_MODE);
+    remove_env_var(keys::INSTALLED_FILE_OWNER);
+    remove_env_var(keys::INSTALLED_FILE_GROUP);
+    remove_env_var(keys::QUARANTINE_DIR);
+    remove_env_var(keys::QUARANTINE_MAX_BYTES);
 }
 
 /// Run a closure with a single env var set, restoring prior value afterward.
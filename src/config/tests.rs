@@ -17,6 +17,12 @@ use crate::config::test_support::{
 fn test_default_config() {
     let config = Config::default();
     assert_eq!(config.l4d2_server_dir, PathBuf::from("/home/steam/l4d2"));
+    assert_eq!(
+        config.addons_dir(),
+        PathBuf::from("/home/steam/l4d2/left4dead2/addons")
+    );
+    assert!(config.server_dirs.is_empty());
+    assert!(!config.read_only);
     assert_eq!(config.registry_path, PathBuf::from("registry.json"));
     assert_eq!(config.backend_api_url, "http://127.0.0.1:3001/api");
     assert_eq!(config.backend_api_key, None);
@@ -24,6 +30,8 @@ fn test_default_config() {
         config.local_api_bind,
         SocketAddr::from_str("127.0.0.1:8080").unwrap()
     );
+    assert!(config.tls_cert_path.is_none());
+    assert!(config.tls_key_path.is_none());
     assert_eq!(config.sync_interval_secs, 300);
     assert_eq!(config.log_level, "info");
     assert_eq!(
@@ -33,10 +41,53 @@ fn test_default_config() {
     assert!(config.hidden_workshop_ids.is_empty());
     assert!(config.hidden_map_ids.is_empty());
     assert_eq!(config.map_update_check_interval_days, 3);
+    assert_eq!(config.max_concurrent_downloads, 2);
+    assert_eq!(config.max_download_bytes_per_sec, 0);
+    assert_eq!(config.install_timeout_secs, 1800);
+    assert_eq!(config.http_connect_timeout_secs, 10);
+    assert_eq!(config.http_read_timeout_secs, 120);
+    assert_eq!(config.steam_breaker_failure_threshold, 5);
+    assert_eq!(config.steam_breaker_cooldown_secs, 300);
+    assert_eq!(config.workshop_collection_max_depth, 3);
+    assert_eq!(config.download_temp_dir, None);
+    assert_eq!(config.temp_cleanup_interval_secs, 3600);
+    assert_eq!(config.temp_cleanup_max_age_secs, 86400);
+    assert_eq!(config.integrity_scan_interval_secs, 0);
+    assert_eq!(config.shutdown_grace_secs, 2);
+    assert_eq!(config.max_vpks_per_archive, 0);
+    assert_eq!(
+        config.download_staging_dir(),
+        std::env::temp_dir().join("kether-downloads")
+    );
     assert!(config.workshop_update_check_enabled);
     assert!(config.workshop_update_auto_apply);
     assert!(config.l4d2center_update_check_enabled);
     assert!(!config.l4d2center_update_auto_apply);
+    assert!(!config.detect_require_checksum);
+    assert!(!config.import_existing_on_start);
+    assert_eq!(config.default_checksum_kind, "md5");
+    assert!(!config.recompute_checksums_on_start);
+    assert!(config.require_addoninfo);
+    assert!(!config.require_signed_vpk);
+    assert!(config.vpk_signing_public_key.is_none());
+    assert!(config.allowed_download_hosts.is_empty());
+    assert!(config.archive_extension_allowlist.is_empty());
+    assert!(config.archive_extension_denylist.is_empty());
+    assert!(config.local_install_dirs.is_empty());
+    assert!(!config.backend_sync_gzip);
+    assert_eq!(config.max_sync_response_bytes, 16 * 1024 * 1024);
+    assert!(config.http_proxy.is_none());
+    assert!(config.https_proxy.is_none());
+    assert!(config.http_user_agent.is_none());
+    assert!(config.post_install_command.is_none());
+    assert!(config.post_uninstall_command.is_none());
+    assert!(config.event_webhook_url.is_none());
+    assert!(config.install_filename_template.is_none());
+    assert!(config.installed_file_mode.is_none());
+    assert!(config.installed_file_owner.is_none());
+    assert!(config.installed_file_group.is_none());
+    assert!(config.quarantine_dir.is_none());
+    assert_eq!(config.quarantine_max_bytes, 1024 * 1024 * 1024);
 }
 
 #[test]
@@ -81,6 +132,154 @@ fn test_load_missing_config_file() {
     }
 }
 
+#[test]
+#[serial]
+fn test_strict_config_requires_env_when_file_missing() {
+    let temp_dir = tempfile::tempdir().expect("tempdir");
+    let config_path = temp_dir.path().join("config.toml");
+    let paths = [config_path.clone()];
+
+    clear_kether_env_vars();
+    set_env_var(keys::STRICT_CONFIG, "1");
+    set_env_var(keys::BACKEND_API_URL, "https://backend.example.com/api");
+
+    let error = Config::load_with_paths(&paths, false).unwrap_err().to_string();
+    assert!(error.contains(keys::L4D2_SERVER_DIR), "{error}");
+    assert!(!error.contains(keys::BACKEND_API_URL), "{error}");
+    assert!(!config_path.exists());
+
+    remove_env_var(keys::BACKEND_API_URL);
+    let error = Config::load_with_paths(&paths, false).unwrap_err().to_string();
+    assert!(error.contains(keys::L4D2_SERVER_DIR) && error.contains(keys::BACKEND_API_URL));
+
+    clear_kether_env_vars();
+}
+
+#[test]
+#[serial]
+fn test_strict_config_loads_from_env_only() {
+    let temp_dir = tempfile::tempdir().expect("tempdir");
+    let config_path = temp_dir.path().join("config.toml");
+
+    clear_kether_env_vars();
+    set_env_var(keys::STRICT_CONFIG, "true");
+    set_env_var(keys::L4D2_SERVER_DIR, "/srv/l4d2");
+    set_env_var(keys::BACKEND_API_URL, "https://backend.example.com/api");
+    set_env_var(keys::SYNC_INTERVAL_SECS, "90");
+
+    let (config, watched_path) =
+        Config::load_with_paths(std::slice::from_ref(&config_path), false).expect("env-only strict load");
+    assert_eq!(config.l4d2_server_dir, PathBuf::from("/srv/l4d2"));
+    assert_eq!(config.backend_api_url, "https://backend.example.com/api");
+    assert_eq!(config.sync_interval_secs, 90);
+    assert_eq!(watched_path, config_path);
+    assert!(!config_path.exists(), "strict mode must not write a default config");
+    config.validate().expect("env-only config validates");
+
+    clear_kether_env_vars();
+}
+
+#[test]
+#[serial]
+fn test_strict_config_parameter_skips_the_default_template() {
+    let temp_dir = tempfile::tempdir().expect("tempdir");
+    let config_path = temp_dir.path().join("config.toml");
+    let paths = [config_path.clone()];
+
+    clear_kether_env_vars();
+    let error = Config::load_with_paths(&paths, true).unwrap_err().to_string();
+    assert!(error.contains(keys::L4D2_SERVER_DIR), "{error}");
+    assert!(!config_path.exists(), "strict mode must not write a default config");
+
+    Config::load_with_paths(&paths, false).expect("non-strict load writes the template");
+    assert!(config_path.exists());
+
+    clear_kether_env_vars();
+}
+
+#[test]
+#[serial]
+fn test_load_layered_config_files() {
+    let temp_dir = tempfile::tempdir().expect("tempdir");
+    let base_path = temp_dir.path().join("base.toml");
+    let override_path = temp_dir.path().join("prod.toml");
+    let missing_path = temp_dir.path().join("missing.toml");
+    fs::write(
+        &base_path,
+        r#"
+l4d2_server_dir = "/base/server"
+registry_path = "registry.json"
+backend_api_url = "http://base.example.com"
+local_api_bind = "127.0.0.1:8080"
+sync_interval_secs = 600
+log_level = "debug"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        &override_path,
+        r#"
+backend_api_url = "http://prod.example.com"
+sync_interval_secs = 60
+"#,
+    )
+    .unwrap();
+
+    let original_config = std::env::var(keys::CONFIG).ok();
+    clear_kether_env_vars();
+    set_env_var(
+        keys::CONFIG,
+        &format!(
+            "{}, {},{}",
+            base_path.display(),
+            missing_path.display(),
+            override_path.display()
+        ),
+    );
+    set_env_var(keys::LOG_LEVEL, "warn");
+
+    let (config, watched_path) = Config::load_with_path().expect("load layered config");
+    assert_eq!(config.backend_api_url, "http://prod.example.com");
+    assert_eq!(config.sync_interval_secs, 60);
+    assert_eq!(config.l4d2_server_dir, PathBuf::from("/base/server"));
+    assert_eq!(config.log_level, "warn");
+    assert_eq!(watched_path, fs::canonicalize(&base_path).unwrap());
+    assert!(!missing_path.exists());
+
+    remove_env_var(keys::LOG_LEVEL);
+    if let Some(val) = original_config {
+        set_env_var(keys::CONFIG, &val);
+    } else {
+        remove_env_var(keys::CONFIG);
+    }
+}
+
+#[test]
+#[serial]
+fn test_cli_config_paths_take_precedence_over_env() {
+    let original_config = std::env::var(keys::CONFIG).ok();
+    set_env_var(keys::CONFIG, "/env/config.toml");
+
+    assert_eq!(
+        crate::config::resolve_config_paths(Some("/cli/base.toml, /cli/override.toml")),
+        vec![PathBuf::from("/cli/base.toml"), PathBuf::from("/cli/override.toml")]
+    );
+    assert_eq!(
+        crate::config::resolve_config_paths(None),
+        vec![PathBuf::from("/env/config.toml")]
+    );
+    assert_eq!(
+        crate::config::resolve_config_paths(Some(" ")),
+        vec![PathBuf::from("/env/config.toml")]
+    );
+
+    if let Some(val) = original_config {
+        set_env_var(keys::CONFIG, &val);
+    } else {
+        remove_env_var(keys::CONFIG);
+    }
+}
+
 #[test]
 #[serial]
 fn test_load_from_toml() {
@@ -143,6 +342,30 @@ fn test_env_var_overrides() {
                 assert_eq!(config.l4d2_server_dir, PathBuf::from("/env/server/path"));
             },
         },
+        Case {
+            key: keys::SERVER_DIRS,
+            value: "/srv/l4d2-a, /srv/l4d2-b",
+            assert: |config| {
+                assert_eq!(
+                    config.server_dirs,
+                    vec![PathBuf::from("/srv/l4d2-a"), PathBuf::from("/srv/l4d2-b")]
+                );
+            },
+        },
+        Case {
+            key: keys::READ_ONLY,
+            value: "true",
+            assert: |config| {
+                assert!(config.read_only);
+            },
+        },
+        Case {
+            key: keys::ADDONS_SUBPATH,
+            value: "left4dead2_dlc/addons",
+            assert: |config| {
+                assert_eq!(config.addons_subpath, PathBuf::from("left4dead2_dlc/addons"));
+            },
+        },
         Case {
             key: keys::REGISTRY_PATH,
             value: "/env/registry.json",
@@ -174,6 +397,13 @@ fn test_env_var_overrides() {
                 );
             },
         },
+        Case {
+            key: keys::TLS_CERT_PATH,
+            value: "/etc/kether/api.crt",
+            assert: |config| {
+                assert_eq!(config.tls_cert_path, Some(PathBuf::from("/etc/kether/api.crt")));
+            },
+        },
         Case {
             key: keys::SYNC_INTERVAL_SECS,
             value: "120",
@@ -188,6 +418,108 @@ fn test_env_var_overrides() {
                 assert_eq!(config.log_level, "trace");
             },
         },
+        Case {
+            key: keys::MAX_CONCURRENT_DOWNLOADS,
+            value: "4",
+            assert: |config| {
+                assert_eq!(config.max_concurrent_downloads, 4);
+            },
+        },
+        Case {
+            key: keys::MAX_DOWNLOAD_BYTES_PER_SEC,
+            value: "1048576",
+            assert: |config| {
+                assert_eq!(config.max_download_bytes_per_sec, 1048576);
+            },
+        },
+        Case {
+            key: keys::INSTALL_TIMEOUT_SECS,
+            value: "90",
+            assert: |config| {
+                assert_eq!(config.install_timeout_secs, 90);
+            },
+        },
+        Case {
+            key: keys::HTTP_CONNECT_TIMEOUT_SECS,
+            value: "5",
+            assert: |config| {
+                assert_eq!(config.http_connect_timeout_secs, 5);
+            },
+        },
+        Case {
+            key: keys::HTTP_READ_TIMEOUT_SECS,
+            value: "30",
+            assert: |config| {
+                assert_eq!(config.http_read_timeout_secs, 30);
+            },
+        },
+        Case {
+            key: keys::STEAM_BREAKER_FAILURE_THRESHOLD,
+            value: "0",
+            assert: |config| {
+                assert_eq!(config.steam_breaker_failure_threshold, 0);
+            },
+        },
+        Case {
+            key: keys::STEAM_BREAKER_COOLDOWN_SECS,
+            value: "60",
+            assert: |config| {
+                assert_eq!(config.steam_breaker_cooldown_secs, 60);
+            },
+        },
+        Case {
+            key: keys::WORKSHOP_COLLECTION_MAX_DEPTH,
+            value: "5",
+            assert: |config| {
+                assert_eq!(config.workshop_collection_max_depth, 5);
+            },
+        },
+        Case {
+            key: keys::TEMP_CLEANUP_INTERVAL_SECS,
+            value: "0",
+            assert: |config| {
+                assert_eq!(config.temp_cleanup_interval_secs, 0);
+            },
+        },
+        Case {
+            key: keys::TEMP_CLEANUP_MAX_AGE_SECS,
+            value: "7200",
+            assert: |config| {
+                assert_eq!(config.temp_cleanup_max_age_secs, 7200);
+            },
+        },
+        Case {
+            key: keys::INTEGRITY_SCAN_INTERVAL_SECS,
+            value: "43200",
+            assert: |config| {
+                assert_eq!(config.integrity_scan_interval_secs, 43200);
+            },
+        },
+        Case {
+            key: keys::MAX_VPKS_PER_ARCHIVE,
+            value: "0",
+            assert: |config| {
+                assert_eq!(config.max_vpks_per_archive, 0);
+            },
+        },
+        Case {
+            key: keys::SHUTDOWN_GRACE_SECS,
+            value: "10",
+            assert: |config| {
+                assert_eq!(config.shutdown_grace_secs, 10);
+            },
+        },
+        Case {
+            key: keys::DOWNLOAD_TEMP_DIR,
+            value: "/env/tmp",
+            assert: |config| {
+                assert_eq!(config.download_temp_dir, Some(PathBuf::from("/env/tmp")));
+                assert_eq!(
+                    config.download_staging_dir(),
+                    PathBuf::from("/env/tmp/kether-downloads")
+                );
+            },
+        },
         Case {
             key: keys::MAP_UPDATE_CHECK_INTERVAL_DAYS,
             value: "5",
@@ -209,6 +541,198 @@ fn test_env_var_overrides() {
                 assert!(config.l4d2center_update_auto_apply);
             },
         },
+        Case {
+            key: keys::DETECT_REQUIRE_CHECKSUM,
+            value: "yes",
+            assert: |config| {
+                assert!(config.detect_require_checksum);
+            },
+        },
+        Case {
+            key: keys::IMPORT_EXISTING_ON_START,
+            value: "true",
+            assert: |config| {
+                assert!(config.import_existing_on_start);
+            },
+        },
+        Case {
+            key: keys::DEFAULT_CHECKSUM_KIND,
+            value: "sha256",
+            assert: |config| {
+                assert_eq!(config.default_checksum_kind, "sha256");
+            },
+        },
+        Case {
+            key: keys::RECOMPUTE_CHECKSUMS_ON_START,
+            value: "true",
+            assert: |config| {
+                assert!(config.recompute_checksums_on_start);
+            },
+        },
+        Case {
+            key: keys::REQUIRE_ADDONINFO,
+            value: "false",
+            assert: |config| {
+                assert!(!config.require_addoninfo);
+            },
+        },
+        Case {
+            key: keys::REQUIRE_SIGNED_VPK,
+            value: "true",
+            assert: |config| {
+                assert!(config.require_signed_vpk);
+            },
+        },
+        Case {
+            key: keys::VPK_SIGNING_PUBLIC_KEY,
+            value: "/srv/kether/vpk-signing.pem",
+            assert: |config| {
+                assert_eq!(
+                    config.vpk_signing_public_key,
+                    Some(PathBuf::from("/srv/kether/vpk-signing.pem"))
+                );
+            },
+        },
+        Case {
+            key: keys::ARCHIVE_EXTENSION_DENYLIST,
+            value: "sh, exe,dll",
+            assert: |config| {
+                assert_eq!(config.archive_extension_denylist, vec!["sh", "exe", "dll"]);
+            },
+        },
+        Case {
+            key: keys::ARCHIVE_EXTENSION_ALLOWLIST,
+            value: "vpk,txt",
+            assert: |config| {
+                assert_eq!(config.archive_extension_allowlist, vec!["vpk", "txt"]);
+            },
+        },
+        Case {
+            key: keys::ALLOWED_DOWNLOAD_HOSTS,
+            value: "l4d2center.com, *.example.com",
+            assert: |config| {
+                assert_eq!(
+                    config.allowed_download_hosts,
+                    vec!["l4d2center.com".to_string(), "*.example.com".to_string()]
+                );
+            },
+        },
+        Case {
+            key: keys::LOCAL_INSTALL_DIRS,
+            value: "/srv/maps, /opt/staging",
+            assert: |config| {
+                assert_eq!(
+                    config.local_install_dirs,
+                    vec![PathBuf::from("/srv/maps"), PathBuf::from("/opt/staging")]
+                );
+            },
+        },
+        Case {
+            key: keys::HTTPS_PROXY,
+            value: "http://proxy.example:3128",
+            assert: |config| {
+                assert_eq!(config.https_proxy.as_deref(), Some("http://proxy.example:3128"));
+            },
+        },
+        Case {
+            key: keys::HTTP_USER_AGENT,
+            value: "MirrorFriendly/1.0",
+            assert: |config| {
+                assert_eq!(config.http_user_agent.as_deref(), Some("MirrorFriendly/1.0"));
+            },
+        },
+        Case {
+            key: keys::POST_INSTALL_COMMAND,
+            value: "/srv/hooks/installed.sh",
+            assert: |config| {
+                assert_eq!(
+                    config.post_install_command,
+                    Some(PathBuf::from("/srv/hooks/installed.sh"))
+                );
+            },
+        },
+        Case {
+            key: keys::POST_UNINSTALL_COMMAND,
+            value: "/srv/hooks/uninstalled.sh",
+            assert: |config| {
+                assert_eq!(
+                    config.post_uninstall_command,
+                    Some(PathBuf::from("/srv/hooks/uninstalled.sh"))
+                );
+            },
+        },
+        Case {
+            key: keys::EVENT_WEBHOOK_URL,
+            value: "https://hooks.example.com/kether",
+            assert: |config| {
+                assert_eq!(
+                    config.event_webhook_url.as_deref(),
+                    Some("https://hooks.example.com/kether")
+                );
+            },
+        },
+        Case {
+            key: keys::INSTALL_FILENAME_TEMPLATE,
+            value: "{map_id}_{original}",
+            assert: |config| {
+                assert_eq!(
+                    config.install_filename_template.as_deref(),
+                    Some("{map_id}_{original}")
+                );
+            },
+        },
+        Case {
+            key: keys::INSTALLED_FILE_MODE,
+            value: "0640",
+            assert: |config| {
+                assert_eq!(config.installed_file_mode.as_deref(), Some("0640"));
+            },
+        },
+        Case {
+            key: keys::INSTALLED_FILE_OWNER,
+            value: "steam",
+            assert: |config| {
+                assert_eq!(config.installed_file_owner.as_deref(), Some("steam"));
+            },
+        },
+        Case {
+            key: keys::INSTALLED_FILE_GROUP,
+            value: "1000",
+            assert: |config| {
+                assert_eq!(config.installed_file_group.as_deref(), Some("1000"));
+            },
+        },
+        Case {
+            key: keys::QUARANTINE_DIR,
+            value: "/srv/kether/quarantine",
+            assert: |config| {
+                assert_eq!(
+                    config.quarantine_dir,
+                    Some(PathBuf::from("/srv/kether/quarantine"))
+                );
+            },
+        },
+        Case {
+            key: keys::QUARANTINE_MAX_BYTES,
+            value: "1048576",
+            assert: |config| {
+                assert_eq!(config.quarantine_max_bytes, 1048576);
+            },
+        },
+        Case {
+            key: keys::BACKEND_SYNC_GZIP,
+            value: "1",
+            assert: |config| {
+                assert!(config.backend_sync_gzip);
+            },
+        },
+        Case {
+            key: keys::MAX_SYNC_RESPONSE_BYTES,
+            value: "65536",
+            assert: |config| {
+                assert_eq!(config.max_sync_response_bytes, 65536);
+            },
+        },
     ];
 
     for case in cases {
@@ -235,6 +759,208 @@ fn test_validate_rejects_zero_map_update_interval_days() {
     assert!(config.validate().is_err());
 }
 
+#[test]
+fn test_validate_rejects_invalid_proxy_url() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.https_proxy = Some("http://[::1".to_string());
+    assert!(config.validate().is_err());
+    config.https_proxy = Some("http://proxy.example:3128".to_string());
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_invalid_user_agent() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.http_user_agent = Some("  ".to_string());
+    assert!(config.validate().is_err());
+    config.http_user_agent = Some("bad\nagent".to_string());
+    assert!(config.validate().is_err());
+    config.http_user_agent = Some("MirrorFriendly/1.0".to_string());
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_quarantine_dir_inside_addons() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.quarantine_dir = Some(config.addons_dir().join("quarantine"));
+    assert!(config.validate().is_err());
+    config.quarantine_dir = Some(PathBuf::from("quarantine"));
+    assert!(config.validate().is_err());
+    config.quarantine_dir = Some(config.l4d2_server_dir.join("quarantine"));
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_internal_event_webhook_url() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.event_webhook_url = Some("http://127.0.0.1:9000/hook".to_string());
+    assert!(config.validate().is_err());
+    config.event_webhook_url = Some("ftp://hooks.example.com/kether".to_string());
+    assert!(config.validate().is_err());
+    config.event_webhook_url = Some("https://hooks.example.com/kether".to_string());
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_relative_hook_command() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.post_install_command = Some(PathBuf::from("hooks/installed.sh"));
+    assert!(config.validate().is_err());
+    config.post_install_command = Some(PathBuf::from("/srv/hooks/installed.sh"));
+    config.post_uninstall_command = Some(PathBuf::from("true"));
+    assert!(config.validate().is_err());
+    config.post_uninstall_command = Some(PathBuf::from("/bin/true"));
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_zero_max_concurrent_downloads() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.max_concurrent_downloads = 0;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_validate_rejects_zero_install_timeout() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.install_timeout_secs = 0;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_validate_rejects_zero_http_timeouts() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.http_connect_timeout_secs = 0;
+    assert!(config.validate().is_err());
+    config.http_connect_timeout_secs = 10;
+    config.http_read_timeout_secs = 0;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_validate_rejects_zero_steam_breaker_cooldown() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.steam_breaker_cooldown_secs = 0;
+    assert!(config.validate().is_err());
+    config.steam_breaker_failure_threshold = 0;
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_zero_workshop_collection_max_depth() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.workshop_collection_max_depth = 0;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_validate_rejects_zero_max_sync_response_bytes() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.max_sync_response_bytes = 0;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_validate_rejects_unsafe_install_filename_template() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.install_filename_template = Some("{map_id}_{original}".to_string());
+    assert!(config.validate().is_ok());
+    config.install_filename_template = Some("maps/{original}".to_string());
+    assert!(config.validate().is_err());
+    config.install_filename_template = Some("{version}.vpk".to_string());
+    assert!(config.validate().is_err());
+    config.install_filename_template = Some("fixed.vpk".to_string());
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_validate_rejects_invalid_installed_file_permissions() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.installed_file_mode = Some("0644".to_string());
+    config.installed_file_owner = Some("0".to_string());
+    assert!(config.validate().is_ok());
+    config.installed_file_mode = Some("rw-r--r--".to_string());
+    assert!(config.validate().is_err());
+    config.installed_file_mode = None;
+    config.installed_file_group = Some("no-such-kether-group".to_string());
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_validate_require_signed_vpk_needs_a_readable_key() {
+    use rsa::pkcs8::{EncodePublicKey, LineEnding};
+
+    let (mut config, dir) = crate::test_helpers::create_test_config();
+    config.require_signed_vpk = true;
+    assert!(config.validate().is_err());
+
+    let key_path = dir.path().join("vpk-signing.pem");
+    config.vpk_signing_public_key = Some(key_path.clone());
+    assert!(config.validate().is_err());
+
+    let pem = crate::test_helpers::test_vpk_signing_key()
+        .to_public_key()
+        .to_public_key_pem(LineEnding::LF)
+        .unwrap();
+    std::fs::write(&key_path, pem).unwrap();
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_archive_extension_lists_keep_vpk_allowed() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.archive_extension_denylist = vec!["sh".to_string(), ".VPK".to_string()];
+    assert!(config.validate().is_err());
+    config.archive_extension_denylist = vec!["sh".to_string()];
+    config.archive_extension_allowlist = vec!["txt".to_string()];
+    assert!(config.validate().is_err());
+    config.archive_extension_allowlist = vec!["vpk".to_string(), "txt".to_string()];
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_temp_cleanup_age_below_install_timeout() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.temp_cleanup_max_age_secs = config.install_timeout_secs - 1;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_custom_addons_subpath_is_joined_to_server_dir() {
+    let (mut config, dir) = crate::test_helpers::create_test_config();
+    config.addons_subpath = PathBuf::from("left4dead2/addons/workshop");
+    assert!(config.validate().is_ok());
+    assert_eq!(
+        config.addons_dir(),
+        dir.path().join("left4dead2/addons/workshop")
+    );
+}
+
+#[test]
+fn test_validate_rejects_addons_subpath_outside_server_dir() {
+    let (mut config, dir) = crate::test_helpers::create_test_config();
+    for subpath in ["../outside/addons", "left4dead2/../../addons", "/srv/addons", ""] {
+        config.addons_subpath = PathBuf::from(subpath);
+        assert!(config.validate().is_err(), "{subpath:?} should be rejected");
+    }
+
+    #[cfg(unix)]
+    {
+        let outside = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(outside.path().join("addons")).unwrap();
+        std::os::unix::fs::symlink(outside.path(), dir.path().join("linked")).unwrap();
+        config.addons_subpath = PathBuf::from("linked/addons");
+        assert!(config.validate().is_err(), "symlink escape should be rejected");
+    }
+}
+
+#[test]
+fn test_validate_rejects_empty_download_temp_dir() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.download_temp_dir = Some(PathBuf::new());
+    assert!(config.validate().is_err());
+}
+
 #[test]
 fn test_validate_rejects_invalid_log_level() {
     let (mut config, _dir) = crate::test_helpers::create_test_config();
@@ -242,6 +968,63 @@ fn test_validate_rejects_invalid_log_level() {
     assert!(config.validate().is_err());
 }
 
+#[test]
+fn test_validate_rejects_unknown_checksum_kind() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.default_checksum_kind = "crc32".to_string();
+    assert!(config.validate().is_err());
+    config.default_checksum_kind = "SHA256".to_string();
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_empty_blocked_url_pattern() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.blocked_url_patterns = vec!["  ".to_string()];
+    assert!(config.validate().is_err());
+    config.blocked_url_patterns = vec!["*/banned.zip".to_string()];
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_malformed_allowed_download_host() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.allowed_download_hosts = vec!["https://example.com/".to_string()];
+    assert!(config.validate().is_err());
+
+    config.allowed_download_hosts = vec!["cdn.*.example.com".to_string()];
+    assert!(config.validate().is_err());
+
+    config.allowed_download_hosts = vec!["*.example.com".to_string()];
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_relative_local_install_dir() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.local_install_dirs = vec![PathBuf::from("staging")];
+    assert!(config.validate().is_err());
+
+    config.local_install_dirs = vec![PathBuf::from("/srv/maps")];
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn test_validate_rejects_relative_or_repeated_server_dir() {
+    let (mut config, _dir) = crate::test_helpers::create_test_config();
+    config.server_dirs = vec![PathBuf::from("l4d2-b")];
+    assert!(config.validate().is_err());
+
+    config.server_dirs = vec![config.l4d2_server_dir.clone()];
+    assert!(config.validate().is_err());
+
+    config.server_dirs = vec![PathBuf::from("/srv/l4d2-b"), PathBuf::from("/srv/l4d2-b")];
+    assert!(config.validate().is_err());
+
+    config.server_dirs = vec![PathBuf::from("/srv/l4d2-b"), PathBuf::from("/srv/l4d2-c")];
+    assert!(config.validate().is_ok());
+}
+
 #[test]
 fn test_validate_accepts_valid_config() {
     let (config, _dir) = crate::test_helpers::create_test_config();
@@ -266,6 +1049,20 @@ fn test_validate_accepts_non_loopback_bind_with_api_key() {
     assert!(config.validate().is_ok());
 }
 
+#[test]
+fn test_validate_requires_tls_cert_and_key_together() {
+    let (mut config, dir) = crate::test_helpers::create_test_config();
+    let cert_path = dir.path().join("api.crt");
+    let key_path = dir.path().join("api.key");
+    config.tls_cert_path = Some(cert_path.clone());
+    assert!(config.validate().is_err());
+    config.tls_key_path = Some(key_path.clone());
+    assert!(config.validate().is_err());
+    std::fs::write(&cert_path, "cert").unwrap();
+    std::fs::write(&key_path, "key").unwrap();
+    assert!(config.validate().is_ok());
+}
+
 #[test]
 fn test_diff_classifies_live_and_restart_fields() {
     let old = Config::default();
@@ -275,6 +1072,7 @@ fn test_diff_classifies_live_and_restart_fields() {
     new.backend_api_key = Some("rotated-key".to_string());
     new.map_update_check_interval_days = 7;
     new.workshop_update_auto_apply = false;
+    new.detect_require_checksum = true;
     new.local_api_bind = SocketAddr::from_str("127.0.0.1:9090").unwrap();
 
     let change = old.diff(&new);
@@ -283,12 +1081,14 @@ fn test_diff_classifies_live_and_restart_fields() {
     assert!(change.live_applied.contains(&"backend_api_key"));
     assert!(change.live_applied.contains(&"map_update_check_interval_days"));
     assert!(change.live_applied.contains(&"workshop_update_auto_apply"));
+    assert!(change.live_applied.contains(&"detect_require_checksum"));
     assert!(change.requires_restart.contains(&"local_api_bind"));
     assert!(!change.unchanged);
 
     let merged = old.with_live_fields_from(&new);
     assert_eq!(merged.map_update_check_interval_days, 7);
     assert!(!merged.workshop_update_auto_apply);
+    assert!(merged.detect_require_checksum);
     assert_eq!(merged.local_api_bind, old.local_api_bind);
 }
 
@@ -314,6 +1114,8 @@ sync_interval_secs = 300
 log_level = "info"
 hidden_workshop_ids = [381419931, 123456789]
 hidden_map_ids = [7, 42]
+blocked_workshop_ids = [555]
+blocked_url_patterns = ["https://bad.example.com/*"]
 "#;
     fs::write(temp_file.path(), config_content).unwrap();
 
@@ -325,6 +1127,8 @@ hidden_map_ids = [7, 42]
     let config = Config::load().unwrap();
     assert_eq!(config.hidden_workshop_ids, vec![381419931, 123456789]);
     assert_eq!(config.hidden_map_ids, vec![7, 42]);
+    assert_eq!(config.blocked_workshop_ids, vec![555]);
+    assert_eq!(config.blocked_url_patterns, vec!["https://bad.example.com/*".to_string()]);
 
     if let Some(val) = original_config {
         set_env_var(keys::CONFIG, &val);
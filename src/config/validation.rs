@@ -1,4 +1,5 @@
 // SPDX-License-Identifier: GPL-3.0-only
+use anyhow::Context;
 use std::path::PathBuf;
 
 use crate::config::model::Config;
@@ -8,7 +9,20 @@ const ALLOWED_LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error"]
 impl Config {
     /// Get the addons directory path
     pub fn addons_dir(&self) -> PathBuf {
-        self.l4d2_server_dir.join("left4dead2").join("addons")
+        self.l4d2_server_dir.join(&self.addons_subpath)
+    }
+
+    /// Addons directories of the `server_dirs` instances, in config order.
+    pub fn instance_addons_dirs(&self) -> Vec<PathBuf> {
+        self.server_dirs.iter().map(|dir| dir.join(&self.addons_subpath)).collect()
+    }
+
+    /// Download staging directory under `download_temp_dir` (or the system temp dir).
+    pub fn download_staging_dir(&self) -> PathBuf {
+        self.download_temp_dir
+            .clone()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("kether-downloads")
     }
 
     /// Validate configuration before starting services.
@@ -21,6 +35,58 @@ impl Config {
             anyhow::bail!("map_update_check_interval_days must be at least 1");
         }
 
+        if self.addons_subpath.as_os_str().is_empty()
+            || self
+                .addons_subpath
+                .components()
+                .any(|c| !matches!(c, std::path::Component::Normal(_) | std::path::Component::CurDir))
+        {
+            anyhow::bail!(
+                "addons_subpath '{}' must be a relative path inside l4d2_server_dir",
+                self.addons_subpath.display()
+            );
+        }
+        // Symlinked mod directories must not lead outside the server either.
+        crate::utils::validate_path_within_base_new(&self.addons_dir(), &self.l4d2_server_dir)
+            .map_err(|e| anyhow::anyhow!("addons_subpath must resolve inside l4d2_server_dir: {e}"))?;
+
+        if self
+            .download_temp_dir
+            .as_ref()
+            .is_some_and(|dir| dir.as_os_str().is_empty())
+        {
+            anyhow::bail!("download_temp_dir must not be empty when set");
+        }
+
+        if self.install_timeout_secs == 0 {
+            anyhow::bail!("install_timeout_secs must be at least 1");
+        }
+
+        if self.http_connect_timeout_secs == 0 {
+            anyhow::bail!("http_connect_timeout_secs must be at least 1");
+        }
+        if self.http_read_timeout_secs == 0 {
+            anyhow::bail!("http_read_timeout_secs must be at least 1");
+        }
+        if self.steam_breaker_failure_threshold > 0 && self.steam_breaker_cooldown_secs == 0 {
+            anyhow::bail!("steam_breaker_cooldown_secs must be at least 1 when the breaker is enabled");
+        }
+        if self.workshop_collection_max_depth == 0 {
+            anyhow::bail!("workshop_collection_max_depth must be at least 1");
+        }
+        if self.max_sync_response_bytes == 0 {
+            anyhow::bail!("max_sync_response_bytes must be greater than 0");
+        }
+
+        // A younger threshold could delete the download of an install still running.
+        if self.temp_cleanup_max_age_secs < self.install_timeout_secs {
+            anyhow::bail!("temp_cleanup_max_age_secs must be at least install_timeout_secs");
+        }
+
+        if self.max_concurrent_downloads == 0 {
+            anyhow::bail!("max_concurrent_downloads must be at least 1");
+        }
+
         if !ALLOWED_LOG_LEVELS.contains(&self.log_level.to_lowercase().as_str()) {
             anyhow::bail!(
                 "Invalid log_level '{}', expected one of: {}",
@@ -29,9 +95,144 @@ impl Config {
             );
         }
 
+        if !crate::utils::SUPPORTED_CHECKSUM_KINDS
+            .contains(&self.default_checksum_kind.to_lowercase().as_str())
+        {
+            anyhow::bail!(
+                "Invalid default_checksum_kind '{}', expected one of: {}",
+                self.default_checksum_kind,
+                crate::utils::SUPPORTED_CHECKSUM_KINDS.join(", ")
+            );
+        }
+
         crate::utils::validate_url(&self.l4d2center_index_url)
             .map_err(|e| anyhow::anyhow!("Invalid l4d2center_index_url: {e}"))?;
 
+        for pattern in &self.allowed_download_hosts {
+            let host = pattern.strip_prefix("*.").unwrap_or(pattern);
+            if host.is_empty() || host.contains(['*', '/', ':']) {
+                anyhow::bail!(
+                    "Invalid allowed_download_hosts entry '{pattern}', expected a hostname or *.domain"
+                );
+            }
+        }
+
+        // Either list could otherwise reject every map archive.
+        let normalize = crate::extractor::entry_policy::normalize_extension;
+        if self.archive_extension_denylist.iter().any(|ext| normalize(ext) == "vpk") {
+            anyhow::bail!("archive_extension_denylist must not contain vpk");
+        }
+        if self.archive_extension_denylist.iter().any(|ext| normalize(ext).is_empty()) {
+            anyhow::bail!("archive_extension_denylist entries must not be empty");
+        }
+        if !self.archive_extension_allowlist.is_empty()
+            && !self.archive_extension_allowlist.iter().any(|ext| normalize(ext) == "vpk")
+        {
+            anyhow::bail!("archive_extension_allowlist must include vpk");
+        }
+
+        if self.blocked_url_patterns.iter().any(|p| p.trim().is_empty()) {
+            anyhow::bail!("blocked_url_patterns entries must not be empty");
+        }
+
+        for (index, dir) in self.server_dirs.iter().enumerate() {
+            if !dir.is_absolute() {
+                anyhow::bail!(
+                    "Invalid server_dirs entry '{}', expected an absolute path",
+                    dir.display()
+                );
+            }
+            if *dir == self.l4d2_server_dir || self.server_dirs[..index].contains(dir) {
+                anyhow::bail!(
+                    "server_dirs entry '{}' is listed twice (l4d2_server_dir is always included)",
+                    dir.display()
+                );
+            }
+        }
+
+        for dir in &self.local_install_dirs {
+            if !dir.is_absolute() {
+                anyhow::bail!(
+                    "Invalid local_install_dirs entry '{}', expected an absolute path",
+                    dir.display()
+                );
+            }
+        }
+
+        for (field, proxy) in [("http_proxy", &self.http_proxy), ("https_proxy", &self.https_proxy)] {
+            if let Some(proxy) = proxy
+                && reqwest::Proxy::all(proxy.as_str()).is_err()
+            {
+                anyhow::bail!("Invalid {field} '{proxy}', expected a proxy URL");
+            }
+        }
+
+        if let Some(user_agent) = &self.http_user_agent
+            && (user_agent.trim().is_empty()
+                || reqwest::header::HeaderValue::from_str(user_agent).is_err())
+        {
+            anyhow::bail!("Invalid http_user_agent '{user_agent}', expected a non-empty header value");
+        }
+
+        for (field, command) in [
+            ("post_install_command", &self.post_install_command),
+            ("post_uninstall_command", &self.post_uninstall_command),
+        ] {
+            if let Some(command) = command
+                && !command.is_absolute()
+            {
+                anyhow::bail!(
+                    "Invalid {field} '{}', expected an absolute path to an executable",
+                    command.display()
+                );
+            }
+        }
+
+        if let Some(url) = &self.event_webhook_url {
+            crate::utils::validate_url(url)
+                .map_err(|e| anyhow::anyhow!("Invalid event_webhook_url: {e}"))?;
+        }
+
+        if let Some(template) = &self.install_filename_template {
+            if !template.contains("{map_id}")
+                && !template.contains("{original}")
+                && !template.contains("{sanitized_name}")
+            {
+                anyhow::bail!(
+                    "install_filename_template must contain {{map_id}}, {{original}} or {{sanitized_name}}"
+                );
+            }
+            crate::utils::render_install_filename(template, 1, "map.vpk", "map")
+                .context("Invalid install_filename_template")?;
+        }
+
+        crate::utils::InstalledFilePermissions::from_config(self)
+            .context("Invalid installed file permissions")?;
+
+        match &self.vpk_signing_public_key {
+            Some(path) => {
+                crate::extractor::vpk_signature::load_public_key(path)
+                    .context("Invalid vpk_signing_public_key")?;
+            }
+            None if self.require_signed_vpk => {
+                anyhow::bail!("require_signed_vpk needs vpk_signing_public_key to be set");
+            }
+            None => {}
+        }
+
+        // Quarantined VPKs must never end up where the game server loads addons from.
+        if let Some(dir) = &self.quarantine_dir {
+            if !dir.is_absolute() {
+                anyhow::bail!(
+                    "Invalid quarantine_dir '{}', expected an absolute path",
+                    dir.display()
+                );
+            }
+            if dir.starts_with(self.addons_dir()) {
+                anyhow::bail!("quarantine_dir must not be inside the addons directory");
+            }
+        }
+
         if !self.local_api_bind.ip().is_loopback()
             && self
                 .backend_api_key
@@ -44,6 +245,20 @@ impl Config {
             );
         }
 
+        match (&self.tls_cert_path, &self.tls_key_path) {
+            (Some(_), None) | (None, Some(_)) => {
+                anyhow::bail!("tls_cert_path and tls_key_path must be set together");
+            }
+            (Some(cert), Some(key)) => {
+                for (field, path) in [("tls_cert_path", cert), ("tls_key_path", key)] {
+                    if !path.is_file() {
+                        anyhow::bail!("{field} '{}' is not a readable file", path.display());
+                    }
+                }
+            }
+            (None, None) => {}
+        }
+
         Ok(())
     }
 }
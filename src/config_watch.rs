@@ -42,7 +42,9 @@ fn remember_identity(path: &Path) -> Result<(), String> {
     Ok(())
 }
 
-pub fn apply_reload(handle: &ConfigHandle, path: &Path) -> Result<ConfigChange, String> {
+/// Reload `config_paths` (watched primary file first, then override layers) into `handle`.
+pub fn apply_reload(handle: &ConfigHandle, config_paths: &[PathBuf]) -> Result<ConfigChange, String> {
+    let path = config_paths[0].as_path();
     if identity_unchanged(path)? {
         return Ok(ConfigChange {
             unchanged: true,
@@ -50,11 +52,8 @@ pub fn apply_reload(handle: &ConfigHandle, path: &Path) -> Result<ConfigChange,
         });
     }
 
-    let mut new_config = {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| format!("Failed to reload config: {}", e))?;
-        Config::from_toml_str(&content).map_err(|e| format!("Failed to reload config: {}", e))?
-    };
+    let mut new_config =
+        Config::reload_from(config_paths).map_err(|e| format!("Failed to reload config: {}", e))?;
 
     apply_env_overrides(&mut new_config)
         .map_err(|e| format!("Failed to apply env overrides on reload: {}", e))?;
@@ -87,6 +86,39 @@ pub fn apply_reload(handle: &ConfigHandle, path: &Path) -> Result<ConfigChange,
     Ok(change)
 }
 
+/// Reload even if the file identity looks unchanged (explicit operator request).
+pub fn force_reload(handle: &ConfigHandle, config_paths: &[PathBuf]) -> Result<ConfigChange, String> {
+    *LAST_FILE_IDENTITY
+        .lock()
+        .map_err(|e| format!("Config watcher state lock poisoned: {}", e))? = None;
+    apply_reload(handle, config_paths)
+}
+
+/// Reload config.toml on `SIGHUP`, e.g. to rotate `backend_api_key` without a restart.
+///
+/// `config_paths` are the files the daemon started from, primary first. Live fields
+/// are swapped into the shared snapshot, so in-flight requests keep the snapshot they
+/// started with; restart-required fields are only reported.
+#[cfg(unix)]
+pub fn spawn_sighup_reloader(
+    handle: ConfigHandle,
+    config_paths: Vec<PathBuf>,
+) -> std::io::Result<tokio::task::JoinHandle<()>> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangups = signal(SignalKind::hangup())?;
+    Ok(tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            tracing::info!(path = %config_paths[0].display(), "SIGHUP received, reloading config");
+            match force_reload(&handle, &config_paths) {
+                Ok(change) if change.unchanged => tracing::info!("Config reload: no changes"),
+                Ok(change) => change.log(),
+                Err(err) => tracing::error!(error = %err, "Config reload failed"),
+            }
+        }
+    }))
+}
+
 fn event_mentions_config(event: &DebouncedEvent, config_path: &Path) -> bool {
     let expected_name = config_path
         .file_name()
@@ -121,13 +153,13 @@ fn is_substantive_config_event(event: &DebouncedEvent, config_path: &Path) -> bo
 
 pub fn spawn_config_watcher(
     handle: ConfigHandle,
-    config_path: PathBuf,
+    config_paths: Vec<PathBuf>,
 ) -> Result<Debouncer<RecommendedWatcher, RecommendedCache>, String> {
-    let watch_parent = config_path
+    let watch_parent = config_paths[0]
         .parent()
         .ok_or_else(|| "Config path has no parent directory".to_string())?
         .to_path_buf();
-    let watched_path = config_path.clone();
+    let watched_path = config_paths[0].clone();
 
     // Seed identity so the first debounced noise does not reload immediately.
     if let Err(err) = remember_identity(&watched_path) {
@@ -146,7 +178,7 @@ pub fn spawn_config_watcher(
                     return;
                 }
 
-                match apply_reload(&handle, &watched_path) {
+                match apply_reload(&handle, &config_paths) {
                     Ok(change) => change.log(),
                     Err(err) => eprintln!("Config hot reload failed: {}", err),
                 }
@@ -210,7 +242,7 @@ hidden_map_ids = []
         let handle = init_handle(initial);
 
         write_config(&config_path, &base_toml("381419931", 300));
-        let change = apply_reload(&handle, &config_path).expect("apply reload");
+        let change = apply_reload(&handle, std::slice::from_ref(&config_path)).expect("apply reload");
         assert!(!change.unchanged);
         assert!(change.live_applied.contains(&"hidden_workshop_ids"));
         assert!(change.requires_restart.is_empty());
@@ -231,7 +263,7 @@ hidden_map_ids = []
         remember_identity(&config_path).expect("seed identity");
 
         write_config(&config_path, "not [valid");
-        let result = apply_reload(&handle, &config_path);
+        let result = apply_reload(&handle, std::slice::from_ref(&config_path));
         assert!(result.is_err());
 
         let snapshot = handle.read().expect("read lock").clone();
@@ -250,7 +282,7 @@ hidden_map_ids = []
         remember_identity(&config_path).expect("seed identity");
 
         write_config(&config_path, &base_toml("", 0));
-        let result = apply_reload(&handle, &config_path);
+        let result = apply_reload(&handle, std::slice::from_ref(&config_path));
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("validation"));
 
@@ -282,7 +314,7 @@ hidden_workshop_ids = []
 hidden_map_ids = []
 "#,
         );
-        let change = apply_reload(&handle, &config_path).expect("apply reload");
+        let change = apply_reload(&handle, std::slice::from_ref(&config_path)).expect("apply reload");
         assert!(change.live_applied.contains(&"sync_interval_secs"));
         assert!(change.requires_restart.contains(&"l4d2_server_dir"));
 
@@ -312,7 +344,7 @@ hidden_map_ids = []
         let handle = init_handle(initial);
         remember_identity(&config_path).expect("seed identity");
 
-        let change = apply_reload(&handle, &config_path).expect("second apply");
+        let change = apply_reload(&handle, std::slice::from_ref(&config_path)).expect("second apply");
         assert!(change.unchanged);
     }
 
@@ -359,7 +391,7 @@ hidden_map_ids = []
         let handle = init_handle(initial);
 
         let _watcher =
-            spawn_config_watcher(handle.clone(), config_path.clone()).expect("watcher start");
+            spawn_config_watcher(handle.clone(), vec![config_path.clone()]).expect("watcher start");
         std::thread::sleep(Duration::from_millis(200));
         write_config(&config_path, &base_toml("999", 300));
 
@@ -0,0 +1,197 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Stops reconnecting to a service that keeps failing (e.g. Steam maintenance).
+//!
+//! After `failure_threshold` consecutive failures the breaker opens and callers are
+//! refused immediately until `cooldown` has passed. The first caller after the
+//! cooldown is let through as a probe and everyone else is still refused while it
+//! runs: success closes the breaker, failure opens it for another cooldown, and a
+//! probe dropped without reporting lets the next caller probe instead.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+#[derive(Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    /// Probe let through after the cooldown that has not reported back yet.
+    probe: Option<u64>,
+    next_probe: u64,
+}
+
+/// Permission to make one call, returned by [`CircuitBreaker::check`]. Report the
+/// outcome with `record_success`/`record_failure`; dropping the probe of a half-open
+/// breaker without that frees the slot for the next caller.
+#[must_use]
+pub struct BreakerCall<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: Option<u64>,
+}
+
+impl Drop for BreakerCall<'_> {
+    fn drop(&mut self) {
+        let Some(probe) = self.probe else {
+            return;
+        };
+        let mut state = self.breaker.state.lock().expect("circuit breaker lock poisoned");
+        if state.probe == Some(probe) {
+            state.probe = None;
+        }
+    }
+}
+
+/// Returned instead of attempting a call while the breaker is open.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("temporarily unavailable after repeated failures; retrying in {}s", retry_in.as_secs().max(1))]
+pub struct CircuitOpen {
+    pub retry_in: Duration,
+}
+
+/// Point-in-time view of a breaker for status reporting.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct CircuitBreakerStatus {
+    /// Whether calls are currently refused without being attempted (also while a
+    /// probe after the cooldown is in flight)
+    pub open: bool,
+    /// Failures since the last success
+    pub consecutive_failures: u32,
+    /// Failures in a row that open the breaker (0 disables it)
+    pub failure_threshold: u32,
+    /// Seconds until the next attempt is allowed, when open
+    pub retry_in_secs: Option<u64>,
+}
+
+impl CircuitBreaker {
+    /// A `failure_threshold` of 0 never opens the breaker.
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold,
+            cooldown,
+            state: Mutex::new(BreakerState::default()),
+        }
+    }
+
+    /// Refuse the call while open. Once the cooldown has passed exactly one caller
+    /// gets through as the probe; the rest are refused until it reports back.
+    pub fn check(&self) -> Result<BreakerCall<'_>, CircuitOpen> {
+        let mut state = self.state.lock().expect("circuit breaker lock poisoned");
+        let Some(until) = state.open_until else {
+            return Ok(BreakerCall { breaker: self, probe: None });
+        };
+        if let Some(retry_in) = until.checked_duration_since(Instant::now())
+            && !retry_in.is_zero()
+        {
+            return Err(CircuitOpen { retry_in });
+        }
+        if state.probe.is_some() {
+            return Err(CircuitOpen { retry_in: Duration::ZERO });
+        }
+        let probe = state.next_probe;
+        state.next_probe = state.next_probe.wrapping_add(1);
+        state.probe = Some(probe);
+        Ok(BreakerCall { breaker: self, probe: Some(probe) })
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.state.lock().expect("circuit breaker lock poisoned");
+        state.consecutive_failures = 0;
+        state.open_until = None;
+        state.probe = None;
+    }
+
+    /// Count a failure; returns true when this failure opened the breaker.
+    pub fn record_failure(&self) -> bool {
+        let mut state = self.state.lock().expect("circuit breaker lock poisoned");
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+        state.probe = None;
+        if self.failure_threshold == 0 || state.consecutive_failures < self.failure_threshold {
+            return false;
+        }
+        state.open_until = Some(Instant::now() + self.cooldown);
+        true
+    }
+
+    pub fn status(&self) -> CircuitBreakerStatus {
+        let state = self.state.lock().expect("circuit breaker lock poisoned");
+        let retry_in = state
+            .open_until
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero());
+        CircuitBreakerStatus {
+            open: retry_in.is_some() || state.probe.is_some(),
+            consecutive_failures: state.consecutive_failures,
+            failure_threshold: self.failure_threshold,
+            retry_in_secs: retry_in.map(|remaining| remaining.as_secs().max(1)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_after_threshold_and_short_circuits_until_cooldown_ends() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        assert!(breaker.check().is_ok());
+        assert!(breaker.record_failure());
+
+        let open = breaker.check().err().unwrap();
+        assert!(open.retry_in <= Duration::from_secs(60));
+        let status = breaker.status();
+        assert!(status.open);
+        assert_eq!(status.consecutive_failures, 3);
+
+        breaker.record_success();
+        assert!(breaker.check().is_ok());
+        assert!(!breaker.status().open);
+        assert_eq!(breaker.status().consecutive_failures, 0);
+    }
+
+    #[test]
+    fn lets_one_probe_through_after_the_cooldown() {
+        // A zero cooldown makes the breaker half-open as soon as it opens.
+        let breaker = CircuitBreaker::new(1, Duration::ZERO);
+        assert!(breaker.record_failure());
+
+        let probe = breaker.check().unwrap();
+        assert!(breaker.check().is_err());
+        assert!(breaker.status().open);
+
+        // Failure re-opens; the next caller after the cooldown becomes the new probe.
+        assert!(breaker.record_failure());
+        drop(probe);
+        let probe = breaker.check().unwrap();
+        assert!(breaker.check().is_err());
+
+        // A probe dropped without reporting frees the slot.
+        drop(probe);
+        let probe = breaker.check().unwrap();
+        breaker.record_success();
+        drop(probe);
+        assert!(breaker.check().is_ok());
+        assert!(breaker.check().is_ok());
+        assert!(!breaker.status().open);
+    }
+
+    #[test]
+    fn zero_threshold_never_opens() {
+        let breaker = CircuitBreaker::new(0, Duration::from_secs(60));
+        for _ in 0..10 {
+            assert!(!breaker.record_failure());
+        }
+        assert!(breaker.check().is_ok());
+    }
+}
@@ -1,11 +1,17 @@
 // SPDX-License-Identifier: GPL-3.0-only
-use reqwest::header::LOCATION;
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, CONTENT_LENGTH, CONTENT_TYPE, ETAG, IF_MODIFIED_SINCE,
+    IF_NONE_MATCH, LAST_MODIFIED, LOCATION,
+};
 use reqwest::redirect::Policy;
-use reqwest::{Client, StatusCode, Url};
+use reqwest::{Client, ClientBuilder, Method, NoProxy, Proxy, RequestBuilder, StatusCode, Url};
+use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tracing::{info, warn};
 
+use crate::config::Config;
 use crate::utils::validate_url_resolved;
 
 const MAX_REDIRECTS: usize = 5;
@@ -15,77 +21,416 @@ const PROGRESS_MIN_BYTES: u64 = 256 * 1024;
 /// Optional download progress hook: `(bytes_downloaded, content_length_hint)`.
 pub type DownloadProgressCallback = Arc<dyn Fn(u64, Option<u64>) + Send + Sync>;
 
+/// Extra check run on every URL a client requests, redirect targets included.
+pub type UrlPolicy = Arc<dyn Fn(&str) -> anyhow::Result<()> + Send + Sync>;
+
+/// Outbound HTTP settings from config, passed to every client the daemon builds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpSettings {
+    pub proxy: ProxyConfig,
+    pub user_agent: String,
+    pub timeouts: HttpTimeouts,
+    /// Per-download throughput cap in bytes per second; 0 is unlimited.
+    pub max_bytes_per_sec: u64,
+}
+
+impl HttpSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            proxy: ProxyConfig::from_config(config),
+            user_agent: user_agent(config),
+            timeouts: HttpTimeouts::from_config(config),
+            max_bytes_per_sec: config.max_download_bytes_per_sec,
+        }
+    }
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            proxy: ProxyConfig::default(),
+            user_agent: default_user_agent(),
+            timeouts: HttpTimeouts::default(),
+            max_bytes_per_sec: 0,
+        }
+    }
+}
+
+/// Outbound HTTP(S) proxies for downloads (Steam CDN included) and backend sync.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProxyConfig {
+    pub http: Option<String>,
+    pub https: Option<String>,
+}
+
+impl ProxyConfig {
+    /// Proxies from config, falling back to the standard `HTTP_PROXY`/`HTTPS_PROXY` env vars.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            http: config.http_proxy.clone().or_else(|| proxy_env("HTTP_PROXY")),
+            https: config.https_proxy.clone().or_else(|| proxy_env("HTTPS_PROXY")),
+        }
+    }
+
+    /// Replace the builder's system proxy detection with these explicit proxies.
+    /// `NO_PROXY` from the environment still applies.
+    pub fn apply(&self, builder: ClientBuilder) -> anyhow::Result<ClientBuilder> {
+        let mut builder = builder.no_proxy();
+        if let Some(url) = self.http.as_deref().filter(|u| !u.trim().is_empty()) {
+            let proxy = Proxy::http(url)
+                .map_err(|e| anyhow::anyhow!("Invalid http_proxy: {e}"))?
+                .no_proxy(NoProxy::from_env());
+            builder = builder.proxy(proxy);
+        }
+        if let Some(url) = self.https.as_deref().filter(|u| !u.trim().is_empty()) {
+            let proxy = Proxy::https(url)
+                .map_err(|e| anyhow::anyhow!("Invalid https_proxy: {e}"))?
+                .no_proxy(NoProxy::from_env());
+            builder = builder.proxy(proxy);
+        }
+        Ok(builder)
+    }
+}
+
+/// `User-Agent` for downloads and backend sync: `http_user_agent` when set,
+/// otherwise `KetherServerDaemon/<crate version>`.
+pub fn user_agent(config: &Config) -> String {
+    config
+        .http_user_agent
+        .clone()
+        .unwrap_or_else(default_user_agent)
+}
+
+/// Paces a single download to an average rate by sleeping between chunks.
+struct Throttle {
+    bytes_per_sec: u64,
+    started: Instant,
+}
+
+impl Throttle {
+    fn new(bytes_per_sec: u64) -> Option<Self> {
+        (bytes_per_sec > 0).then(|| Self {
+            bytes_per_sec,
+            started: Instant::now(),
+        })
+    }
+
+    /// Wait until `downloaded` bytes are no longer ahead of the allowed rate.
+    async fn pace(&self, downloaded: u64) {
+        let allowed_at = Duration::from_secs_f64(downloaded as f64 / self.bytes_per_sec as f64);
+        if let Some(wait) = allowed_at.checked_sub(self.started.elapsed()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+fn default_user_agent() -> String {
+    format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+}
+
+/// Connection setup and stall timeouts, independent of the overall request timeout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HttpTimeouts {
+    /// Give up on a host that does not accept the connection within this time.
+    pub connect: Duration,
+    /// Abort when no bytes arrive for this long, however long the whole download runs.
+    pub read: Duration,
+}
+
+impl HttpTimeouts {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            connect: Duration::from_secs(config.http_connect_timeout_secs),
+            read: Duration::from_secs(config.http_read_timeout_secs),
+        }
+    }
+}
+
+impl Default for HttpTimeouts {
+    /// The `http_connect_timeout_secs`/`http_read_timeout_secs` config defaults.
+    fn default() -> Self {
+        Self::from_config(&Config::default())
+    }
+}
+
+fn proxy_env(name: &str) -> Option<String> {
+    std::env::var(name)
+        .or_else(|_| std::env::var(name.to_lowercase()))
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Header names whose values are masked whenever download options are logged.
+const SENSITIVE_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "x-api-key",
+];
+
+/// Per-download request extras for mirrors behind basic auth or header checks.
+///
+/// Headers and credentials are only sent to the origin of the requested URL;
+/// they are dropped once a redirect leaves that origin.
+#[derive(Clone, Default)]
+pub struct DownloadOptions {
+    pub headers: HashMap<String, String>,
+    pub basic_auth: Option<(String, String)>,
+}
+
+impl DownloadOptions {
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty() && self.basic_auth.is_none()
+    }
+
+    /// Check that every header name and value is valid on the wire.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for (name, value) in &self.headers {
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| anyhow::anyhow!("Invalid header name '{name}'"))?;
+            HeaderValue::from_str(value)
+                .map_err(|_| anyhow::anyhow!("Invalid value for header '{name}'"))?;
+        }
+        Ok(())
+    }
+
+    fn apply(&self, mut request: RequestBuilder) -> RequestBuilder {
+        for (name, value) in &self.headers {
+            request = request.header(name.as_str(), value.as_str());
+        }
+        if let Some((username, password)) = &self.basic_auth {
+            request = request.basic_auth(username, Some(password));
+        }
+        request
+    }
+
+    /// Make the download conditional on the file having changed since `validators`
+    /// were recorded; an unchanged file then fails with [`NotModified`].
+    pub fn with_conditional(mut self, validators: &CacheValidators) -> Self {
+        if let Some(last_modified) = &validators.last_modified {
+            self.headers
+                .insert(IF_MODIFIED_SINCE.to_string(), last_modified.clone());
+        }
+        if let Some(etag) = &validators.etag {
+            self.headers.insert(IF_NONE_MATCH.to_string(), etag.clone());
+        }
+        self
+    }
+}
+
+impl fmt::Debug for DownloadOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut headers: Vec<(&str, &str)> = self
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                let sensitive = SENSITIVE_HEADERS
+                    .iter()
+                    .any(|s| name.eq_ignore_ascii_case(s));
+                (name.as_str(), if sensitive { "<redacted>" } else { value.as_str() })
+            })
+            .collect();
+        headers.sort_unstable();
+        f.debug_struct("DownloadOptions")
+            .field("headers", &headers)
+            .field(
+                "basic_auth",
+                &self.basic_auth.as_ref().map(|(user, _)| (user, "<redacted>")),
+            )
+            .finish()
+    }
+}
+
+/// What a `HEAD` request reports about a download before any body is fetched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrecheckResult {
+    pub status: StatusCode,
+    pub content_length: Option<u64>,
+    pub content_type: Option<String>,
+}
+
+impl PrecheckResult {
+    /// Reject downloads the `HEAD` already shows to be missing or too large.
+    ///
+    /// Any other non-success status is taken to mean the server does not handle
+    /// `HEAD` properly, and the `GET` is left to decide.
+    pub fn check(&self, max_download_size: u64) -> anyhow::Result<()> {
+        if self.status == StatusCode::NOT_FOUND || self.status == StatusCode::GONE {
+            return Err(anyhow::anyhow!("Request failed with status {} (HEAD precheck)", self.status));
+        }
+        if !self.status.is_success() {
+            return Ok(());
+        }
+        if let Some(content_length) = self.content_length
+            && content_length > max_download_size
+        {
+            return Err(anyhow::anyhow!(
+                "File size {} exceeds maximum download size {} bytes (HEAD precheck)",
+                content_length,
+                max_download_size
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// `Last-Modified` and `ETag` of a download, kept so a later re-download can ask
+/// the server whether the file changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheValidators {
+    pub last_modified: Option<String>,
+    pub etag: Option<String>,
+}
+
+impl CacheValidators {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            last_modified: header(LAST_MODIFIED),
+            etag: header(ETAG),
+        }
+    }
+}
+
+/// A conditional download was answered with `304 Not Modified`.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("Remote file is not modified since the last download")]
+pub struct NotModified;
+
+/// Something other than a regular file (e.g. a planted symlink) sits at the download target.
+#[derive(thiserror::Error, Debug)]
+#[error("Refusing to download to {}: it is not a regular file", .0.display())]
+pub struct UnsafeDownloadTarget(pub std::path::PathBuf);
+
+/// Create `path` for a download without ever writing through a symlink there.
+///
+/// A regular file left by an earlier attempt is replaced. Anything else at `path`,
+/// or an entry appearing between that removal and the exclusive create, fails with
+/// [`UnsafeDownloadTarget`].
+async fn create_download_file(path: &std::path::Path) -> anyhow::Result<tokio::fs::File> {
+    match tokio::fs::symlink_metadata(path).await {
+        Ok(metadata) if metadata.is_file() => tokio::fs::remove_file(path).await?,
+        Ok(_) => return Err(UnsafeDownloadTarget(path.to_path_buf()).into()),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+        Err(error) => return Err(error.into()),
+    }
+    tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .custom_flags(libc::O_NOFOLLOW)
+        .open(path)
+        .await
+        .map_err(|error| match error.kind() {
+            std::io::ErrorKind::AlreadyExists => UnsafeDownloadTarget(path.to_path_buf()).into(),
+            _ => anyhow::Error::from(error),
+        })
+}
+
 pub struct HttpClient {
     client: Client,
     max_retries: u32,
     max_download_size: u64,
     enforce_ssrf: bool,
+    /// Per-download throughput cap in bytes per second; 0 is unlimited.
+    max_bytes_per_sec: u64,
+    url_policy: Option<UrlPolicy>,
 }
 
 impl HttpClient {
-    pub fn new(max_download_size: u64) -> anyhow::Result<Self> {
-        Self::build(max_download_size, true)
+    pub fn new(max_download_size: u64, settings: &HttpSettings) -> anyhow::Result<Self> {
+        Self::build(max_download_size, true, settings)
     }
 
     /// Test helper: skip SSRF checks so loopback mock servers work.
     #[cfg(test)]
     pub fn new_insecure_for_tests(max_download_size: u64) -> anyhow::Result<Self> {
-        Self::build(max_download_size, false)
+        Self::build(max_download_size, false, &HttpSettings::default())
     }
 
-    fn build(max_download_size: u64, enforce_ssrf: bool) -> anyhow::Result<Self> {
-        let client = Client::builder()
-            .no_proxy()
-            .pool_max_idle_per_host(2)
-            // Large workshop VPKs can take well over 5 minutes on typical links.
-            .timeout(Duration::from_secs(3600))
-            .read_timeout(Duration::from_secs(120))
-            .redirect(Policy::none())
-            .no_gzip()
-            .no_brotli()
-            .no_deflate()
-            .user_agent(format!(
-                "{}/{}",
-                env!("CARGO_PKG_NAME"),
-                env!("CARGO_PKG_VERSION")
-            ))
-            .build()?;
-
+    fn build(
+        max_download_size: u64,
+        enforce_ssrf: bool,
+        settings: &HttpSettings,
+    ) -> anyhow::Result<Self> {
         Ok(Self {
-            client,
+            client: Self::build_client(settings)?,
             max_retries: 3,
             max_download_size,
             enforce_ssrf,
+            max_bytes_per_sec: settings.max_bytes_per_sec,
+            url_policy: None,
         })
     }
 
+    fn build_client(settings: &HttpSettings) -> anyhow::Result<Client> {
+        Ok(settings
+            .proxy
+            .apply(Client::builder())?
+            .pool_max_idle_per_host(2)
+            // Large workshop VPKs can take well over 5 minutes on typical links.
+            .timeout(Duration::from_secs(3600))
+            .connect_timeout(settings.timeouts.connect)
+            .read_timeout(settings.timeouts.read)
+            .redirect(Policy::none())
+            // Mirrors may serve archives with `Content-Encoding`; reqwest decodes it
+            // transparently so the size limit applies to the bytes actually written.
+            .gzip(true)
+            .brotli(true)
+            .deflate(true)
+            .user_agent(settings.user_agent.as_str())
+            .build()?)
+    }
+
+    /// Rebuild the connection pool with `settings`, keeping the size limit and URL policy.
+    pub fn with_http_settings(mut self, settings: &HttpSettings) -> anyhow::Result<Self> {
+        self.client = Self::build_client(settings)?;
+        self.max_bytes_per_sec = settings.max_bytes_per_sec;
+        Ok(self)
+    }
+
+    /// Refuse any request, including each redirect hop, whose URL `policy` rejects.
+    pub fn with_url_policy(mut self, policy: UrlPolicy) -> Self {
+        self.url_policy = Some(policy);
+        self
+    }
+
     pub async fn download_with_retry(
         &self,
         url: &str,
         output_path: &std::path::Path,
     ) -> anyhow::Result<()> {
-        self.download_with_retry_progress(url, output_path, None)
+        self.download_with_retry_progress(url, output_path, &DownloadOptions::default(), None)
             .await
+            .map(|_| ())
     }
 
+    /// Download `url` to `output_path`, returning the response's cache validators.
     pub async fn download_with_retry_progress(
         &self,
         url: &str,
         output_path: &std::path::Path,
+        options: &DownloadOptions,
         on_progress: Option<DownloadProgressCallback>,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<CacheValidators> {
         let mut last_error = None;
 
         for attempt in 1..=self.max_retries {
             match self
-                .download_once(url, output_path, on_progress.as_ref())
+                .download_once(url, output_path, options, on_progress.as_ref())
                 .await
             {
-                Ok(()) => {
+                Ok(validators) => {
                     if attempt > 1 {
                         info!(url = %url, attempt, "Download succeeded after retry");
                     }
-                    return Ok(());
+                    return Ok(validators);
                 }
                 Err(e) => {
                     let retryable = Self::is_retryable_error(&e);
@@ -110,11 +455,21 @@ impl HttpClient {
         &self,
         url: &str,
         output_path: &std::path::Path,
+        options: &DownloadOptions,
         on_progress: Option<&DownloadProgressCallback>,
-    ) -> anyhow::Result<()> {
-        info!(url = %url, path = %output_path.display(), "Starting download");
+    ) -> anyhow::Result<CacheValidators> {
+        if options.is_empty() {
+            info!(url = %url, path = %output_path.display(), "Starting download");
+        } else {
+            info!(url = %url, path = %output_path.display(), ?options, "Starting download");
+        }
 
-        let response = self.send_validated(url).await?;
+        let response = self.send_validated(url, options).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            info!(url = %url, "Remote file not modified; skipping download");
+            return Err(NotModified.into());
+        }
+        let validators = CacheValidators::from_headers(response.headers());
 
         let content_length = response.content_length();
         if let Some(content_length) = content_length
@@ -126,6 +481,10 @@ impl HttpClient {
                 self.max_download_size
             ));
         }
+        if let Some(content_length) = content_length {
+            let dir = output_path.parent().unwrap_or_else(|| std::path::Path::new("."));
+            crate::utils::check_sufficient_space(dir, content_length)?;
+        }
 
         use futures_util::StreamExt;
         use tokio::io::AsyncWriteExt;
@@ -136,7 +495,8 @@ impl HttpClient {
             .checked_sub(PROGRESS_MIN_INTERVAL)
             .unwrap_or_else(Instant::now);
         let mut last_report_bytes: u64 = 0;
-        let mut file = tokio::fs::File::create(output_path).await?;
+        let mut file = create_download_file(output_path).await?;
+        let throttle = Throttle::new(self.max_bytes_per_sec);
 
         if let Some(cb) = on_progress {
             cb(0, content_length);
@@ -147,6 +507,16 @@ impl HttpClient {
                 Ok(chunk) => chunk,
                 Err(error) => {
                     let _ = tokio::fs::remove_file(output_path).await;
+                    if let Some(expected) = content_length
+                        && downloaded < expected
+                    {
+                        return Err(anyhow::anyhow!(
+                            "Download truncated: received {} of {} bytes advertised by Content-Length: {}",
+                            downloaded,
+                            expected,
+                            error
+                        ));
+                    }
                     return Err(anyhow::anyhow!(
                         "Download stream failed after {} bytes: {}",
                         downloaded,
@@ -174,6 +544,10 @@ impl HttpClient {
                 ));
             }
 
+            if let Some(throttle) = &throttle {
+                throttle.pace(downloaded).await;
+            }
+
             if let Some(cb) = on_progress {
                 let bytes_delta = downloaded.saturating_sub(last_report_bytes);
                 let due = last_report_at.elapsed() >= PROGRESS_MIN_INTERVAL
@@ -188,22 +562,77 @@ impl HttpClient {
 
         file.flush().await?;
 
+        // A dropped connection can end the body early; keep the partial file out of
+        // the install path and let the retry loop fetch it again.
+        if let Some(expected) = content_length
+            && downloaded != expected
+        {
+            let _ = tokio::fs::remove_file(output_path).await;
+            return Err(anyhow::anyhow!(
+                "Download truncated: received {} of {} bytes advertised by Content-Length",
+                downloaded,
+                expected
+            ));
+        }
+
         if downloaded == 0 {
             let _ = tokio::fs::remove_file(output_path).await;
             return Err(anyhow::anyhow!("Download completed with 0 bytes"));
         }
 
+        if let Err(error) = self.unwrap_gzip_layer(output_path).await {
+            let _ = tokio::fs::remove_file(output_path).await;
+            return Err(error);
+        }
+
         if let Some(cb) = on_progress {
             cb(downloaded, content_length.or(Some(downloaded)));
         }
 
         info!(url = %url, path = %output_path.display(), size = downloaded, "Download completed");
+        Ok(validators)
+    }
+
+    /// Decompress a download that is still gzip after transport decoding.
+    ///
+    /// Some mirrors gzip a `.zip` on disk and then serve it with `Content-Encoding: gzip`
+    /// on top (or without the header at all), leaving a gzip stream where a map archive
+    /// is expected. One such layer is stripped in place; anything else is left alone.
+    async fn unwrap_gzip_layer(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        if let Some(size) = crate::utils::gunzip_in_place(path, self.max_download_size).await? {
+            info!(path = %path.display(), size, "Decompressed gzip-wrapped download");
+        }
         Ok(())
     }
 
+    /// Send a `HEAD` for `url` (following redirects with the same SSRF checks as a
+    /// download) and report its status and headers without judging them.
+    pub async fn precheck(
+        &self,
+        url: &str,
+        options: &DownloadOptions,
+    ) -> anyhow::Result<PrecheckResult> {
+        let response = self.send_following_redirects(Method::HEAD, url, options).await?;
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(str::trim)
+        };
+        // Read the header itself: a HEAD body is always empty, whatever the length says.
+        let content_length = header(CONTENT_LENGTH).and_then(|value| value.parse().ok());
+        let content_type = header(CONTENT_TYPE).map(str::to_string);
+        Ok(PrecheckResult {
+            status: response.status(),
+            content_length,
+            content_type,
+        })
+    }
+
     /// GET with SSRF + redirect re-validation, returning response body as text.
     pub async fn get_text(&self, url: &str) -> anyhow::Result<String> {
-        let response = self.send_validated(url).await?;
+        let response = self.send_validated(url, &DownloadOptions::default()).await?;
         if let Some(content_length) = response.content_length()
             && content_length > self.max_download_size
         {
@@ -231,18 +660,55 @@ impl HttpClient {
         String::from_utf8(body).map_err(|e| anyhow::anyhow!("Response is not valid UTF-8: {e}"))
     }
 
-    async fn send_validated(&self, url: &str) -> anyhow::Result<reqwest::Response> {
+    async fn send_validated(
+        &self,
+        url: &str,
+        options: &DownloadOptions,
+    ) -> anyhow::Result<reqwest::Response> {
+        let response = self.send_following_redirects(Method::GET, url, options).await?;
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND {
+            return Err(anyhow::anyhow!("Request failed with status 404 Not Found"));
+        }
+        // Fail-fast on other client errors (do not retry as transient).
+        if status.is_client_error() {
+            return Err(anyhow::anyhow!(
+                "Request failed with client error status {status}"
+            ));
+        }
+        response.error_for_status_ref()?;
+        Ok(response)
+    }
+
+    /// Send `method` to `url`, re-validating every redirect target against SSRF rules.
+    async fn send_following_redirects(
+        &self,
+        method: Method,
+        url: &str,
+        options: &DownloadOptions,
+    ) -> anyhow::Result<reqwest::Response> {
         let mut current_url = url.to_string();
+        let origin = Url::parse(url).ok().map(|u| u.origin());
 
         for hop in 0..=MAX_REDIRECTS {
+            if let Some(policy) = &self.url_policy {
+                policy(&current_url)?;
+            }
             if self.enforce_ssrf || hop > 0 {
                 validate_url_resolved(&current_url).await?;
             }
 
-            let candidate = self.client.get(&current_url).send().await?;
+            let mut request = self.client.request(method.clone(), &current_url);
+            let same_origin = origin.is_some()
+                && Url::parse(&current_url).ok().map(|u| u.origin()) == origin;
+            if same_origin {
+                request = options.apply(request);
+            }
+            let candidate = request.send().await?;
             let status = candidate.status();
 
-            if status.is_redirection() {
+            // A 304 answers a conditional request and carries no `Location`.
+            if status.is_redirection() && status != StatusCode::NOT_MODIFIED {
                 if hop == MAX_REDIRECTS {
                     return Err(anyhow::anyhow!(
                         "Too many redirects while requesting {url} (limit {MAX_REDIRECTS})"
@@ -263,16 +729,6 @@ impl HttpClient {
                 continue;
             }
 
-            if status == StatusCode::NOT_FOUND {
-                return Err(anyhow::anyhow!("Request failed with status 404 Not Found"));
-            }
-            // Fail-fast on other client errors (do not retry as transient).
-            if status.is_client_error() {
-                return Err(anyhow::anyhow!(
-                    "Request failed with client error status {status}"
-                ));
-            }
-            candidate.error_for_status_ref()?;
             return Ok(candidate);
         }
 
@@ -281,6 +737,9 @@ impl HttpClient {
 
     /// True when the error is worth retrying (transient network / server faults).
     pub(crate) fn is_retryable_error(error: &anyhow::Error) -> bool {
+        if error.is::<NotModified>() || error.is::<UnsafeDownloadTarget>() {
+            return false;
+        }
         let message = error.to_string();
         if message.contains("404")
             || message.contains("client error")
@@ -289,12 +748,30 @@ impl HttpClient {
             || message.contains("internal")
             || message.contains("Invalid URL")
             || message.contains("scheme")
+            || message.contains("Insufficient disk space")
+            || message.contains("allowed_download_hosts")
+            || message.contains("blocked by policy")
         {
             return false;
         }
         true
     }
 
+    pub fn max_download_size(&self) -> u64 {
+        self.max_download_size
+    }
+
+    /// Apply the URL policy and SSRF checks a request to `url` would get, without sending one.
+    pub async fn validate_target(&self, url: &str) -> anyhow::Result<()> {
+        if let Some(policy) = &self.url_policy {
+            policy(url)?;
+        }
+        if self.enforce_ssrf {
+            validate_url_resolved(url).await?;
+        }
+        Ok(())
+    }
+
     pub fn client(&self) -> &Client {
         &self.client
     }
@@ -302,7 +779,7 @@ impl HttpClient {
 
 impl Default for HttpClient {
     fn default() -> Self {
-        Self::new(100 * 1024 * 1024).expect("Failed to create HTTP client") // 100MB default
+        Self::new(100 * 1024 * 1024, &HttpSettings::default()).expect("Failed to create HTTP client") // 100MB default
     }
 }
 
@@ -336,6 +813,25 @@ mod tests {
         assert_eq!(content, "test file content");
     }
 
+    #[tokio::test]
+    async fn test_download_refuses_symlink_at_target() {
+        let http = acquire_http_test_lock().await;
+        let client = HttpClient::new_insecure_for_tests(100 * 1024 * 1024).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let victim = temp_dir.path().join("victim.txt");
+        std::fs::write(&victim, "keep me").unwrap();
+        let output_path = temp_dir.path().join("downloaded.zip");
+        std::os::unix::fs::symlink(&victim, &output_path).unwrap();
+
+        let error = client
+            .download_with_retry(&http.url("/test.zip"), &output_path)
+            .await
+            .unwrap_err();
+        assert!(error.is::<UnsafeDownloadTarget>(), "{error:#}");
+        assert_eq!(std::fs::read_to_string(&victim).unwrap(), "keep me");
+        assert!(std::fs::symlink_metadata(&output_path).unwrap().file_type().is_symlink());
+    }
+
     #[tokio::test]
     async fn test_download_reports_progress() {
         let http = acquire_http_test_lock().await;
@@ -351,7 +847,12 @@ mod tests {
         });
 
         let result = client
-            .download_with_retry_progress(&url, &output_path, Some(on_progress))
+            .download_with_retry_progress(
+                &url,
+                &output_path,
+                &DownloadOptions::default(),
+                Some(on_progress),
+            )
             .await;
         assert!(result.is_ok());
         let captured = reports.lock().unwrap().clone();
@@ -359,6 +860,104 @@ mod tests {
         assert_eq!(captured.last().unwrap().0, b"test file content".len() as u64);
     }
 
+    #[tokio::test]
+    async fn download_is_throttled_to_bandwidth_limit() {
+        const BODY_LEN: usize = 48 * 1024;
+        const LIMIT: u64 = 32 * 1024;
+
+        let app = Router::new().route("/big.zip", get(|| async { vec![7u8; BODY_LEN] }));
+        let addr = crate::test_helpers::spawn_test_server(app).await;
+
+        let settings = HttpSettings {
+            max_bytes_per_sec: LIMIT,
+            ..HttpSettings::default()
+        };
+        let client = HttpClient::new_insecure_for_tests(100 * 1024 * 1024)
+            .unwrap()
+            .with_http_settings(&settings)
+            .unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("big.zip");
+
+        let started = Instant::now();
+        client
+            .download_with_retry(&format!("http://{addr}/big.zip"), &output_path)
+            .await
+            .unwrap();
+        let elapsed = started.elapsed();
+
+        // 48 KiB at 32 KiB/s cannot finish in under 1.5s.
+        assert!(elapsed >= Duration::from_millis(1500), "finished too fast: {elapsed:?}");
+        assert_eq!(std::fs::metadata(&output_path).unwrap().len(), BODY_LEN as u64);
+    }
+
+    #[tokio::test]
+    async fn test_download_refuses_content_length_beyond_free_space() {
+        let http = acquire_http_test_lock().await;
+        let client = HttpClient::new_insecure_for_tests(100 * 1024 * 1024).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("downloaded.zip");
+        let url = http.url("/test.zip");
+
+        let result = crate::utils::disk_space::with_available_space(
+            1,
+            client.download_with_retry(&url, &output_path),
+        )
+        .await;
+        let error = result.unwrap_err();
+        assert!(error.to_string().contains("Insufficient disk space"));
+        assert!(!HttpClient::is_retryable_error(&error));
+        assert!(!output_path.exists());
+    }
+
+    #[tokio::test]
+    async fn precheck_reports_head_headers_and_tolerates_unsupported_head() {
+        let router = Router::new()
+            .route(
+                "/map.zip",
+                get(|| async { "zip bytes" }).head(|| async {
+                    (
+                        [
+                            (axum::http::header::CONTENT_LENGTH, "2048"),
+                            (axum::http::header::CONTENT_TYPE, "application/zip"),
+                        ],
+                        AxumStatus::OK,
+                    )
+                }),
+            )
+            .route(
+                "/no-head.zip",
+                get(|| async { "zip bytes" }).head(|| async { AxumStatus::METHOD_NOT_ALLOWED }),
+            )
+            .route("/gone.zip", get(|| async { AxumStatus::NOT_FOUND }));
+        let addr = crate::test_helpers::spawn_test_server(router).await;
+        let client = HttpClient::new_insecure_for_tests(1024).unwrap();
+        let options = DownloadOptions::default();
+
+        let precheck = client
+            .precheck(&format!("http://{addr}/map.zip"), &options)
+            .await
+            .unwrap();
+        assert_eq!(precheck.status, StatusCode::OK);
+        assert_eq!(precheck.content_length, Some(2048));
+        assert_eq!(precheck.content_type.as_deref(), Some("application/zip"));
+        assert!(precheck.check(1024).is_err());
+        assert!(precheck.check(4096).is_ok());
+
+        let unsupported = client
+            .precheck(&format!("http://{addr}/no-head.zip"), &options)
+            .await
+            .unwrap();
+        assert_eq!(unsupported.status, StatusCode::METHOD_NOT_ALLOWED);
+        assert!(unsupported.check(1024).is_ok());
+
+        let missing = client
+            .precheck(&format!("http://{addr}/gone.zip"), &options)
+            .await
+            .unwrap();
+        assert!(missing.check(1024).is_err());
+    }
+
     #[tokio::test]
     async fn test_download_404_error() {
         let http = acquire_http_test_lock().await;
@@ -415,7 +1014,7 @@ mod tests {
 
     #[tokio::test]
     async fn download_rejects_private_literal_url() {
-        let client = HttpClient::new(1024 * 1024).unwrap();
+        let client = HttpClient::new(1024 * 1024, &HttpSettings::default()).unwrap();
         let temp_dir = TempDir::new().unwrap();
         let output_path = temp_dir.path().join("out.bin");
         let result = client
@@ -426,8 +1025,6 @@ mod tests {
 
     #[tokio::test]
     async fn download_rejects_redirect_to_private_ip() {
-        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-        let addr = listener.local_addr().unwrap();
         let app = Router::new().route(
             "/redir",
             get(|| async {
@@ -441,9 +1038,7 @@ mod tests {
                     .unwrap()
             }),
         );
-        tokio::spawn(async move {
-            axum::serve(listener, app).await.unwrap();
-        });
+        let addr = crate::test_helpers::spawn_test_server(app).await;
 
         // Allow the loopback first hop (test client), but redirect re-validation must fail.
         let client = HttpClient::new_insecure_for_tests(1024 * 1024).unwrap();
@@ -461,4 +1056,312 @@ mod tests {
         );
         assert!(!output_path.exists());
     }
+
+    #[tokio::test]
+    async fn download_rejects_redirect_to_host_refused_by_url_policy() {
+        let app = Router::new().route(
+            "/redir",
+            get(|| async {
+                Response::builder()
+                    .status(AxumStatus::FOUND)
+                    .header(
+                        axum::http::header::LOCATION,
+                        HeaderValue::from_static("http://93.184.216.34/map.zip"),
+                    )
+                    .body(Body::empty())
+                    .unwrap()
+            }),
+        );
+        let addr = crate::test_helpers::spawn_test_server(app).await;
+
+        let allowed_hosts = vec!["127.0.0.1".to_string()];
+        let client = HttpClient::new_insecure_for_tests(1024 * 1024)
+            .unwrap()
+            .with_url_policy(Arc::new(move |url| {
+                crate::utils::validate_download_host(url, &allowed_hosts)
+            }));
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("out.bin");
+        let url = format!("http://{addr}/redir");
+        let message = client
+            .download_with_retry(&url, &output_path)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            message.contains("'93.184.216.34' is not in allowed_download_hosts"),
+            "unexpected error: {message}"
+        );
+        assert!(!output_path.exists());
+    }
+
+    async fn spawn_basic_auth_mirror() -> std::net::SocketAddr {
+        let app = Router::new().route(
+            "/protected.zip",
+            get(|headers: axum::http::HeaderMap| async move {
+                let authorized = headers
+                    .get(axum::http::header::AUTHORIZATION)
+                    .is_some_and(|v| v == "Basic bWlycm9yOnNlY3JldA==");
+                if authorized {
+                    Response::new(Body::from("protected content"))
+                } else {
+                    Response::builder()
+                        .status(AxumStatus::UNAUTHORIZED)
+                        .body(Body::empty())
+                        .unwrap()
+                }
+            }),
+        );
+        crate::test_helpers::spawn_test_server(app).await
+    }
+
+    #[tokio::test]
+    async fn download_with_basic_auth_requires_credentials() {
+        let addr = spawn_basic_auth_mirror().await;
+        let client = HttpClient::new_insecure_for_tests(1024 * 1024).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("out.zip");
+        let url = format!("http://{addr}/protected.zip");
+
+        let result = client.download_with_retry(&url, &output_path).await;
+        assert!(result.unwrap_err().to_string().contains("401"));
+        assert!(!output_path.exists());
+
+        let options = DownloadOptions {
+            headers: HashMap::from([("Referer".to_string(), "https://mirror.example".to_string())]),
+            basic_auth: Some(("mirror".to_string(), "secret".to_string())),
+        };
+        client
+            .download_with_retry_progress(&url, &output_path, &options, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&output_path).unwrap(),
+            "protected content"
+        );
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder =
+            flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    async fn spawn_gzip_mirror() -> std::net::SocketAddr {
+        let gzip_response = |body: Vec<u8>| {
+            Response::builder()
+                .header(axum::http::header::CONTENT_ENCODING, "gzip")
+                .body(Body::from(body))
+                .unwrap()
+        };
+        let app = Router::new()
+            .route(
+                "/encoded.zip",
+                get(move || async move { gzip_response(gzip(b"PK\x03\x04 map archive")) }),
+            )
+            .route(
+                "/double.zip",
+                get(move || async move {
+                    gzip_response(gzip(&gzip(b"PK\x03\x04 map archive")))
+                }),
+            );
+        crate::test_helpers::spawn_test_server(app).await
+    }
+
+    #[tokio::test]
+    async fn download_decodes_gzip_content_encoding() {
+        let addr = spawn_gzip_mirror().await;
+        let client = HttpClient::new_insecure_for_tests(1024 * 1024).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+
+        for route in ["encoded.zip", "double.zip"] {
+            let output_path = temp_dir.path().join(route);
+            client
+                .download_with_retry(&format!("http://{addr}/{route}"), &output_path)
+                .await
+                .unwrap();
+            assert_eq!(
+                std::fs::read(&output_path).unwrap(),
+                b"PK\x03\x04 map archive",
+                "{route}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn download_retries_and_fails_when_body_is_shorter_than_content_length() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(AtomicUsize::new(0));
+        let requests_server = Arc::clone(&requests);
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                requests_server.fetch_add(1, Ordering::SeqCst);
+                let mut request = [0u8; 1024];
+                let _ = socket.read(&mut request).await;
+                let _ = socket
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 1000\r\nConnection: close\r\n\r\npartial body")
+                    .await;
+                let _ = socket.shutdown().await;
+            }
+        });
+
+        let client = HttpClient::new_insecure_for_tests(1024 * 1024).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("truncated.zip");
+
+        let error = client
+            .download_with_retry(&format!("http://{addr}/map.zip"), &output_path)
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("Download truncated"), "{error}");
+        assert!(HttpClient::is_retryable_error(&error), "{error}");
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+        assert!(!output_path.exists());
+    }
+
+    #[test]
+    fn download_options_debug_redacts_credentials() {
+        let options = DownloadOptions {
+            headers: HashMap::from([
+                ("Cookie".to_string(), "session=abc".to_string()),
+                ("Referer".to_string(), "https://mirror.example".to_string()),
+            ]),
+            basic_auth: Some(("mirror".to_string(), "secret".to_string())),
+        };
+        let rendered = format!("{options:?}");
+        assert!(rendered.contains("https://mirror.example"));
+        assert!(rendered.contains("mirror"));
+        assert!(!rendered.contains("session=abc"));
+        assert!(!rendered.contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn download_routes_through_configured_proxy() {
+        // Minimal forward proxy: answers every absolute-form request itself.
+        let app = Router::new().fallback(|uri: axum::http::Uri| async move {
+            format!("proxied {uri}")
+        });
+        let addr = crate::test_helpers::spawn_test_server(app).await;
+
+        let settings = HttpSettings {
+            proxy: ProxyConfig {
+                http: Some(format!("http://{addr}")),
+                https: None,
+            },
+            ..HttpSettings::default()
+        };
+        let client =
+            HttpClient::build(1024 * 1024, false, &settings).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("out.zip");
+        client
+            .download_with_retry("http://maps.example.invalid/map.zip", &output_path)
+            .await
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&output_path).unwrap(),
+            "proxied http://maps.example.invalid/map.zip"
+        );
+    }
+
+    #[tokio::test]
+    async fn download_sends_the_configured_user_agent() {
+        let app = Router::new().route(
+            "/map.zip",
+            get(|headers: HeaderMap| async move {
+                headers
+                    .get(reqwest::header::USER_AGENT)
+                    .and_then(|value| value.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string()
+            }),
+        );
+        let addr = crate::test_helpers::spawn_test_server(app).await;
+
+        let settings = HttpSettings {
+            user_agent: "MirrorFriendly/1.0".to_string(),
+            ..HttpSettings::default()
+        };
+        let client = HttpClient::build(1024, false, &settings).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("out.zip");
+        client
+            .download_with_retry(&format!("http://{addr}/map.zip"), &output_path)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&output_path).unwrap(), "MirrorFriendly/1.0");
+    }
+
+    #[test]
+    fn invalid_proxy_url_is_rejected() {
+        let settings = HttpSettings {
+            proxy: ProxyConfig {
+                http: None,
+                https: Some("http://[::1".to_string()),
+            },
+            ..HttpSettings::default()
+        };
+        assert!(HttpClient::build(1024, true, &settings).is_err());
+    }
+
+    #[tokio::test]
+    async fn connect_timeout_fires_before_request_timeout() {
+        let settings = HttpSettings {
+            timeouts: HttpTimeouts {
+                connect: Duration::from_secs(1),
+                read: Duration::from_secs(120),
+            },
+            ..HttpSettings::default()
+        };
+        let client = HttpClient::build(1024, false, &settings).unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("out.zip");
+
+        // A listener that never accepts, with its backlog filled: further SYNs go
+        // unanswered, so only the connect timeout ends the attempt.
+        let socket = tokio::net::TcpSocket::new_v4().unwrap();
+        socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let listener = socket.listen(1).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut backlog = Vec::new();
+        for _ in 0..4 {
+            if let Ok(Ok(stream)) = tokio::time::timeout(
+                Duration::from_millis(200),
+                tokio::net::TcpStream::connect(addr),
+            )
+            .await
+            {
+                backlog.push(stream);
+            }
+        }
+
+        let started = Instant::now();
+        let error = client
+            .download_once(
+                &format!("http://{addr}/map.zip"),
+                &output_path,
+                &DownloadOptions::default(),
+                None,
+            )
+            .await
+            .unwrap_err();
+        let elapsed = started.elapsed();
+        let request_error = error
+            .chain()
+            .find_map(|e| e.downcast_ref::<reqwest::Error>())
+            .unwrap_or_else(|| panic!("expected a reqwest error, got {error:#}"));
+        assert!(request_error.is_connect(), "expected a connect error, got {error:#}");
+        assert!(request_error.is_timeout(), "expected a timeout, got {error:#}");
+        assert!(
+            elapsed >= Duration::from_secs(1) && elapsed < Duration::from_secs(10),
+            "connect attempt took {elapsed:?}"
+        );
+    }
 }
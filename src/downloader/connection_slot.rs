@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! A lazily established connection shared by concurrent callers and replaced when it dies.
+//!
+//! Setup runs under the slot lock, so callers arriving while a connection is being
+//! opened wait for it instead of each opening their own. Every connection carries a
+//! generation number: a caller that saw a connection fail asks to replace *that*
+//! generation, so several callers failing on the same dead connection trigger a
+//! single reconnect rather than tearing down each other's fresh one.
+
+use std::future::Future;
+
+use tokio::sync::Mutex;
+
+pub struct ConnectionSlot<C> {
+    current: Mutex<SlotState<C>>,
+}
+
+struct SlotState<C> {
+    connection: Option<C>,
+    generation: u64,
+}
+
+/// A connection handed out by the slot, tagged with the generation it belongs to.
+#[derive(Clone)]
+pub struct Leased<C> {
+    pub connection: C,
+    pub generation: u64,
+}
+
+impl<C: Clone> ConnectionSlot<C> {
+    pub fn new() -> Self {
+        Self {
+            current: Mutex::new(SlotState {
+                connection: None,
+                generation: 0,
+            }),
+        }
+    }
+
+    /// The cached connection, or a new one from `connect` when there is none.
+    /// A failed `connect` leaves the slot empty so the next call tries again.
+    pub async fn get_or_connect<F, Fut, E>(&self, connect: F) -> Result<Leased<C>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<C, E>>,
+    {
+        let mut state = self.current.lock().await;
+        if let Some(connection) = &state.connection {
+            return Ok(Leased {
+                connection: connection.clone(),
+                generation: state.generation,
+            });
+        }
+        Self::establish(&mut state, connect).await
+    }
+
+    /// Replace the connection of generation `failed` with a new one. When another
+    /// caller already replaced it, the newer connection is returned unchanged.
+    pub async fn reconnect<F, Fut, E>(&self, failed: u64, connect: F) -> Result<Leased<C>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<C, E>>,
+    {
+        let mut state = self.current.lock().await;
+        if state.generation == failed {
+            state.connection = None;
+        }
+        if let Some(connection) = &state.connection {
+            return Ok(Leased {
+                connection: connection.clone(),
+                generation: state.generation,
+            });
+        }
+        Self::establish(&mut state, connect).await
+    }
+
+    /// Drop the connection of generation `failed` so the next caller reconnects.
+    pub async fn evict(&self, failed: u64) {
+        let mut state = self.current.lock().await;
+        if state.generation == failed {
+            state.connection = None;
+        }
+    }
+
+    /// The cached connection without connecting.
+    pub async fn current(&self) -> Option<Leased<C>> {
+        let state = self.current.lock().await;
+        state.connection.clone().map(|connection| Leased {
+            connection,
+            generation: state.generation,
+        })
+    }
+
+    async fn establish<F, Fut, E>(state: &mut SlotState<C>, connect: F) -> Result<Leased<C>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<C, E>>,
+    {
+        let connection = connect().await?;
+        state.generation += 1;
+        state.connection = Some(connection.clone());
+        Ok(Leased {
+            connection,
+            generation: state.generation,
+        })
+    }
+}
+
+impl<C: Clone> Default for ConnectionSlot<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    /// Fake connector: fails while `failures` is positive, otherwise yields the attempt number.
+    async fn connect(attempts: &AtomicU32, failures: &AtomicU32) -> Result<u32, String> {
+        let attempt = attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        if failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok()
+        {
+            return Err(format!("connect attempt {attempt} failed"));
+        }
+        Ok(attempt)
+    }
+
+    #[tokio::test]
+    async fn failed_first_connect_is_retried_on_next_use() {
+        let slot = ConnectionSlot::new();
+        let attempts = AtomicU32::new(0);
+        let failures = AtomicU32::new(1);
+
+        let first = slot.get_or_connect(|| connect(&attempts, &failures)).await;
+        assert!(first.is_err());
+        assert!(slot.current().await.is_none());
+
+        let second = slot
+            .get_or_connect(|| connect(&attempts, &failures))
+            .await
+            .unwrap();
+        assert_eq!(second.connection, 2);
+        let cached = slot
+            .get_or_connect(|| connect(&attempts, &failures))
+            .await
+            .unwrap();
+        assert_eq!(cached.connection, 2);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn concurrent_callers_share_one_connection_setup() {
+        let slot = Arc::new(ConnectionSlot::new());
+        let attempts = Arc::new(AtomicU32::new(0));
+        let failures = Arc::new(AtomicU32::new(0));
+
+        let tasks: Vec<_> = (0..8)
+            .map(|_| {
+                let (slot, attempts, failures) =
+                    (Arc::clone(&slot), Arc::clone(&attempts), Arc::clone(&failures));
+                tokio::spawn(async move {
+                    slot.get_or_connect(|| connect(&attempts, &failures))
+                        .await
+                        .unwrap()
+                        .connection
+                })
+            })
+            .collect();
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), 1);
+        }
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn stale_failures_trigger_a_single_reconnect() {
+        let slot = ConnectionSlot::new();
+        let attempts = AtomicU32::new(0);
+        let failures = AtomicU32::new(0);
+        let dead = slot
+            .get_or_connect(|| connect(&attempts, &failures))
+            .await
+            .unwrap();
+
+        let fresh = slot
+            .reconnect(dead.generation, || connect(&attempts, &failures))
+            .await
+            .unwrap();
+        assert_eq!(fresh.connection, 2);
+        // A second caller that also saw the dead connection gets the fresh one.
+        let again = slot
+            .reconnect(dead.generation, || connect(&attempts, &failures))
+            .await
+            .unwrap();
+        assert_eq!(again.connection, 2);
+
+        slot.evict(dead.generation).await;
+        assert!(slot.current().await.is_some());
+        slot.evict(fresh.generation).await;
+        assert!(slot.current().await.is_none());
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+}
@@ -1,11 +1,14 @@
 // SPDX-License-Identifier: GPL-3.0-only
 pub mod traits;
 pub mod client;
+pub mod circuit_breaker;
+pub mod connection_slot;
+pub mod resolver;
 pub mod workshop;
 pub mod zip;
 pub mod steam;
 
 #[cfg(test)]
-mod test_lock;
+pub(crate) mod test_lock;
 
 
@@ -0,0 +1,297 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Turn share links into direct download URLs before a map is fetched.
+//!
+//! File hosts often hand out links to a preview page rather than the file. Each
+//! [`UrlResolver`] recognises one host's links and returns the URL that serves the
+//! bytes; URLs no resolver claims are downloaded as given.
+
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::Url;
+use std::sync::LazyLock;
+use tracing::info;
+
+use crate::downloader::client::{DownloadOptions, HttpClient};
+
+const GOOGLE_DRIVE_DOWNLOAD_URL: &str = "https://drive.google.com/uc";
+
+#[async_trait]
+pub trait UrlResolver: Send + Sync {
+    /// Whether this resolver handles `url`.
+    fn matches(&self, url: &Url) -> bool;
+
+    /// The direct download URL for `url`; may make requests through `client`.
+    async fn resolve(&self, url: &Url, client: &HttpClient) -> anyhow::Result<Url>;
+}
+
+/// Resolvers for the file hosts maps are commonly shared from.
+pub fn default_resolvers() -> Vec<Box<dyn UrlResolver>> {
+    vec![Box::new(DropboxResolver), Box::new(GoogleDriveResolver::default())]
+}
+
+/// Dropbox share links (`?dl=0`) show a preview page; `dl=1` serves the file.
+pub struct DropboxResolver;
+
+#[async_trait]
+impl UrlResolver for DropboxResolver {
+    fn matches(&self, url: &Url) -> bool {
+        matches!(url.host_str(), Some("dropbox.com" | "www.dropbox.com"))
+    }
+
+    async fn resolve(&self, url: &Url, _client: &HttpClient) -> anyhow::Result<Url> {
+        let mut direct = url.clone();
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(key, _)| key != "dl")
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        direct
+            .query_pairs_mut()
+            .clear()
+            .extend_pairs(pairs)
+            .append_pair("dl", "1");
+        Ok(direct)
+    }
+}
+
+/// Google Drive file links. Large files answer the download URL with a virus-scan
+/// warning page; the confirm form on that page leads to the actual file.
+pub struct GoogleDriveResolver {
+    download_url: String,
+}
+
+impl Default for GoogleDriveResolver {
+    fn default() -> Self {
+        Self {
+            download_url: GOOGLE_DRIVE_DOWNLOAD_URL.to_string(),
+        }
+    }
+}
+
+impl GoogleDriveResolver {
+    /// Test helper: request downloads from a local mock instead of Google.
+    #[cfg(test)]
+    fn with_download_url(download_url: String) -> Self {
+        Self { download_url }
+    }
+}
+
+#[async_trait]
+impl UrlResolver for GoogleDriveResolver {
+    fn matches(&self, url: &Url) -> bool {
+        matches!(url.host_str(), Some("drive.google.com" | "docs.google.com"))
+            && drive_file_id(url).is_some()
+    }
+
+    async fn resolve(&self, url: &Url, client: &HttpClient) -> anyhow::Result<Url> {
+        let id = drive_file_id(url)
+            .ok_or_else(|| anyhow::anyhow!("Google Drive URL has no file ID: {url}"))?;
+        let mut download = Url::parse(&self.download_url)?;
+        download
+            .query_pairs_mut()
+            .append_pair("export", "download")
+            .append_pair("id", &id);
+
+        // Small files are served straight away; only an HTML answer needs confirming.
+        let precheck = client.precheck(download.as_str(), &DownloadOptions::default()).await?;
+        let is_html = precheck
+            .content_type
+            .as_deref()
+            .is_some_and(|content_type| content_type.starts_with("text/html"));
+        if !is_html {
+            return Ok(download);
+        }
+
+        let page = client.get_text(download.as_str()).await?;
+        confirm_download_url(&page, &download).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Google Drive did not offer a download for file {id}; is it shared publicly?"
+            )
+        })
+    }
+}
+
+/// File ID from `/file/d/<id>/...`, `/open?id=<id>` or `/uc?id=<id>` links.
+fn drive_file_id(url: &Url) -> Option<String> {
+    let mut segments = url.path_segments()?;
+    let from_path = std::iter::from_fn(|| segments.next())
+        .skip_while(|segment| *segment != "d")
+        .nth(1)
+        .map(str::to_string);
+    from_path
+        .or_else(|| {
+            url.query_pairs()
+                .find(|(key, _)| key == "id")
+                .map(|(_, value)| value.into_owned())
+        })
+        .filter(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+}
+
+static FORM_ACTION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<form[^>]*id="download-form"[^>]*action="([^"]+)""#).unwrap()
+});
+static HIDDEN_INPUT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)<input[^>]*type="hidden"[^>]*name="([^"]+)"[^>]*value="([^"]*)""#).unwrap()
+});
+static CONFIRM_TOKEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"confirm=([0-9A-Za-z_-]+)").unwrap());
+
+/// The URL behind the "Download anyway" button of a Drive warning page.
+fn confirm_download_url(page: &str, page_url: &Url) -> Option<Url> {
+    if let Some(action) = FORM_ACTION.captures(page) {
+        let mut url = page_url.join(&html_unescape(&action[1])).ok()?;
+        {
+            let mut query = url.query_pairs_mut();
+            for input in HIDDEN_INPUT.captures_iter(page) {
+                query.append_pair(&html_unescape(&input[1]), &html_unescape(&input[2]));
+            }
+        }
+        return Some(url);
+    }
+
+    // Older pages link to the download URL with a confirm token instead of a form.
+    let token = CONFIRM_TOKEN.captures(page)?;
+    let mut url = page_url.clone();
+    url.query_pairs_mut().append_pair("confirm", &token[1]);
+    Some(url)
+}
+
+fn html_unescape(value: &str) -> String {
+    value
+        .replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+}
+
+/// Apply the first resolver that claims `url`; unclaimed URLs are returned unchanged.
+pub async fn resolve_download_url(
+    resolvers: &[Box<dyn UrlResolver>],
+    url: &str,
+    client: &HttpClient,
+) -> anyhow::Result<String> {
+    let Ok(parsed) = Url::parse(url) else {
+        return Ok(url.to_string());
+    };
+    let Some(resolver) = resolvers.iter().find(|resolver| resolver.matches(&parsed)) else {
+        return Ok(url.to_string());
+    };
+    let resolved = resolver.resolve(&parsed, client).await?;
+    if resolved != parsed {
+        client.validate_target(resolved.as_str()).await?;
+        info!(url = %url, resolved = %resolved, "Resolved share link to direct download URL");
+    }
+    Ok(resolved.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::Query, http::header, routing::get, Router};
+    use std::collections::HashMap;
+
+    async fn resolve(url: &str) -> String {
+        let client = HttpClient::new_insecure_for_tests(1024 * 1024).unwrap();
+        resolve_download_url(&default_resolvers(), url, &client).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn dropbox_share_links_are_rewritten_to_direct_downloads() {
+        assert_eq!(
+            resolve("https://www.dropbox.com/scl/fi/abc/map.zip?rlkey=xyz&dl=0").await,
+            "https://www.dropbox.com/scl/fi/abc/map.zip?rlkey=xyz&dl=1"
+        );
+        assert_eq!(
+            resolve("https://dropbox.com/s/abc/map.vpk").await,
+            "https://dropbox.com/s/abc/map.vpk?dl=1"
+        );
+        // Other hosts pass through untouched.
+        assert_eq!(
+            resolve("https://example.com/map.zip?dl=0").await,
+            "https://example.com/map.zip?dl=0"
+        );
+    }
+
+    #[test]
+    fn drive_file_id_is_read_from_share_and_download_links() {
+        let id = |url: &str| drive_file_id(&Url::parse(url).unwrap());
+        assert_eq!(
+            id("https://drive.google.com/file/d/1AbC-d_9/view?usp=sharing").as_deref(),
+            Some("1AbC-d_9")
+        );
+        assert_eq!(id("https://drive.google.com/open?id=XYZ").as_deref(), Some("XYZ"));
+        assert_eq!(id("https://drive.google.com/uc?export=download&id=XYZ").as_deref(), Some("XYZ"));
+        assert_eq!(id("https://drive.google.com/drive/folders"), None);
+    }
+
+    #[tokio::test]
+    async fn drive_warning_page_is_confirmed_to_the_download_form_target() {
+        let app = Router::new()
+            .route(
+                "/uc",
+                get(|Query(query): Query<HashMap<String, String>>| async move {
+                    assert_eq!(query.get("id").map(String::as_str), Some("FILE123"));
+                    (
+                        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+                        r#"<html><body>Google Drive can't scan this file for viruses.
+<form id="download-form" action="/download" method="get">
+<input type="submit" value="Download anyway"/>
+<input type="hidden" name="id" value="FILE123">
+<input type="hidden" name="export" value="download">
+<input type="hidden" name="confirm" value="t">
+<input type="hidden" name="uuid" value="0f-42">
+</form></body></html>"#,
+                    )
+                }),
+            )
+            .route("/download", get(|| async { "PK\x03\x04" }));
+        let addr = crate::test_helpers::spawn_test_server(app).await;
+
+        let client = HttpClient::new_insecure_for_tests(1024 * 1024).unwrap();
+        let resolvers: Vec<Box<dyn UrlResolver>> = vec![Box::new(
+            GoogleDriveResolver::with_download_url(format!("http://{addr}/uc")),
+        )];
+        let resolved = resolve_download_url(
+            &resolvers,
+            "https://drive.google.com/file/d/FILE123/view?usp=sharing",
+            &client,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            resolved,
+            format!("http://{addr}/download?id=FILE123&export=download&confirm=t&uuid=0f-42")
+        );
+    }
+
+    #[tokio::test]
+    async fn drive_files_served_directly_need_no_confirmation() {
+        let app = Router::new().route(
+            "/uc",
+            get(|| async { ([(header::CONTENT_TYPE, "application/zip")], "PK\x03\x04") }),
+        );
+        let addr = crate::test_helpers::spawn_test_server(app).await;
+
+        let client = HttpClient::new_insecure_for_tests(1024 * 1024).unwrap();
+        let resolver = GoogleDriveResolver::with_download_url(format!("http://{addr}/uc"));
+        let resolved = resolver
+            .resolve(&Url::parse("https://drive.google.com/open?id=SMALL").unwrap(), &client)
+            .await
+            .unwrap();
+
+        assert_eq!(resolved.as_str(), format!("http://{addr}/uc?export=download&id=SMALL"));
+    }
+
+    #[test]
+    fn legacy_confirm_link_is_appended_to_the_download_url() {
+        let page_url = Url::parse("https://drive.google.com/uc?export=download&id=OLD").unwrap();
+        let page = r#"<a id="uc-download-link" href="/uc?export=download&amp;confirm=AbC1&amp;id=OLD">Download anyway</a>"#;
+        assert_eq!(
+            confirm_download_url(page, &page_url).unwrap().as_str(),
+            "https://drive.google.com/uc?export=download&id=OLD&confirm=AbC1"
+        );
+        assert!(confirm_download_url("<html>Access denied</html>", &page_url).is_none());
+    }
+}
@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use chrono::{DateTime, TimeZone, Utc};
+use std::collections::HashSet;
 use std::time::Duration;
 use steam_vent::{
     Connection, ConnectionTrait, ServerList,
@@ -10,6 +11,7 @@ use steam_vent_proto_steam::{
     },
     steammessages_publishedfile_steamclient::{
         CPublishedFile_GetDetails_Request, CPublishedFile_GetDetails_Response,
+        PublishedFileDetails,
     },
 };
 use tracing::{info, warn};
@@ -20,6 +22,8 @@ const STEAM_DOWNLOAD_URL_RETRIES: u32 = 3;
 const STEAM_CONNECT_RETRY_LIMIT: u32 = 3;
 const STEAM_CONNECT_BACKOFF_BASE_SECS: u64 = 1;
 const STEAM_CONNECT_BACKOFF_MAX_SECS: u64 = 30;
+/// `EWorkshopFileType` of a Workshop collection.
+const WORKSHOP_FILE_TYPE_COLLECTION: u32 = 2;
 
 #[derive(thiserror::Error, Debug)]
 pub enum SteamError {
@@ -42,7 +46,29 @@ pub enum SteamError {
     NoDownloadUrl,
 }
 
+/// How a caller should react to a failed Steam request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SteamRetry {
+    /// The transport is gone: drop the cached connection and retry on a new one.
+    Reconnect,
+    /// Transient failure on a usable connection: retry after a backoff.
+    Retry,
+    /// Retrying cannot help (unknown item, no URL, Steam refused the request).
+    FailFast,
+}
+
 impl SteamError {
+    pub fn retry_action(&self) -> SteamRetry {
+        match self {
+            Self::ServerDiscovery(_) | Self::Connection(_) => SteamRetry::Reconnect,
+            Self::Network(_) if self.is_connection_error() => SteamRetry::Reconnect,
+            Self::Network(_) => SteamRetry::Retry,
+            Self::WorkshopIdNotFound(_) | Self::DownloadUrlFailed(_) | Self::NoDownloadUrl => {
+                SteamRetry::FailFast
+            }
+        }
+    }
+
     pub fn is_connection_error(&self) -> bool {
         matches!(
             self,
@@ -66,11 +92,108 @@ pub struct WorkshopFileDetails {
     pub file_size: u64,
     /// Direct CDN URL when available (preferred over UFS hcontent lookup).
     pub file_url: Option<String>,
-    /// Workshop item title from Steam when available.
+    pub metadata: WorkshopMetadata,
+}
+
+/// Descriptive fields of a workshop item as published on Steam.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkshopMetadata {
+    /// Workshop item title when available.
     pub title: Option<String>,
+    /// SteamID64 of the item's creator.
+    pub creator: Option<u64>,
+    /// Workshop tags (e.g. "Campaigns", "Versus"), in Steam's order.
+    pub tags: Vec<String>,
 }
 
-fn parse_file_url(item: &steam_vent_proto_steam::steammessages_publishedfile_steamclient::PublishedFileDetails) -> Option<String> {
+impl WorkshopMetadata {
+    pub fn from_details(item: &PublishedFileDetails) -> Self {
+        Self {
+            title: parse_title(item),
+            creator: Some(item.creator()).filter(|&creator| creator != 0),
+            tags: item
+                .tags
+                .iter()
+                .map(|tag| tag.tag().trim())
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// A workshop item as seen while expanding collections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkshopNode {
+    /// A collection and the IDs of the items (or collections) it contains.
+    Collection { workshop_id: u64, children: Vec<u64> },
+    /// A downloadable item.
+    Item(WorkshopFileDetails),
+}
+
+/// Expand `roots` into the downloadable items they contain, following collections
+/// nested up to `max_depth` levels deep (the roots themselves are level 1).
+///
+/// `fetch` resolves one level of IDs at a time. Every ID is fetched at most once, so
+/// collections that contain themselves or each other still terminate; items listed
+/// by several collections appear once.
+pub async fn expand_collections<F, Fut, E>(
+    roots: &[u64],
+    max_depth: u32,
+    mut fetch: F,
+) -> Result<Vec<WorkshopFileDetails>, E>
+where
+    F: FnMut(Vec<u64>) -> Fut,
+    Fut: Future<Output = Result<Vec<WorkshopNode>, E>>,
+{
+    let mut visited = HashSet::new();
+    let mut level: Vec<u64> = roots.iter().copied().filter(|&id| visited.insert(id)).collect();
+    let mut items = Vec::new();
+    let mut depth = 1;
+
+    while !level.is_empty() {
+        let mut next = Vec::new();
+        for node in fetch(level).await? {
+            match node {
+                WorkshopNode::Item(details) => items.push(details),
+                WorkshopNode::Collection { workshop_id, .. } if depth >= max_depth => {
+                    warn!(workshop_id, max_depth, "Workshop collection nested too deeply; skipping its contents");
+                }
+                WorkshopNode::Collection { children, .. } => {
+                    next.extend(children.into_iter().filter(|&id| visited.insert(id)));
+                }
+            }
+        }
+        level = next;
+        depth += 1;
+    }
+
+    Ok(items)
+}
+
+/// Downloadable details of `item`, or `None` when Steam offers no way to fetch it.
+fn parse_file_details(item: &PublishedFileDetails) -> Option<WorkshopFileDetails> {
+    let workshop_id = item.publishedfileid();
+    let hcontent = item.hcontent_file();
+    if workshop_id == 0 {
+        return None;
+    }
+    let file_url = parse_file_url(item);
+    // Keep CDN URL entries even when hcontent is missing (UFS fallback needs hcontent).
+    if hcontent == 0 && file_url.is_none() {
+        return None;
+    }
+    Some(WorkshopFileDetails {
+        workshop_id,
+        hcontent,
+        time_updated: item.time_updated(),
+        file_size: item.file_size(),
+        file_url,
+        metadata: WorkshopMetadata::from_details(item),
+    })
+}
+
+fn parse_file_url(item: &PublishedFileDetails) -> Option<String> {
     if !item.has_file_url() {
         return None;
     }
@@ -82,7 +205,7 @@ fn parse_file_url(item: &steam_vent_proto_steam::steammessages_publishedfile_ste
     }
 }
 
-fn parse_title(item: &steam_vent_proto_steam::steammessages_publishedfile_steamclient::PublishedFileDetails) -> Option<String> {
+fn parse_title(item: &PublishedFileDetails) -> Option<String> {
     if !item.has_title() {
         return None;
     }
@@ -100,6 +223,16 @@ pub fn steam_time_to_utc(secs: u32) -> DateTime<Utc> {
         .unwrap_or_else(Utc::now)
 }
 
+/// Bound a Steam request even if steam-vent never resolves it (e.g. a silently dead socket).
+async fn with_steam_timeout<T>(
+    request: impl std::future::Future<Output = Result<T, steam_vent::NetworkError>>,
+) -> Result<T, SteamError> {
+    match tokio::time::timeout(STEAM_JOB_TIMEOUT + Duration::from_secs(5), request).await {
+        Ok(result) => result.map_err(SteamError::Network),
+        Err(_) => Err(SteamError::Network(steam_vent::NetworkError::Timeout)),
+    }
+}
+
 fn is_steam_timeout(error: &SteamError) -> bool {
     matches!(
         error,
@@ -163,39 +296,59 @@ impl SteamConnection {
 
         let mut req = CPublishedFile_GetDetails_Request::new();
         req.publishedfileids = workshop_ids.to_vec();
+        req.includetags = Some(true);
         req.appid = Some(550); // Left 4 Dead 2 app ID
 
-        let response: CPublishedFile_GetDetails_Response = self
-            .connection
-            .service_method(req)
-            .await
-            .map_err(SteamError::Network)?;
-
-        let mut details = Vec::new();
-        for item in &response.publishedfiledetails {
-            let workshop_id = item.publishedfileid();
-            let hcontent = item.hcontent_file();
-            if workshop_id == 0 {
-                continue;
-            }
-            let file_url = parse_file_url(item);
-            // Keep CDN URL entries even when hcontent is missing (UFS fallback needs hcontent).
-            if hcontent == 0 && file_url.is_none() {
-                continue;
-            }
-            details.push(WorkshopFileDetails {
-                workshop_id,
-                hcontent,
-                time_updated: item.time_updated(),
-                file_size: item.file_size(),
-                file_url,
-                title: parse_title(item),
-            });
-        }
+        let response: CPublishedFile_GetDetails_Response =
+            with_steam_timeout(self.connection.service_method(req)).await?;
+
+        let details: Vec<_> = response
+            .publishedfiledetails
+            .iter()
+            .filter_map(parse_file_details)
+            .collect();
 
         info!(returned = details.len(), "Got workshop file details");
         Ok(details)
     }
+
+    /// Fetch workshop items, listing the children of any collections among them.
+    pub async fn get_workshop_nodes(
+        &self,
+        workshop_ids: &[u64],
+    ) -> Result<Vec<WorkshopNode>, SteamError> {
+        if workshop_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut req = CPublishedFile_GetDetails_Request::new();
+        req.publishedfileids = workshop_ids.to_vec();
+        req.includetags = Some(true);
+        req.includechildren = Some(true);
+        req.appid = Some(550);
+
+        let response: CPublishedFile_GetDetails_Response =
+            with_steam_timeout(self.connection.service_method(req)).await?;
+
+        Ok(response
+            .publishedfiledetails
+            .iter()
+            .filter_map(|item| {
+                if item.file_type() != WORKSHOP_FILE_TYPE_COLLECTION {
+                    return parse_file_details(item).map(WorkshopNode::Item);
+                }
+                Some(WorkshopNode::Collection {
+                    workshop_id: item.publishedfileid(),
+                    children: item
+                        .children
+                        .iter()
+                        .map(|child| child.publishedfileid())
+                        .filter(|&id| id != 0)
+                        .collect(),
+                })
+            })
+            .collect())
+    }
     
     /// Get download URL from hcontent handle, with retries on transient Steam timeouts.
     pub async fn get_download_url(&self, hcontent: u64) -> Result<String, SteamError> {
@@ -230,11 +383,11 @@ impl SteamConnection {
         let mut req = CMsgClientUFSGetUGCDetails::new();
         req.set_hcontent(hcontent);
         
-        let response: CMsgClientUFSGetUGCDetailsResponse = self
-            .connection
-            .job::<CMsgClientUFSGetUGCDetails, CMsgClientUFSGetUGCDetailsResponse>(req)
-            .await
-            .map_err(SteamError::Network)?;
+        let response: CMsgClientUFSGetUGCDetailsResponse = with_steam_timeout(
+            self.connection
+                .job::<CMsgClientUFSGetUGCDetails, CMsgClientUFSGetUGCDetailsResponse>(req),
+        )
+        .await?;
         
         let eresult = response.eresult();
         if eresult != 1 {
@@ -261,6 +414,27 @@ impl SteamConnection {
 mod tests {
     use super::*;
     use std::io;
+    use steam_vent_proto_steam::steammessages_publishedfile_steamclient::published_file_details::Tag;
+
+    #[test]
+    fn metadata_maps_title_creator_and_tags() {
+        let mut item = PublishedFileDetails::new();
+        item.title = Some("  Dark Carnival Remix ".to_string());
+        item.creator = Some(76561197960287930);
+        for name in ["Campaigns", " ", "Versus"] {
+            let mut tag = Tag::new();
+            tag.tag = Some(name.to_string());
+            item.tags.push(tag);
+        }
+
+        let metadata = WorkshopMetadata::from_details(&item);
+        assert_eq!(metadata.title.as_deref(), Some("Dark Carnival Remix"));
+        assert_eq!(metadata.creator, Some(76561197960287930));
+        assert_eq!(metadata.tags, vec!["Campaigns".to_string(), "Versus".to_string()]);
+
+        let empty = WorkshopMetadata::from_details(&PublishedFileDetails::new());
+        assert_eq!(empty, WorkshopMetadata::default());
+    }
 
     #[test]
     fn connection_errors_are_recoverable() {
@@ -286,6 +460,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn retry_action_reconnects_retries_or_fails_fast() {
+        let cases = [
+            (
+                SteamError::Connection(steam_vent::ConnectionError::Aborted),
+                SteamRetry::Reconnect,
+            ),
+            (
+                SteamError::Network(steam_vent::NetworkError::EOF),
+                SteamRetry::Reconnect,
+            ),
+            (
+                SteamError::Network(steam_vent::NetworkError::InvalidHeader),
+                SteamRetry::Retry,
+            ),
+            (SteamError::WorkshopIdNotFound(123), SteamRetry::FailFast),
+            (SteamError::NoDownloadUrl, SteamRetry::FailFast),
+            (SteamError::DownloadUrlFailed(9), SteamRetry::FailFast),
+        ];
+
+        for (error, expected) in cases {
+            assert_eq!(error.retry_action(), expected, "{error}");
+        }
+    }
+
     #[test]
     fn application_and_protocol_errors_are_not_connection_errors() {
         let errors = [
@@ -301,4 +500,78 @@ mod tests {
             );
         }
     }
+
+    fn item(workshop_id: u64) -> WorkshopFileDetails {
+        WorkshopFileDetails {
+            workshop_id,
+            hcontent: workshop_id * 10,
+            time_updated: 0,
+            file_size: 0,
+            file_url: None,
+            metadata: WorkshopMetadata::default(),
+        }
+    }
+
+    /// Expand `roots` against a fake Workshop where `collections` maps IDs to children
+    /// and every other ID is a downloadable item. Returns the leaf hcontents and every
+    /// ID that was fetched.
+    async fn expand(
+        collections: &[(u64, &[u64])],
+        roots: &[u64],
+        max_depth: u32,
+    ) -> (Vec<u64>, Vec<u64>) {
+        let collections: std::collections::HashMap<u64, Vec<u64>> = collections
+            .iter()
+            .map(|(id, children)| (*id, children.to_vec()))
+            .collect();
+        let mut fetched = Vec::new();
+        let items = expand_collections(roots, max_depth, |ids: Vec<u64>| {
+            fetched.extend(ids.iter().copied());
+            let nodes = ids
+                .into_iter()
+                .map(|id| match collections.get(&id) {
+                    Some(children) => WorkshopNode::Collection {
+                        workshop_id: id,
+                        children: children.clone(),
+                    },
+                    None => WorkshopNode::Item(item(id)),
+                })
+                .collect();
+            async move { Ok::<_, SteamError>(nodes) }
+        })
+        .await
+        .unwrap();
+        (items.iter().map(|item| item.hcontent).collect(), fetched)
+    }
+
+    #[tokio::test]
+    async fn nested_collections_expand_to_their_leaf_items() {
+        // 1 -> {10, 2}; 2 -> {20, 21, 10}: item 10 is listed twice.
+        let (hcontents, fetched) = expand(&[(1, &[10, 2]), (2, &[20, 21, 10])], &[1], 3).await;
+        assert_eq!(hcontents, vec![100, 200, 210]);
+        assert_eq!(fetched, vec![1, 10, 2, 20, 21]);
+
+        // With depth 1 the nested collection is not followed.
+        let (hcontents, _) = expand(&[(1, &[10, 2]), (2, &[20, 21, 10])], &[1], 1).await;
+        assert!(hcontents.is_empty());
+        let (hcontents, _) = expand(&[(1, &[10, 2]), (2, &[20, 21, 10])], &[1], 2).await;
+        assert_eq!(hcontents, vec![100]);
+    }
+
+    #[tokio::test]
+    async fn cyclic_collections_terminate() {
+        // 1 -> {2, 30}; 2 -> {3, 1}; 3 -> {1, 2, 3, 31}
+        let (hcontents, fetched) = expand(
+            &[(1, &[2, 30]), (2, &[3, 1]), (3, &[1, 2, 3, 31])],
+            &[1],
+            u32::MAX,
+        )
+        .await;
+        let mut hcontents = hcontents;
+        hcontents.sort_unstable();
+        assert_eq!(hcontents, vec![300, 310]);
+        let mut fetched = fetched;
+        fetched.sort_unstable();
+        assert_eq!(fetched, vec![1, 2, 3, 30, 31]);
+    }
 }
@@ -39,6 +39,16 @@ async fn spawn_local_server() -> LocalTestServer {
             get(|| async { (StatusCode::OK, "map content") }),
         )
         .route("/download", get(|| async { (StatusCode::OK, "content") }))
+        .route(
+            "/error-page.zip",
+            get(|| async {
+                (
+                    StatusCode::OK,
+                    [("content-type", "text/html")],
+                    "<!DOCTYPE html><html><body>File not found</body></html>",
+                )
+            }),
+        )
         .route(
             "/large.zip",
             get(|| async { (StatusCode::OK, "x".repeat(32 * 1024)) }),
@@ -3,37 +3,66 @@ use async_trait::async_trait;
 use serde::Deserialize;
 use std::future::Future;
 use std::path::PathBuf;
-use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 use crate::downloader::{
-    client::HttpClient,
-    steam::{SteamConnection, SteamError, WorkshopFileDetails},
+    circuit_breaker::{CircuitBreaker, CircuitBreakerStatus},
+    client::{HttpClient, HttpSettings},
+    connection_slot::ConnectionSlot,
+    steam::{expand_collections, SteamConnection, SteamError, SteamRetry, WorkshopFileDetails},
     traits::Downloader,
 };
 
 const STEAM_PUBLISHED_FILE_DETAILS_URL: &str =
     "https://api.steampowered.com/ISteamRemoteStorage/GetPublishedFileDetails/v1/";
 const STEAM_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Attempts per Steam request when failures are transient.
+const STEAM_CALL_MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first Steam request retry; doubled for each further attempt.
+const STEAM_CALL_RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+/// Failed connection attempts in a row before Steam is treated as down.
+pub const DEFAULT_STEAM_BREAKER_THRESHOLD: u32 = 5;
+/// How long to refuse Steam work once the breaker has opened.
+pub const DEFAULT_STEAM_BREAKER_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// A Steam request that failed for good, keeping the [`SteamError`] for callers
+/// that need to tell a missing item from an unreachable Steam.
+#[derive(thiserror::Error, Debug)]
+#[error("{operation} failed: {source}")]
+pub struct SteamRequestError {
+    pub operation: &'static str,
+    #[source]
+    pub source: SteamError,
+}
 
 pub struct WorkshopDownloader {
     client: HttpClient,
+    http: HttpSettings,
     temp_dir: PathBuf,
     max_download_size_bytes: u64,
-    steam_connection: Arc<Mutex<Option<SteamConnection>>>,
+    steam_connection: ConnectionSlot<SteamConnection>,
+    steam_breaker: CircuitBreaker,
     /// Override for tests (local mock Steam Web API).
     published_file_details_url: String,
 }
 
 impl WorkshopDownloader {
-    pub fn new(temp_dir: PathBuf, max_download_size_bytes: u64) -> anyhow::Result<Self> {
+    pub fn new(
+        temp_dir: PathBuf,
+        max_download_size_bytes: u64,
+        http: &HttpSettings,
+    ) -> anyhow::Result<Self> {
         Ok(Self {
-            client: HttpClient::new(max_download_size_bytes)?,
+            client: HttpClient::new(max_download_size_bytes, http)?,
+            http: http.clone(),
             temp_dir,
             max_download_size_bytes,
-            steam_connection: Arc::new(Mutex::new(None)),
+            steam_connection: ConnectionSlot::new(),
+            steam_breaker: CircuitBreaker::new(
+                DEFAULT_STEAM_BREAKER_THRESHOLD,
+                DEFAULT_STEAM_BREAKER_COOLDOWN,
+            ),
             published_file_details_url: STEAM_PUBLISHED_FILE_DETAILS_URL.to_string(),
         })
     }
@@ -44,46 +73,57 @@ impl WorkshopDownloader {
         max_download_size_bytes: u64,
         published_file_details_url: String,
     ) -> anyhow::Result<Self> {
-        let mut downloader = Self::new(temp_dir, max_download_size_bytes)?;
+        let mut downloader = Self::new(temp_dir, max_download_size_bytes, &HttpSettings::default())?;
         downloader.published_file_details_url = published_file_details_url;
         Ok(downloader)
     }
 
-    async fn connect_steam(&self) -> anyhow::Result<SteamConnection> {
-        SteamConnection::connect_with_retry()
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to establish Steam connection: {}", e))
+    /// Rebuild the HTTP client with `http` (proxy etc.), keeping everything else.
+    pub fn with_http_settings(mut self, http: &HttpSettings) -> anyhow::Result<Self> {
+        self.client = self.client.with_http_settings(http)?;
+        self.http = http.clone();
+        Ok(self)
     }
-    
-    /// Get or initialize Steam connection (reconnects when missing).
-    async fn get_steam_connection(&self) -> anyhow::Result<SteamConnection> {
-        {
-            let guard = self.steam_connection.lock().await;
-            if let Some(connection) = guard.as_ref() {
-                return Ok(connection.clone());
-            }
-        }
 
-        let connection = self.connect_steam().await?;
-        let mut guard = self.steam_connection.lock().await;
-        if let Some(existing) = guard.as_ref() {
-            return Ok(existing.clone());
-        }
-        *guard = Some(connection.clone());
-        Ok(connection)
+    /// Open the Steam circuit breaker after `failure_threshold` failed connection
+    /// attempts in a row (0 disables it), refusing Steam work for `cooldown`.
+    pub fn with_steam_breaker(mut self, failure_threshold: u32, cooldown: Duration) -> Self {
+        self.steam_breaker = CircuitBreaker::new(failure_threshold, cooldown);
+        self
     }
 
-    async fn reset_steam_connection(&self) -> anyhow::Result<SteamConnection> {
-        warn!("Resetting Steam connection after request failure");
-        {
-            let mut guard = self.steam_connection.lock().await;
-            *guard = None;
+    pub fn steam_breaker_status(&self) -> CircuitBreakerStatus {
+        self.steam_breaker.status()
+    }
+
+    async fn connect_steam(&self) -> anyhow::Result<SteamConnection> {
+        let _call = match self.steam_breaker.check() {
+            Ok(call) => call,
+            Err(open) => {
+                debug!(retry_in_secs = open.retry_in.as_secs(), "Steam circuit breaker open; not connecting");
+                return Err(anyhow::Error::new(open).context("Steam temporarily unavailable"));
+            }
+        };
+        match SteamConnection::connect_with_retry().await {
+            Ok(connection) => {
+                self.steam_breaker.record_success();
+                Ok(connection)
+            }
+            Err(e) => {
+                if self.steam_breaker.record_failure() {
+                    let status = self.steam_breaker.status();
+                    warn!(
+                        error = %e,
+                        failures = status.consecutive_failures,
+                        retry_in_secs = ?status.retry_in_secs,
+                        "Steam circuit breaker opened after repeated connection failures"
+                    );
+                }
+                Err(anyhow::anyhow!("Failed to establish Steam connection: {}", e))
+            }
         }
-        let connection = self.connect_steam().await?;
-        let mut guard = self.steam_connection.lock().await;
-        *guard = Some(connection.clone());
-        Ok(connection)
     }
+    
 
     async fn call_with_reconnect<T, F, Fut>(
         &self,
@@ -94,45 +134,59 @@ impl WorkshopDownloader {
         F: Fn(SteamConnection) -> Fut,
         Fut: Future<Output = Result<T, SteamError>>,
     {
-        let connection = self.get_steam_connection().await?;
-        match operation(connection).await {
-            Ok(value) => Ok(value),
-            Err(error) if error.is_connection_error() => {
-                warn!(
-                    error = %error,
-                    operation = operation_name,
-                    "Steam call failed due to a dead connection, reconnecting"
-                );
-                let connection = self.reset_steam_connection().await?;
-                match operation(connection).await {
-                    Ok(value) => Ok(value),
-                    Err(error) => {
-                        if error.is_connection_error() {
-                            self.steam_connection.lock().await.take();
-                        }
-                        Err(anyhow::anyhow!(
-                            "{operation_name} failed after reconnect: {error}"
-                        ))
-                    }
+        let mut leased = self
+            .steam_connection
+            .get_or_connect(|| self.connect_steam())
+            .await?;
+        let mut attempt = 1;
+        loop {
+            let error = match operation(leased.connection.clone()).await {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+            let action = error.retry_action();
+            if action == SteamRetry::FailFast || attempt >= STEAM_CALL_MAX_ATTEMPTS {
+                if action == SteamRetry::Reconnect {
+                    self.steam_connection.evict(leased.generation).await;
+                }
+                return Err(SteamRequestError {
+                    operation: operation_name,
+                    source: error,
                 }
+                .into());
+            }
+
+            let delay = STEAM_CALL_RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+            warn!(
+                error = %error,
+                operation = operation_name,
+                attempt,
+                max_attempts = STEAM_CALL_MAX_ATTEMPTS,
+                delay_ms = delay.as_millis() as u64,
+                reconnect = action == SteamRetry::Reconnect,
+                "Transient Steam failure, retrying"
+            );
+            tokio::time::sleep(delay).await;
+            if action == SteamRetry::Reconnect {
+                warn!("Resetting Steam connection after request failure");
+                leased = self
+                    .steam_connection
+                    .reconnect(leased.generation, || self.connect_steam())
+                    .await?;
             }
-            Err(error) => Err(anyhow::anyhow!("{operation_name} failed: {error}")),
+            attempt += 1;
         }
     }
 
     /// Probe a cached Steam connection and evict it if its transport is dead.
     pub async fn health_check(&self) {
-        let connection = {
-            let guard = self.steam_connection.lock().await;
-            match guard.as_ref() {
-                Some(connection) => connection.clone(),
-                None => return,
-            }
+        let Some(leased) = self.steam_connection.current().await else {
+            return;
         };
 
         let result = tokio::time::timeout(
             STEAM_HEALTH_CHECK_TIMEOUT,
-            connection.get_workshop_file_details(&[0]),
+            leased.connection.get_workshop_file_details(&[0]),
         )
         .await;
 
@@ -140,14 +194,14 @@ impl WorkshopDownloader {
             Ok(Ok(_)) => debug!("Steam connection health check succeeded"),
             Ok(Err(error)) if error.is_connection_error() => {
                 warn!(error = %error, "Steam connection health check failed; evicting connection");
-                self.steam_connection.lock().await.take();
+                self.steam_connection.evict(leased.generation).await;
             }
             Err(_) => {
                 warn!(
                     timeout_secs = STEAM_HEALTH_CHECK_TIMEOUT.as_secs(),
                     "Steam connection health check timed out; evicting connection"
                 );
-                self.steam_connection.lock().await.take();
+                self.steam_connection.evict(leased.generation).await;
             }
             Ok(Err(error)) => {
                 debug!(
@@ -198,6 +252,18 @@ impl WorkshopDownloader {
             .await
     }
 
+    /// Look up a current download URL for `workshop_id` without downloading it.
+    pub async fn fresh_download_url(&self, workshop_id: u64) -> anyhow::Result<String> {
+        let details = self.get_workshop_file_details(&[workshop_id]).await?;
+        let detail = details
+            .iter()
+            .find(|d| d.workshop_id == workshop_id)
+            .ok_or_else(|| anyhow::anyhow!("Workshop item {workshop_id} not found on Steam"))?;
+        self.resolve_download_url(detail).await?.ok_or_else(|| {
+            anyhow::anyhow!("No download URL available for workshop item {workshop_id}")
+        })
+    }
+
     async fn resolve_download_url(
         &self,
         detail: &WorkshopFileDetails,
@@ -303,6 +369,23 @@ impl WorkshopDownloader {
         .await
     }
 
+    /// Downloadable items in collection `collection_id`, following nested collections
+    /// up to `max_depth` levels. A plain item ID expands to itself.
+    pub async fn expand_collection(
+        &self,
+        collection_id: u64,
+        max_depth: u32,
+    ) -> anyhow::Result<Vec<WorkshopFileDetails>> {
+        expand_collections(&[collection_id], max_depth, |ids| async move {
+            self.call_with_reconnect("Steam workshop collection request", |steam| {
+                let ids = ids.clone();
+                async move { steam.get_workshop_nodes(&ids).await }
+            })
+            .await
+        })
+        .await
+    }
+
     async fn download_from_url(
         &self,
         workshop_id: u64,
@@ -336,8 +419,14 @@ impl WorkshopDownloader {
             "Downloading workshop file"
         );
         
+        let guard = crate::utils::TempPathGuard::new(output_path.clone());
         self.client
-            .download_with_retry_progress(download_url, &output_path, on_progress)
+            .download_with_retry_progress(
+                download_url,
+                &output_path,
+                &crate::downloader::client::DownloadOptions::default(),
+                on_progress,
+            )
             .await
             .map_err(|e| anyhow::anyhow!("Failed to download file: {}", e))?;
         
@@ -347,7 +436,7 @@ impl WorkshopDownloader {
             "Workshop download completed"
         );
         
-        Ok(output_path)
+        Ok(guard.keep())
     }
 }
 
@@ -361,7 +450,8 @@ impl Downloader for WorkshopDownloader {
         // Delegate to ZIP downloader
         use crate::downloader::zip::ZipDownloader;
         let zip_downloader =
-            ZipDownloader::new(self.temp_dir.clone(), self.max_download_size_bytes).await?;
+            ZipDownloader::new(self.temp_dir.clone(), self.max_download_size_bytes, &self.http)
+                .await?;
         zip_downloader.download_zip(url).await
     }
 }
@@ -398,7 +488,7 @@ mod tests {
         let http = acquire_http_test_lock().await;
         let temp_dir = TempDir::new().unwrap();
         let downloader =
-            WorkshopDownloader::new(temp_dir.path().to_path_buf(), 100 * 1024 * 1024).unwrap();
+            WorkshopDownloader::new(temp_dir.path().to_path_buf(), 100 * 1024 * 1024, &HttpSettings::default()).unwrap();
         let url = http.url("/workshop.zip");
 
         let result = downloader.download_zip(&url).await;
@@ -408,7 +498,7 @@ mod tests {
     #[tokio::test]
     async fn test_download_workshop_requires_steam_connection() {
         let temp_dir = TempDir::new().unwrap();
-        let downloader = WorkshopDownloader::new(temp_dir.path().to_path_buf(), 100 * 1024 * 1024).unwrap();
+        let downloader = WorkshopDownloader::new(temp_dir.path().to_path_buf(), 100 * 1024 * 1024, &HttpSettings::default()).unwrap();
         
         let result = downloader.download_workshop(123456789).await;
         assert!(result.is_err());
@@ -2,21 +2,34 @@
 use async_trait::async_trait;
 use std::path::PathBuf;
 use tracing::info;
-use crate::downloader::{client::HttpClient, traits::Downloader};
+use crate::downloader::{
+    client::{
+        CacheValidators, DownloadOptions, DownloadProgressCallback, HttpClient, HttpSettings,
+        PrecheckResult, UrlPolicy,
+    },
+    resolver::{default_resolvers, resolve_download_url, UrlResolver},
+    traits::Downloader,
+};
 use uuid::Uuid;
 
 pub struct ZipDownloader {
     client: HttpClient,
     temp_dir: PathBuf,
+    resolvers: Vec<Box<dyn UrlResolver>>,
 }
 
 impl ZipDownloader {
-    pub async fn new(temp_dir: PathBuf, max_download_size_bytes: u64) -> anyhow::Result<Self> {
+    pub async fn new(
+        temp_dir: PathBuf,
+        max_download_size_bytes: u64,
+        http: &HttpSettings,
+    ) -> anyhow::Result<Self> {
         tokio::fs::create_dir_all(&temp_dir).await?;
 
         Ok(Self {
-            client: HttpClient::new(max_download_size_bytes)?,
+            client: HttpClient::new(max_download_size_bytes, http)?,
             temp_dir,
+            resolvers: default_resolvers(),
         })
     }
 
@@ -30,8 +43,28 @@ impl ZipDownloader {
         Ok(Self {
             client: HttpClient::new_insecure_for_tests(max_download_size_bytes)?,
             temp_dir,
+            resolvers: default_resolvers(),
         })
     }
+
+    /// Test helper: keep this downloader's staging directory but skip SSRF checks.
+    #[cfg(test)]
+    pub fn into_insecure_for_tests(mut self) -> Self {
+        self.client = HttpClient::new_insecure_for_tests(self.client.max_download_size()).unwrap();
+        self
+    }
+
+    /// Rebuild the HTTP client with `http` (proxy etc.), keeping limits and URL policy.
+    pub fn with_http_settings(mut self, http: &HttpSettings) -> anyhow::Result<Self> {
+        self.client = self.client.with_http_settings(http)?;
+        Ok(self)
+    }
+
+    /// Check every download URL, redirect targets and resolved share links included, with `policy`.
+    pub fn with_url_policy(mut self, policy: UrlPolicy) -> Self {
+        self.client = self.client.with_url_policy(policy);
+        self
+    }
 }
 
 #[async_trait]
@@ -41,17 +74,45 @@ impl Downloader for ZipDownloader {
     }
     
     async fn download_zip(&self, url: &str) -> anyhow::Result<PathBuf> {
-        self.download_zip_with_progress(url, None).await
+        self.download_zip_with_progress(url, &DownloadOptions::default(), None)
+            .await
+            .map(|(path, _)| path)
     }
 }
 
 impl ZipDownloader {
-    /// Download a ZIP with optional byte-progress reporting.
+    /// Rewrite share links (Dropbox, Google Drive) to the URL that serves the file.
+    pub async fn resolve_url(&self, url: &str) -> anyhow::Result<String> {
+        resolve_download_url(&self.resolvers, url, &self.client).await
+    }
+
+    /// `HEAD` the URL to learn its status, size and type before downloading.
+    pub async fn precheck(
+        &self,
+        url: &str,
+        options: &DownloadOptions,
+    ) -> anyhow::Result<PrecheckResult> {
+        self.client.precheck(url, options).await
+    }
+
+    /// Apply the URL policy and SSRF checks a download of `url` would get, without sending a request.
+    pub async fn validate_target(&self, url: &str) -> anyhow::Result<()> {
+        self.client.validate_target(url).await
+    }
+
+    pub fn max_download_size(&self) -> u64 {
+        self.client.max_download_size()
+    }
+
+    /// Download a ZIP with extra request options and optional byte-progress reporting.
+    ///
+    /// Returns the file with the `Last-Modified`/`ETag` the server sent for it.
     pub async fn download_zip_with_progress(
         &self,
         url: &str,
-        on_progress: Option<crate::downloader::client::DownloadProgressCallback>,
-    ) -> anyhow::Result<PathBuf> {
+        options: &DownloadOptions,
+        on_progress: Option<DownloadProgressCallback>,
+    ) -> anyhow::Result<(PathBuf, CacheValidators)> {
         // Extract filename from URL and sanitize it
         let raw_filename = url
             .split('/')
@@ -72,11 +133,13 @@ impl ZipDownloader {
         
         info!(url = %url, path = %output_path.display(), "Downloading ZIP file");
         
-        self.client
-            .download_with_retry_progress(url, &output_path, on_progress)
+        let guard = crate::utils::TempPathGuard::new(output_path.clone());
+        let validators = self
+            .client
+            .download_with_retry_progress(url, &output_path, options, on_progress)
             .await?;
         
-        Ok(output_path)
+        Ok((guard.keep(), validators))
     }
 }
 
@@ -145,7 +208,7 @@ mod tests {
     #[tokio::test]
     async fn test_download_workshop_not_supported() {
         let temp_dir = TempDir::new().unwrap();
-        let downloader = ZipDownloader::new(temp_dir.path().to_path_buf(), 100 * 1024 * 1024).await.unwrap();
+        let downloader = ZipDownloader::new(temp_dir.path().to_path_buf(), 100 * 1024 * 1024, &HttpSettings::default()).await.unwrap();
 
         let result = downloader.download_workshop(123456789).await;
         assert!(result.is_err());
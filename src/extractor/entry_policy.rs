@@ -0,0 +1,102 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Which file types an archive may contain besides its VPKs, and how many VPKs.
+//!
+//! Checked against the archive listing before anything is extracted, so an archive
+//! that also ships e.g. a `.exe` or `.sh`, or thousands of tiny VPKs, is rejected
+//! as a whole.
+
+use crate::config::Config;
+
+/// Extension allow/deny lists, lowercase and without the leading dot.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchiveEntryPolicy {
+    /// When non-empty, every file must have one of these extensions
+    allow: Vec<String>,
+    deny: Vec<String>,
+    /// Most `.vpk` files an archive may hold; 0 means no limit
+    max_vpks: u64,
+}
+
+impl ArchiveEntryPolicy {
+    pub fn new(allow: &[String], deny: &[String]) -> Self {
+        Self {
+            allow: allow.iter().map(|ext| normalize_extension(ext)).collect(),
+            deny: deny.iter().map(|ext| normalize_extension(ext)).collect(),
+            max_vpks: 0,
+        }
+    }
+
+    pub fn with_max_vpks(mut self, max_vpks: u64) -> Self {
+        self.max_vpks = max_vpks;
+        self
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(&config.archive_extension_allowlist, &config.archive_extension_denylist)
+            .with_max_vpks(config.max_vpks_per_archive)
+    }
+
+    /// Fail on the first file entry in `names` the policy does not permit, or when
+    /// there are more VPKs than allowed. Directory entries (ending in `/`) are ignored.
+    pub fn check_names<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> anyhow::Result<()> {
+        if self.allow.is_empty() && self.deny.is_empty() && self.max_vpks == 0 {
+            return Ok(());
+        }
+        let mut vpks = 0u64;
+        for name in names {
+            if name.ends_with('/') || name.ends_with('\\') {
+                continue;
+            }
+            let extension = std::path::Path::new(name)
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            if extension == "vpk" {
+                vpks += 1;
+            }
+            if self.deny.contains(&extension) {
+                anyhow::bail!("Archive entry {name} has a denied extension (archive_extension_denylist)");
+            }
+            if !self.allow.is_empty() && !self.allow.contains(&extension) {
+                anyhow::bail!("Archive entry {name} is not in archive_extension_allowlist");
+            }
+        }
+        if self.max_vpks > 0 && vpks > self.max_vpks {
+            anyhow::bail!(
+                "Archive holds {vpks} VPK files; max_vpks_per_archive allows {}",
+                self.max_vpks
+            );
+        }
+        Ok(())
+    }
+}
+
+/// `".SH"` and `"sh"` both become `"sh"`.
+pub fn normalize_extension(extension: &str) -> String {
+    extension.trim().trim_start_matches('.').to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allow_and_deny_lists_are_case_insensitive() {
+        let policy = ArchiveEntryPolicy::new(&[], &[".SH".to_string(), "exe".to_string()]);
+        assert!(policy.check_names(["maps/", "map.vpk", "readme.txt"]).is_ok());
+        assert!(policy.check_names(["map.vpk", "tools/install.Sh"]).is_err());
+
+        let policy = ArchiveEntryPolicy::new(&["vpk".to_string(), "txt".to_string()], &[]);
+        assert!(policy.check_names(["map.VPK", "readme.txt"]).is_ok());
+        assert!(policy.check_names(["map.vpk", "LICENSE"]).is_err());
+        assert!(ArchiveEntryPolicy::default().check_names(["run.exe"]).is_ok());
+    }
+
+    #[test]
+    fn vpk_count_is_capped_when_configured() {
+        let policy = ArchiveEntryPolicy::default().with_max_vpks(2);
+        assert!(policy.check_names(["a.vpk", "maps/", "b.VPK", "readme.txt"]).is_ok());
+        assert!(policy.check_names(["a.vpk", "b.vpk", "c.vpk"]).is_err());
+        assert!(ArchiveEntryPolicy::default().check_names(["a.vpk", "b.vpk", "c.vpk"]).is_ok());
+    }
+}
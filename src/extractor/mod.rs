@@ -1,9 +1,11 @@
 // SPDX-License-Identifier: GPL-3.0-only
+pub mod entry_policy;
 pub mod limiting_writer;
 pub mod traits;
 pub mod sevenz;
 pub mod vpk;
 pub mod vpk_v1;
+pub mod vpk_signature;
 pub mod zip;
 
 
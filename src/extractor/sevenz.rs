@@ -7,9 +7,13 @@ use async_trait::async_trait;
 use sevenz_rust::{Password, SevenZReader};
 use tracing::info;
 
+use crate::extractor::entry_policy::ArchiveEntryPolicy;
 use crate::extractor::limiting_writer::LimitingWriter;
 use crate::extractor::traits::Extractor;
-use crate::utils::{resolve_archive_entry_path, validate_archive_entry_name};
+use crate::utils::{
+    ensure_no_symlinks_under, is_symlink_mode, resolve_archive_entry_path,
+    validate_archive_entry_name,
+};
 
 pub struct SevenZExtractor {
     max_extraction_size: u64,
@@ -24,15 +28,15 @@ impl SevenZExtractor {
         }
     }
 
-    fn archive_contains_vpk(archive_path: &Path) -> anyhow::Result<bool> {
+    fn archive_contains_vpk(archive_path: &Path, policy: &ArchiveEntryPolicy) -> anyhow::Result<bool> {
         let file = File::open(archive_path)?;
         let len = file.metadata()?.len();
         let mut reader = BufReader::new(file);
         reader.seek(SeekFrom::Start(0))?;
         let seven = SevenZReader::new(reader, len, Password::empty())?;
-        Ok(seven
-            .archive()
-            .files
+        let files = &seven.archive().files;
+        policy.check_names(files.iter().filter(|entry| !entry.is_directory()).map(|entry| entry.name()))?;
+        Ok(files
             .iter()
             .any(|entry| entry.name().to_lowercase().ends_with(".vpk")))
     }
@@ -71,6 +75,12 @@ impl SevenZExtractor {
         for entry in files {
             validate_archive_entry_name(entry.name())?;
             resolve_archive_entry_path(dest, entry.name())?;
+            if Self::entry_is_symlink(entry) {
+                anyhow::bail!(
+                    "7z entry {} is a symbolic link; refusing to extract",
+                    entry.name()
+                );
+            }
         }
 
         let dest = dest.to_path_buf();
@@ -97,6 +107,20 @@ impl SevenZExtractor {
         Ok(())
     }
 
+    /// 7z stores links as reparse points (Windows) or a Unix mode in the high attribute bits.
+    fn entry_is_symlink(entry: &sevenz_rust::SevenZArchiveEntry) -> bool {
+        const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+        const FILE_ATTRIBUTE_UNIX_EXTENSION: u32 = 0x8000;
+
+        if !entry.has_windows_attributes {
+            return false;
+        }
+        let attributes = entry.windows_attributes();
+        attributes & FILE_ATTRIBUTE_REPARSE_POINT != 0
+            || (attributes & FILE_ATTRIBUTE_UNIX_EXTENSION != 0
+                && is_symlink_mode(attributes >> 16))
+    }
+
     fn extract_entry(
         entry: &sevenz_rust::SevenZArchiveEntry,
         reader: &mut dyn Read,
@@ -105,6 +129,7 @@ impl SevenZExtractor {
     ) -> anyhow::Result<u64> {
         validate_archive_entry_name(entry.name())?;
         let out_path = resolve_archive_entry_path(dest, entry.name())?;
+        ensure_no_symlinks_under(dest, &out_path)?;
 
         if entry.is_directory() {
             std::fs::create_dir_all(&out_path)?;
@@ -186,9 +211,15 @@ impl Extractor for SevenZExtractor {
 }
 
 impl SevenZExtractor {
-    pub async fn sevenz_contains_vpk(&self, archive_path: &Path) -> anyhow::Result<bool> {
+    /// Whether the archive has a `.vpk` entry; errors if it holds a file `policy` does
+    /// not permit, so callers reject it before extracting.
+    pub async fn sevenz_contains_vpk(
+        &self,
+        archive_path: &Path,
+        policy: ArchiveEntryPolicy,
+    ) -> anyhow::Result<bool> {
         let archive_path = archive_path.to_path_buf();
-        tokio::task::spawn_blocking(move || Self::archive_contains_vpk(&archive_path)).await?
+        tokio::task::spawn_blocking(move || Self::archive_contains_vpk(&archive_path, &policy)).await?
     }
 }
 
@@ -201,7 +232,7 @@ mod tests {
     async fn sevenz_contains_vpk_rejects_missing_file() {
         let extractor = SevenZExtractor::new(1024, 10);
         let result = extractor
-            .sevenz_contains_vpk(Path::new("/nonexistent/archive.7z"))
+            .sevenz_contains_vpk(Path::new("/nonexistent/archive.7z"), ArchiveEntryPolicy::default())
             .await;
         assert!(result.is_err());
     }
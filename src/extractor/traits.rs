@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// Metadata extracted from a VPK file
@@ -8,6 +9,17 @@ pub struct VpkMetadata {
     pub title: String,
     pub version: String,
     pub workshop_id: Option<u64>,
+    /// Lowercase names of the `addonContent_*` flags set in `addoninfo.txt`
+    pub content_flags: Vec<String>,
+}
+
+/// A file in a VPK's directory tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct VpkFileEntry {
+    /// Path inside the VPK, e.g. `maps/c1m1_hotel.bsp`
+    pub path: String,
+    /// Preload plus archive data bytes
+    pub size: u64,
 }
 
 #[async_trait]
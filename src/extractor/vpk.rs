@@ -6,7 +6,8 @@ use std::path::{Path, PathBuf};
 use tracing::info;
 use regex::Regex;
 
-use crate::extractor::traits::{Extractor, VpkMetadata};
+use crate::extractor::traits::{Extractor, VpkFileEntry, VpkMetadata};
+use crate::extractor::vpk_signature::{self, VpkSignature};
 use crate::extractor::vpk_v1::{self, VpkV1Header};
 
 pub struct VpkExtractor;
@@ -24,6 +25,14 @@ impl VpkExtractor {
             .and_then(|caps| caps.get(1).map(|m| m.as_str().to_string()))
     }
 
+    /// Name of an `addonContent_<Name> "1"` flag that is set, lowercased.
+    fn parse_content_flag(line: &str) -> Option<String> {
+        let re = Regex::new(r#"^\s*(?i)"?addonContent_(\w+)"?\s+"?\s*(\d+)\s*"?"#).unwrap();
+        let caps = re.captures(line)?;
+        let set = caps.get(2)?.as_str().parse::<u64>().ok()? != 0;
+        set.then(|| caps[1].to_ascii_lowercase())
+    }
+
     fn parse_workshop_id(url: &str) -> Option<u64> {
         let re = Regex::new(r"(?i)steamcommunity\.com/.*[?&]id=(\d+)").ok()?;
         re.captures(url)?
@@ -39,8 +48,12 @@ impl VpkExtractor {
         let mut title: Option<String> = None;
         let mut version: Option<String> = None;
         let mut addon_url: Option<String> = None;
+        let mut content_flags: Vec<String> = Vec::new();
 
         for line in content.lines() {
+            if let Some(flag) = Self::parse_content_flag(line) {
+                content_flags.push(flag);
+            }
             if title.is_none()
                 && let Some(val) = Self::extract_value(line, "addonTitle")
             {
@@ -61,11 +74,14 @@ impl VpkExtractor {
         let workshop_id = addon_url
             .as_deref()
             .and_then(Self::parse_workshop_id);
+        content_flags.sort_unstable();
+        content_flags.dedup();
 
         VpkMetadata {
             title: title.unwrap_or_else(|| "Unknown".to_string()),
-            version: version.unwrap_or_else(|| "Unknown".to_string()),
+            version: version.unwrap_or_else(|| crate::utils::UNKNOWN_VERSION.to_string()),
             workshop_id,
+            content_flags,
         }
     }
 
@@ -84,6 +100,76 @@ impl VpkExtractor {
         Self::extract_vpk_metadata_sourcepak_fallback(path, &header)
     }
 
+    /// Check the v1 header and directory tree parse, without requiring `addoninfo.txt`.
+    pub async fn validate_structure(&self, archive_path: PathBuf) -> anyhow::Result<()> {
+        tokio::task::spawn_blocking(move || {
+            let mut file = File::open(&archive_path)?;
+            let header = vpk_v1::read_header(&mut file)?;
+            let file_len = file.metadata()?.len();
+            if header.header_size + header.tree_size as u64 > file_len {
+                anyhow::bail!(
+                    "VPK directory tree ({} bytes) extends past end of file ({} bytes)",
+                    header.tree_size,
+                    file_len
+                );
+            }
+            vpk_v1::find_addoninfo_entry(&mut file, &header)?;
+            Ok(())
+        })
+        .await?
+    }
+
+    /// Check a v2 signature section against `trusted`; see [`vpk_signature::verify_file`].
+    pub async fn verify_signature(
+        &self,
+        archive_path: PathBuf,
+        trusted: rsa::RsaPublicKey,
+    ) -> anyhow::Result<VpkSignature> {
+        tokio::task::spawn_blocking(move || vpk_signature::verify_file(&archive_path, &trusted)).await?
+    }
+
+    /// Every file in the directory tree, sorted by path. For split archives this is
+    /// the `_dir.vpk`, whose tree describes the files in all parts.
+    pub async fn list_files(&self, archive_path: PathBuf) -> anyhow::Result<Vec<VpkFileEntry>> {
+        tokio::task::spawn_blocking(move || {
+            match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                Self::list_files_blocking(&archive_path)
+            })) {
+                Ok(result) => result,
+                Err(_) => Err(anyhow::anyhow!(
+                    "VPK listing panicked (sourcepak cannot decode non-UTF-8 paths in this VPK)"
+                )),
+            }
+        })
+        .await?
+    }
+
+    fn list_files_blocking(path: &Path) -> anyhow::Result<Vec<VpkFileEntry>> {
+        use sourcepak::common::format::{VPKDirectoryEntry, VPKTree};
+
+        // v1 and v2 share the tree layout; only where it starts differs.
+        let mut file = File::open(path)?;
+        let header = vpk_v1::read_header(&mut file)?;
+        let files = VPKTree::<VPKDirectoryEntry>::from(
+            &mut file,
+            header.header_size,
+            header.tree_size as u64,
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to read VPK directory tree: {}", e))?
+        .files;
+
+        // Root-level files are keyed as " /name.ext".
+        let mut entries: Vec<VpkFileEntry> = files
+            .into_iter()
+            .map(|(key, entry)| VpkFileEntry {
+                path: key.strip_prefix(" /").unwrap_or(&key).to_string(),
+                size: u64::from(entry.preload_length) + u64::from(entry.entry_length),
+            })
+            .collect();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(entries)
+    }
+
     fn extract_vpk_metadata_sourcepak_fallback(
         path: &Path,
         header: &VpkV1Header,
@@ -226,6 +312,29 @@ mod tests {
         assert_eq!(workshop_id, Some(121786282));
     }
 
+    #[test]
+    fn test_parse_metadata_collects_set_content_flags() {
+        let addoninfo = br#"
+"AddonInfo"
+{
+    addonTitle "Flagged Campaign"
+    "addonContent_Campaign" "1"
+    addonContent_Map 1
+    addonContent_Survivor "0"
+    "addoncontent_Music" " 1 "
+    addonContent_Script "1"
+    addonContent_Campaign "1"
+    addonContent_Sound ""
+}
+"#;
+        let metadata = VpkExtractor::parse_metadata_from_bytes(addoninfo);
+        assert_eq!(metadata.title, "Flagged Campaign");
+        assert_eq!(metadata.content_flags, vec!["campaign", "map", "music", "script"]);
+
+        let bare = VpkExtractor::parse_metadata_from_bytes(br#"addonTitle "Plain""#);
+        assert!(bare.content_flags.is_empty());
+    }
+
     #[tokio::test]
     async fn test_extract_vpk_metadata_from_minimal_fixture() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Signature sections of VPK v2 files.
+//!
+//! A signed v2 VPK ends with the signer's public key (DER) and an RSA PKCS#1 v1.5
+//! SHA-256 signature over everything before that section. v1 VPKs, which most L4D2
+//! addons are, have no signature section.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::Context;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs8::DecodePublicKey;
+use rsa::{Pkcs1v15Sign, RsaPublicKey};
+use sha2::{Digest, Sha256};
+
+use crate::extractor::vpk_v1::{VPK_SIGNATURE_V1, VPK_V2_HEADER_SIZE, VPK_VERSION_V2};
+
+/// Outcome of a signature check that did not fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VpkSignature {
+    /// v1 VPK, or v2 without a signature section
+    Unsigned,
+    /// Signed with the trusted key and the signature matches
+    Verified,
+}
+
+/// Read a trusted RSA public key from a PEM (`PUBLIC KEY` or `RSA PUBLIC KEY`) or DER file.
+pub fn load_public_key(path: &Path) -> anyhow::Result<RsaPublicKey> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("Failed to read VPK signing key {}", path.display()))?;
+    let key = match std::str::from_utf8(&bytes) {
+        Ok(pem) if pem.trim_start().starts_with("-----BEGIN") => RsaPublicKey::from_public_key_pem(pem)
+            .or_else(|_| RsaPublicKey::from_pkcs1_pem(pem))
+            .ok(),
+        _ => RsaPublicKey::from_public_key_der(&bytes)
+            .or_else(|_| RsaPublicKey::from_pkcs1_der(&bytes))
+            .ok(),
+    };
+    key.ok_or_else(|| anyhow::anyhow!("{} is not an RSA public key", path.display()))
+}
+
+/// Check the signature section of `path` against `trusted`.
+///
+/// Errors when the VPK is signed by another key, the signature does not match the
+/// signed bytes, or the section is malformed; unsigned files are reported, not rejected.
+pub fn verify_file(path: &Path, trusted: &RsaPublicKey) -> anyhow::Result<VpkSignature> {
+    let mut file = File::open(path)?;
+    let mut header = [0u8; VPK_V2_HEADER_SIZE as usize];
+    file.read_exact(&mut header[..8])?;
+    let field = |header: &[u8], index: usize| {
+        u32::from_le_bytes(header[index * 4..index * 4 + 4].try_into().unwrap())
+    };
+    if field(&header, 0) != VPK_SIGNATURE_V1 {
+        anyhow::bail!("Invalid VPK signature: {:#x}", field(&header, 0));
+    }
+    if field(&header, 1) != VPK_VERSION_V2 {
+        return Ok(VpkSignature::Unsigned);
+    }
+    file.read_exact(&mut header[8..])?;
+    let [tree_size, data_size, archive_md5_size, other_md5_size, signature_size] =
+        [2, 3, 4, 5, 6].map(|index| u64::from(field(&header, index)));
+    if signature_size == 0 {
+        return Ok(VpkSignature::Unsigned);
+    }
+
+    let signed_len = VPK_V2_HEADER_SIZE + tree_size + data_size + archive_md5_size + other_md5_size;
+    if signed_len + signature_size > file.metadata()?.len() {
+        anyhow::bail!("VPK signature section extends past end of file");
+    }
+    file.seek(SeekFrom::Start(signed_len))?;
+    let mut section = vec![0u8; signature_size as usize];
+    file.read_exact(&mut section)?;
+    let mut fields = section.as_slice();
+    let public_key = take_sized(&mut fields).context("Malformed VPK signature section")?;
+    let signature = take_sized(&mut fields).context("Malformed VPK signature section")?;
+
+    let signer = RsaPublicKey::from_public_key_der(public_key)
+        .map_err(|_| anyhow::anyhow!("VPK signature section holds an invalid public key"))?;
+    if signer != *trusted {
+        anyhow::bail!("VPK is signed by a key other than vpk_signing_public_key");
+    }
+
+    file.seek(SeekFrom::Start(0))?;
+    let mut hasher = Sha256::new();
+    let mut signed = file.take(signed_len);
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let read = signed.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    trusted
+        .verify(Pkcs1v15Sign::new::<Sha256>(), &hasher.finalize(), signature)
+        .map_err(|_| anyhow::anyhow!("VPK signature does not match its contents"))?;
+    Ok(VpkSignature::Verified)
+}
+
+/// Split a `u32` length-prefixed field off the front of `bytes`.
+fn take_sized<'a>(bytes: &mut &'a [u8]) -> anyhow::Result<&'a [u8]> {
+    let (len, rest) = bytes
+        .split_first_chunk::<4>()
+        .ok_or_else(|| anyhow::anyhow!("truncated length field"))?;
+    let len = u32::from_le_bytes(*len) as usize;
+    if len > rest.len() {
+        anyhow::bail!("field of {len} bytes overruns the section");
+    }
+    let (field, rest) = rest.split_at(len);
+    *bytes = rest;
+    Ok(field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers;
+    use tempfile::TempDir;
+
+    #[test]
+    fn signed_vpk_verifies_and_tampering_is_detected() {
+        let temp = TempDir::new().unwrap();
+        let trusted = test_helpers::test_vpk_signing_key().to_public_key();
+        let signed = temp.path().join("signed.vpk");
+        test_helpers::write_signed_test_vpk(&signed, "Signed Map").unwrap();
+        assert_eq!(verify_file(&signed, &trusted).unwrap(), VpkSignature::Verified);
+
+        // Flip a byte of the signature itself.
+        let mut bytes = std::fs::read(&signed).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        let tampered = temp.path().join("tampered.vpk");
+        std::fs::write(&tampered, &bytes).unwrap();
+        assert!(verify_file(&tampered, &trusted).is_err());
+
+        let unsigned = temp.path().join("unsigned.vpk");
+        test_helpers::write_minimal_test_vpk(&unsigned, "Plain Map").unwrap();
+        assert_eq!(verify_file(&unsigned, &trusted).unwrap(), VpkSignature::Unsigned);
+    }
+}
@@ -5,14 +5,21 @@ use std::path::Path;
 
 pub const VPK_SIGNATURE_V1: u32 = 0x55AA1234;
 pub const VPK_VERSION_V1: u32 = 1;
+pub const VPK_VERSION_V2: u32 = 2;
 pub const VPK_ENTRY_TERMINATOR: u16 = 0xFFFF;
 pub const VPK_EMBEDDED_ARCHIVE_INDEX: u16 = 0x7FFF;
 /// On-disk v1 header is signature(4) + version(4) + tree_size(4).
 pub const VPK_V1_HEADER_SIZE: u64 = 12;
+/// v2 adds file data, archive MD5, other MD5 and signature section sizes (4 each).
+pub const VPK_V2_HEADER_SIZE: u64 = 28;
 
+/// Header fields the directory walk needs. v2 files share the v1 tree layout, so they
+/// are read the same way once their longer header is skipped.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VpkV1Header {
     pub tree_size: u32,
+    /// Where the directory tree starts
+    pub header_size: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -34,11 +41,18 @@ pub fn read_header(file: &mut File) -> anyhow::Result<VpkV1Header> {
             "Invalid VPK signature: expected {VPK_SIGNATURE_V1:#x}, got {signature:#x}"
         );
     }
-    if version != VPK_VERSION_V1 {
-        anyhow::bail!("Unsupported VPK version: expected {VPK_VERSION_V1}, got {version}");
-    }
+    let header_size = match version {
+        VPK_VERSION_V1 => VPK_V1_HEADER_SIZE,
+        VPK_VERSION_V2 => {
+            skip_bytes(file, (VPK_V2_HEADER_SIZE - VPK_V1_HEADER_SIZE) as usize)?;
+            VPK_V2_HEADER_SIZE
+        }
+        _ => anyhow::bail!(
+            "Unsupported VPK version: expected {VPK_VERSION_V1} or {VPK_VERSION_V2}, got {version}"
+        ),
+    };
 
-    Ok(VpkV1Header { tree_size })
+    Ok(VpkV1Header { tree_size, header_size })
 }
 
 /// Walk the VPK v1 directory tree without requiring UTF-8 path strings.
@@ -99,7 +113,7 @@ pub fn read_addoninfo_bytes(
 
     if entry.entry_length > 0 {
         let chunk = if entry.archive_index == VPK_EMBEDDED_ARCHIVE_INDEX {
-            let seek_pos = VPK_V1_HEADER_SIZE + header.tree_size as u64 + entry.entry_offset as u64;
+            let seek_pos = header.header_size + header.tree_size as u64 + entry.entry_offset as u64;
             file.seek(SeekFrom::Start(seek_pos))?;
             read_n(file, entry.entry_length as usize)?
         } else {
@@ -133,7 +147,7 @@ fn read_split_archive_bytes(vpk_path: &Path, entry: &VpkDirectoryEntry) -> anyho
 
     if entry.archive_index == 0xFF7F {
         let chunk_header = read_header(&mut chunk)?;
-        let seek_pos = VPK_V1_HEADER_SIZE
+        let seek_pos = chunk_header.header_size
             + chunk_header.tree_size as u64
             + entry.entry_offset as u64;
         chunk.seek(SeekFrom::Start(seek_pos))?;
@@ -1,18 +1,23 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use async_trait::async_trait;
+use std::fmt::Write as _;
 use std::fs::File;
-use std::io::{BufReader, Write};
-use std::path::PathBuf;
+use std::io::{BufReader, Read, Seek, Write};
+use std::path::{Path, PathBuf};
 use tracing::info;
+use zip::read::ZipFile;
 use zip::ZipArchive;
 
+use crate::extractor::entry_policy::ArchiveEntryPolicy;
 use crate::extractor::limiting_writer::LimitingWriter;
 use crate::extractor::traits::Extractor;
-use crate::utils::resolve_archive_entry_path;
+use crate::utils::{ensure_no_symlinks_under, is_symlink_mode, resolve_archive_entry_path};
 
 pub struct ZipExtractor {
     max_extraction_size: u64,
     max_file_count: u64,
+    /// Lowercase extensions (without the dot) to extract; `None` extracts everything.
+    extensions: Option<Vec<String>>,
 }
 
 impl ZipExtractor {
@@ -20,92 +25,197 @@ impl ZipExtractor {
         Self {
             max_extraction_size,
             max_file_count,
+            extensions: None,
         }
     }
+
+    /// Extract only files with one of `extensions` (e.g. `["vpk"]`); other entries
+    /// are skipped and do not count toward the size or file-count limits.
+    pub fn with_extension_filter(mut self, extensions: &[&str]) -> Self {
+        self.extensions = Some(extensions.iter().map(|e| e.to_ascii_lowercase()).collect());
+        self
+    }
 }
 
-#[async_trait]
-impl Extractor for ZipExtractor {
-    async fn extract_zip(&self, archive_path: PathBuf, dest: PathBuf) -> anyhow::Result<()> {
+/// Whether a file entry passes the extension filter; directories never do.
+fn wanted_entry(name: &str, extensions: Option<&[String]>) -> bool {
+    let Some(extensions) = extensions else {
+        return true;
+    };
+    if name.ends_with('/') {
+        return false;
+    }
+    std::path::Path::new(name)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| extensions.iter().any(|wanted| e.eq_ignore_ascii_case(wanted)))
+}
+
+impl ZipExtractor {
+    /// Like [`Extractor::extract_zip`], but fails before writing anything when the
+    /// archive has no `.vpk` entry or holds a file `policy` does not permit. The
+    /// archive is opened once for both the checks and the extraction.
+    pub async fn extract_zip_requiring_vpk(
+        &self,
+        archive_path: PathBuf,
+        dest: PathBuf,
+        policy: ArchiveEntryPolicy,
+    ) -> anyhow::Result<()> {
         info!(archive = %archive_path.display(), dest = %dest.display(), "Extracting ZIP archive");
+        self.extract_opened(move || File::open(&archive_path), dest, true, policy)
+            .await
+    }
 
+    /// Open the archive with `open` and extract it into `dest` in a single blocking task.
+    async fn extract_opened<R, F>(
+        &self,
+        open: F,
+        dest: PathBuf,
+        require_vpk: bool,
+        policy: ArchiveEntryPolicy,
+    ) -> anyhow::Result<()>
+    where
+        R: Read + Seek,
+        F: FnOnce() -> std::io::Result<R> + Send + 'static,
+    {
         tokio::fs::create_dir_all(&dest).await?;
 
-        let archive_path_clone = archive_path.clone();
-        let dest_clone = dest.clone();
         let max_extraction_size = self.max_extraction_size;
         let max_file_count = self.max_file_count;
+        let extensions = self.extensions.clone();
+        let dest_clone = dest.clone();
 
         tokio::task::spawn_blocking(move || {
-            let file = File::open(&archive_path_clone)?;
-            let mut archive = ZipArchive::new(BufReader::new(file))?;
-
-            if archive.len() as u64 > max_file_count {
-                return Err(anyhow::anyhow!(
-                    "Archive contains {} files, exceeds maximum of {} files",
-                    archive.len(),
-                    max_file_count
-                ));
+            let mut archive = ZipArchive::new(BufReader::new(open()?))?;
+            if require_vpk && !archive.file_names().any(is_vpk_name) {
+                return Err(anyhow::anyhow!("ZIP file does not contain any .vpk files"));
             }
+            policy.check_names(archive.file_names())?;
+            extract_archive(
+                &mut archive,
+                &dest_clone,
+                max_extraction_size,
+                max_file_count,
+                extensions.as_deref(),
+            )
+        })
+        .await??;
 
-            let mut total_written: u64 = 0;
+        info!(dest = %dest.display(), "ZIP extraction completed");
+        Ok(())
+    }
+}
 
-            for i in 0..archive.len() {
-                let mut file = archive.by_index(i)?;
-                let raw_name = file.name().to_string();
-                let entry_name = file.enclosed_name().ok_or_else(|| {
-                    anyhow::anyhow!("ZIP entry has unsafe or absolute path: {raw_name}")
-                })?;
-                let entry_name_str = entry_name.to_string_lossy().into_owned();
-                let outpath = resolve_archive_entry_path(&dest_clone, &entry_name_str)?;
+fn is_vpk_name(name: &str) -> bool {
+    name.to_ascii_lowercase().ends_with(".vpk")
+}
 
-                if raw_name.ends_with('/') {
-                    std::fs::create_dir_all(&outpath)?;
-                    continue;
-                }
+fn extract_archive<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    dest: &Path,
+    max_extraction_size: u64,
+    max_file_count: u64,
+    extensions: Option<&[String]>,
+) -> anyhow::Result<()> {
+    let file_count = match extensions {
+        None => archive.len(),
+        Some(_) => archive
+            .file_names()
+            .filter(|name| wanted_entry(name, extensions))
+            .count(),
+    };
+    if file_count as u64 > max_file_count {
+        return Err(anyhow::anyhow!(
+            "Archive contains {} files, exceeds maximum of {} files",
+            file_count,
+            max_file_count
+        ));
+    }
 
-                if let Some(parent) = outpath.parent() {
-                    std::fs::create_dir_all(parent)?;
-                }
+    // Reject symlink entries up front so nothing is written from such an archive.
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i)?;
+        if entry.unix_mode().is_some_and(is_symlink_mode) {
+            return Err(anyhow::anyhow!(
+                "ZIP entry {} is a symbolic link; refusing to extract",
+                entry.name()
+            ));
+        }
+    }
 
-                let remaining = max_extraction_size.saturating_sub(total_written);
-                if remaining == 0 {
-                    return Err(anyhow::anyhow!(
-                        "Total extraction size exceeds maximum of {} bytes",
-                        max_extraction_size
-                    ));
-                }
+    let mut total_written: u64 = 0;
 
-                let outfile = File::create(&outpath)?;
-                let mut limited = LimitingWriter::new(outfile, remaining);
-                match std::io::copy(&mut file, &mut limited) {
-                    Ok(_) => {
-                        limited.flush()?;
-                        total_written = total_written.saturating_add(limited.written());
-                    }
-                    Err(error) => {
-                        let _ = std::fs::remove_file(&outpath);
-                        return Err(anyhow::anyhow!(
-                            "ZIP extraction failed for {entry_name_str}: {error}"
-                        ));
-                    }
-                }
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        let raw_name = file.name().to_string();
+        if file.enclosed_name().is_none() {
+            return Err(anyhow::anyhow!(
+                "ZIP entry has unsafe or absolute path: {raw_name}"
+            ));
+        }
+        let entry_name_str = lossless_entry_name(&file);
+        if !wanted_entry(&raw_name, extensions) {
+            continue;
+        }
+        let outpath = resolve_archive_entry_path(dest, &entry_name_str)?;
+        ensure_no_symlinks_under(dest, &outpath)?;
 
-                if total_written > max_extraction_size {
-                    return Err(anyhow::anyhow!(
-                        "Total extraction size {} exceeds maximum {} bytes",
-                        total_written,
-                        max_extraction_size
-                    ));
-                }
-            }
+        if raw_name.ends_with('/') {
+            std::fs::create_dir_all(&outpath)?;
+            continue;
+        }
 
-            Ok::<(), anyhow::Error>(())
-        })
-        .await??;
+        if let Some(parent) = outpath.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
 
-        info!(archive = %archive_path.display(), dest = %dest.display(), "ZIP extraction completed");
-        Ok(())
+        let remaining = max_extraction_size.saturating_sub(total_written);
+        if remaining == 0 {
+            return Err(anyhow::anyhow!(
+                "Total extraction size exceeds maximum of {} bytes",
+                max_extraction_size
+            ));
+        }
+
+        // An entry that declares more than the remaining budget fails before its file
+        // is created; the limiting writer catches entries that under-report their size.
+        if file.size() > remaining {
+            return Err(anyhow::anyhow!(
+                "ZIP entry {entry_name_str} is {} bytes, exceeds remaining extraction budget of {remaining} bytes",
+                file.size()
+            ));
+        }
+
+        let mut limited = LimitingWriter::new(File::create(&outpath)?, remaining);
+        if let Err(error) = std::io::copy(&mut file, &mut limited).and_then(|_| limited.flush()) {
+            // Delete the partial file right away rather than leaving it to the
+            // caller's temp dir cleanup.
+            drop(limited);
+            let _ = std::fs::remove_file(&outpath);
+            return Err(anyhow::anyhow!(
+                "ZIP extraction failed for {entry_name_str}: {error}"
+            ));
+        }
+        total_written = total_written.saturating_add(limited.written());
+
+        if total_written > max_extraction_size {
+            return Err(anyhow::anyhow!(
+                "Total extraction size {} exceeds maximum {} bytes",
+                total_written,
+                max_extraction_size
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl Extractor for ZipExtractor {
+    async fn extract_zip(&self, archive_path: PathBuf, dest: PathBuf) -> anyhow::Result<()> {
+        info!(archive = %archive_path.display(), dest = %dest.display(), "Extracting ZIP archive");
+        self.extract_opened(move || File::open(&archive_path), dest, false, ArchiveEntryPolicy::default())
+            .await
     }
 
     async fn extract_vpk(&self, _archive_path: PathBuf, _dest: PathBuf) -> anyhow::Result<()> {
@@ -126,6 +236,41 @@ impl Extractor for ZipExtractor {
     }
 }
 
+/// Entry name to extract under, distinct for every distinct raw name.
+///
+/// Valid names are used as they are. The zip crate decodes names flagged UTF-8 lossily,
+/// though, so two names differing only in invalid bytes would both become the same
+/// U+FFFD placeholder and overwrite each other. Such bytes are escaped as `%XX` from the
+/// raw name instead, and the file stem gets a `~<hash>` of the raw name so the escaped
+/// form does not land on an entry literally named that way.
+fn lossless_entry_name(file: &ZipFile<'_>) -> String {
+    use sha2::{Digest, Sha256};
+
+    let raw = file.name_raw();
+    let lossy = std::str::from_utf8(raw).is_err() && file.name().contains(char::REPLACEMENT_CHARACTER);
+    if !lossy {
+        return file.name().to_string();
+    }
+    let mut name = String::with_capacity(raw.len() * 3 + 9);
+    for chunk in raw.utf8_chunks() {
+        name.push_str(chunk.valid());
+        for byte in chunk.invalid() {
+            let _ = write!(name, "%{byte:02X}");
+        }
+    }
+    let mut tag = String::from("~");
+    for byte in &Sha256::digest(raw)[..4] {
+        let _ = write!(tag, "{byte:02x}");
+    }
+    let file_start = name.rfind(['/', '\\']).map_or(0, |i| i + 1);
+    let tag_at = name[file_start..]
+        .rfind('.')
+        .filter(|&i| i > 0)
+        .map_or(name.len(), |i| file_start + i);
+    name.insert_str(tag_at, &tag);
+    name
+}
+
 impl Default for ZipExtractor {
     fn default() -> Self {
         Self::new(1024 * 1024 * 1024, 10000)
@@ -273,6 +418,35 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_extract_rejects_symlink_entry() {
+        let extractor = ZipExtractor::new(1024 * 1024, 100);
+        let temp_dir = TempDir::new().unwrap();
+        let zip_path = temp_dir.path().join("symlink.zip");
+        {
+            let file = std::fs::File::create(&zip_path).unwrap();
+            let mut zip = ZipWriter::new(file);
+            zip.start_file(
+                "readme.txt",
+                FileOptions::default().compression_method(CompressionMethod::Stored),
+            )
+            .unwrap();
+            zip.write_all(b"hello").unwrap();
+            zip.add_symlink("addons", "/etc", FileOptions::default())
+                .unwrap();
+            zip.finish().unwrap();
+        }
+        let dest_dir = TempDir::new().unwrap();
+        let result = extractor
+            .extract_zip(zip_path, dest_dir.path().to_path_buf())
+            .await;
+
+        assert!(result.unwrap_err().to_string().contains("symbolic link"));
+        let link = dest_dir.path().join("addons");
+        assert!(std::fs::symlink_metadata(&link).is_err());
+        assert!(!dest_dir.path().join("readme.txt").exists());
+    }
+
     #[tokio::test]
     async fn test_extract_aborts_when_actual_bytes_exceed_limit() {
         let extractor = ZipExtractor::new(8, 100);
@@ -285,6 +459,54 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("exceed"));
     }
 
+    #[tokio::test]
+    async fn test_tripped_extraction_keeps_disk_usage_within_limit() {
+        fn dir_size(path: &Path) -> u64 {
+            std::fs::read_dir(path)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .map(|entry| match entry.metadata() {
+                    Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+                    Ok(meta) => meta.len(),
+                    Err(_) => 0,
+                })
+                .sum()
+        }
+
+        const LIMIT: u64 = 64 * 1024;
+        let huge = vec![0u8; 4 * 1024 * 1024];
+        let (zip_path, _zip_temp) =
+            create_test_zip(&[("small.txt", &[1u8; 1024]), ("maps/huge.bin", &huge)]);
+        let dest_dir = TempDir::new().unwrap();
+        let dest_path = dest_dir.path().to_path_buf();
+
+        // Sample the destination while extraction runs to catch a transient overshoot.
+        let done = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let sampler = {
+            let done = done.clone();
+            let dest_path = dest_path.clone();
+            std::thread::spawn(move || {
+                let mut peak = 0;
+                while !done.load(std::sync::atomic::Ordering::SeqCst) {
+                    peak = peak.max(dir_size(&dest_path));
+                    std::thread::yield_now();
+                }
+                peak.max(dir_size(&dest_path))
+            })
+        };
+        let result = ZipExtractor::new(LIMIT, 100)
+            .extract_zip(zip_path, dest_path.clone())
+            .await;
+        done.store(true, std::sync::atomic::Ordering::SeqCst);
+        let peak = sampler.join().unwrap();
+
+        assert!(result.unwrap_err().to_string().contains("exceed"));
+        assert!(peak <= LIMIT, "peak disk usage {peak} exceeded {LIMIT}");
+        assert!(!dest_path.join("maps/huge.bin").exists());
+        assert!(dir_size(&dest_path) <= LIMIT);
+    }
+
     #[tokio::test]
     async fn test_extract_vpk_not_supported() {
         let extractor = ZipExtractor::new(1024 * 1024 * 1024, 10000);
@@ -325,4 +547,153 @@ mod tests {
         assert!(dest_path.join("file-with-dashes.txt").exists());
         assert!(dest_path.join("file_with_underscores.txt").exists());
     }
+
+    /// Replace every occurrence of `from` with `to` (same length) in the archive bytes.
+    fn patch_bytes(bytes: &mut [u8], from: &[u8], to: &[u8]) {
+        assert_eq!(from.len(), to.len());
+        let mut i = 0;
+        while i + from.len() <= bytes.len() {
+            if &bytes[i..i + from.len()] == from {
+                bytes[i..i + from.len()].copy_from_slice(to);
+                i += from.len();
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_extract_keeps_invalid_utf8_names_distinct() {
+        // Non-ASCII names make the writer set the UTF-8 flag; the bytes are then
+        // swapped for invalid UTF-8 that the zip crate would decode to the same name.
+        let (zip_path, _zip_temp) = create_test_zip(&[
+            ("\u{e9}.txt", b"first"),
+            ("\u{e8}.txt", b"second"),
+            ("%FF%FF.txt", b"literal"),
+        ]);
+        let mut bytes = std::fs::read(&zip_path).unwrap();
+        patch_bytes(&mut bytes, "\u{e9}.txt".as_bytes(), b"\xff\xff.txt");
+        patch_bytes(&mut bytes, "\u{e8}.txt".as_bytes(), b"\xfe\xfe.txt");
+        std::fs::write(&zip_path, bytes).unwrap();
+
+        let extractor = ZipExtractor::new(1024 * 1024, 100);
+        let dest_dir = TempDir::new().unwrap();
+        extractor
+            .extract_zip(zip_path, dest_dir.path().to_path_buf())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dest_dir.path().join("%FF%FF.txt")).unwrap(),
+            "literal"
+        );
+        let mut escaped: Vec<(String, String)> = std::fs::read_dir(dest_dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.file_name().unwrap() != "%FF%FF.txt")
+            .map(|path| {
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                (name, std::fs::read_to_string(&path).unwrap())
+            })
+            .collect();
+        escaped.sort_by(|a, b| a.1.cmp(&b.1));
+        assert_eq!(escaped.len(), 2);
+        assert!(escaped[0].0.starts_with("%FF%FF~") && escaped[0].0.ends_with(".txt"), "{escaped:?}");
+        assert_eq!(escaped[0].1, "first");
+        assert!(escaped[1].0.starts_with("%FE%FE~") && escaped[1].0.ends_with(".txt"), "{escaped:?}");
+        assert_eq!(escaped[1].1, "second");
+    }
+
+    #[tokio::test]
+    async fn test_extract_keeps_valid_names_with_percent_unchanged() {
+        let (zip_path, _zip_temp) = create_test_zip(&[("maps/100%.vpk", b"vpk bytes"), ("50%25.txt", b"notes")]);
+        let extractor = ZipExtractor::new(1024 * 1024, 100);
+        let dest_dir = TempDir::new().unwrap();
+        extractor
+            .extract_zip(zip_path, dest_dir.path().to_path_buf())
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(dest_dir.path().join("maps/100%.vpk")).unwrap(), b"vpk bytes");
+        assert_eq!(std::fs::read(dest_dir.path().join("50%25.txt")).unwrap(), b"notes");
+    }
+
+    #[tokio::test]
+    async fn test_extension_filter_extracts_only_matching_files() {
+        let screenshot = vec![0u8; 4096];
+        let (zip_path, _zip_temp) = create_test_zip(&[
+            ("readme.txt", b"Install me"),
+            ("screens/shot.png", &screenshot),
+            ("maps/mymap.VPK", b"vpk bytes"),
+        ]);
+        let dest_dir = TempDir::new().unwrap();
+        let dest_path = dest_dir.path().to_path_buf();
+
+        // The screenshot alone would exceed the size limit and the file count is 1.
+        let extractor = ZipExtractor::new(1024, 1).with_extension_filter(&["vpk"]);
+        extractor
+            .extract_zip(zip_path.clone(), dest_path.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(dest_path.join("maps/mymap.VPK")).unwrap(), b"vpk bytes");
+        assert!(!dest_path.join("readme.txt").exists());
+        assert!(!dest_path.join("screens").exists());
+
+        let unfiltered = ZipExtractor::new(1024, 10);
+        assert!(unfiltered
+            .extract_zip(zip_path, TempDir::new().unwrap().path().to_path_buf())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_vpk_check_and_extraction_share_one_archive_open() {
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let (zip_path, _zip_temp) = create_test_zip(&[
+            ("readme.txt", b"notes"),
+            ("maps/mymap.vpk", b"vpk bytes"),
+        ]);
+        let dest_dir = TempDir::new().unwrap();
+        let opens = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&opens);
+
+        let extractor = ZipExtractor::new(1024, 10).with_extension_filter(&["vpk"]);
+        extractor
+            .extract_opened(
+                move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    File::open(&zip_path)
+                },
+                dest_dir.path().to_path_buf(),
+                true,
+                ArchiveEntryPolicy::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(opens.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            std::fs::read(dest_dir.path().join("maps/mymap.vpk")).unwrap(),
+            b"vpk bytes"
+        );
+        assert!(!dest_dir.path().join("readme.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_requiring_vpk_rejects_archive_without_vpk_before_writing() {
+        let (zip_path, _zip_temp) = create_test_zip(&[("readme.txt", b"notes")]);
+        let dest_dir = TempDir::new().unwrap();
+        let dest_path = dest_dir.path().join("out");
+
+        let error = ZipExtractor::new(1024, 10)
+            .extract_zip_requiring_vpk(zip_path, dest_path.clone(), ArchiveEntryPolicy::default())
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("does not contain any .vpk files"));
+        assert_eq!(std::fs::read_dir(&dest_path).unwrap().count(), 0);
+    }
 }
@@ -1,15 +1,18 @@
 // SPDX-License-Identifier: GPL-3.0-only
 mod api;
 mod catalog;
+mod cli;
 mod config;
 mod config_watch;
 mod downloader;
 mod extractor;
 mod logging;
 mod map_installer;
+mod maps_blocklist;
 mod maps_denylist;
 mod repl;
 mod registry;
+mod shutdown;
 mod sync;
 mod utils;
 mod watcher;
@@ -21,20 +24,24 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use clap::Parser;
 use tokio::signal;
 use tracing::{debug, error, info, warn};
 
 use config::{init_handle, read_config, Config};
 use logging::setup_logging;
+use downloader::client::HttpSettings;
 use registry::{JsonRegistry, Registry, SourceKind};
-use sync::{BackendSyncService, SyncService};
-use watcher::{InotifyWatcher, PendingEntry, Watcher, schedule_pending, should_force_sync};
+use sync::{BackendSyncService, SyncRunner, SyncService};
+use watcher::{InotifyWatcher, PendingEntry, Watcher, WatcherStatus, schedule_pending, should_force_sync};
+use api::drain::DrainState;
 use api::HttpServer;
 use map_installer::{is_watched_map_path, MapInstallationService};
-use maps_denylist::Mapsdenylist;
 use repl::{DaemonCommand, start_key_listener};
 
 const STEAM_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// How often a disabled periodic task checks whether config re-enabled it.
+const DISABLED_TASK_RECHECK: Duration = Duration::from_secs(60);
 
 enum WatcherWork {
     Sync {
@@ -47,10 +54,6 @@ enum WatcherWork {
     },
 }
 
-fn read_denylist(handle: &config::ConfigHandle) -> Mapsdenylist {
-    Mapsdenylist::from_config(&read_config(handle))
-}
-
 fn notify_console_updates(source: &str, updates: &[map_installer::AvailableMapUpdate]) {
     println!("Map updates available ({source}): {}", updates.len());
     for item in updates {
@@ -97,32 +100,35 @@ async fn cleanup_download_temp_dir(temp_dir: &std::path::Path) {
     }
 }
 
-fn registry_sync_fingerprint(maps: &[registry::MapEntry]) -> u64 {
-    use std::collections::hash_map::DefaultHasher;
-    use std::hash::{Hash, Hasher};
-
-    let mut hasher = DefaultHasher::new();
-    for map in maps {
-        map.id.hash(&mut hasher);
-        map.name.hash(&mut hasher);
-        map.installed_path.hash(&mut hasher);
-        map.source_url.hash(&mut hasher);
-        map.checksum.hash(&mut hasher);
-        map.workshop_id.hash(&mut hasher);
-        map.installed_at.timestamp().hash(&mut hasher);
-    }
-    hasher.finish()
-}
-
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = cli::Cli::parse();
+    let mut config_paths = config::resolve_config_paths(cli.config.as_deref());
+    if cli.check_config {
+        match cli::check_config(&config_paths) {
+            Ok(summary) => {
+                println!("{summary}");
+                std::process::exit(0);
+            }
+            Err(error) => {
+                eprintln!("Config invalid: {error:#}");
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Load configuration
-    let (config, config_path) = Config::load_with_path()?;
+    let (config, config_path) = Config::load_with_paths(&config_paths, cli.strict_config)?;
     config.validate()?;
+    // Reloads read the watched (canonical) primary file plus the same override layers.
+    config_paths[0] = config_path.clone();
 
     let config_handle = init_handle(config);
-    let _config_watcher = config_watch::spawn_config_watcher(config_handle.clone(), config_path)
-        .map_err(|e| anyhow::anyhow!(e))?;
+    let _config_watcher =
+        config_watch::spawn_config_watcher(config_handle.clone(), config_paths.clone())
+            .map_err(|e| anyhow::anyhow!(e))?;
+    #[cfg(unix)]
+    let _sighup_reloader = config_watch::spawn_sighup_reloader(config_handle.clone(), config_paths)?;
 
     let config = read_config(&config_handle);
     
@@ -146,11 +152,17 @@ async fn main() -> anyhow::Result<()> {
     
     let mut watcher = InotifyWatcher::new();
     let watcher_events = watcher.watch(addons_dir.clone()).await?;
+    let watcher_status = WatcherStatus::new();
+    watcher_status.set_running(true);
     
     // Create temp directory for downloads
-    let temp_dir = std::env::temp_dir().join("kether-downloads");
-    tokio::fs::create_dir_all(&temp_dir).await?;
+    let temp_dir = config.download_staging_dir();
+    utils::ensure_writable_dir(&temp_dir).await?;
+    if let Err(error) = utils::check_sufficient_space(&temp_dir, 512 * 1024 * 1024) {
+        warn!(path = %temp_dir.display(), error = %error, "Download temp directory is low on space");
+    }
     cleanup_download_temp_dir(&temp_dir).await;
+    info!("Download temp directory: {}", temp_dir.display());
     
     // Initialize map installation service
     let installer = Arc::new(
@@ -163,9 +175,28 @@ async fn main() -> anyhow::Result<()> {
             config.max_extraction_file_count,
         )
         .await?
+        .with_http_settings(HttpSettings::from_config(&config))?
+        .with_config(config_handle.clone())
+        .with_audit_log(config.registry_path.with_extension("audit.jsonl"))
     );
     info!("Map installation service initialized");
 
+    // The watcher is already subscribed, so files added during the import are not missed;
+    // its events for files imported here resolve to the existing entries.
+    if config.import_existing_on_start
+        && let Err(error) = installer.import_existing_maps().await
+    {
+        warn!(error = %error, "Failed to import existing addons on startup");
+    }
+    if let Err(error) = installer.backfill_map_sizes().await {
+        warn!(error = %error, "Failed to backfill map sizes");
+    }
+    if config.recompute_checksums_on_start
+        && let Err(error) = installer.recompute_checksums().await
+    {
+        warn!(error = %error, "Failed to recompute checksums on startup");
+    }
+
     let (daemon_tx, mut daemon_rx) = tokio::sync::mpsc::unbounded_channel::<DaemonCommand>();
     
     // Spawn tasks
@@ -174,7 +205,7 @@ async fn main() -> anyhow::Result<()> {
     let (watcher_work_tx, mut watcher_work_rx) =
         tokio::sync::mpsc::channel::<WatcherWork>(128);
 
-    let watcher_worker = tokio::spawn(async move {
+    let watcher_worker = tokio::spawn(map_installer::with_audit_actor("watcher", async move {
         info!("Watcher sync worker started");
         while let Some(work) = watcher_work_rx.recv().await {
             match work {
@@ -218,8 +249,9 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
         }
-    });
+    }));
 
+    let watcher_task_status = watcher_status.clone();
     let watcher_task = tokio::spawn(async move {
         info!("Watcher task started");
         let mut receiver = watcher_events;
@@ -331,152 +363,81 @@ async fn main() -> anyhow::Result<()> {
                 }
             }
         }
+        watcher_task_status.set_running(false);
+        warn!("Addons watcher stopped delivering events");
     });
     
-    let installer_sync = Arc::clone(&installer);
-    let sync_service_clone = Arc::clone(&sync_service);
-    let sync_config_handle = config_handle.clone();
+    let sync_runner = Arc::new(
+        SyncRunner::new(
+            Arc::clone(&installer),
+            Arc::clone(&sync_service),
+            config_handle.clone(),
+        )
+        .with_state_path(config.registry_path.with_extension("sync.json")),
+    );
+    let sync_runner_task = Arc::clone(&sync_runner);
     let sync_task = tokio::spawn(async move {
         info!("Sync task started");
-        let mut interval_secs = read_config(&sync_config_handle).sync_interval_secs;
-        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
-        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
-
-        // Per-update exponential backoff for repeatedly failing backend actions.
-        let mut failure_backoff: HashMap<String, (u32, Instant)> = HashMap::new();
-        let mut last_push_fingerprint: Option<u64> = None;
+        sync_runner_task.run_periodic().await;
+    });
 
+    let installer_steam_health = Arc::clone(&installer);
+    let steam_health_task = tokio::spawn(async move {
+        info!("Steam connection health-check task started");
+        let mut interval = tokio::time::interval(STEAM_HEALTH_CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
         loop {
             interval.tick().await;
+            installer_steam_health.steam_health_check().await;
+        }
+    });
 
-            let current_config = read_config(&sync_config_handle);
-            if current_config.sync_interval_secs != interval_secs {
-                interval_secs = current_config.sync_interval_secs;
-                // Avoid an immediate burst tick when rebuilding the interval.
-                interval = tokio::time::interval_at(
-                    tokio::time::Instant::now() + Duration::from_secs(interval_secs),
-                    Duration::from_secs(interval_secs),
-                );
-                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
-            }
-
-            // Prune stale backoff entries (keep for at most 1h past expiry).
-            let backoff_cutoff = Instant::now() - Duration::from_secs(3600);
-            failure_backoff.retain(|_, (_, retry_after)| *retry_after > backoff_cutoff);
-
-            // Fetch updates from backend
-            match sync_service_clone.fetch_updates().await {
-                Ok(updates) => {
-                    let now = Instant::now();
-                    for update in updates {
-                        let update_key = format!("{}:{}", update.action, update.map_id);
-                        if let Some((_, retry_after)) = failure_backoff.get(&update_key)
-                            && *retry_after > now
-                        {
-                            debug!(
-                                map_id = %update.map_id,
-                                action = %update.action,
-                                "Skipping backend update due to backoff"
-                            );
-                            continue;
-                        }
-
-                        let result = match update.action.as_str() {
-                            "install" => {
-                                info!(map_id = %update.map_id, "Backend requested map installation");
-                                if let Some(ref map_entry) = update.map_entry {
-                                    if let Some(workshop_id) = map_entry.workshop_id {
-                                        installer_sync
-                                            .install_from_workshop_id(workshop_id, None)
-                                            .await
-                                            .map(|_| ())
-                                    } else {
-                                        installer_sync
-                                            .install_from_url(
-                                                map_entry.source_url.clone(),
-                                                Some(map_entry.name.clone()),
-                                            )
-                                            .await
-                                            .map(|_| ())
-                                    }
-                                } else {
-                                    warn!(map_id = %update.map_id, "Backend update missing installation details");
-                                    Ok(())
-                                }
-                            }
-                            "uninstall" => {
-                                info!(map_id = %update.map_id, "Backend requested map uninstallation");
-                                match update.map_id.parse::<u64>() {
-                                    Ok(map_id) => match installer_sync.uninstall_map(map_id).await {
-                                        Ok(()) => Ok(()),
-                                        Err(e) if e.to_string().contains("not found") => Ok(()),
-                                        Err(e) => Err(e),
-                                    },
-                                    Err(e) => {
-                                        error!(error = %e, map_id = %update.map_id, "Invalid map ID format from backend");
-                                        Ok(())
-                                    }
-                                }
-                            }
-                            _ => {
-                                warn!(action = %update.action, "Unknown sync action");
-                                Ok(())
-                            }
-                        };
-
-                        match result {
-                            Ok(()) => {
-                                failure_backoff.remove(&update_key);
-                            }
-                            Err(e) => {
-                                error!(error = %e, map_id = %update.map_id, action = %update.action, "Failed to apply backend update");
-                                let failures = failure_backoff
-                                    .get(&update_key)
-                                    .map(|(count, _)| *count)
-                                    .unwrap_or(0)
-                                    .saturating_add(1);
-                                let delay_secs = 2_u64.saturating_pow(failures.min(6));
-                                failure_backoff.insert(
-                                    update_key,
-                                    (failures, Instant::now() + Duration::from_secs(delay_secs)),
-                                );
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(error = %e, "Failed to fetch updates from backend");
-                }
+    let installer_temp_cleanup = Arc::clone(&installer);
+    let temp_cleanup_config_handle = config_handle.clone();
+    let temp_cleanup_task = tokio::spawn(async move {
+        info!("Temp cleanup task started");
+        // Startup already emptied the temp dir, so the first sweep waits one interval.
+        loop {
+            let interval_secs = read_config(&temp_cleanup_config_handle).temp_cleanup_interval_secs;
+            if interval_secs == 0 {
+                tokio::time::sleep(DISABLED_TASK_RECHECK).await;
+                continue;
             }
+            tokio::time::sleep(Duration::from_secs(interval_secs)).await;
 
-            // Push local state to backend when content changed.
-            match installer_sync.registry().list_maps().await {
-                Ok(maps) => {
-                    let visible = read_denylist(&sync_config_handle).filter_visible(maps);
-                    let fingerprint = registry_sync_fingerprint(&visible);
-                    if last_push_fingerprint == Some(fingerprint) {
-                        debug!("Skipping registry push; content unchanged");
-                    } else if let Err(e) = sync_service_clone.sync_registry(visible).await {
-                        error!(error = %e, "Failed to sync registry to backend");
-                    } else {
-                        last_push_fingerprint = Some(fingerprint);
-                    }
-                }
-                Err(e) => {
-                    error!(error = %e, "Failed to list maps for sync");
-                }
+            let max_age = read_config(&temp_cleanup_config_handle).temp_cleanup_max_age_secs;
+            if let Err(e) = installer_temp_cleanup
+                .cleanup_temp(Duration::from_secs(max_age))
+                .await
+            {
+                warn!(error = %e, "Periodic temp cleanup failed");
             }
         }
     });
 
-    let installer_steam_health = Arc::clone(&installer);
-    let steam_health_task = tokio::spawn(async move {
-        info!("Steam connection health-check task started");
-        let mut interval = tokio::time::interval(STEAM_HEALTH_CHECK_INTERVAL);
-        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    let installer_integrity = Arc::clone(&installer);
+    let integrity_config_handle = config_handle.clone();
+    let integrity_scan_task = tokio::spawn(async move {
+        info!("Integrity scan task started");
         loop {
-            interval.tick().await;
-            installer_steam_health.steam_health_check().await;
+            let interval_secs = read_config(&integrity_config_handle).integrity_scan_interval_secs;
+            if interval_secs == 0 {
+                tokio::time::sleep(DISABLED_TASK_RECHECK).await;
+                continue;
+            }
+            tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+
+            match installer_integrity.scan_integrity().await {
+                Ok(report) if !report.missing.is_empty() || !report.mismatched.is_empty() => {
+                    warn!(
+                        missing = ?report.missing,
+                        mismatched = ?report.mismatched,
+                        "Integrity scan found drifted maps"
+                    );
+                }
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, "Periodic integrity scan failed"),
+            }
         }
     });
 
@@ -504,8 +465,9 @@ async fn main() -> anyhow::Result<()> {
 
             let workshop_enabled = current.workshop_update_check_enabled;
             let l4d2_enabled = current.l4d2center_update_check_enabled;
-            let workshop_auto_apply = current.workshop_update_auto_apply;
-            let l4d2_auto_apply = current.l4d2center_update_auto_apply;
+            // Read-only mode still checks, so updates are listed, but never applies them.
+            let workshop_auto_apply = current.workshop_update_auto_apply && !current.read_only;
+            let l4d2_auto_apply = current.l4d2center_update_auto_apply && !current.read_only;
             let index_url = current.l4d2center_index_url.clone();
 
             // Check + pending refresh under the lock; auto-apply runs after drop.
@@ -665,12 +627,21 @@ async fn main() -> anyhow::Result<()> {
     let installer_http = Arc::clone(&installer);
     let http_addr = config.local_api_bind;
     let http_config_handle = config_handle.clone();
-    let http_server = HttpServer::new(
+    let drain = DrainState::new();
+    let mut http_server = HttpServer::new(
         registry_http,
         installer_http,
         http_addr,
         http_config_handle,
-    );
+    )
+    .with_sync_pause(sync_runner.pause_control())
+    .with_sync_trigger(sync_runner.trigger_control())
+    .with_sync_health(sync_runner.health_control())
+    .with_watcher_status(watcher_status)
+    .with_drain(drain.clone());
+    if let (Some(cert_path), Some(key_path)) = (&config.tls_cert_path, &config.tls_key_path) {
+        http_server = http_server.with_tls(cert_path.clone(), key_path.clone());
+    }
     let http_task = tokio::spawn(async move {
         if let Err(e) = http_server.serve().await {
             error!(error = %e, "HTTP server error");
@@ -710,17 +681,27 @@ async fn main() -> anyhow::Result<()> {
     
     // Graceful shutdown
     info!("Initiating graceful shutdown...");
+    // Refuse new API work before tearing anything down.
+    if !drain.drain() {
+        info!("Entered drain mode for shutdown");
+    }
     
-    watcher_task.abort();
-    watcher_worker.abort();
-    sync_task.abort();
-    steam_health_task.abort();
-    map_update_task.abort();
-    http_task.abort();
-    repl_task.abort();
-    
-    // Give aborted tasks a brief moment to unwind and flush logs.
-    tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+    let shutdown_grace = Duration::from_secs(read_config(&config_handle).shutdown_grace_secs);
+    shutdown::stop_tasks(
+        vec![
+            ("watcher", watcher_task),
+            ("watcher_worker", watcher_worker),
+            ("sync", sync_task),
+            ("steam_health", steam_health_task),
+            ("temp_cleanup", temp_cleanup_task),
+            ("integrity_scan", integrity_scan_task),
+            ("map_update", map_update_task),
+            ("http", http_task),
+            ("repl", repl_task),
+        ],
+        shutdown_grace,
+    )
+    .await;
     
     info!("Shutdown complete");
     
@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Append-only install/uninstall/update history.
+//!
+//! Entries are stored as JSON lines next to the registry. The acting party
+//! ("api", "sync", "watcher", ...) is taken from the task-local scope set by the
+//! caller via [`with_audit_actor`], so service methods need no extra parameter.
+
+use std::future::Future;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+use utoipa::ToSchema;
+
+/// Actor recorded when no caller scope is set (periodic tasks, startup).
+const DEFAULT_ACTOR: &str = "daemon";
+
+tokio::task_local! {
+    static AUDIT_ACTOR: &'static str;
+}
+
+/// Run `fut` with `actor` recorded on any audit entries it writes.
+pub async fn with_audit_actor<F: Future>(actor: &'static str, fut: F) -> F::Output {
+    AUDIT_ACTOR.scope(actor, fut).await
+}
+
+fn current_actor() -> &'static str {
+    AUDIT_ACTOR.try_with(|actor| *actor).unwrap_or(DEFAULT_ACTOR)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Install,
+    Uninstall,
+    Update,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AuditEntry {
+    pub action: AuditAction,
+    pub map_id: u64,
+    /// Download URL, workshop source or installed path, when known.
+    pub source: Option<String>,
+    pub timestamp: DateTime<Utc>,
+    pub actor: String,
+}
+
+/// JSON-lines audit file; a log without a path records nothing.
+#[derive(Default)]
+pub struct AuditLog {
+    path: Option<PathBuf>,
+    write_lock: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path: Some(path),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Append an entry for the current actor; failures are logged, never returned.
+    pub async fn record(&self, action: AuditAction, map_id: u64, source: Option<String>) {
+        let entry = AuditEntry {
+            action,
+            map_id,
+            source,
+            timestamp: Utc::now(),
+            actor: current_actor().to_string(),
+        };
+        if let Err(e) = self.append(&entry).await {
+            warn!(error = %e, map_id, action = ?action, "Failed to write audit entry");
+        }
+    }
+
+    async fn append(&self, entry: &AuditEntry) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let _guard = self.write_lock.lock().await;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .with_context(|| format!("Failed to open audit log {}", path.display()))?;
+        file.write_all(&line).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// Most recent entries first, optionally only for `map_id`.
+    pub async fn recent(&self, map_id: Option<u64>, limit: usize) -> anyhow::Result<Vec<AuditEntry>> {
+        let Some(path) = &self.path else {
+            return Ok(Vec::new());
+        };
+        let content = match tokio::fs::read_to_string(path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read audit log {}", path.display()));
+            }
+        };
+
+        Ok(content
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+            .filter(|entry| map_id.is_none_or(|id| entry.map_id == id))
+            .take(limit)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn records_actor_from_scope_and_returns_newest_first() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let log = AuditLog::new(dir.path().join("registry.audit.jsonl"));
+
+        log.record(AuditAction::Install, 1, Some("https://example.com/a.zip".into()))
+            .await;
+        with_audit_actor("sync", log.record(AuditAction::Uninstall, 1, None)).await;
+        log.record(AuditAction::Install, 2, None).await;
+
+        let all = log.recent(None, 10).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].map_id, 2);
+
+        let map_one = log.recent(Some(1), 10).await.unwrap();
+        assert_eq!(map_one[0].action, AuditAction::Uninstall);
+        assert_eq!(map_one[0].actor, "sync");
+        assert_eq!(map_one[1].action, AuditAction::Install);
+        assert_eq!(map_one[1].actor, DEFAULT_ACTOR);
+
+        assert_eq!(log.recent(None, 1).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::warn;
+
+/// Buffered events per subscriber before slow readers start lagging.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Install/uninstall/sync activity published for live observers (`GET /api/events`,
+/// or `GET /api/operations/{id}/stream` for a single install).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MapEvent {
+    InstallStarted {
+        source: String,
+        /// Pass to `POST /api/operations/{id}/cancel` to abort this install
+        operation_id: u64,
+    },
+    /// Download progress of a running install (throttled like download progress logs)
+    InstallProgress {
+        operation_id: u64,
+        bytes_downloaded: u64,
+        /// Content-Length, when the server sent one
+        bytes_total: Option<u64>,
+    },
+    InstallCompleted {
+        source: String,
+        operation_id: u64,
+        map_id: u64,
+        name: String,
+    },
+    InstallFailed {
+        source: String,
+        operation_id: u64,
+        error: String,
+    },
+    Uninstalled {
+        map_id: u64,
+        name: String,
+    },
+    SyncCompleted {
+        applied: usize,
+        failed: usize,
+        pushed: bool,
+    },
+}
+
+impl MapEvent {
+    /// Event name used for the SSE `event:` field (matches the serialized `type`).
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::InstallStarted { .. } => "install_started",
+            Self::InstallProgress { .. } => "install_progress",
+            Self::InstallCompleted { .. } => "install_completed",
+            Self::InstallFailed { .. } => "install_failed",
+            Self::Uninstalled { .. } => "uninstalled",
+            Self::SyncCompleted { .. } => "sync_completed",
+        }
+    }
+
+    /// Install operation the event belongs to, if any.
+    pub fn operation_id(&self) -> Option<u64> {
+        match self {
+            Self::InstallStarted { operation_id, .. }
+            | Self::InstallProgress { operation_id, .. }
+            | Self::InstallCompleted { operation_id, .. }
+            | Self::InstallFailed { operation_id, .. } => Some(*operation_id),
+            Self::Uninstalled { .. } | Self::SyncCompleted { .. } => None,
+        }
+    }
+
+    /// Whether this is the last event of its install operation.
+    pub fn ends_operation(&self) -> bool {
+        matches!(self, Self::InstallCompleted { .. } | Self::InstallFailed { .. })
+    }
+}
+
+/// Events of install `operation_id` from `receiver`, ending after its completed or
+/// failed event (or when the bus closes).
+pub fn operation_events(
+    receiver: broadcast::Receiver<MapEvent>,
+    operation_id: u64,
+) -> impl Stream<Item = MapEvent> {
+    futures_util::stream::unfold(Some(receiver), move |receiver| async move {
+        let mut receiver = receiver?;
+        loop {
+            match receiver.recv().await {
+                Ok(event) if event.operation_id() == Some(operation_id) => {
+                    let next = (!event.ends_operation()).then_some(receiver);
+                    return Some((event, next));
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(operation_id, skipped, "Operation stream lagged; events dropped");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+/// Broadcast channel shared by the installer and its subscribers.
+#[derive(Clone)]
+pub struct MapEventBus {
+    sender: broadcast::Sender<MapEvent>,
+}
+
+impl MapEventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Publish an event; dropped silently when nobody is subscribed.
+    pub fn publish(&self, event: MapEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<MapEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for MapEventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kind_matches_serialized_type() {
+        let event = MapEvent::InstallCompleted {
+            source: "https://example.com/map.zip".to_string(),
+            operation_id: 1,
+            map_id: 3,
+            name: "map".to_string(),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], event.kind());
+    }
+
+    #[tokio::test]
+    async fn subscribers_receive_published_events() {
+        let bus = MapEventBus::new();
+        let mut rx = bus.subscribe();
+        bus.publish(MapEvent::Uninstalled {
+            map_id: 1,
+            name: "gone".to_string(),
+        });
+        assert_eq!(rx.recv().await.unwrap().kind(), "uninstalled");
+    }
+}
@@ -85,7 +85,7 @@ pub fn source_kind_from_url(url: &str) -> SourceKind {
     } else if lower.contains("sirplease.vercel.app") {
         SourceKind::SirPlease
     } else {
-        SourceKind::Other
+        SourceKind::Url
     }
 }
 
@@ -195,10 +195,10 @@ mod tests {
     }
 
     #[test]
-    fn source_kind_from_url_defaults_to_other() {
+    fn source_kind_from_url_defaults_to_url() {
         assert_eq!(
             source_kind_from_url("https://example.com/map.zip"),
-            SourceKind::Other
+            SourceKind::Url
         );
     }
 }
@@ -0,0 +1,177 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Operator commands run after a map is installed or uninstalled.
+//!
+//! The command is started directly rather than through a shell, and map details are
+//! passed only as environment variables, so map names cannot inject arguments. It gets
+//! no other environment (no daemon secrets) and is killed if it outlives
+//! [`MAP_HOOK_TIMEOUT`].
+
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+use crate::registry::models::MapEntry;
+
+pub const MAP_ID_ENV: &str = "KETHER_MAP_ID";
+pub const MAP_NAME_ENV: &str = "KETHER_MAP_NAME";
+pub const MAP_PATH_ENV: &str = "KETHER_MAP_PATH";
+
+/// How long a background hook may run before it is killed.
+pub const MAP_HOOK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Run `command` for `entry` (installed at `path`) and wait up to `timeout` for it to
+/// exit; past that it is killed and an error returned.
+pub async fn run_map_hook(
+    command: &Path,
+    entry: &MapEntry,
+    path: &Path,
+    timeout: Duration,
+) -> anyhow::Result<ExitStatus> {
+    let mut child = tokio::process::Command::new(command)
+        .env_clear()
+        .env(MAP_ID_ENV, entry.id.to_string())
+        .env(MAP_NAME_ENV, &entry.name)
+        .env(MAP_PATH_ENV, path)
+        .stdin(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+    match tokio::time::timeout(timeout, child.wait()).await {
+        Ok(status) => Ok(status?),
+        Err(_) => {
+            child.kill().await?;
+            Err(anyhow::anyhow!(
+                "Map hook timed out after {}s and was killed",
+                timeout.as_secs()
+            ))
+        }
+    }
+}
+
+/// Run `command` in the background; a failure to start or a non-zero exit is only logged.
+pub fn spawn_map_hook(hook: &'static str, command: PathBuf, entry: &MapEntry, path: PathBuf) {
+    let entry = entry.clone();
+    tokio::spawn(async move {
+        match run_map_hook(&command, &entry, &path, MAP_HOOK_TIMEOUT).await {
+            Ok(status) if status.success() => {
+                info!(hook, map_id = entry.id, command = %command.display(), "Map hook finished");
+            }
+            Ok(status) => {
+                warn!(hook, map_id = entry.id, command = %command.display(), %status, "Map hook exited unsuccessfully");
+            }
+            Err(error) => {
+                warn!(hook, map_id = entry.id, command = %command.display(), error = %error, "Failed to run map hook");
+            }
+        }
+    });
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn write_script(dir: &Path, body: &str) -> PathBuf {
+        let script = dir.join("hook.sh");
+        std::fs::write(&script, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        script
+    }
+
+    fn hook_entry(name: &str) -> MapEntry {
+        MapEntry {
+            id: 42,
+            source_url: String::new(),
+            installed_path: "evil.vpk".to_string(),
+            ..crate::test_helpers::test_map_entry(name)
+        }
+    }
+
+    #[tokio::test]
+    async fn hook_receives_map_details_as_env_vars() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("env.txt");
+        let script = write_script(
+            dir.path(),
+            &format!(
+                "printf '%s\\n%s\\n%s\\n' \"$KETHER_MAP_ID\" \"$KETHER_MAP_NAME\" \"$KETHER_MAP_PATH\" > '{}'",
+                out.display()
+            ),
+        );
+        // Shell metacharacters in the name must reach the hook verbatim.
+        let entry = hook_entry("evil; touch pwned $(id)");
+
+        let status = run_map_hook(&script, &entry, Path::new("/addons/evil.vpk"), MAP_HOOK_TIMEOUT)
+            .await
+            .unwrap();
+
+        assert!(status.success());
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "42\nevil; touch pwned $(id)\n/addons/evil.vpk\n"
+        );
+        assert!(!dir.path().join("pwned").exists());
+    }
+
+    #[tokio::test]
+    async fn non_zero_exit_is_reported_not_raised() {
+        let entry = hook_entry("map");
+        let status = run_map_hook(Path::new("/bin/false"), &entry, Path::new("/x.vpk"), MAP_HOOK_TIMEOUT)
+            .await
+            .unwrap();
+        assert!(!status.success());
+        let status = run_map_hook(Path::new("/bin/true"), &entry, Path::new("/x.vpk"), MAP_HOOK_TIMEOUT)
+            .await
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[tokio::test]
+    async fn hook_gets_only_the_map_variables() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("env.txt");
+        // `env` is called by absolute path: the hook does not inherit the daemon's PATH.
+        let script = write_script(
+            dir.path(),
+            &format!("/usr/bin/env > '{}'", out.display()),
+        );
+
+        let status = run_map_hook(&script, &hook_entry("map"), Path::new("/x.vpk"), MAP_HOOK_TIMEOUT)
+            .await
+            .unwrap();
+
+        assert!(status.success());
+        let mut names: Vec<String> = std::fs::read_to_string(&out)
+            .unwrap()
+            .lines()
+            .filter_map(|line| line.split_once('=').map(|(name, _)| name.to_string()))
+            // Set by the shell itself, not passed in by the daemon.
+            .filter(|name| !matches!(name.as_str(), "PWD" | "OLDPWD" | "SHLVL" | "_"))
+            .collect();
+        names.sort();
+        assert_eq!(names, vec![MAP_ID_ENV, MAP_NAME_ENV, MAP_PATH_ENV]);
+    }
+
+    #[tokio::test]
+    async fn hook_is_killed_after_the_timeout() {
+        let dir = TempDir::new().unwrap();
+        let pid_file = dir.path().join("pid");
+        let script = write_script(
+            dir.path(),
+            &format!("echo $$ > '{}'\nexec /bin/sleep 30", pid_file.display()),
+        );
+
+        let started = std::time::Instant::now();
+        let error = run_map_hook(&script, &hook_entry("map"), Path::new("/x.vpk"), Duration::from_millis(500))
+            .await
+            .unwrap_err();
+
+        assert!(error.to_string().contains("timed out"), "{error}");
+        assert!(started.elapsed() < Duration::from_secs(10));
+        let pid: i32 = std::fs::read_to_string(&pid_file).unwrap().trim().parse().unwrap();
+        // SAFETY: signal 0 only checks whether the (already reaped) process still exists.
+        assert_eq!(unsafe { libc::kill(pid, 0) }, -1, "hook process {pid} is still running");
+    }
+}
@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Per-name locks so concurrent installs of the same map name run one at a time.
+//!
+//! `op_lock` already serializes the final registry write, but only after each
+//! install has downloaded its file. Holding a name lock for the whole install lets
+//! a second request for the same name wait, then see the first one's entry before
+//! it downloads anything.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+type NameLock = Arc<Mutex<()>>;
+
+#[derive(Default)]
+pub struct InstallNameLocks {
+    locks: Mutex<HashMap<String, NameLock>>,
+}
+
+impl InstallNameLocks {
+    /// Wait until no other install holds `name`; the lock is released when the guard drops.
+    pub async fn lock(&self, name: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().await;
+            // Drop locks nobody holds or waits on so the map only tracks live installs.
+            locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+            Arc::clone(locks.entry(name.to_string()).or_default())
+        };
+        lock.lock_owned().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn same_name_waits_and_other_names_do_not() {
+        let locks = Arc::new(InstallNameLocks::default());
+        let held = locks.lock("c1m1").await;
+
+        let other = tokio::time::timeout(Duration::from_millis(100), locks.lock("c2m1")).await;
+        assert!(other.is_ok(), "different names must not block each other");
+
+        let waiter = tokio::spawn({
+            let locks = Arc::clone(&locks);
+            async move { drop(locks.lock("c1m1").await) }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiter.is_finished(), "same name must wait for the holder");
+
+        drop(held);
+        waiter.await.unwrap();
+    }
+}
@@ -1,16 +1,28 @@
 // SPDX-License-Identifier: GPL-3.0-only
+pub mod audit;
 mod helpers;
 mod active_updates;
+mod events;
+mod hooks;
+mod install_locks;
+mod operations;
 mod pending_updates;
+mod quarantine;
 mod service;
+mod webhook;
 
 pub use helpers::is_watched_map_path;
 pub use active_updates::{
     ActiveMapUpdate, ActiveUpdateGuard, ActiveUpdatesState, UpdatePhase, UpdateProgressPatch,
 };
+pub use audit::{with_audit_actor, AuditEntry};
+pub use events::{MapEvent, MapEventBus};
+pub use operations::{capture_operation_id, InstallOperation, InstallOperations, InstallTimedOut};
 pub use pending_updates::{AvailableMapUpdate, MapUpdatesStatus, PendingUpdatesState};
+pub use webhook::WebhookNotifier;
 pub use service::{
-    CompactReport, DiscoveryMode, DiscoveryReport, L4d2CenterUpdateReport,
-    MapInstallationService, WorkshopUpdateReport,
+    CleanupReport, CompactReport, DiscoveryMode, ForceUninstallReport, InstalledFileInfo, DiscoveryReport, ExpectedChecksum, InstallOptions,
+    L4d2CenterUpdateReport, MapFootprint, MapInstallationService, ReconcileReport,
+    WorkshopUpdateReport,
 };
 
@@ -0,0 +1,281 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! In-flight installs that API clients can list and cancel.
+//!
+//! Each install gets an operation ID (announced in the `install_started` event) and a
+//! cancellation token. Cancelling drops the install future, which runs the same
+//! temp-path guards as an install timeout.
+//!
+//! Once an install calls [`enter_commit`] (right before placing its file in addons)
+//! neither a timeout nor a cancellation drops it: it runs on until the map is
+//! registered or rolled back.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use utoipa::ToSchema;
+
+/// An install ran past `install_timeout_secs` before it reached its commit point.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Install of {target} timed out after {timeout_secs}s")]
+pub struct InstallTimedOut {
+    pub target: String,
+    pub timeout_secs: u64,
+}
+
+/// An install that has started and not yet finished.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct InstallOperation {
+    pub id: u64,
+    /// Install source as shown in install events (URL, workshop ID or path)
+    pub source: String,
+    pub started_at: DateTime<Utc>,
+}
+
+struct ActiveOperation {
+    operation: InstallOperation,
+    token: CancellationToken,
+}
+
+/// The running operation as seen from inside its install future.
+#[derive(Clone)]
+struct OperationScope {
+    id: u64,
+    source: String,
+    deadline: Instant,
+    timeout_secs: u64,
+    token: CancellationToken,
+    committed: Arc<AtomicBool>,
+}
+
+tokio::task_local! {
+    static CURRENT_OPERATION: OperationScope;
+    static STARTED_OPERATION: Arc<OnceLock<u64>>;
+}
+
+/// Run `fut`, also returning the ID of the first install operation it started.
+pub async fn capture_operation_id<F: std::future::Future>(fut: F) -> (F::Output, Option<u64>) {
+    let started = Arc::new(OnceLock::new());
+    let output = STARTED_OPERATION.scope(started.clone(), fut).await;
+    (output, started.get().copied())
+}
+
+/// The install operation the calling task runs in, if any.
+pub fn current_operation_id() -> Option<u64> {
+    CURRENT_OPERATION.try_with(|scope| scope.id).ok()
+}
+
+/// Mark the current install as committed, so a timeout or cancellation lets it finish
+/// instead of dropping it half-installed. Fails when the install was already cancelled,
+/// or with [`InstallTimedOut`] when the deadline has already passed. Outside an install
+/// operation this does nothing.
+pub fn enter_commit() -> anyhow::Result<()> {
+    CURRENT_OPERATION
+        .try_with(|scope| {
+            if scope.token.is_cancelled() {
+                anyhow::bail!("Install of {} was cancelled", scope.source);
+            }
+            if Instant::now() >= scope.deadline {
+                return Err(InstallTimedOut {
+                    target: scope.source.clone(),
+                    timeout_secs: scope.timeout_secs,
+                }
+                .into());
+            }
+            scope.committed.store(true, Ordering::SeqCst);
+            Ok(())
+        })
+        .unwrap_or(Ok(()))
+}
+
+#[derive(Clone, Default)]
+pub struct InstallOperations {
+    next_id: Arc<AtomicU64>,
+    active: Arc<Mutex<HashMap<u64, ActiveOperation>>>,
+}
+
+impl InstallOperations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an install; it is listed until the returned guard is dropped.
+    pub fn begin(&self, source: String) -> InstallOperationGuard {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let _ = STARTED_OPERATION.try_with(|started| started.set(id));
+        let token = CancellationToken::new();
+        let operation = InstallOperation {
+            id,
+            source: source.clone(),
+            started_at: Utc::now(),
+        };
+        self.active.lock().expect("install operations lock poisoned").insert(
+            id,
+            ActiveOperation {
+                operation,
+                token: token.clone(),
+            },
+        );
+        InstallOperationGuard {
+            operations: self.clone(),
+            id,
+            source,
+            token,
+            committed: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Request cancellation of operation `id`; false when no such install is running.
+    pub fn cancel(&self, id: u64) -> bool {
+        let active = self.active.lock().expect("install operations lock poisoned");
+        match active.get(&id) {
+            Some(entry) => {
+                entry.token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_running(&self, id: u64) -> bool {
+        self.active
+            .lock()
+            .expect("install operations lock poisoned")
+            .contains_key(&id)
+    }
+
+    pub fn list(&self) -> Vec<InstallOperation> {
+        let active = self.active.lock().expect("install operations lock poisoned");
+        let mut operations: Vec<_> = active.values().map(|e| e.operation.clone()).collect();
+        operations.sort_by_key(|operation| operation.id);
+        operations
+    }
+}
+
+/// Keeps an install listed while it runs; removes it when dropped.
+pub struct InstallOperationGuard {
+    operations: InstallOperations,
+    id: u64,
+    source: String,
+    token: CancellationToken,
+    committed: Arc<AtomicBool>,
+}
+
+impl InstallOperationGuard {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Run `fut` as this operation, which has to commit within `timeout_secs`
+    /// (see [`current_operation_id`] and [`enter_commit`]).
+    pub async fn run<F: std::future::Future>(
+        &self,
+        deadline: Instant,
+        timeout_secs: u64,
+        fut: F,
+    ) -> F::Output {
+        let scope = OperationScope {
+            id: self.id,
+            source: self.source.clone(),
+            deadline,
+            timeout_secs,
+            token: self.token.clone(),
+            committed: self.committed.clone(),
+        };
+        CURRENT_OPERATION.scope(scope, fut).await
+    }
+
+    /// Whether the install has passed [`enter_commit`].
+    pub fn is_committed(&self) -> bool {
+        self.committed.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for InstallOperationGuard {
+    fn drop(&mut self) {
+        self.operations
+            .active
+            .lock()
+            .expect("install operations lock poisoned")
+            .remove(&self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancel_triggers_token_and_drop_unlists() {
+        let operations = InstallOperations::new();
+        let first = operations.begin("https://example.com/a.zip".to_string());
+        let second = operations.begin("123".to_string());
+        assert_eq!(
+            operations.list().iter().map(|o| o.id).collect::<Vec<_>>(),
+            vec![first.id(), second.id()]
+        );
+
+        assert!(operations.cancel(first.id()));
+        assert!(first.token().is_cancelled());
+        assert!(!second.token().is_cancelled());
+
+        let first_id = first.id();
+        drop(first);
+        assert!(!operations.cancel(first_id));
+        assert_eq!(operations.list().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn enter_commit_marks_the_operation_until_its_deadline() {
+        let operations = InstallOperations::new();
+        let operation = operations.begin("map.zip".to_string());
+        let later = Instant::now() + std::time::Duration::from_secs(60);
+        operation
+            .run(later, 60, async { enter_commit() })
+            .await
+            .unwrap();
+        assert!(operation.is_committed());
+
+        let late = operations.begin("late.zip".to_string());
+        let error = late
+            .run(Instant::now(), 5, async { enter_commit() })
+            .await
+            .unwrap_err();
+        assert!(error.is::<InstallTimedOut>());
+        assert!(!late.is_committed());
+        assert!(enter_commit().is_ok());
+    }
+
+    #[tokio::test]
+    async fn enter_commit_fails_once_cancelled() {
+        let operations = InstallOperations::new();
+        let operation = operations.begin("map.zip".to_string());
+        operations.cancel(operation.id());
+        let later = Instant::now() + std::time::Duration::from_secs(60);
+        let error = operation
+            .run(later, 60, async { enter_commit() })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("cancelled"));
+        assert!(!operation.is_committed());
+    }
+
+    #[tokio::test]
+    async fn capture_operation_id_returns_the_first_started_operation() {
+        let operations = InstallOperations::new();
+        let ((first, _second), captured) = capture_operation_id(async {
+            (operations.begin("a.zip".to_string()), operations.begin("b.zip".to_string()))
+        })
+        .await;
+        assert_eq!(captured, Some(first.id()));
+        assert_eq!(capture_operation_id(async {}).await.1, None);
+    }
+}
@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Rejected downloads kept for inspection instead of being deleted.
+//!
+//! Each file is moved into the quarantine directory under a timestamped name with a
+//! `<name>.reason` note beside it. Once the directory holds more than its size cap,
+//! the oldest files (and their notes) are removed.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use chrono::Utc;
+use tracing::{info, warn};
+
+const REASON_SUFFIX: &str = ".reason";
+
+/// Move `path` into `dir` with a note explaining why it was rejected, then trim the
+/// directory to `max_bytes`. Returns where the file now lives.
+pub async fn quarantine_file(
+    dir: &Path,
+    max_bytes: u64,
+    path: &Path,
+    reason: &str,
+) -> anyhow::Result<PathBuf> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("Failed to create quarantine directory {}", dir.display()))?;
+
+    let file_name = path
+        .file_name()
+        .map(|name| crate::utils::sanitize_filename(&name.to_string_lossy()))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "download".to_string());
+    let target = dir.join(format!("{}-{file_name}", Utc::now().format("%Y%m%dT%H%M%S%.3fZ")));
+
+    move_file(path, &target).await.with_context(|| {
+        format!("Failed to move {} into quarantine", path.display())
+    })?;
+    let note = format!(
+        "original: {}\nquarantined_at: {}\nreason: {reason}\n",
+        path.display(),
+        Utc::now().to_rfc3339()
+    );
+    tokio::fs::write(reason_path(&target), note).await?;
+    info!(path = %target.display(), reason, "Quarantined rejected download");
+
+    if let Err(error) = rotate(dir, max_bytes).await {
+        warn!(error = %error, dir = %dir.display(), "Failed to trim quarantine directory");
+    }
+    Ok(target)
+}
+
+fn reason_path(file: &Path) -> PathBuf {
+    let mut name = file.as_os_str().to_owned();
+    name.push(REASON_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Rename, falling back to copy + remove when the quarantine is on another filesystem.
+async fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if tokio::fs::rename(from, to).await.is_ok() {
+        return Ok(());
+    }
+    tokio::fs::copy(from, to).await?;
+    tokio::fs::remove_file(from).await
+}
+
+/// Remove the oldest quarantined files until the directory is within `max_bytes`.
+async fn rotate(dir: &Path, max_bytes: u64) -> anyhow::Result<()> {
+    let mut files = Vec::new();
+    let mut total: u64 = 0;
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let metadata = entry.metadata().await?;
+        if !metadata.is_file() {
+            continue;
+        }
+        total += metadata.len();
+        if entry.file_name().to_string_lossy().ends_with(REASON_SUFFIX) {
+            continue;
+        }
+        files.push((metadata.modified()?, entry.path()));
+    }
+
+    files.sort();
+    for (_, path) in files {
+        if total <= max_bytes {
+            break;
+        }
+        let note = reason_path(&path);
+        for victim in [path.as_path(), note.as_path()] {
+            if let Ok(metadata) = tokio::fs::metadata(victim).await
+                && tokio::fs::remove_file(victim).await.is_ok()
+            {
+                total = total.saturating_sub(metadata.len());
+            }
+        }
+        info!(path = %path.display(), "Removed oldest quarantined file to stay within quarantine_max_bytes");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn oldest_files_are_rotated_out_past_the_size_cap() {
+        let temp = TempDir::new().unwrap();
+        let quarantine = temp.path().join("quarantine");
+
+        let mut kept = Vec::new();
+        for name in ["first.vpk", "second.vpk", "third.vpk"] {
+            let source = temp.path().join(name);
+            std::fs::write(&source, vec![0u8; 400]).unwrap();
+            kept.push(quarantine_file(&quarantine, 1200, &source, "test").await.unwrap());
+            assert!(!source.exists());
+            // Distinct modification times so "oldest" is well defined.
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+
+        assert!(!kept[0].exists());
+        assert!(!reason_path(&kept[0]).exists());
+        assert!(kept[1].exists() && kept[2].exists());
+        assert!(reason_path(&kept[2]).exists());
+    }
+}
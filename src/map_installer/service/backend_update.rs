@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use std::cmp::Ordering;
+use std::path::PathBuf;
+use anyhow::Context;
+use tracing::{info, warn};
+
+use super::{ExpectedChecksum, MapInstallationService};
+use crate::downloader::client::{CacheValidators, DownloadOptions, NotModified};
+use crate::map_installer::audit::AuditAction;
+use crate::map_installer::helpers::workshop_source_url;
+use crate::registry::models::{MapEntry, SourceKind};
+use crate::utils::{compare_versions, is_known_version, DownloadKind};
+
+impl MapInstallationService {
+    /// Replace the file of installed map `map_id` with the version the backend describes.
+    ///
+    /// The entry keeps its ID and installed path; version, checksum and source are
+    /// taken from `target`. Returns `None` when `map_id` is not installed locally so
+    /// the caller can install it instead.
+    ///
+    /// When both sides carry a known version and `target`'s is older (see
+    /// [`compare_versions`](crate::utils::compare_versions)), nothing is downloaded
+    /// and the installed entry is returned unchanged.
+    ///
+    /// URL sources fall back to `mirror_urls` in order when the source URL is refused
+    /// by policy, fails to download or does not match `target`'s checksum.
+    pub async fn update_map(
+        &self,
+        map_id: u64,
+        target: &MapEntry,
+        mirror_urls: &[String],
+    ) -> anyhow::Result<Option<MapEntry>> {
+        self.ensure_writable()?;
+        let Some(existing) = self.registry.get_map(map_id).await? else {
+            return Ok(None);
+        };
+        if let (Some(installed), Some(offered)) = (existing.version.as_deref(), target.version.as_deref())
+            && is_known_version(installed)
+            && is_known_version(offered)
+            && compare_versions(offered, installed) == Ordering::Less
+        {
+            info!(map_id, installed, offered, "Backend offers an older map version; keeping the installed one");
+            return Ok(Some(existing));
+        }
+        info!(map_id, name = %existing.name, "Updating map to backend version");
+
+        if let Some(workshop_id) = target.workshop_id {
+            self.blocklist().check_workshop_id(workshop_id)?;
+            let url = self.workshop_download_url(workshop_id).await?;
+            return self.update_map_from_url(&existing, target, &url).await.map(Some);
+        }
+
+        let mut last_error = None;
+        for candidate in std::iter::once(&target.source_url).chain(mirror_urls) {
+            let result = match self.validate_source_url(candidate).await {
+                Ok(()) => self.update_map_from_url(&existing, target, candidate).await,
+                Err(error) => Err(error),
+            };
+            match result {
+                Ok(updated) => return Ok(Some(updated)),
+                Err(error) => {
+                    if !mirror_urls.is_empty() {
+                        warn!(map_id, url = %candidate, error = %error, "Mirror failed to update map; trying the next one");
+                    }
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No download URL given")))
+    }
+
+    /// Download `url` and swap it in as `existing`'s file; the URL must already be vetted.
+    ///
+    /// When `url` is the one `existing` was installed from, the download is made
+    /// conditional on its stored `Last-Modified`/`ETag`; a `304 Not Modified` leaves
+    /// the map untouched and returns `existing` as is.
+    pub(super) async fn update_map_from_url(
+        &self,
+        existing: &MapEntry,
+        target: &MapEntry,
+        url: &str,
+    ) -> anyhow::Result<MapEntry> {
+        let options = if url == existing.source_url {
+            DownloadOptions::default().with_conditional(&CacheValidators {
+                last_modified: existing.source_last_modified.clone(),
+                etag: existing.source_etag.clone(),
+            })
+        } else {
+            DownloadOptions::default()
+        };
+        let download = async {
+            let _download_permit = self.acquire_download_permit().await;
+            let direct_url = self.zip_downloader.resolve_url(url).await?;
+            self.precheck_download(&direct_url, &options).await?;
+            self.zip_downloader
+                .download_zip_with_progress(&direct_url, &options, None)
+                .await
+        };
+        let (downloaded, validators) = match download.await {
+            Ok(downloaded) => downloaded,
+            Err(error) if error.is::<NotModified>() => {
+                info!(map_id = existing.id, url = %url, "Map source not modified; keeping installed file");
+                return Ok(existing.clone());
+            }
+            Err(error) => return Err(error),
+        };
+        let kind = self.ensure_map_download(&downloaded, url).await?;
+        let downloaded = Self::with_download_kind_extension(downloaded, kind).await?;
+        let _downloaded_guard = crate::utils::TempPathGuard::new(downloaded.clone());
+
+        self.replace_from_backend_download(existing, target, downloaded, validators)
+            .await
+    }
+
+    /// Rename a download so its extension matches the sniffed content, as
+    /// `prepare_vpk_from_download` dispatches on the extension.
+    async fn with_download_kind_extension(
+        downloaded: PathBuf,
+        kind: DownloadKind,
+    ) -> anyhow::Result<PathBuf> {
+        let extension = match kind {
+            DownloadKind::Vpk => "vpk",
+            DownloadKind::Zip => "zip",
+            DownloadKind::SevenZ => "7z",
+        };
+        let matches = downloaded
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| e.eq_ignore_ascii_case(extension));
+        if matches {
+            return Ok(downloaded);
+        }
+        let renamed = downloaded.with_extension(extension);
+        tokio::fs::rename(&downloaded, &renamed).await?;
+        Ok(renamed)
+    }
+
+    async fn replace_from_backend_download(
+        &self,
+        existing: &MapEntry,
+        target: &MapEntry,
+        downloaded: PathBuf,
+        validators: CacheValidators,
+    ) -> anyhow::Result<MapEntry> {
+        let install_path = self.addons_dir.join(&existing.installed_path);
+        crate::utils::validate_path_within_base_new(&install_path, &self.addons_dir)
+            .context("Attempted to update map outside of addons directory")?;
+        crate::utils::ensure_no_symlinks_under(&self.addons_dir, &install_path)?;
+
+        let (source_vpk, temp_cleanup) = self.prepare_vpk_from_download(downloaded).await?;
+        if let Some(expected) = ExpectedChecksum::from_map_entry(target)
+            && let Err(error) = expected.verify(&source_vpk).await
+        {
+            self.reject_download(&source_vpk, &format!("{error:#}")).await;
+            temp_cleanup.cleanup().await;
+            return Err(error);
+        }
+
+        let _guard = self.op_lock.lock().await;
+
+        let backup_path = install_path.with_extension("vpk.bak");
+        let had_existing = install_path.exists();
+        if let Err(error) = crate::utils::check_space_for_copy(&source_vpk, &self.addons_dir).await {
+            temp_cleanup.cleanup().await;
+            return Err(error).context("Disk space check failed before updating map");
+        }
+        if had_existing && let Err(error) = tokio::fs::copy(&install_path, &backup_path).await {
+            temp_cleanup.cleanup().await;
+            let _ = tokio::fs::remove_file(&backup_path).await;
+            return Err(error).context("Failed to back up existing map before update");
+        }
+
+        if let Err(error) = self.place_in_addons(&source_vpk, &install_path).await {
+            temp_cleanup.cleanup().await;
+            let _ = tokio::fs::remove_file(&backup_path).await;
+            return Err(error).context("Failed to replace installed map file");
+        }
+        info!(
+            map_id = existing.id,
+            dest = %install_path.display(),
+            "Replaced installed map file from backend update"
+        );
+
+        temp_cleanup.cleanup().await;
+
+        let metadata = match self.read_vpk_metadata(&install_path).await {
+            Ok(metadata) => metadata,
+            Err(error) => {
+                Self::restore_vpk_backup(&install_path, &backup_path, had_existing).await;
+                return Err(error);
+            }
+        };
+        let source_kind = match target.workshop_id {
+            Some(_) => SourceKind::Workshop,
+            None => existing.source_kind,
+        };
+        let (checksum, checksum_kind) = match self.checksum_installed(&install_path, source_kind).await {
+            Ok(value) => value,
+            Err(error) => {
+                Self::restore_vpk_backup(&install_path, &backup_path, had_existing).await;
+                return Err(error).context("Failed to checksum updated map");
+            }
+        };
+        let installed_at = Self::file_modified_time(&install_path)
+            .await
+            .unwrap_or_else(chrono::Utc::now);
+
+        let mut updated = existing.clone();
+        updated.version = target.version.clone().or(Some(metadata.version));
+        updated.content_flags = metadata.content_flags;
+        updated.checksum = Some(checksum);
+        updated.checksum_kind = Some(checksum_kind);
+        updated.installed_at = installed_at;
+        updated.size_bytes = Self::installed_size(&install_path).await;
+        match target.workshop_id {
+            Some(workshop_id) => {
+                updated.source_kind = SourceKind::Workshop;
+                updated.workshop_id = Some(workshop_id);
+                updated.workshop_updated_at = target.workshop_updated_at;
+                updated.source_url = workshop_source_url(workshop_id);
+                updated.source_last_modified = None;
+                updated.source_etag = None;
+            }
+            None => {
+                updated.source_url = target.source_url.clone();
+                updated.source_last_modified = validators.last_modified;
+                updated.source_etag = validators.etag;
+            }
+        }
+        updated.instance_paths = match self.copy_to_instances(&install_path, &install_path).await {
+            Ok(paths) => paths,
+            Err(error) => {
+                Self::restore_vpk_backup(&install_path, &backup_path, had_existing).await;
+                return Err(error);
+            }
+        };
+
+        if let Err(error) = self.registry.update_map(updated.clone()).await {
+            Self::restore_vpk_backup(&install_path, &backup_path, had_existing).await;
+            return Err(error).context("Failed to persist map update in registry");
+        }
+        self.audit
+            .record(AuditAction::Update, updated.id, Some(updated.source_url.clone()))
+            .await;
+        self.notify_webhook(AuditAction::Update, &updated);
+
+        let _ = tokio::fs::remove_file(&backup_path).await;
+        Ok(updated)
+    }
+}
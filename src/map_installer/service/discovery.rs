@@ -5,8 +5,11 @@ use anyhow::Context;
 use tracing::{info, warn};
 
 use super::{
-    CompactReport, DiscoveryMode, DiscoveryReport, MapInstallationService,
+    CompactReport, DiscoveryMode, DiscoveryReport, ExpectedChecksum, InstalledFileInfo,
+    IntegrityReport, MapFootprint, MapInstallationService, ReconcileReport,
 };
+use crate::extractor::traits::VpkFileEntry;
+use crate::map_installer::audit::AuditAction;
 use crate::map_installer::helpers::{self, workshop_source_url};
 use crate::registry::models::{MapEntry, SourceKind};
 
@@ -25,7 +28,10 @@ impl MapInstallationService {
         };
 
         if let Some(existing) = self.find_map_by_installed_path(&relative_path).await? {
-            let Some(mut fresh_entry) = self.build_map_entry_from_file(&path, &relative_path).await?
+            let checksum_kind = self.checksum_kind_of(&existing);
+            let Some(mut fresh_entry) = self
+                .build_map_entry_from_file(&path, &relative_path, &checksum_kind)
+                .await?
             else {
                 return Ok(Some(existing));
             };
@@ -64,6 +70,10 @@ impl MapInstallationService {
         if let Some(existing) = self.find_map_by_installed_path(&relative_path).await? {
             let id = existing.id;
             self.registry.remove_map(id).await?;
+            self.audit
+                .record(AuditAction::Uninstall, id, Some(relative_path))
+                .await;
+            self.notify_webhook(AuditAction::Uninstall, &existing);
             return Ok(Some(id));
         }
 
@@ -119,7 +129,11 @@ impl MapInstallationService {
                     continue;
                 }
 
-                match self.build_map_entry_from_file(&path, &relative_path).await {
+                let checksum_kind = self.checksum_kind_of(&existing);
+                match self
+                    .build_map_entry_from_file(&path, &relative_path, &checksum_kind)
+                    .await
+                {
                     Ok(Some(mut fresh_entry)) => {
                         let changed = mode == DiscoveryMode::ForceUpdate
                             || fresh_entry.checksum != existing.checksum;
@@ -170,6 +184,21 @@ impl MapInstallationService {
         Ok(report)
     }
 
+    /// Register VPKs already in addons_dir that the registry does not track yet.
+    ///
+    /// Runs at startup so `list_maps` reflects the server before the watcher sees
+    /// any change; tracked files are left untouched.
+    pub async fn import_existing_maps(&self) -> anyhow::Result<DiscoveryReport> {
+        let report = self.discover_maps(DiscoveryMode::Add).await?;
+        info!(
+            imported = report.added.len(),
+            already_tracked = report.skipped,
+            failed = report.failed,
+            "Imported existing addons"
+        );
+        Ok(report)
+    }
+
     /// Prune registry records whose map files are missing, sort survivors by name,
     /// and reassign sequential IDs starting at 1. Does not delete any files.
     pub async fn compact_registry(&self) -> anyhow::Result<CompactReport> {
@@ -231,14 +260,268 @@ impl MapInstallationService {
         })
     }
 
+    /// Repair drift between registry and addons: register untracked VPKs and drop
+    /// entries whose files are gone. Unlike `compact_registry`, IDs are left untouched.
+    pub async fn reconcile(&self) -> anyhow::Result<ReconcileReport> {
+        let _guard = self.op_lock.lock().await;
+
+        info!("Reconciling registry with addons directory");
+
+        let mut report = ReconcileReport {
+            registered: Vec::new(),
+            removed: Vec::new(),
+            unchanged: 0,
+            failed: 0,
+        };
+
+        for entry in self.registry.list_maps().await? {
+            let installed_path_abs = self.addons_dir.join(&entry.installed_path);
+            let exists = crate::utils::validate_path_within_base_new(
+                &installed_path_abs,
+                &self.addons_dir,
+            )
+            .is_ok()
+                && tokio::fs::metadata(&installed_path_abs).await.is_ok();
+
+            if exists {
+                report.unchanged += 1;
+                continue;
+            }
+            warn!(map_id = entry.id, path = %entry.installed_path, "Removing registry entry with missing file");
+            self.registry.remove_map(entry.id).await?;
+            report.removed.push(entry);
+        }
+
+        let vpk_files = self.find_vpk_files_in_extracted(self.addons_dir.clone()).await?;
+        for path in vpk_files {
+            if !helpers::is_watched_map_path(&self.addons_dir, &path) {
+                continue;
+            }
+            let Some(relative_path) = helpers::addons_relative_path(&self.addons_dir, &path) else {
+                continue;
+            };
+            if self.find_map_by_installed_path(&relative_path).await?.is_some() {
+                continue;
+            }
+
+            match self.register_new_map(&path, &relative_path).await {
+                Ok(Some(entry)) => report.registered.push(entry),
+                Ok(None) => report.failed += 1,
+                Err(error) => {
+                    warn!(error = %error, path = %path.display(), "Reconcile failed to register map");
+                    report.failed += 1;
+                }
+            }
+        }
+
+        info!(
+            registered = report.registered.len(),
+            removed = report.removed.len(),
+            unchanged = report.unchanged,
+            failed = report.failed,
+            "Reconcile complete"
+        );
+        Ok(report)
+    }
+
+    /// Sum the recorded sizes of all registered maps, overall and per source kind.
+    pub async fn map_footprint(&self) -> anyhow::Result<MapFootprint> {
+        let mut footprint = MapFootprint::default();
+        for entry in self.registry.list_maps().await? {
+            footprint.map_count += 1;
+            let Some(size) = entry.size_bytes else {
+                footprint.unsized_map_ids.push(entry.id);
+                continue;
+            };
+            footprint.total_bytes += size;
+            *footprint.by_source_kind.entry(entry.source_kind).or_default() += size;
+        }
+        Ok(footprint)
+    }
+
+    /// Record `size_bytes` for entries written before sizes were tracked.
+    /// Entries whose file is missing keep no size.
+    pub async fn backfill_map_sizes(&self) -> anyhow::Result<usize> {
+        let _guard = self.op_lock.lock().await;
+
+        let mut backfilled = 0;
+        for mut entry in self.registry.list_maps().await? {
+            if entry.size_bytes.is_some() {
+                continue;
+            }
+            let path = self.addons_dir.join(&entry.installed_path);
+            let Some(size) = Self::installed_size(&path).await else {
+                continue;
+            };
+            entry.size_bytes = Some(size);
+            self.registry.update_map(entry).await?;
+            backfilled += 1;
+        }
+        if backfilled > 0 {
+            info!(count = backfilled, "Backfilled size_bytes for legacy registry entries");
+        }
+        Ok(backfilled)
+    }
+
+    /// Re-hash entries recorded with another algorithm than `default_checksum_kind`.
+    ///
+    /// The old checksum is verified first so a file that drifted is not silently
+    /// re-blessed under the new algorithm; such entries, and entries whose file is
+    /// missing, are left as they are; so is an entry that fails to hash or save, after a
+    /// warning. Returns how many entries were migrated.
+    pub async fn recompute_checksums(&self) -> anyhow::Result<usize> {
+        let _guard = self.op_lock.lock().await;
+
+        let mut migrated = 0;
+        for mut entry in self.registry.list_maps().await? {
+            let Some(expected) = ExpectedChecksum::from_map_entry(&entry) else {
+                continue;
+            };
+            let wanted = self.checksum_kind_for(entry.source_kind);
+            if self.checksum_kind_of(&entry) == wanted {
+                continue;
+            }
+            let path = self.addons_dir.join(&entry.installed_path);
+            if let Err(error) = expected.verify(&path).await {
+                warn!(map_id = entry.id, error = %error, "Not recomputing checksum of unverified map");
+                continue;
+            }
+            let checksum = match crate::utils::calculate_file_checksum(&path, &wanted).await {
+                Ok(checksum) => checksum,
+                Err(error) => {
+                    warn!(map_id = entry.id, error = %error, "Failed to recompute checksum");
+                    continue;
+                }
+            };
+            entry.checksum = Some(checksum);
+            entry.checksum_kind = Some(wanted);
+            let map_id = entry.id;
+            if let Err(error) = self.registry.update_map(entry).await {
+                warn!(map_id, error = %error, "Failed to store recomputed checksum");
+                continue;
+            }
+            migrated += 1;
+        }
+        if migrated > 0 {
+            info!(count = migrated, "Recomputed checksums with the default algorithm");
+        }
+        Ok(migrated)
+    }
+
+    /// List the VPKs actually present in addons, flagging which ones the registry tracks.
+    ///
+    /// Read-only on both sides: nothing is registered or removed, unlike `reconcile`.
+    pub async fn scan_addons_dir(&self) -> anyhow::Result<Vec<InstalledFileInfo>> {
+        let tracked: HashMap<String, u64> = self
+            .registry
+            .list_maps()
+            .await?
+            .into_iter()
+            .map(|entry| (entry.installed_path, entry.id))
+            .collect();
+
+        let mut files = Vec::new();
+        if tokio::fs::metadata(&self.addons_dir).await.is_err() {
+            return Ok(files);
+        }
+        for path in self.find_vpk_files_in_extracted(self.addons_dir.clone()).await? {
+            if !helpers::is_watched_map_path(&self.addons_dir, &path) {
+                continue;
+            }
+            let Some(relative_path) = helpers::addons_relative_path(&self.addons_dir, &path) else {
+                continue;
+            };
+            let Ok(metadata) = tokio::fs::metadata(&path).await else {
+                continue;
+            };
+            let map_id = tracked.get(&relative_path).copied();
+            files.push(InstalledFileInfo {
+                path: relative_path,
+                size: metadata.len(),
+                modified: metadata.modified().ok().map(Into::into),
+                tracked: map_id.is_some(),
+                map_id,
+            });
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// Files inside a map's VPK, read from its directory tree without extracting anything.
+    pub async fn list_vpk_contents(&self, id: u64) -> anyhow::Result<Vec<VpkFileEntry>> {
+        let entry = self
+            .registry
+            .get_map(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Map not found: {id}"))?;
+        let path = self.addons_dir.join(&entry.installed_path);
+        if tokio::fs::metadata(&path).await.is_err() {
+            anyhow::bail!("VPK of map #{id} is missing: {}", entry.installed_path);
+        }
+        self.vpk_extractor
+            .list_files(path)
+            .await
+            .with_context(|| format!("Failed to list files of {}", entry.installed_path))
+    }
+
+    /// Re-hash every installed file and report entries that drifted from their checksum.
+    ///
+    /// Runs without `op_lock` so long scans never block installs; an entry whose
+    /// checksum changed while it was hashed (an update landed) is not reported.
+    pub async fn scan_integrity(&self) -> anyhow::Result<IntegrityReport> {
+        let mut report = IntegrityReport::default();
+
+        for entry in self.registry.list_maps().await? {
+            let Some(expected) = ExpectedChecksum::from_map_entry(&entry) else {
+                report.skipped += 1;
+                continue;
+            };
+            let installed_path_abs = self.addons_dir.join(&entry.installed_path);
+            let exists = crate::utils::validate_path_within_base_new(
+                &installed_path_abs,
+                &self.addons_dir,
+            )
+            .is_ok()
+                && tokio::fs::metadata(&installed_path_abs).await.is_ok();
+            if !exists {
+                warn!(map_id = entry.id, path = %entry.installed_path, "Integrity scan: installed file is missing");
+                report.missing.push(entry.id);
+                continue;
+            }
+
+            match expected.verify(&installed_path_abs).await {
+                Ok(()) => report.verified += 1,
+                Err(error) => {
+                    let current = self.registry.get_map(entry.id).await?;
+                    if current.is_none_or(|current| current.checksum != entry.checksum) {
+                        continue;
+                    }
+                    warn!(map_id = entry.id, error = %error, "Integrity scan: checksum mismatch");
+                    report.mismatched.push(entry.id);
+                }
+            }
+        }
+
+        info!(
+            verified = report.verified,
+            skipped = report.skipped,
+            missing = report.missing.len(),
+            mismatched = report.mismatched.len(),
+            "Integrity scan complete"
+        );
+        Ok(report)
+    }
+
     fn parse_source_kind(value: &str) -> anyhow::Result<SourceKind> {
         match value.to_lowercase().as_str() {
             "workshop" => Ok(SourceKind::Workshop),
             "sirplease" => Ok(SourceKind::SirPlease),
             "l4d2center" => Ok(SourceKind::L4d2Center),
+            "url" => Ok(SourceKind::Url),
+            "detected" => Ok(SourceKind::Detected),
             "other" => Ok(SourceKind::Other),
             other => Err(anyhow::anyhow!(
-                "Invalid source_kind '{other}' (expected: workshop, sirplease, other)"
+                "Invalid source_kind '{other}' (expected: workshop, sirplease, l4d2center, url, detected, other)"
             )),
         }
     }
@@ -278,6 +561,7 @@ impl MapInstallationService {
                 entry.source_url = workshop_source_url(wid);
             }
             "installed_path" | "path" => {
+                self.ensure_writable()?;
                 self.apply_installed_path_change(&mut entry, value).await?;
             }
             other => {
@@ -291,6 +575,43 @@ impl MapInstallationService {
         Ok(entry)
     }
 
+    /// Rename a map and its VPK: the name is sanitized like install names, the file
+    /// becomes `<name>.vpk` in the same directory, and both fields are updated together.
+    pub async fn rename_map(&self, id: u64, new_name: &str) -> anyhow::Result<MapEntry> {
+        self.ensure_writable()?;
+        let _guard = self.op_lock.lock().await;
+
+        let mut entry = self
+            .registry
+            .get_map(id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Map not found: {id}"))?;
+
+        let name = crate::utils::sanitize_map_name(new_name)
+            .with_context(|| format!("Invalid map name '{}'", new_name.trim()))?;
+
+        if let Some(other) = self.find_map_by_name(&name).await?
+            && other.id != id
+        {
+            return Err(anyhow::anyhow!(
+                "Map name '{name}' is already used by map #{}",
+                other.id
+            ));
+        }
+
+        let new_relative = match entry.installed_path.rsplit_once('/') {
+            Some((dir, _)) => format!("{dir}/{name}.vpk"),
+            None => format!("{name}.vpk"),
+        };
+        self.apply_installed_path_change(&mut entry, &new_relative)
+            .await?;
+        entry.name = name;
+
+        self.registry.update_map(entry.clone()).await?;
+        info!(map_id = id, name = %entry.name, path = %entry.installed_path, "Renamed map");
+        Ok(entry)
+    }
+
     async fn apply_installed_path_change(
         &self,
         entry: &mut MapEntry,
@@ -351,6 +672,7 @@ impl MapInstallationService {
             );
         }
 
+        entry.instance_paths = self.move_instance_copies(&entry.instance_paths, &new_relative).await;
         entry.installed_path = new_relative;
         Ok(())
     }
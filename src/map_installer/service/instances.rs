@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Copies of installed VPKs in the addons directories of further server instances.
+//!
+//! Only the primary addons directory (`l4d2_server_dir`) is watched and checked;
+//! the `server_dirs` instances get a copy of each installed file, recorded in the
+//! entry's `instance_paths` so uninstall and rename can find them again.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use tracing::{info, warn};
+
+use super::MapInstallationService;
+
+impl MapInstallationService {
+    /// Copy `source` to the path `install_path` has under the primary addons
+    /// directory in every instance addons directory.
+    ///
+    /// Returns the absolute paths of the copies. If any instance cannot take its
+    /// copy, the copies already made are removed and the error is returned, so a
+    /// map is never recorded as installed in only some of the instances.
+    pub(super) async fn copy_to_instances(&self, source: &Path, install_path: &Path) -> anyhow::Result<Vec<String>> {
+        let Ok(relative) = install_path.strip_prefix(&self.addons_dir) else {
+            return Ok(Vec::new());
+        };
+        let mut copies = Vec::with_capacity(self.instance_addons_dirs.len());
+        for dir in &self.instance_addons_dirs {
+            let dest = dir.join(relative);
+            if let Err(error) = self.copy_to_instance(source, dir, &dest).await {
+                self.remove_instance_copies(&copies).await;
+                return Err(error).with_context(|| {
+                    format!("Failed to copy VPK file into server instance {}", dir.display())
+                });
+            }
+            info!(dest = %dest.display(), "Copied VPK file into server instance");
+            copies.push(dest.to_string_lossy().into_owned());
+        }
+        Ok(copies)
+    }
+
+    async fn copy_to_instance(&self, source: &Path, dir: &Path, dest: &Path) -> anyhow::Result<()> {
+        if !dir.is_dir() {
+            anyhow::bail!("Instance addons directory {} does not exist", dir.display());
+        }
+        crate::utils::validate_path_within_base_new(dest, dir)?;
+        crate::utils::ensure_no_symlinks_under(dir, dest)?;
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        crate::utils::check_space_for_copy(source, dir).await?;
+        self.place_in_addons(source, dest).await
+    }
+
+    /// Delete the instance copies at `paths`; failures are logged, not returned.
+    ///
+    /// Paths outside every configured instance addons directory (e.g. after
+    /// `server_dirs` changed) are left alone.
+    pub(super) async fn remove_instance_copies(&self, paths: &[String]) {
+        for path in paths.iter().map(Path::new) {
+            if self.instance_dir_of(path).is_none() {
+                warn!(path = %path.display(), "Map copy is not inside a server_dirs instance; leaving it");
+                continue;
+            }
+            match tokio::fs::remove_file(path).await {
+                Ok(()) => info!(path = %path.display(), "Removed map copy from server instance"),
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => {
+                    warn!(error = %error, path = %path.display(), "Failed to remove map copy from server instance");
+                }
+            }
+        }
+    }
+
+    /// Move the instance copies at `paths` to `new_relative` inside their instance,
+    /// returning the paths to record. A copy that cannot be moved keeps its old path.
+    pub(super) async fn move_instance_copies(&self, paths: &[String], new_relative: &str) -> Vec<String> {
+        let mut moved = Vec::with_capacity(paths.len());
+        for old in paths {
+            let Some(dir) = self.instance_dir_of(Path::new(old)) else {
+                moved.push(old.clone());
+                continue;
+            };
+            let new = dir.join(new_relative);
+            let result = match crate::utils::validate_path_within_base_new(&new, dir) {
+                Ok(()) if new.exists() => Err(anyhow::anyhow!("{} already exists", new.display())),
+                Ok(()) => tokio::fs::rename(old, &new).await.map_err(Into::into),
+                Err(error) => Err(error),
+            };
+            match result {
+                Ok(()) => moved.push(new.to_string_lossy().into_owned()),
+                Err(error) => {
+                    warn!(error = %format!("{error:#}"), path = %old, "Failed to rename map copy in server instance");
+                    moved.push(old.clone());
+                }
+            }
+        }
+        moved
+    }
+
+    fn instance_dir_of(&self, path: &Path) -> Option<&PathBuf> {
+        self.instance_addons_dirs
+            .iter()
+            .find(|dir| path.starts_with(dir) && crate::utils::validate_path_within_base_new(path, dir).is_ok())
+    }
+}
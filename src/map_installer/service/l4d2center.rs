@@ -5,11 +5,13 @@ use anyhow::Context;
 use tracing::{info, warn};
 
 use super::{
-    MapInstallationService, MapOperationFailure, L4d2CenterUpdateAvailable, L4d2CenterUpdateReport,
+    InstallTarget, MapInstallationService, MapOperationFailure, L4d2CenterUpdateAvailable,
+    L4d2CenterUpdateReport,
 };
 use crate::catalog::l4d2center::{encode_download_url, enrich_with_registry, fetch_index, find_index_entry};
+use crate::downloader::client::{CacheValidators, DownloadOptions};
 use crate::downloader::traits::Downloader;
-use crate::extractor::traits::Extractor;
+use crate::map_installer::audit::AuditAction;
 use crate::registry::models::{MapEntry, SourceKind};
 use crate::utils::{md5_matches, verify_file_md5};
 
@@ -35,7 +37,7 @@ impl MapInstallationService {
         &self,
         index_url: &str,
     ) -> anyhow::Result<Vec<crate::catalog::L4d2CenterCatalogEntry>> {
-        let entries = fetch_index(index_url).await?;
+        let entries = fetch_index(index_url, &self.http).await?;
         enrich_with_registry(entries, self.registry.as_ref()).await
     }
 
@@ -44,13 +46,10 @@ impl MapInstallationService {
         index_url: &str,
         name: &str,
     ) -> anyhow::Result<MapEntry> {
-        let _download_permit = self
-            .download_semaphore
-            .acquire()
-            .await
-            .expect("download semaphore closed");
+        self.ensure_writable()?;
+        let _download_permit = self.acquire_download_permit().await;
 
-        let entries = fetch_index(index_url).await?;
+        let entries = fetch_index(index_url, &self.http).await?;
         let Some(index_entry) = find_index_entry(&entries, name) else {
             anyhow::bail!("Map '{name}' not found in L4D2Center catalog");
         };
@@ -67,42 +66,54 @@ impl MapInstallationService {
         }
 
         let download_url = encode_download_url(&index_entry.download_link);
+        crate::utils::validate_download_host(&download_url, &self.config().allowed_download_hosts)?;
         crate::utils::validate_url_resolved(&download_url)
             .await
             .context("Invalid L4D2Center download URL (SSRF protection)")?;
 
-        let downloaded_path = self.zip_downloader.download_zip(&download_url).await?;
-        let map_entry = self
-            .install_downloaded_file(
-                downloaded_path,
-                SourceKind::L4d2Center,
-                None,
-                None,
-                Some(download_url),
-                Some(name.to_string()),
-            )
-            .await?;
-
-        let install_path = self.addons_dir.join(&map_entry.installed_path);
-        match verify_file_md5(&install_path, &index_entry.md5).await {
-            Ok(true) => {}
-            Ok(false) => {
-                warn!(
-                    map_id = map_entry.id,
-                    name,
-                    "Installed L4D2Center map MD5 does not match catalog entry"
-                );
-            }
-            Err(error) => {
-                warn!(
-                    map_id = map_entry.id,
-                    error = %error,
-                    "Failed to verify L4D2Center map MD5 after install"
-                );
+        let source = download_url.clone();
+        let install = async {
+            let downloaded_path = self.zip_downloader.download_zip(&download_url).await?;
+            let kind = self.ensure_map_download(&downloaded_path, &download_url).await?;
+            let map_entry = self
+                .install_detected_file(
+                    downloaded_path,
+                    kind,
+                    InstallTarget {
+                        source_kind: SourceKind::L4d2Center,
+                        workshop_id: None,
+                        provided_name: None,
+                        source_url: Some(download_url),
+                        expected_installed_filename: Some(name.to_string()),
+                        force: false,
+                        expected_checksum: None,
+                        source_validators: CacheValidators::default(),
+                    },
+                )
+                .await?;
+
+            let install_path = self.addons_dir.join(&map_entry.installed_path);
+            match verify_file_md5(&install_path, &index_entry.md5).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    warn!(
+                        map_id = map_entry.id,
+                        name,
+                        "Installed L4D2Center map MD5 does not match catalog entry"
+                    );
+                }
+                Err(error) => {
+                    warn!(
+                        map_id = map_entry.id,
+                        error = %error,
+                        "Failed to verify L4D2Center map MD5 after install"
+                    );
+                }
             }
-        }
 
-        Ok(map_entry)
+            Ok(map_entry)
+        };
+        self.with_install_events(source, install).await
     }
 
     pub async fn update_l4d2center_maps(
@@ -113,6 +124,9 @@ impl MapInstallationService {
         force: bool,
         check_only: bool,
     ) -> anyhow::Result<L4d2CenterUpdateReport> {
+        if !check_only {
+            self.ensure_writable()?;
+        }
         // Do not hold op_lock across downloads — only around disk/registry replace.
 
         let mut report = L4d2CenterUpdateReport {
@@ -123,7 +137,7 @@ impl MapInstallationService {
             not_l4d2center: 0,
         };
 
-        let index_entries = fetch_index(index_url).await?;
+        let index_entries = fetch_index(index_url, &self.http).await?;
 
         let entries = if let Some(id) = map_id {
             match self.registry.get_map(id).await? {
@@ -189,11 +203,7 @@ impl MapInstallationService {
 
             info!(map_id, name = %index_entry.name, "Updating outdated L4D2Center map");
 
-            let _download_permit = self
-                .download_semaphore
-                .acquire()
-                .await
-                .expect("download semaphore closed");
+            let _download_permit = self.acquire_download_permit().await;
 
             let Some(_active) = crate::map_installer::ActiveUpdateGuard::try_begin(
                 self.active_updates.clone(),
@@ -209,6 +219,16 @@ impl MapInstallationService {
             };
 
             let download_url = encode_download_url(&index_entry.download_link);
+            if let Err(error) = crate::utils::validate_download_host(
+                &download_url,
+                &self.config().allowed_download_hosts,
+            ) {
+                report.failed.push(MapOperationFailure {
+                    map_id,
+                    error: error.to_string(),
+                });
+                continue;
+            }
             let progress_state = self.active_updates.clone();
             let on_progress: crate::downloader::client::DownloadProgressCallback =
                 std::sync::Arc::new(move |downloaded, total| {
@@ -225,10 +245,10 @@ impl MapInstallationService {
 
             let downloaded = match self
                 .zip_downloader
-                .download_zip_with_progress(&download_url, Some(on_progress))
+                .download_zip_with_progress(&download_url, &DownloadOptions::default(), Some(on_progress))
                 .await
             {
-                Ok(path) => path,
+                Ok((path, _)) => path,
                 Err(error) => {
                     report.failed.push(MapOperationFailure {
                         map_id,
@@ -279,6 +299,7 @@ impl MapInstallationService {
         let install_path = self.addons_dir.join(&existing.installed_path);
         crate::utils::validate_path_within_base_new(&install_path, &self.addons_dir)
             .context("Attempted to update map outside of addons directory")?;
+        crate::utils::ensure_no_symlinks_under(&self.addons_dir, &install_path)?;
 
         self.active_updates.set_progress(
             existing.id,
@@ -304,13 +325,17 @@ impl MapInstallationService {
 
         let backup_path = install_path.with_extension("vpk.bak");
         let had_existing = install_path.exists();
+        if let Err(error) = crate::utils::check_space_for_copy(&source_vpk, &self.addons_dir).await {
+            temp_cleanup.cleanup().await;
+            return Err(error).context("Disk space check failed before updating L4D2Center map");
+        }
         if had_existing {
             tokio::fs::copy(&install_path, &backup_path)
                 .await
                 .context("Failed to back up existing L4D2Center map before update")?;
         }
 
-        if let Err(error) = crate::utils::atomic_replace_file(&source_vpk, &install_path).await {
+        if let Err(error) = self.place_in_addons(&source_vpk, &install_path).await {
             temp_cleanup.cleanup().await;
             let _ = tokio::fs::remove_file(&backup_path).await;
             return Err(error).context("Failed to replace installed L4D2Center map file");
@@ -338,11 +363,7 @@ impl MapInstallationService {
             ));
         }
 
-        let metadata = match self
-            .vpk_extractor
-            .extract_vpk_metadata(install_path.clone())
-            .await
-        {
+        let metadata = match self.read_vpk_metadata(&install_path).await {
             Ok(metadata) => metadata,
             Err(error) => {
                 Self::restore_vpk_backup(&install_path, &backup_path, had_existing).await;
@@ -355,15 +376,28 @@ impl MapInstallationService {
 
         let mut updated = existing.clone();
         updated.version = Some(metadata.version);
+        updated.content_flags = metadata.content_flags;
         updated.checksum = Some(checksum);
         updated.checksum_kind = Some("md5".to_string());
         updated.installed_at = installed_at;
+        updated.size_bytes = Self::installed_size(&install_path).await;
         updated.source_kind = SourceKind::L4d2Center;
+        updated.instance_paths = match self.copy_to_instances(&install_path, &install_path).await {
+            Ok(paths) => paths,
+            Err(error) => {
+                Self::restore_vpk_backup(&install_path, &backup_path, had_existing).await;
+                return Err(error);
+            }
+        };
 
         if let Err(error) = self.registry.update_map(updated.clone()).await {
             Self::restore_vpk_backup(&install_path, &backup_path, had_existing).await;
             return Err(error).context("Failed to persist L4D2Center map update in registry");
         }
+        self.audit
+            .record(AuditAction::Update, updated.id, Some(updated.source_url.clone()))
+            .await;
+        self.notify_webhook(AuditAction::Update, &updated);
 
         let _ = tokio::fs::remove_file(&backup_path).await;
         Ok(updated)
@@ -1,20 +1,32 @@
 // SPDX-License-Identifier: GPL-3.0-only
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use anyhow::Context;
-use tokio::sync::{Mutex, Semaphore};
-use tracing::{info, warn};
+use tokio::sync::{Mutex, Semaphore, SemaphorePermit};
+use tracing::{debug, info, warn};
 
+use crate::config::{init_handle, read_config, Config, ConfigHandle};
 use crate::map_installer::helpers::{source_kind_from_url, workshop_source_url};
-use crate::map_installer::{ActiveUpdatesState, PendingUpdatesState};
+use crate::map_installer::audit::{AuditAction, AuditLog};
+use crate::map_installer::install_locks::InstallNameLocks;
+use crate::map_installer::operations::{enter_commit, InstallTimedOut};
+use crate::maps_blocklist::MapsBlocklist;
+use crate::map_installer::{
+    ActiveUpdatesState, InstallOperation, InstallOperations, MapEvent, MapEventBus,
+    PendingUpdatesState, WebhookNotifier,
+};
 use crate::downloader::{
-    steam::steam_time_to_utc,
+    circuit_breaker::CircuitBreakerStatus,
+    client::{CacheValidators, DownloadOptions, DownloadProgressCallback, HttpSettings, UrlPolicy},
+    steam::{steam_time_to_utc, WorkshopFileDetails},
     workshop::WorkshopDownloader,
     zip::ZipDownloader,
-    traits::Downloader,
 };
-use crate::extractor::{sevenz::SevenZExtractor, zip::ZipExtractor, traits::Extractor, vpk::VpkExtractor};
-use crate::registry::{models::{MapEntry, SourceKind}, traits::Registry};
+use crate::extractor::{entry_policy::ArchiveEntryPolicy, sevenz::SevenZExtractor, zip::ZipExtractor, traits::{Extractor, VpkMetadata}, vpk::VpkExtractor, vpk_signature::VpkSignature};
+use crate::registry::{models::{sanitize_tag, MapEntry, SourceKind}, traits::Registry};
+use crate::utils::DownloadKind;
 use serde::{Deserialize, Serialize};
 
 pub struct MapInstallationService {
@@ -25,14 +37,29 @@ pub struct MapInstallationService {
     sevenz_extractor: SevenZExtractor,
     vpk_extractor: VpkExtractor,
     addons_dir: PathBuf,
+    /// Addons directories of `server_dirs` that installed VPKs are copied into.
+    pub(super) instance_addons_dirs: Vec<PathBuf>,
     temp_dir: PathBuf,
+    /// Live config for install/detect policies; defaults until `with_config` is called.
+    config: ConfigHandle,
     pub(super) op_lock: Mutex<()>,
-    /// Caps concurrent heavy download/install work before op_lock is taken.
+    /// Caps concurrent heavy download/install work before op_lock is taken (`max_concurrent_downloads`).
     pub(super) download_semaphore: Semaphore,
     pub(super) pending_updates: PendingUpdatesState,
     pub(super) active_updates: ActiveUpdatesState,
+    pub(super) events: MapEventBus,
     /// Single-flight for bulk workshop+l4d2center update checks (manual + periodic).
     pub(super) updates_check_lock: Mutex<()>,
+    /// Install/uninstall/update history; disabled until `with_audit_log` is called.
+    pub(super) audit: AuditLog,
+    /// Serializes installs that request the same (sanitized) map name.
+    pub(super) install_name_locks: InstallNameLocks,
+    /// In-flight installs, cancellable through the API.
+    pub(super) operations: InstallOperations,
+    /// Delivers events to `event_webhook_url`.
+    pub(super) webhook: WebhookNotifier,
+    /// Outbound HTTP settings for downloads, catalog fetches and webhooks.
+    pub(super) http: HttpSettings,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,6 +76,75 @@ pub struct CompactReport {
     pub kept: Vec<MapEntry>,
 }
 
+/// Outcome of `cleanup_temp`: leftover download and extraction artifacts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CleanupReport {
+    /// Names of temp files and `extract-*` directories that were removed.
+    pub removed: Vec<String>,
+    /// Entries younger than the age threshold (possibly still in use).
+    pub kept: usize,
+    /// Entries that could not be removed.
+    pub failed: usize,
+}
+
+/// Outcome of `reconcile`: what was done to bring registry and addons back in line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconcileReport {
+    /// Untracked VPKs found in addons and registered.
+    pub registered: Vec<MapEntry>,
+    /// Entries removed because their file no longer exists.
+    pub removed: Vec<MapEntry>,
+    /// Entries whose file is present.
+    pub unchanged: usize,
+    /// Untracked VPKs that could not be registered.
+    pub failed: usize,
+}
+
+/// A VPK found in the addons directory by `scan_addons_dir`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledFileInfo {
+    /// Path relative to the addons directory.
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<chrono::DateTime<chrono::Utc>>,
+    /// Whether a registry entry points at this file.
+    pub tracked: bool,
+    pub map_id: Option<u64>,
+}
+
+/// What `force_uninstall_by_path` removed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ForceUninstallReport {
+    /// Path relative to the addons directory.
+    pub path: String,
+    pub file_removed: bool,
+    /// Registry entries that pointed at the file.
+    pub removed_map_ids: Vec<u64>,
+}
+
+/// Disk space used by registered maps, from the sizes recorded at install time.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MapFootprint {
+    pub total_bytes: u64,
+    pub by_source_kind: BTreeMap<SourceKind, u64>,
+    pub map_count: usize,
+    /// Entries with no recorded size; they are left out of the byte totals.
+    pub unsized_map_ids: Vec<u64>,
+}
+
+/// Outcome of `scan_integrity`: installed files checked against their recorded checksum.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    /// Entries whose file still matches the recorded checksum.
+    pub verified: usize,
+    /// Entries without a recorded checksum.
+    pub skipped: usize,
+    /// Entries whose file is gone from addons.
+    pub missing: Vec<u64>,
+    /// Entries whose file no longer matches (or could not be hashed).
+    pub mismatched: Vec<u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkshopUpdateAvailable {
     pub map: MapEntry,
@@ -107,7 +203,86 @@ pub fn needs_workshop_update(
     true
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+/// How a downloaded file should be registered once it validates.
+#[derive(Debug, Clone)]
+pub(super) struct InstallTarget {
+    pub source_kind: SourceKind,
+    pub workshop_id: Option<u64>,
+    pub provided_name: Option<String>,
+    pub source_url: Option<String>,
+    pub expected_installed_filename: Option<String>,
+    /// Replace an existing map with the same name instead of failing.
+    pub force: bool,
+    /// Reject the install unless the map VPK matches this checksum.
+    pub expected_checksum: Option<ExpectedChecksum>,
+    /// `Last-Modified`/`ETag` of the download, stored for conditional re-downloads.
+    pub source_validators: CacheValidators,
+}
+
+/// Caller-supplied knobs for URL and workshop installs.
+#[derive(Debug, Clone, Default)]
+pub struct InstallOptions {
+    /// Replace an existing map with the same name instead of failing.
+    pub force: bool,
+    /// Extra headers/credentials for URL mirrors (ignored for workshop installs).
+    pub download: DownloadOptions,
+    pub expected_checksum: Option<ExpectedChecksum>,
+    /// Fallback URLs tried in order when the primary URL fails to yield a map file.
+    pub mirror_urls: Vec<String>,
+}
+
+/// Checksum the installed VPK must match, e.g. from a backend `MapEntry`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedChecksum {
+    pub value: String,
+    /// `md5` or `sha256`.
+    pub kind: String,
+}
+
+impl ExpectedChecksum {
+    pub fn md5(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            kind: "md5".to_string(),
+        }
+    }
+
+    /// Expected checksum recorded on a registry entry; entries without a kind are MD5.
+    pub fn from_map_entry(entry: &MapEntry) -> Option<Self> {
+        let value = entry.checksum.as_deref().map(str::trim).filter(|v| !v.is_empty())?;
+        Some(Self {
+            value: value.to_string(),
+            kind: entry.checksum_kind.clone().unwrap_or_else(|| "md5".to_string()),
+        })
+    }
+
+    /// Compare `path` against the expected checksum.
+    pub async fn verify(&self, path: &Path) -> anyhow::Result<()> {
+        let actual = crate::utils::calculate_file_checksum(path, &self.kind).await?;
+        if !actual.eq_ignore_ascii_case(&self.value) {
+            return Err(ChecksumMismatch {
+                path: path.to_path_buf(),
+                kind: self.kind.to_ascii_lowercase(),
+                expected: self.value.clone(),
+                actual,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// A downloaded file did not match its [`ExpectedChecksum`].
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("Checksum mismatch for {}: expected {kind} {expected}, got {actual}", .path.display())]
+pub struct ChecksumMismatch {
+    pub path: PathBuf,
+    pub kind: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum DiscoveryMode {
     #[default]
@@ -116,6 +291,17 @@ pub enum DiscoveryMode {
     ForceUpdate,
 }
 
+/// Policy for URL downloads, read from the live config on every request so redirect
+/// hops and resolved share links get the same `blocked_url_patterns` and
+/// `allowed_download_hosts` checks as the URL that was asked for.
+fn download_url_policy(config: ConfigHandle) -> UrlPolicy {
+    Arc::new(move |url| {
+        let config = read_config(&config);
+        MapsBlocklist::from_config(&config).check_url(url)?;
+        crate::utils::validate_download_host(url, &config.allowed_download_hosts)
+    })
+}
+
 impl MapInstallationService {
     pub async fn new(
         registry: Arc<dyn Registry>,
@@ -127,21 +313,132 @@ impl MapInstallationService {
     ) -> anyhow::Result<Self> {
         Ok(Self {
             registry,
-            workshop_downloader: WorkshopDownloader::new(temp_dir.clone(), max_download_size_bytes)?,
-            zip_downloader: ZipDownloader::new(temp_dir.clone(), max_download_size_bytes).await?,
-            zip_extractor: ZipExtractor::new(max_extraction_size_bytes, max_extraction_file_count),
+            workshop_downloader: WorkshopDownloader::new(
+                temp_dir.clone(),
+                max_download_size_bytes,
+                &HttpSettings::default(),
+            )?,
+            zip_downloader: ZipDownloader::new(
+                temp_dir.clone(),
+                max_download_size_bytes,
+                &HttpSettings::default(),
+            )
+            .await?,
+            // Only VPKs are installed from archives; readmes and screenshots are never written.
+            zip_extractor: ZipExtractor::new(max_extraction_size_bytes, max_extraction_file_count)
+                .with_extension_filter(&["vpk"]),
             sevenz_extractor: SevenZExtractor::new(max_extraction_size_bytes, max_extraction_file_count),
             vpk_extractor: VpkExtractor::new(),
             addons_dir,
+            instance_addons_dirs: Vec::new(),
             temp_dir,
+            config: init_handle(Config::default()),
             op_lock: Mutex::new(()),
-            download_semaphore: Semaphore::new(2),
+            download_semaphore: Semaphore::new(Config::default().max_concurrent_downloads),
             pending_updates: PendingUpdatesState::new(),
             active_updates: ActiveUpdatesState::new(),
+            events: MapEventBus::new(),
             updates_check_lock: Mutex::new(()),
+            audit: AuditLog::default(),
+            install_name_locks: InstallNameLocks::default(),
+            operations: InstallOperations::new(),
+            webhook: WebhookNotifier::new(&HttpSettings::default())?,
+            http: HttpSettings::default(),
         })
     }
 
+    /// Send downloads, catalog fetches and webhooks with `http` (proxy etc.).
+    pub fn with_http_settings(mut self, http: HttpSettings) -> anyhow::Result<Self> {
+        self.workshop_downloader = self.workshop_downloader.with_http_settings(&http)?;
+        self.zip_downloader = self.zip_downloader.with_http_settings(&http)?;
+        self.webhook = WebhookNotifier::new(&http)?;
+        self.http = http;
+        Ok(self)
+    }
+
+    /// Attach the shared config handle so policy fields follow hot reloads.
+    ///
+    /// The download limit is sized from `max_concurrent_downloads` and the instance
+    /// addons directories are taken from `server_dirs` here; neither follows a reload.
+    pub fn with_config(mut self, config: ConfigHandle) -> Self {
+        let cfg = read_config(&config);
+        self.download_semaphore = Semaphore::new(cfg.max_concurrent_downloads.max(1));
+        self.instance_addons_dirs = cfg.instance_addons_dirs();
+        self.workshop_downloader = self.workshop_downloader.with_steam_breaker(
+            cfg.steam_breaker_failure_threshold,
+            Duration::from_secs(cfg.steam_breaker_cooldown_secs),
+        );
+        self.zip_downloader = self
+            .zip_downloader
+            .with_url_policy(download_url_policy(config.clone()));
+        self.config = config;
+        self
+    }
+
+    /// Test helper: send webhook events with a client that allows loopback receivers.
+    #[cfg(test)]
+    pub fn with_webhook_notifier(mut self, webhook: WebhookNotifier) -> Self {
+        self.webhook = webhook;
+        self
+    }
+
+    /// Record install/uninstall/update history as JSON lines at `path`.
+    pub fn with_audit_log(mut self, path: PathBuf) -> Self {
+        self.audit = AuditLog::new(path);
+        self
+    }
+
+    pub fn audit_log(&self) -> &AuditLog {
+        &self.audit
+    }
+
+    /// Hold the install lock for a caller-provided name for the whole install.
+    ///
+    /// Once the lock is held, a name another install already registered is rejected
+    /// (unless `force`) before anything is downloaded. Unnamed installs take their
+    /// name from the file and rely on `op_lock` for the final collision check.
+    pub(super) async fn lock_install_name(
+        &self,
+        name: Option<&str>,
+        force: bool,
+    ) -> anyhow::Result<Option<tokio::sync::OwnedMutexGuard<()>>> {
+        let Some(name) = name.map(str::trim).filter(|n| !n.is_empty()) else {
+            return Ok(None);
+        };
+        let map_name = crate::utils::sanitize_map_name(name).context("Invalid map name provided")?;
+        let guard = self.install_name_locks.lock(&map_name).await;
+        if !force && self.find_map_by_name(&map_name).await?.is_some() {
+            return Err(anyhow::anyhow!("Map with name '{map_name}' already installed"));
+        }
+        Ok(Some(guard))
+    }
+
+    /// Wait for a slot in the daemon-wide download limit; hold the permit for the whole download.
+    pub(super) async fn acquire_download_permit(&self) -> SemaphorePermit<'_> {
+        self.download_semaphore
+            .acquire()
+            .await
+            .expect("download semaphore closed")
+    }
+
+    pub(super) fn config(&self) -> Arc<Config> {
+        read_config(&self.config)
+    }
+
+    /// Refuse a change to the addons directory while `read_only` is set. Checked by
+    /// every mutating entry point, so the REPL and CLI honour it like the API does.
+    pub(super) fn ensure_writable(&self) -> anyhow::Result<()> {
+        if self.config().read_only {
+            anyhow::bail!("Daemon is in read-only mode; the addons directory is not modified");
+        }
+        Ok(())
+    }
+
+    /// Install policy from the live config, so edits apply without a restart.
+    fn blocklist(&self) -> MapsBlocklist {
+        MapsBlocklist::from_config(&self.config())
+    }
+
     pub fn pending_updates(&self) -> PendingUpdatesState {
         self.pending_updates.clone()
     }
@@ -150,6 +447,124 @@ impl MapInstallationService {
         self.active_updates.clone()
     }
 
+    pub fn events(&self) -> MapEventBus {
+        self.events.clone()
+    }
+
+    /// Installs currently running, oldest first.
+    pub fn install_operations(&self) -> Vec<InstallOperation> {
+        self.operations.list()
+    }
+
+    /// Events of the running install `operation_id` up to its completed/failed event;
+    /// `None` when it is not running.
+    pub fn watch_install(&self, operation_id: u64) -> Option<impl futures_util::Stream<Item = MapEvent> + use<>> {
+        // Subscribe before checking so the final event cannot slip in between.
+        let receiver = self.events.subscribe();
+        self.operations
+            .is_running(operation_id)
+            .then(|| crate::map_installer::events::operation_events(receiver, operation_id))
+    }
+
+    /// `install_progress` events for the install the current task runs in, if any.
+    fn install_progress_callback(&self) -> Option<DownloadProgressCallback> {
+        let operation_id = crate::map_installer::operations::current_operation_id()?;
+        let events = self.events.clone();
+        Some(Arc::new(move |bytes_downloaded, bytes_total| {
+            events.publish(MapEvent::InstallProgress {
+                operation_id,
+                bytes_downloaded,
+                bytes_total,
+            });
+        }))
+    }
+
+    /// Abort the running install `operation_id`; false when it is not running.
+    pub fn cancel_install(&self, operation_id: u64) -> bool {
+        self.operations.cancel(operation_id)
+    }
+
+    /// Run an install, publishing started and completed/failed events around it.
+    pub(super) async fn with_install_events(
+        &self,
+        source: String,
+        install: impl std::future::Future<Output = anyhow::Result<MapEntry>>,
+    ) -> anyhow::Result<MapEntry> {
+        let operation = self.operations.begin(source.clone());
+        self.events.publish(MapEvent::InstallStarted {
+            source: source.clone(),
+            operation_id: operation.id(),
+        });
+        // Dropping the install future on expiry or cancellation runs its temp-path guards.
+        // Past its commit point the install is left to finish placing and registering
+        // the map, so neither a timeout nor a cancellation leaves a file in addons
+        // without a registry entry.
+        let timeout_secs = self.config().install_timeout_secs;
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(timeout_secs);
+        let result = {
+            let install = operation.run(deadline, timeout_secs, install);
+            tokio::pin!(install);
+            tokio::select! {
+                result = &mut install => result,
+                _ = tokio::time::sleep_until(deadline) => {
+                    if operation.is_committed() {
+                        warn!(source = %source, timeout_secs, "Install timed out while committing; letting it finish");
+                        install.await
+                    } else {
+                        warn!(source = %source, timeout_secs, "Install timed out");
+                        Err(InstallTimedOut { target: source.clone(), timeout_secs }.into())
+                    }
+                },
+                _ = operation.token().cancelled() => {
+                    if operation.is_committed() {
+                        warn!(source = %source, operation_id = operation.id(), "Install cancelled while committing; letting it finish");
+                        install.await
+                    } else {
+                        warn!(source = %source, operation_id = operation.id(), "Install cancelled");
+                        Err(anyhow::anyhow!("Install of {source} was cancelled"))
+                    }
+                }
+            }
+        };
+        let operation_id = operation.id();
+        drop(operation);
+        if let Ok(entry) = &result {
+            self.audit
+                .record(AuditAction::Install, entry.id, Some(source.clone()))
+                .await;
+            self.notify_webhook(AuditAction::Install, entry);
+            self.spawn_post_hook(self.config().post_install_command.clone(), "post_install", entry);
+        }
+        self.events.publish(match &result {
+            Ok(entry) => MapEvent::InstallCompleted {
+                source,
+                operation_id,
+                map_id: entry.id,
+                name: entry.name.clone(),
+            },
+            Err(e) => MapEvent::InstallFailed {
+                source,
+                operation_id,
+                error: e.to_string(),
+            },
+        });
+        result
+    }
+
+    /// POST `action` for `entry` to `event_webhook_url`, if configured, in the background.
+    pub(super) fn notify_webhook(&self, action: AuditAction, entry: &MapEntry) {
+        self.webhook
+            .notify(self.config().event_webhook_url.as_deref(), action, entry);
+    }
+
+    /// Start the configured post-install/uninstall `command`, if any, for `entry`.
+    fn spawn_post_hook(&self, command: Option<PathBuf>, hook: &'static str, entry: &MapEntry) {
+        if let Some(command) = command {
+            let path = self.addons_dir.join(&entry.installed_path);
+            crate::map_installer::hooks::spawn_map_hook(hook, command, entry, path);
+        }
+    }
+
     /// Try to acquire the bulk update-check lock. Returns `None` if another check is running.
     pub fn try_lock_updates_check(
         &self,
@@ -162,48 +577,139 @@ impl MapInstallationService {
         self.updates_check_lock.lock().await
     }
     
-    /// Install a map from a URL or workshop ID
+    /// Install a map from a URL.
     pub async fn install_from_url(
         &self,
         url: String,
         name: Option<String>,
+        options: InstallOptions,
     ) -> anyhow::Result<MapEntry> {
-        let _download_permit = self
-            .download_semaphore
-            .acquire()
-            .await
-            .expect("download semaphore closed");
-        info!(url = %url, "Starting map installation from URL");
+        self.ensure_writable()?;
+        info!(url = %url, mirrors = options.mirror_urls.len(), "Starting map installation from URL");
+
+        for candidate in std::iter::once(&url).chain(&options.mirror_urls) {
+            if let Some(existing) = self.find_map_by_source_url(candidate).await? {
+                info!(
+                    map_id = existing.id,
+                    url = %candidate,
+                    "Map with this source URL already installed, skipping download"
+                );
+                return Ok(existing);
+            }
+        }
 
-        // Validate URL format - should be HTTP/HTTPS
-        crate::utils::validate_url_resolved(&url)
-            .await
-            .context("Invalid URL format (SSRF protection)")?;
+        // Install from ZIP URL (url parser no longer needed since workshop_id is separate)
+        self.install_from_zip_url(&url, name, options).await
+    }
 
-        if let Some(existing) = self.find_map_by_source_url(&url).await? {
+    /// Install a VPK/ZIP/7z already on the host, read from one of `local_install_dirs`.
+    ///
+    /// The file is copied into the temp dir first so the install pipeline never
+    /// touches (or removes) the operator's original.
+    pub async fn install_from_local_path(
+        &self,
+        path: PathBuf,
+        name: Option<String>,
+        options: InstallOptions,
+    ) -> anyhow::Result<MapEntry> {
+        self.ensure_writable()?;
+        info!(path = %path.display(), "Starting map installation from local path");
+
+        let source = self.resolve_local_install_path(&path).await?;
+        let source_url = format!("file:{}", source.display());
+
+        if let Some(existing) = self.find_map_by_source_url(&source_url).await? {
             info!(
                 map_id = existing.id,
-                url = %url,
-                "Map with this source URL already installed, skipping download"
+                path = %source.display(),
+                "Map from this local path already installed, skipping copy"
             );
             return Ok(existing);
         }
+        let _name_guard = self.lock_install_name(name.as_deref(), options.force).await?;
 
-        // Install from ZIP URL (url parser no longer needed since workshop_id is separate)
-        self.install_from_zip_url(&url, name).await
+        let install = async {
+            crate::utils::check_space_for_copy(&source, &self.temp_dir)
+                .await
+                .context("Disk space check failed before staging local file")?;
+
+            let file_name = source
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "local".to_string());
+            let staged_path = self
+                .temp_dir
+                .join(format!("{}-{}", uuid::Uuid::new_v4(), file_name));
+            let _staged_guard = crate::utils::TempPathGuard::new(staged_path.clone());
+            tokio::fs::copy(&source, &staged_path)
+                .await
+                .with_context(|| format!("Failed to stage {}", source.display()))?;
+            let kind = self.ensure_map_download(&staged_path, &source_url).await?;
+
+            self.install_detected_file(
+                staged_path.clone(),
+                kind,
+                InstallTarget {
+                    source_kind: SourceKind::Other,
+                    workshop_id: None,
+                    provided_name: name,
+                    source_url: Some(source_url.clone()),
+                    expected_installed_filename: None,
+                    force: options.force,
+                    expected_checksum: options.expected_checksum,
+                    source_validators: CacheValidators::default(),
+                },
+            )
+            .await
+        };
+        self.with_install_events(source_url.clone(), install).await
+    }
+
+    /// Canonicalize `path` and require a regular file inside a configured local install dir.
+    async fn resolve_local_install_path(&self, path: &Path) -> anyhow::Result<PathBuf> {
+        let allowed_dirs = self.config().local_install_dirs.clone();
+        if allowed_dirs.is_empty() {
+            anyhow::bail!("Invalid local path: local installs are disabled (local_install_dirs is empty)");
+        }
+
+        let resolved = tokio::fs::canonicalize(path)
+            .await
+            .with_context(|| format!("Invalid local path {}: cannot be resolved", path.display()))?;
+
+        let mut permitted = false;
+        for dir in &allowed_dirs {
+            if let Ok(dir) = tokio::fs::canonicalize(dir).await
+                && resolved.starts_with(&dir)
+            {
+                permitted = true;
+                break;
+            }
+        }
+        if !permitted {
+            anyhow::bail!(
+                "Invalid local path {}: not inside local_install_dirs",
+                path.display()
+            );
+        }
+
+        let metadata = tokio::fs::metadata(&resolved)
+            .await
+            .with_context(|| format!("Invalid local path {}: cannot be read", path.display()))?;
+        if !metadata.is_file() {
+            anyhow::bail!("Invalid local path {}: not a regular file", path.display());
+        }
+        Ok(resolved)
     }
 
-    /// Install a map from Steam Workshop ID
+    /// Install a map from Steam Workshop ID.
     pub async fn install_from_workshop_id(
         &self,
         workshop_id: u64,
         name: Option<String>,
+        options: InstallOptions,
     ) -> anyhow::Result<MapEntry> {
-        let _download_permit = self
-            .download_semaphore
-            .acquire()
-            .await
-            .expect("download semaphore closed");
+        self.ensure_writable()?;
+        self.blocklist().check_workshop_id(workshop_id)?;
         info!(workshop_id, "Installing map from Steam Workshop");
 
         if let Some(existing) = self.find_map_by_workshop_id(workshop_id).await? {
@@ -214,46 +720,70 @@ impl MapInstallationService {
             );
             return Ok(existing);
         }
-
-        let details = self
-            .workshop_downloader
-            .get_workshop_file_details(&[workshop_id])
-            .await?;
-        let detail = details
-            .iter()
-            .find(|d| d.workshop_id == workshop_id)
-            .ok_or_else(|| anyhow::anyhow!("Workshop item {workshop_id} not found on Steam"))?;
-
-        // Guard: check we have enough disk space before initiating the download.
-        // file_size == 0 means Steam didn't report it — we skip the guard in that case.
-        if detail.file_size > 0 {
-            crate::utils::check_sufficient_space(&self.temp_dir, detail.file_size)
-                .with_context(|| format!("Disk space check failed before downloading workshop map {workshop_id}"))?;
+        // Same order as `install_from_zip_url`: name lock first, then the download slot.
+        let _name_guard = self.lock_install_name(name.as_deref(), options.force).await?;
+        // A same-name install may have registered this item while we waited.
+        if let Some(existing) = self.find_map_by_workshop_id(workshop_id).await? {
+            return Ok(existing);
         }
+        let _download_permit = self.acquire_download_permit().await;
+
+        let install = async {
+            let details = self
+                .workshop_downloader
+                .get_workshop_file_details(&[workshop_id])
+                .await?;
+            let detail = details
+                .iter()
+                .find(|d| d.workshop_id == workshop_id)
+                .ok_or_else(|| anyhow::anyhow!("Workshop item {workshop_id} not found on Steam"))?;
+
+            // Guard: check we have enough disk space before initiating the download.
+            // file_size == 0 means Steam didn't report it — we skip the guard in that case.
+            if detail.file_size > 0 {
+                crate::utils::check_sufficient_space(&self.temp_dir, detail.file_size)
+                    .with_context(|| format!("Disk space check failed before downloading workshop map {workshop_id}"))?;
+            }
 
-        let downloaded_path = self
-            .workshop_downloader
-            .download_from_details(detail)
-            .await?;
-
-        let name = name.or_else(|| detail.title.clone().filter(|t| !t.trim().is_empty()));
-
-        let mut map_entry = self
-            .install_downloaded_file(
-                downloaded_path,
-                SourceKind::Workshop,
-                Some(workshop_id),
-                name,
-                None,
-                None,
-            )
-            .await?;
-
-        map_entry.workshop_updated_at = Some(steam_time_to_utc(detail.time_updated));
-        self.registry.update_map(map_entry.clone()).await?;
-        
-        info!(map_id = %map_entry.id, workshop_id, "Workshop map installed successfully");
-        Ok(map_entry)
+            let downloaded_path = self
+                .workshop_downloader
+                .download_from_details_with_progress(detail, self.install_progress_callback())
+                .await?;
+            let _download_guard = crate::utils::TempPathGuard::new(downloaded_path.clone());
+
+            let name = name.or_else(|| detail.metadata.title.clone());
+
+            let mut map_entry = self
+                .install_downloaded_file(
+                    downloaded_path,
+                    InstallTarget {
+                        source_kind: SourceKind::Workshop,
+                        workshop_id: Some(workshop_id),
+                        provided_name: name,
+                        source_url: None,
+                        expected_installed_filename: None,
+                        force: options.force,
+                        expected_checksum: options.expected_checksum,
+                        source_validators: CacheValidators::default(),
+                    },
+                )
+                .await?;
+
+            map_entry.workshop_updated_at = Some(steam_time_to_utc(detail.time_updated));
+            map_entry.author = detail.metadata.creator.map(|creator| creator.to_string());
+            // Workshop tags (e.g. "Campaigns") seed the entry's labels.
+            map_entry
+                .tags
+                .extend(detail.metadata.tags.iter().filter_map(|tag| sanitize_tag(tag)));
+            map_entry.tags.sort_unstable();
+            map_entry.tags.dedup();
+            self.registry.update_map(map_entry.clone()).await?;
+
+            info!(map_id = %map_entry.id, workshop_id, "Workshop map installed successfully");
+            Ok(map_entry)
+        };
+        self.with_install_events(workshop_source_url(workshop_id), install)
+            .await
     }
     
     /// Install a map from ZIP URL
@@ -261,38 +791,163 @@ impl MapInstallationService {
         &self,
         url: &str,
         name: Option<String>,
+        options: InstallOptions,
     ) -> anyhow::Result<MapEntry> {
-        info!(url = %url, "Installing map from URL");
+        // Wait on a same-name install before taking a download slot it would sit on.
+        let _name_guard = self.lock_install_name(name.as_deref(), options.force).await?;
+        // Queueing for a download slot does not count towards the install timeout.
+        let _download_permit = self.acquire_download_permit().await;
+        let install = async {
+            info!(url = %url, "Installing map from URL");
+
+            // Guard: check for a reasonable minimum of free disk space (512 MiB) before
+            // downloading from an external URL where the final size is not yet known.
+            crate::utils::check_sufficient_space(&self.temp_dir, 512 * 1024 * 1024)
+                .with_context(|| format!("Disk space check failed before downloading from {url}"))?;
+
+            let candidates: Vec<&str> = std::iter::once(url)
+                .chain(options.mirror_urls.iter().map(String::as_str))
+                .collect();
+            let mut remaining = &candidates[..];
+            loop {
+                let (index, downloaded_path, kind, source_validators) =
+                    self.download_from_mirrors(url, remaining, &options.download).await?;
+                let source_url = remaining[index];
+                remaining = &remaining[index + 1..];
+                let _download_guard = crate::utils::TempPathGuard::new(downloaded_path.clone());
+
+                let result = self
+                    .install_detected_file(
+                        downloaded_path,
+                        kind,
+                        InstallTarget {
+                            source_kind: source_kind_from_url(source_url),
+                            workshop_id: None,
+                            provided_name: name.clone(),
+                            source_url: Some(source_url.to_string()),
+                            expected_installed_filename: None,
+                            force: options.force,
+                            expected_checksum: options.expected_checksum.clone(),
+                            source_validators,
+                        },
+                    )
+                    .await;
+                match result {
+                    Err(error)
+                        if !remaining.is_empty()
+                            && error.chain().any(|e| e.is::<ChecksumMismatch>()) =>
+                    {
+                        warn!(url = %source_url, error = %error, "Mirror served a file with the wrong checksum; trying the next one");
+                    }
+                    result => return result,
+                }
+            }
+        };
+        self.with_install_events(url.to_string(), install).await
+    }
+    
+    /// Download the first of `candidates` that passes the URL policy and yields a map file.
+    ///
+    /// Each candidate is checked against the blocklist, `allowed_download_hosts` and SSRF
+    /// rules just before it is tried, so a bad mirror is skipped rather than failing the
+    /// install. Returns the index of the candidate that worked with the downloaded file,
+    /// its detected kind and cache validators; when every candidate fails, the last error
+    /// is returned. Request headers and credentials only go to URLs on `url`'s origin.
+    async fn download_from_mirrors(
+        &self,
+        url: &str,
+        candidates: &[&str],
+        download: &DownloadOptions,
+    ) -> anyhow::Result<(usize, PathBuf, DownloadKind, CacheValidators)> {
+        let origin = reqwest::Url::parse(url).ok().map(|u| u.origin());
+        let no_options = DownloadOptions::default();
+        let mut last_error = None;
+        for (index, candidate) in candidates.iter().enumerate() {
+            let same_origin = origin.is_some()
+                && reqwest::Url::parse(candidate).ok().map(|u| u.origin()) == origin;
+            let options = if same_origin { download } else { &no_options };
+            let result = match self.validate_source_url(candidate).await {
+                Ok(()) => self.download_map_file(candidate, options).await,
+                Err(error) => Err(error),
+            };
+            match result {
+                Ok((path, kind, validators)) => {
+                    if *candidate != url {
+                        info!(url = %url, mirror = %candidate, "Downloaded map from fallback mirror");
+                    }
+                    return Ok((index, path, kind, validators));
+                }
+                Err(error) => {
+                    if candidates.len() > 1 {
+                        warn!(url = %candidate, error = %error, "Mirror failed; trying the next one");
+                    }
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No download URL given")))
+    }
 
-        let source_kind = source_kind_from_url(url);
+    /// Check `url` against the blocklist, `allowed_download_hosts` and SSRF rules.
+    pub(super) async fn validate_source_url(&self, url: &str) -> anyhow::Result<()> {
+        self.blocklist().check_url(url)?;
+        crate::utils::validate_download_host(url, &self.config().allowed_download_hosts)?;
 
-        // Guard: check for a reasonable minimum of free disk space (512 MiB) before
-        // downloading from an external URL where the final size is not yet known.
-        crate::utils::check_sufficient_space(&self.temp_dir, 512 * 1024 * 1024)
-            .with_context(|| format!("Disk space check failed before downloading from {url}"))?;
+        // Validate URL format - should be HTTP/HTTPS
+        self.zip_downloader
+            .validate_target(url)
+            .await
+            .context("Invalid URL format (SSRF protection)")?;
+        Ok(())
+    }
 
-        let downloaded_path = self.zip_downloader.download_zip(url).await?;
+    /// Precheck, download and sniff `url`; the file is removed unless it is a map file.
+    async fn download_map_file(
+        &self,
+        url: &str,
+        download: &DownloadOptions,
+    ) -> anyhow::Result<(PathBuf, DownloadKind, CacheValidators)> {
+        let direct_url = self.zip_downloader.resolve_url(url).await?;
+        self.precheck_download(&direct_url, download).await?;
+        let (downloaded_path, validators) = self
+            .zip_downloader
+            .download_zip_with_progress(&direct_url, download, self.install_progress_callback())
+            .await?;
+        let guard = crate::utils::TempPathGuard::new(downloaded_path.clone());
+        let kind = self.ensure_map_download(&downloaded_path, url).await?;
+        Ok((guard.keep(), kind, validators))
+    }
 
-        self.install_downloaded_file(
-            downloaded_path,
-            source_kind,
-            None,
-            name,
-            Some(url.to_string()),
-            None,
-        )
-        .await
+    /// Refuse `url` before downloading when a `HEAD` shows it missing or over the
+    /// download limit. Servers that cannot answer the `HEAD` are left to the `GET`.
+    pub(super) async fn precheck_download(
+        &self,
+        url: &str,
+        options: &DownloadOptions,
+    ) -> anyhow::Result<()> {
+        match self.zip_downloader.precheck(url, options).await {
+            Ok(precheck) => {
+                info!(
+                    url = %url,
+                    status = %precheck.status,
+                    content_length = ?precheck.content_length,
+                    content_type = ?precheck.content_type,
+                    "Download precheck"
+                );
+                precheck.check(self.zip_downloader.max_download_size())
+            }
+            Err(error) => {
+                warn!(url = %url, error = %error, "HEAD precheck failed; falling back to GET");
+                Ok(())
+            }
+        }
     }
-    
+
     /// Install a downloaded file (ZIP or VPK)
-    async fn install_downloaded_file(
+    pub(super) async fn install_downloaded_file(
         &self,
         file_path: PathBuf,
-        source_kind: SourceKind,
-        workshop_id: Option<u64>,
-        provided_name: Option<String>,
-        source_url: Option<String>,
-        expected_installed_filename: Option<String>,
+        target: InstallTarget,
     ) -> anyhow::Result<MapEntry> {
         let file_ext = file_path
             .extension()
@@ -302,86 +957,264 @@ impl MapInstallationService {
 
         match file_ext.as_str() {
             "vpk" => {
-                self.install_vpk_file(
-                    file_path,
-                    source_kind,
-                    workshop_id,
-                    provided_name,
-                    source_url,
-                    expected_installed_filename,
-                )
+                self.install_vpk_file(file_path, target)
                 .await
             }
             "zip" => {
-                self.install_zip_file(
-                    file_path,
-                    source_kind,
-                    workshop_id,
-                    provided_name,
-                    source_url,
-                    expected_installed_filename,
-                )
+                self.install_zip_file(file_path, target)
                 .await
             }
             "7z" => {
-                self.install_sevenz_file(
-                    file_path,
-                    source_kind,
-                    workshop_id,
-                    provided_name,
-                    source_url,
-                    expected_installed_filename,
-                )
+                self.install_sevenz_file(file_path, target)
                 .await
             }
             _ => {
-                if file_path.extension().is_none() || file_ext.is_empty() {
-                    if self.is_vpk_file(&file_path).await? {
-                        return self
-                            .install_vpk_file(
-                                file_path,
-                                source_kind,
-                                workshop_id,
-                                provided_name,
-                                source_url,
-                                expected_installed_filename,
-                            )
-                            .await;
-                    }
-                }
-                Err(anyhow::anyhow!("Unsupported file type: {}", file_ext))
+                self.install_sniffed_file(file_path, &file_ext, target)
+                .await
             }
         }
     }
+
+    /// Install a file whose extension is missing or unrecognised, going by its content.
+    ///
+    /// A single gzip layer is stripped in place first, so a gzipped archive saved
+    /// as `.dat` still resolves to the ZIP/7z/VPK inside it.
+    async fn install_sniffed_file(
+        &self,
+        file_path: PathBuf,
+        file_ext: &str,
+        target: InstallTarget,
+    ) -> anyhow::Result<MapEntry> {
+        if let Some(size) =
+            crate::utils::gunzip_in_place(&file_path, self.config().max_download_size_bytes).await?
+        {
+            info!(path = %file_path.display(), size, "Decompressed gzip-wrapped file");
+        }
+        let kind = crate::utils::detect_download_kind(&file_path)
+            .await
+            .with_context(|| format!("Unsupported file type: {file_ext}"))?;
+        info!(path = %file_path.display(), ?kind, "Detected file type from content");
+        self.install_detected_file(file_path, kind, target).await
+    }
     
+    /// Install a download by its sniffed content rather than the name it was saved under.
+    ///
+    /// Mirrors serve bare VPKs and archives behind arbitrary URLs, so the file
+    /// extension derived from the URL cannot be trusted to pick the install path.
+    pub(super) async fn install_detected_file(
+        &self,
+        file_path: PathBuf,
+        kind: DownloadKind,
+        target: InstallTarget,
+    ) -> anyhow::Result<MapEntry> {
+        match kind {
+            DownloadKind::Vpk => self.install_vpk_file(file_path, target).await,
+            DownloadKind::Zip => self.install_zip_file(file_path, target).await,
+            DownloadKind::SevenZ => self.install_sevenz_file(file_path, target).await,
+        }
+    }
+
+    /// Reject a download that is not a ZIP/7z/VPK (e.g. an HTML error page), removing it.
+    pub(super) async fn ensure_map_download(
+        &self,
+        path: &Path,
+        url: &str,
+    ) -> anyhow::Result<DownloadKind> {
+        match crate::utils::detect_download_kind(path).await {
+            Ok(kind) => Ok(kind),
+            Err(e) => {
+                warn!(url = %url, error = %e, "Downloaded file is not a map archive");
+                self.reject_download(path, &format!("{e:#} (from {url})")).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Copy `source` over `install_path` in the addons directory with the configured
+    /// installed-file mode and owner, set before the file is renamed into place.
+    /// Failing to apply them is logged, not fatal: the map is in place and an operator
+    /// can fix its permissions by hand.
+    pub(super) async fn place_in_addons(&self, source: &Path, install_path: &Path) -> anyhow::Result<()> {
+        let permissions = crate::utils::InstalledFilePermissions::from_config(&self.config())
+            .unwrap_or_else(|error| {
+                warn!(error = %error, "Invalid installed file permissions; leaving defaults");
+                crate::utils::InstalledFilePermissions::default()
+            });
+        crate::utils::atomic_replace_file_with_permissions(source, install_path, &permissions).await
+    }
+
+    /// Dispose of a download that failed validation: move it to `quarantine_dir` with
+    /// `reason` when one is configured, otherwise delete it.
+    pub(super) async fn reject_download(&self, path: &Path, reason: &str) {
+        let config = self.config();
+        if let Some(dir) = &config.quarantine_dir {
+            match crate::map_installer::quarantine::quarantine_file(
+                dir,
+                config.quarantine_max_bytes,
+                path,
+                reason,
+            )
+            .await
+            {
+                Ok(_) => return,
+                Err(error) => {
+                    warn!(error = %error, path = %path.display(), "Failed to quarantine rejected download; deleting it");
+                }
+            }
+        }
+        if let Err(cleanup) = tokio::fs::remove_file(path).await
+            && cleanup.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!(error = %cleanup, path = %path.display(), "Failed to clean up rejected download");
+        }
+    }
+
     /// Check if a file is a VPK file
     pub(super) async fn is_vpk_file(&self, path: &Path) -> anyhow::Result<bool> {
         // Simple check: try to extract metadata
-        match self.vpk_extractor.extract_vpk_metadata(path.to_path_buf()).await {
+        match self.read_vpk_metadata(path).await {
             Ok(_) => Ok(true),
             Err(_) => Ok(false),
         }
     }
+
+    /// Extension allow/deny lists archives are checked against before extraction.
+    pub(super) fn archive_entry_policy(&self) -> ArchiveEntryPolicy {
+        ArchiveEntryPolicy::from_config(&self.config())
+    }
+
+    /// Check the VPK's signature against `vpk_signing_public_key`, if one is configured.
+    ///
+    /// Only `require_signed_vpk` turns an unsigned VPK or a failed check into an error;
+    /// otherwise failures are logged and the install goes ahead.
+    pub(super) async fn check_vpk_signature(&self, path: &Path) -> anyhow::Result<()> {
+        let config = self.config();
+        let Some(key_path) = &config.vpk_signing_public_key else {
+            return Ok(());
+        };
+        let result = match crate::extractor::vpk_signature::load_public_key(key_path) {
+            Ok(trusted) => self.vpk_extractor.verify_signature(path.to_path_buf(), trusted).await,
+            Err(error) => Err(error),
+        };
+        match result {
+            Ok(VpkSignature::Verified) => {
+                debug!(path = %path.display(), "VPK signature verified");
+                Ok(())
+            }
+            Ok(VpkSignature::Unsigned) if config.require_signed_vpk => {
+                anyhow::bail!("VPK {} is not signed and require_signed_vpk is enabled", path.display())
+            }
+            Ok(VpkSignature::Unsigned) => Ok(()),
+            Err(error) if config.require_signed_vpk => {
+                Err(error.context(format!("VPK signature check failed for {}", path.display())))
+            }
+            Err(error) => {
+                warn!(error = %error, path = %path.display(), "VPK signature check failed; installing anyway");
+                Ok(())
+            }
+        }
+    }
+
+    /// Read VPK metadata, honoring `require_addoninfo`.
+    ///
+    /// In lenient mode a structurally valid VPK whose `addoninfo.txt` is missing or
+    /// unreadable (common for pure content packs) is named after its filename. An
+    /// `addoninfo.txt` without a title also falls back to the filename (the archive
+    /// entry name, or the download name without the downloader's `<uuid>-` prefix).
+    pub(super) async fn read_vpk_metadata(&self, path: &Path) -> anyhow::Result<VpkMetadata> {
+        let error = match self.vpk_extractor.extract_vpk_metadata(path.to_path_buf()).await {
+            Ok(mut metadata) => {
+                let untitled = metadata.title.trim().is_empty()
+                    || metadata.title.trim().eq_ignore_ascii_case("unknown");
+                if untitled && let Some(title) = Self::filename_vpk_title(path) {
+                    metadata.title = title.to_string();
+                }
+                return Ok(metadata);
+            }
+            Err(error) => error,
+        };
+        if self.config().require_addoninfo {
+            return Err(error);
+        }
+        self.vpk_extractor
+            .validate_structure(path.to_path_buf())
+            .await
+            .with_context(|| format!("Invalid VPK structure in {}", path.display()))?;
+
+        let Some(title) = Self::filename_vpk_title(path) else {
+            return Err(error);
+        };
+        warn!(path = %path.display(), error = %error, title, "VPK has no usable addoninfo.txt, using filename");
+        Ok(VpkMetadata {
+            title: title.to_string(),
+            version: "Unknown".to_string(),
+            workshop_id: None,
+            content_flags: Vec::new(),
+        })
+    }
     
+    /// Map name from a VPK filename, without the downloader's `<uuid>-` prefix or `_dir` suffix.
+    fn filename_vpk_title(path: &Path) -> Option<&str> {
+        let stem = path.file_stem()?.to_str()?;
+        let stem = match stem.split_at_checked(36) {
+            Some((prefix, rest)) if uuid::Uuid::parse_str(prefix).is_ok() => {
+                rest.strip_prefix('-').unwrap_or(rest)
+            }
+            _ => stem,
+        };
+        let title = stem.strip_suffix("_dir").unwrap_or(stem);
+        (!title.is_empty()).then_some(title)
+    }
+
     /// Install a VPK file
     async fn install_vpk_file(
         &self,
         vpk_path: PathBuf,
-        source_kind: SourceKind,
-        workshop_id: Option<u64>,
-        provided_name: Option<String>,
-        source_url: Option<String>,
-        expected_installed_filename: Option<String>,
+        target: InstallTarget,
     ) -> anyhow::Result<MapEntry> {
+        let InstallTarget {
+            source_kind,
+            workshop_id,
+            provided_name,
+            source_url,
+            expected_installed_filename,
+            force,
+            expected_checksum,
+            source_validators,
+        } = target;
         info!(path = %vpk_path.display(), "Installing VPK file");
         
         // Integrity check: verify VPK magic bytes (0x55AA1234 / 0x34 0x12 0xAA 0x55)
         // before doing anything else with the file.
-        crate::utils::disk_space::validate_vpk_magic(&vpk_path).await
-            .with_context(|| format!("VPK integrity check failed for {}", vpk_path.display()))?;
+        if let Err(e) = crate::utils::disk_space::validate_vpk_magic(&vpk_path)
+            .await
+            .with_context(|| format!("VPK integrity check failed for {}", vpk_path.display()))
+        {
+            self.reject_download(&vpk_path, &format!("{e:#}")).await;
+            return Err(e);
+        }
+
+        if let Some(expected) = &expected_checksum
+            && let Err(e) = expected.verify(&vpk_path).await
+        {
+            warn!(error = %e, path = %vpk_path.display(), "Rejecting VPK with unexpected checksum");
+            self.reject_download(&vpk_path, &format!("{e:#}")).await;
+            return Err(e);
+        }
+        if let Err(e) = self.check_vpk_signature(&vpk_path).await {
+            warn!(error = %e, path = %vpk_path.display(), "Rejecting VPK that failed signature verification");
+            self.reject_download(&vpk_path, &format!("{e:#}")).await;
+            return Err(e);
+        }
         
         // Extract metadata to get name and version
-        let metadata = self.vpk_extractor.extract_vpk_metadata(vpk_path.clone()).await?;
+        let metadata = match self.read_vpk_metadata(&vpk_path).await {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                self.reject_download(&vpk_path, &format!("{e:#}")).await;
+                return Err(e);
+            }
+        };
 
         let preferred_stem =
             Self::preferred_vpk_stem(provided_name.as_deref(), &metadata.title);
@@ -403,7 +1236,11 @@ impl MapInstallationService {
 
         let resolved_workshop_id = match source_kind {
             SourceKind::Workshop => workshop_id,
-            SourceKind::SirPlease | SourceKind::L4d2Center | SourceKind::Other => None,
+            SourceKind::SirPlease
+            | SourceKind::L4d2Center
+            | SourceKind::Url
+            | SourceKind::Detected
+            | SourceKind::Other => None,
         };
 
         if let Some(wid) = resolved_workshop_id
@@ -414,17 +1251,25 @@ impl MapInstallationService {
                 return Ok(existing);
             }
 
-        if self.find_map_by_name(&map_name).await?.is_some() {
-            if let Err(e) = tokio::fs::remove_file(&vpk_path).await {
-                warn!(error = %e, path = %vpk_path.display(), "Failed to clean up downloaded file after name collision");
+        let replaced = match self.find_map_by_name(&map_name).await? {
+            Some(existing) if force => Some(existing),
+            Some(_) => {
+                if let Err(e) = tokio::fs::remove_file(&vpk_path).await {
+                    warn!(error = %e, path = %vpk_path.display(), "Failed to clean up downloaded file after name collision");
+                }
+                return Err(anyhow::anyhow!(
+                    "Map with name '{}' already installed",
+                    map_name
+                ));
             }
-            return Err(anyhow::anyhow!(
-                "Map with name '{}' already installed",
-                map_name
-            ));
-        }
+            None => None,
+        };
 
-        if let Some(existing) = self.find_map_by_installed_path(&vpk_filename).await? {
+        if let Some(existing) = self
+            .find_map_by_installed_path(&vpk_filename)
+            .await?
+            .filter(|existing| replaced.as_ref().is_none_or(|r| r.id != existing.id))
+        {
             let same_workshop = match (resolved_workshop_id, existing.workshop_id) {
                 (Some(a), Some(b)) => a == b,
                 _ => false,
@@ -462,17 +1307,33 @@ impl MapInstallationService {
             vpk_filename = fallback;
         }
 
+        let (vpk_filename, reserved_id) = self
+            .templated_vpk_filename(
+                vpk_filename,
+                &map_name,
+                expected_installed_filename.is_some(),
+                replaced.as_ref(),
+            )
+            .await?;
         let install_path = self.addons_dir.join(&vpk_filename);
+        crate::utils::ensure_no_symlinks_under(&self.addons_dir, &install_path)?;
 
-        // Atomic install into addons directory
-        crate::utils::atomic_replace_file(&vpk_path, &install_path)
+        crate::utils::check_space_for_copy(&vpk_path, &self.addons_dir)
             .await
-            .context("Failed to install VPK file into addons directory")?;
+            .context("Disk space check failed before installing into addons directory")?;
+
+        // Atomic install into addons directory; from here on a timeout lets it finish.
+        enter_commit()?;
+        let instance_paths = self.copy_to_instances(&vpk_path, &install_path).await?;
+        if let Err(error) = self.place_in_addons(&vpk_path, &install_path).await {
+            self.remove_instance_copies(&instance_paths).await;
+            return Err(error).context("Failed to install VPK file into addons directory");
+        }
         info!(source = %vpk_path.display(), dest = %install_path.display(), "Installed VPK file");
 
-        // Calculate MD5 checksum
-        let checksum = crate::utils::calculate_file_md5(&install_path).await.ok();
-        let checksum_kind = checksum.as_ref().map(|_| "md5".to_string());
+        let (checksum, checksum_kind) =
+            self.checksum_installed(&install_path, source_kind).await.ok().unzip();
+        let size_bytes = Self::installed_size(&install_path).await;
 
         // Determine source URL
         let source_url = match source_kind {
@@ -493,14 +1354,33 @@ impl MapInstallationService {
             version: Some(metadata.version),
             checksum,
             checksum_kind,
+            size_bytes,
+            author: None,
+            content_flags: metadata.content_flags,
+            updated_at: None,
+            tags: Vec::new(),
+            source_last_modified: source_validators.last_modified,
+            source_etag: source_validators.etag,
+            instance_paths,
         };
 
+        if let Some(replaced) = &replaced
+            && let Err(e) = self.retire_replaced_map(replaced, &vpk_filename).await
+        {
+            if replaced.installed_path != vpk_filename {
+                let _ = tokio::fs::remove_file(&install_path).await;
+                self.remove_instance_copies(&map_entry.instance_paths).await;
+            }
+            return Err(e);
+        }
+
         // Register in database and get assigned ID
-        let assigned_id = match self.registry.add_map(map_entry.clone()).await {
+        let assigned_id = match self.add_to_registry(map_entry.clone(), reserved_id).await {
             Ok(id) => id,
             Err(e) => {
                 // Clean up installed file on error
                 let _ = tokio::fs::remove_file(&install_path).await;
+                self.remove_instance_copies(&map_entry.instance_paths).await;
                 return Err(e);
             }
         };
@@ -522,18 +1402,10 @@ impl MapInstallationService {
     async fn install_zip_file(
         &self,
         zip_path: PathBuf,
-        source_kind: SourceKind,
-        workshop_id: Option<u64>,
-        provided_name: Option<String>,
-        source_url: Option<String>,
-        expected_installed_filename: Option<String>,
+        target: InstallTarget,
     ) -> anyhow::Result<MapEntry> {
         info!(path = %zip_path.display(), "Installing ZIP file");
 
-        if !self.zip_contains_vpk(&zip_path).await? {
-            return Err(anyhow::anyhow!("ZIP file does not contain any .vpk files"));
-        }
-
         let extract_temp = self.temp_dir.join(format!(
             "extract-{}",
             std::time::SystemTime::now()
@@ -542,17 +1414,19 @@ impl MapInstallationService {
                 .as_nanos()
         ));
         tokio::fs::create_dir_all(&extract_temp).await?;
+        let _extract_guard = crate::utils::TempPathGuard::new(extract_temp.clone());
 
         if let Err(error) = self
             .zip_extractor
-            .extract_zip(zip_path.clone(), extract_temp.clone())
+            .extract_zip_requiring_vpk(zip_path.clone(), extract_temp.clone(), self.archive_entry_policy())
             .await
         {
             let _ = tokio::fs::remove_dir_all(&extract_temp).await;
+            self.reject_download(&zip_path, &format!("{error:#}")).await;
             return Err(error);
         }
 
-        let fallback_source_url = source_url.clone().unwrap_or_else(|| {
+        let fallback_source_url = target.source_url.clone().unwrap_or_else(|| {
             zip_path
                 .file_name()
                 .and_then(|n| n.to_str())
@@ -563,11 +1437,10 @@ impl MapInstallationService {
         self.install_vpk_from_extracted_dir(
             extract_temp,
             zip_path,
-            source_kind,
-            workshop_id,
-            provided_name,
-            Some(fallback_source_url),
-            expected_installed_filename,
+            InstallTarget {
+                source_url: Some(fallback_source_url),
+                ..target
+            },
         )
         .await
     }
@@ -576,15 +1449,15 @@ impl MapInstallationService {
     async fn install_sevenz_file(
         &self,
         archive_path: PathBuf,
-        source_kind: SourceKind,
-        workshop_id: Option<u64>,
-        provided_name: Option<String>,
-        source_url: Option<String>,
-        expected_installed_filename: Option<String>,
+        target: InstallTarget,
     ) -> anyhow::Result<MapEntry> {
         info!(path = %archive_path.display(), "Installing 7z file");
 
-        if !self.sevenz_extractor.sevenz_contains_vpk(&archive_path).await? {
+        if !self
+            .sevenz_extractor
+            .sevenz_contains_vpk(&archive_path, self.archive_entry_policy())
+            .await?
+        {
             return Err(anyhow::anyhow!("7z file does not contain any .vpk files"));
         }
 
@@ -596,6 +1469,7 @@ impl MapInstallationService {
                 .as_nanos()
         ));
         tokio::fs::create_dir_all(&extract_temp).await?;
+        let _extract_guard = crate::utils::TempPathGuard::new(extract_temp.clone());
 
         if let Err(error) = self
             .sevenz_extractor
@@ -606,7 +1480,7 @@ impl MapInstallationService {
             return Err(error);
         }
 
-        let fallback_source_url = source_url.clone().unwrap_or_else(|| {
+        let fallback_source_url = target.source_url.clone().unwrap_or_else(|| {
             archive_path
                 .file_name()
                 .and_then(|n| n.to_str())
@@ -617,11 +1491,10 @@ impl MapInstallationService {
         self.install_vpk_from_extracted_dir(
             extract_temp,
             archive_path,
-            source_kind,
-            workshop_id,
-            provided_name,
-            Some(fallback_source_url),
-            expected_installed_filename,
+            InstallTarget {
+                source_url: Some(fallback_source_url),
+                ..target
+            },
         )
         .await
     }
@@ -630,24 +1503,49 @@ impl MapInstallationService {
         &self,
         extract_temp: PathBuf,
         archive_path: PathBuf,
-        source_kind: SourceKind,
-        workshop_id: Option<u64>,
-        provided_name: Option<String>,
-        source_url: Option<String>,
-        expected_installed_filename: Option<String>,
+        target: InstallTarget,
     ) -> anyhow::Result<MapEntry> {
+        let InstallTarget {
+            source_kind,
+            workshop_id,
+            provided_name,
+            source_url,
+            expected_installed_filename,
+            force,
+            expected_checksum,
+            source_validators,
+        } = target;
         let vpk_files = self.find_vpk_files_in_extracted(extract_temp.clone()).await?;
         if vpk_files.is_empty() {
             let _ = tokio::fs::remove_dir_all(&extract_temp).await;
-            let _ = tokio::fs::remove_file(&archive_path).await;
-            return Err(anyhow::anyhow!("No .vpk files found in extracted archive"));
+            let error = anyhow::anyhow!("No .vpk files found in extracted archive");
+            self.reject_download(&archive_path, &error.to_string()).await;
+            return Err(error);
         }
 
         let source_vpk_path = vpk_files[0].clone();
-        let metadata = self
-            .vpk_extractor
-            .extract_vpk_metadata(source_vpk_path.clone())
-            .await?;
+        if let Some(expected) = &expected_checksum
+            && let Err(e) = expected.verify(&source_vpk_path).await
+        {
+            warn!(error = %e, path = %archive_path.display(), "Rejecting archive with unexpected checksum");
+            let _ = tokio::fs::remove_dir_all(&extract_temp).await;
+            self.reject_download(&archive_path, &format!("{e:#}")).await;
+            return Err(e);
+        }
+        if let Err(e) = self.check_vpk_signature(&source_vpk_path).await {
+            warn!(error = %e, path = %archive_path.display(), "Rejecting archive whose VPK failed signature verification");
+            let _ = tokio::fs::remove_dir_all(&extract_temp).await;
+            self.reject_download(&archive_path, &format!("{e:#}")).await;
+            return Err(e);
+        }
+        let metadata = match self.read_vpk_metadata(&source_vpk_path).await {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                let _ = tokio::fs::remove_dir_all(&extract_temp).await;
+                self.reject_download(&archive_path, &format!("{e:#}")).await;
+                return Err(e);
+            }
+        };
 
         let preferred_stem =
             Self::preferred_vpk_stem(provided_name.as_deref(), &metadata.title);
@@ -668,7 +1566,11 @@ impl MapInstallationService {
 
         let resolved_workshop_id = match source_kind {
             SourceKind::Workshop => workshop_id,
-            SourceKind::SirPlease | SourceKind::L4d2Center | SourceKind::Other => None,
+            SourceKind::SirPlease
+            | SourceKind::L4d2Center
+            | SourceKind::Url
+            | SourceKind::Detected
+            | SourceKind::Other => None,
         };
 
         if let Some(wid) = resolved_workshop_id
@@ -679,19 +1581,27 @@ impl MapInstallationService {
             return Ok(existing);
         }
 
-        if self.find_map_by_name(&map_name).await?.is_some() {
-            if let Err(e) = tokio::fs::remove_dir_all(&extract_temp).await {
-                warn!(error = %e, path = %extract_temp.display(), "Failed to clean up extract temp after name collision");
-            }
-            if let Err(e) = tokio::fs::remove_file(&archive_path).await {
-                warn!(error = %e, path = %archive_path.display(), "Failed to clean up archive after name collision");
+        let replaced = match self.find_map_by_name(&map_name).await? {
+            Some(existing) if force => Some(existing),
+            Some(_) => {
+                if let Err(e) = tokio::fs::remove_dir_all(&extract_temp).await {
+                    warn!(error = %e, path = %extract_temp.display(), "Failed to clean up extract temp after name collision");
+                }
+                if let Err(e) = tokio::fs::remove_file(&archive_path).await {
+                    warn!(error = %e, path = %archive_path.display(), "Failed to clean up archive after name collision");
+                }
+                return Err(anyhow::anyhow!(
+                    "Map with name '{map_name}' already installed"
+                ));
             }
-            return Err(anyhow::anyhow!(
-                "Map with name '{map_name}' already installed"
-            ));
-        }
+            None => None,
+        };
 
-        if let Some(existing) = self.find_map_by_installed_path(&vpk_filename).await? {
+        if let Some(existing) = self
+            .find_map_by_installed_path(&vpk_filename)
+            .await?
+            .filter(|existing| replaced.as_ref().is_none_or(|r| r.id != existing.id))
+        {
             let same_workshop = match (resolved_workshop_id, existing.workshop_id) {
                 (Some(a), Some(b)) => a == b,
                 _ => false,
@@ -725,19 +1635,35 @@ impl MapInstallationService {
             vpk_filename = fallback;
         }
 
+        let (vpk_filename, reserved_id) = self
+            .templated_vpk_filename(
+                vpk_filename,
+                &map_name,
+                expected_installed_filename.is_some(),
+                replaced.as_ref(),
+            )
+            .await?;
         let install_path = self.addons_dir.join(&vpk_filename);
+        crate::utils::ensure_no_symlinks_under(&self.addons_dir, &install_path)?;
 
-        crate::utils::atomic_replace_file(&source_vpk_path, &install_path)
+        crate::utils::check_space_for_copy(&source_vpk_path, &self.addons_dir)
             .await
-            .context("Failed to install VPK file into addons directory")?;
+            .context("Disk space check failed before installing into addons directory")?;
+        enter_commit()?;
+        let instance_paths = self.copy_to_instances(&source_vpk_path, &install_path).await?;
+        if let Err(error) = self.place_in_addons(&source_vpk_path, &install_path).await {
+            self.remove_instance_copies(&instance_paths).await;
+            return Err(error).context("Failed to install VPK file into addons directory");
+        }
         info!(
             source = %source_vpk_path.display(),
             dest = %install_path.display(),
             "Installed VPK file from archive"
         );
 
-        let checksum = crate::utils::calculate_file_md5(&install_path).await.ok();
-        let checksum_kind = checksum.as_ref().map(|_| "md5".to_string());
+        let (checksum, checksum_kind) =
+            self.checksum_installed(&install_path, source_kind).await.ok().unzip();
+        let size_bytes = Self::installed_size(&install_path).await;
         let source_url = source_url.unwrap_or_else(|| {
             archive_path
                 .file_name()
@@ -758,12 +1684,31 @@ impl MapInstallationService {
             version: Some(metadata.version),
             checksum,
             checksum_kind,
+            size_bytes,
+            author: None,
+            content_flags: metadata.content_flags,
+            updated_at: None,
+            tags: Vec::new(),
+            source_last_modified: source_validators.last_modified,
+            source_etag: source_validators.etag,
+            instance_paths,
         };
 
-        let assigned_id = match self.registry.add_map(map_entry.clone()).await {
+        if let Some(replaced) = &replaced
+            && let Err(e) = self.retire_replaced_map(replaced, &vpk_filename).await
+        {
+            if replaced.installed_path != vpk_filename {
+                let _ = tokio::fs::remove_file(&install_path).await;
+                self.remove_instance_copies(&map_entry.instance_paths).await;
+            }
+            return Err(e);
+        }
+
+        let assigned_id = match self.add_to_registry(map_entry.clone(), reserved_id).await {
             Ok(id) => id,
             Err(error) => {
                 let _ = tokio::fs::remove_file(&install_path).await;
+                self.remove_instance_copies(&map_entry.instance_paths).await;
                 return Err(error);
             }
         };
@@ -777,6 +1722,87 @@ impl MapInstallationService {
         Ok(map_entry)
     }
 
+    /// Drop a force-replaced map once its successor's file is in place.
+    async fn retire_replaced_map(
+        &self,
+        replaced: &MapEntry,
+        new_installed_path: &str,
+    ) -> anyhow::Result<()> {
+        self.registry.remove_map(replaced.id).await?;
+        self.pending_updates.remove_map_ids(&[replaced.id]);
+        self.active_updates.clear(replaced.id);
+
+        if replaced.installed_path != new_installed_path {
+            let old_path = self.addons_dir.join(&replaced.installed_path);
+            crate::utils::validate_path_within_base_new(&old_path, &self.addons_dir)
+                .context("Replaced map path escapes addons directory")?;
+            match tokio::fs::remove_file(&old_path).await {
+                Ok(()) => info!(path = %old_path.display(), "Removed replaced map file"),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => {
+                    warn!(error = %e, path = %old_path.display(), "Failed to remove replaced map file");
+                }
+            }
+            self.remove_instance_copies(&replaced.instance_paths).await;
+        }
+
+        info!(map_id = replaced.id, name = %replaced.name, "Replaced existing map (force reinstall)");
+        Ok(())
+    }
+
+    /// Apply `install_filename_template` to the filename picked for a new install.
+    ///
+    /// Installs pinned to an expected filename keep it. For `{map_id}` the registry
+    /// reserves the ID the entry is then added under (see [`Self::add_to_registry`]),
+    /// returned alongside the filename.
+    async fn templated_vpk_filename(
+        &self,
+        vpk_filename: String,
+        map_name: &str,
+        pinned: bool,
+        replaced: Option<&MapEntry>,
+    ) -> anyhow::Result<(String, Option<u64>)> {
+        let config = self.config();
+        let Some(template) = config
+            .install_filename_template
+            .as_deref()
+            .filter(|template| !template.trim().is_empty() && !pinned)
+        else {
+            return Ok((vpk_filename, None));
+        };
+        let is_replaced = |id: u64| replaced.is_some_and(|r| r.id == id);
+        let reserved_id = if template.contains("{map_id}") {
+            Some(self.registry.reserve_map_id().await?)
+        } else {
+            None
+        };
+        let templated = crate::utils::render_install_filename(
+            template,
+            reserved_id.unwrap_or_default(),
+            &vpk_filename,
+            map_name,
+        )?;
+        if let Some(existing) = self
+            .find_map_by_installed_path(&templated)
+            .await?
+            .filter(|existing| !is_replaced(existing.id))
+        {
+            anyhow::bail!(
+                "Installed filename '{templated}' from install_filename_template is already used by map #{}",
+                existing.id
+            );
+        }
+        Ok((templated, reserved_id))
+    }
+
+    /// Add `entry` to the registry, under `reserved_id` when one was reserved for it.
+    async fn add_to_registry(&self, entry: MapEntry, reserved_id: Option<u64>) -> anyhow::Result<u64> {
+        match reserved_id {
+            Some(id) => self.registry.add_map_with_id(MapEntry { id, ..entry }).await,
+            None => self.registry.add_map(entry).await,
+        }
+    }
+
     fn preferred_vpk_stem(provided_name: Option<&str>, metadata_title: &str) -> Option<String> {
         let raw = provided_name
             .map(str::trim)
@@ -824,59 +1850,50 @@ impl MapInstallationService {
         }
     }
     
-    /// Check if ZIP file contains at least one .vpk file
-    pub(super) async fn zip_contains_vpk(&self, zip_path: &Path) -> anyhow::Result<bool> {
-        let zip_path = zip_path.to_path_buf();
-        
-        tokio::task::spawn_blocking(move || {
-            use std::fs::File;
-            use std::io::BufReader;
-            use zip::ZipArchive;
-            
-            let file = File::open(&zip_path)?;
-            let mut archive = ZipArchive::new(BufReader::new(file))?;
-            
-            for i in 0..archive.len() {
-                let file = archive.by_index(i)?;
-                let name = file.name();
-                if name.to_lowercase().ends_with(".vpk") {
-                    return Ok(true);
-                }
-            }
-            
-            Ok(false)
-        })
-        .await?
-    }
-    
-    /// Find all .vpk files in extracted directory (recursive)
-    pub(super) fn find_vpk_files_in_extracted(
+    /// Find all .vpk files in extracted directory (recursive).
+    ///
+    /// Symlinks are never followed, and directories are tracked by canonical path so
+    /// a cyclic tree fails with an error instead of looping forever.
+    pub(super) async fn find_vpk_files_in_extracted(
         &self,
         dir: PathBuf,
-    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = anyhow::Result<Vec<PathBuf>>> + Send + '_>>
-    {
-        Box::pin(async move {
-            let mut vpk_files = Vec::new();
+    ) -> anyhow::Result<Vec<PathBuf>> {
+        let mut vpk_files = Vec::new();
+        let mut visited = std::collections::HashSet::new();
+        let mut pending = vec![dir];
+
+        while let Some(dir) = pending.pop() {
+            let canonical = tokio::fs::canonicalize(&dir)
+                .await
+                .with_context(|| format!("Failed to resolve {}", dir.display()))?;
+            if !visited.insert(canonical) {
+                anyhow::bail!("Directory cycle detected at {}", dir.display());
+            }
+
             let mut entries = tokio::fs::read_dir(&dir).await?;
-            
             while let Some(entry) = entries.next_entry().await? {
                 let path = entry.path();
                 let file_type = entry.file_type().await?;
-                
-                if file_type.is_file() {
+
+                if file_type.is_symlink() {
+                    match tokio::fs::canonicalize(&path).await {
+                        Ok(target) if visited.contains(&target) => {
+                            anyhow::bail!("Symlink loop detected at {}", path.display());
+                        }
+                        _ => warn!(path = %path.display(), "Skipping symlink during VPK scan"),
+                    }
+                } else if file_type.is_file() {
                     if let Some(ext) = path.extension().and_then(|e| e.to_str())
                         && ext.eq_ignore_ascii_case("vpk") {
                             vpk_files.push(path);
                         }
                 } else if file_type.is_dir() {
-                    // Recursively search subdirectories
-                    let mut sub_files = self.find_vpk_files_in_extracted(path).await?;
-                    vpk_files.append(&mut sub_files);
+                    pending.push(path);
                 }
             }
-            
-            Ok(vpk_files)
-        })
+        }
+
+        Ok(vpk_files)
     }
     
     /// Detect map name from extracted ZIP contents
@@ -972,6 +1989,7 @@ impl MapInstallationService {
     
     /// Uninstall a map
     pub async fn uninstall_map(&self, map_id: u64) -> anyhow::Result<()> {
+        self.ensure_writable()?;
         let _guard = self.op_lock.lock().await;
 
         info!(map_id = map_id, "Uninstalling map");
@@ -1006,15 +2024,150 @@ impl MapInstallationService {
             })?;
             info!(path = %installed_path_abs.display(), "Removed map files");
         }
+        self.remove_instance_copies(&map_entry.instance_paths).await;
 
         self.registry.remove_map(map_id).await?;
         self.pending_updates.remove_map_ids(&[map_id]);
         self.active_updates.clear(map_id);
+        self.audit
+            .record(AuditAction::Uninstall, map_id, Some(map_entry.source_url.clone()))
+            .await;
+        self.notify_webhook(AuditAction::Uninstall, &map_entry);
+        self.events.publish(MapEvent::Uninstalled {
+            map_id,
+            name: map_entry.name.clone(),
+        });
+        self.spawn_post_hook(self.config().post_uninstall_command.clone(), "post_uninstall", &map_entry);
 
         info!(map_id = map_id, "Map uninstalled successfully");
         Ok(())
     }
 
+    /// Delete one part file (`<name>_NNN.vpk`) of a split map while keeping its entry.
+    ///
+    /// `filename` must name one of the parts next to the map's `<name>_dir.vpk`; the
+    /// `_dir.vpk` itself is only removed by `uninstall_map`. The entry's `size_bytes`
+    /// is recomputed from what is left.
+    pub async fn remove_map_file(&self, map_id: u64, filename: &str) -> anyhow::Result<MapEntry> {
+        self.ensure_writable()?;
+        let _guard = self.op_lock.lock().await;
+
+        let Some(mut map_entry) = self.registry.get_map(map_id).await? else {
+            return Err(anyhow::anyhow!("Map #{map_id} not found"));
+        };
+        let installed_path_abs = self.addons_dir.join(&map_entry.installed_path);
+        crate::utils::validate_path_within_base_new(&installed_path_abs, &self.addons_dir)
+            .context("Attempted to remove a map file outside of addons directory")?;
+
+        if installed_path_abs.file_name().is_some_and(|name| name == filename) {
+            return Err(anyhow::anyhow!(
+                "{filename} is the main file of map #{map_id}; uninstall the map instead"
+            ));
+        }
+        let Some(part) = Self::split_archive_parts(&installed_path_abs)
+            .await
+            .into_iter()
+            .find(|part| part.file_name().is_some_and(|name| name == filename))
+        else {
+            return Err(anyhow::anyhow!("{filename} is not a file of map #{map_id}"));
+        };
+        crate::utils::validate_path_within_base_new(&part, &self.addons_dir)
+            .context("Attempted to remove a map file outside of addons directory")?;
+        crate::utils::ensure_no_symlinks_under(&self.addons_dir, &part)?;
+
+        tokio::fs::remove_file(&part)
+            .await
+            .with_context(|| format!("Failed to delete map file {}", part.display()))?;
+        info!(map_id, path = %part.display(), "Removed map file");
+
+        map_entry.size_bytes = Self::installed_size(&installed_path_abs).await;
+        self.registry.update_map(map_entry.clone()).await?;
+        Ok(map_entry)
+    }
+
+    /// Delete the VPK at `relative_path` under addons whatever the registry says,
+    /// along with any registry entries pointing at it.
+    ///
+    /// Reaches orphaned files `uninstall_map` cannot, as it needs an entry. Fails
+    /// with "not found" when there is neither a file nor an entry.
+    pub async fn force_uninstall_by_path(
+        &self,
+        relative_path: &str,
+    ) -> anyhow::Result<ForceUninstallReport> {
+        self.ensure_writable()?;
+        let relative = Path::new(relative_path);
+        let plain_relative = !relative_path.is_empty()
+            && relative
+                .components()
+                .all(|component| matches!(component, std::path::Component::Normal(_)));
+        if !plain_relative {
+            anyhow::bail!("Invalid path '{relative_path}': expected a path relative to addons");
+        }
+        if !relative
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("vpk"))
+        {
+            anyhow::bail!("Invalid path '{relative_path}': only .vpk files can be removed");
+        }
+
+        let _guard = self.op_lock.lock().await;
+
+        let path = self.addons_dir.join(relative);
+        crate::utils::validate_path_within_base_new(&path, &self.addons_dir)
+            .with_context(|| format!("Invalid path '{relative_path}'"))?;
+        crate::utils::ensure_no_symlinks_under(&self.addons_dir, &path)
+            .with_context(|| format!("Invalid path '{relative_path}'"))?;
+
+        let entries: Vec<MapEntry> = self
+            .registry
+            .list_maps()
+            .await?
+            .into_iter()
+            .filter(|entry| Path::new(&entry.installed_path) == relative)
+            .collect();
+
+        let file_removed = match tokio::fs::symlink_metadata(&path).await {
+            Ok(metadata) if metadata.is_file() => {
+                tokio::fs::remove_file(&path)
+                    .await
+                    .with_context(|| format!("Failed to delete {}", path.display()))?;
+                info!(path = %path.display(), "Force-removed map file");
+                true
+            }
+            Ok(_) => anyhow::bail!("Invalid path '{relative_path}': not a regular file"),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => false,
+            Err(error) => return Err(error).context("Failed to inspect map file"),
+        };
+        if !file_removed && entries.is_empty() {
+            anyhow::bail!("File {relative_path} not found in addons or registry");
+        }
+
+        let mut removed_map_ids = Vec::with_capacity(entries.len());
+        for entry in entries {
+            self.remove_instance_copies(&entry.instance_paths).await;
+            self.registry.remove_map(entry.id).await?;
+            self.pending_updates.remove_map_ids(&[entry.id]);
+            self.active_updates.clear(entry.id);
+            self.audit
+                .record(AuditAction::Uninstall, entry.id, Some(entry.source_url.clone()))
+                .await;
+            self.notify_webhook(AuditAction::Uninstall, &entry);
+            self.events.publish(MapEvent::Uninstalled {
+                map_id: entry.id,
+                name: entry.name.clone(),
+            });
+            self.spawn_post_hook(self.config().post_uninstall_command.clone(), "post_uninstall", &entry);
+            removed_map_ids.push(entry.id);
+        }
+
+        info!(path = relative_path, file_removed, removed = removed_map_ids.len(), "Force uninstall complete");
+        Ok(ForceUninstallReport {
+            path: relative_path.to_string(),
+            file_removed,
+            removed_map_ids,
+        })
+    }
+
     pub(super) async fn find_map_by_installed_path(
         &self,
         relative_path: &str,
@@ -1050,15 +2203,99 @@ impl MapInstallationService {
             return Ok(Some(existing));
         }
 
-        let Some(mut map_entry) = self.build_map_entry_from_file(path, relative_path).await? else {
+        let checksum_kind = self.checksum_kind_for(SourceKind::Detected);
+        let Some(mut map_entry) = self
+            .build_map_entry_from_file(path, relative_path, &checksum_kind)
+            .await?
+        else {
             return Ok(None);
         };
 
         let assigned_id = self.registry.add_map(map_entry.clone()).await?;
         map_entry.id = assigned_id;
+        self.audit
+            .record(AuditAction::Install, assigned_id, Some(map_entry.installed_path.clone()))
+            .await;
+        self.notify_webhook(AuditAction::Install, &map_entry);
         Ok(Some(map_entry))
     }
 
+    /// Checksum algorithm recorded for a new entry from `source_kind`.
+    ///
+    /// L4D2Center entries stay MD5 because update checks compare them against
+    /// the MD5 the catalog publishes.
+    pub(super) fn checksum_kind_for(&self, source_kind: SourceKind) -> String {
+        match source_kind {
+            SourceKind::L4d2Center => "md5".to_string(),
+            _ => self.config().default_checksum_kind.to_ascii_lowercase(),
+        }
+    }
+
+    /// Algorithm to re-hash `entry`'s file with so the result is comparable to its
+    /// stored checksum; entries without a checksum get the one for their source.
+    pub(super) fn checksum_kind_of(&self, entry: &MapEntry) -> String {
+        match (&entry.checksum, &entry.checksum_kind) {
+            (Some(_), Some(kind)) => kind.to_ascii_lowercase(),
+            (Some(_), None) => "md5".to_string(),
+            (None, _) => self.checksum_kind_for(entry.source_kind),
+        }
+    }
+
+    /// Hash an installed file for a new entry from `source_kind`, returning `(checksum, kind)`.
+    pub(super) async fn checksum_installed(
+        &self,
+        path: &Path,
+        source_kind: SourceKind,
+    ) -> anyhow::Result<(String, String)> {
+        let kind = self.checksum_kind_for(source_kind);
+        let checksum = crate::utils::calculate_file_checksum(path, &kind).await?;
+        Ok((checksum, kind))
+    }
+
+    /// Bytes `path` occupies, plus the `<name>_NNN.vpk` parts of a split `<name>_dir.vpk`.
+    pub(super) async fn installed_size(path: &Path) -> Option<u64> {
+        let mut total = tokio::fs::metadata(path).await.ok()?.len();
+        for part in Self::split_archive_parts(path).await {
+            if let Ok(metadata) = tokio::fs::metadata(&part).await {
+                total += metadata.len();
+            }
+        }
+        Some(total)
+    }
+
+    /// The `<name>_000.vpk`, `<name>_001.vpk`, … parts next to a split `<name>_dir.vpk`,
+    /// in index order and including any left after a gap; empty for any other file.
+    async fn split_archive_parts(path: &Path) -> Vec<PathBuf> {
+        let Some(base) = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.strip_suffix("_dir"))
+        else {
+            return Vec::new();
+        };
+        let Some(parent) = path.parent() else {
+            return Vec::new();
+        };
+        let Ok(mut entries) = tokio::fs::read_dir(parent).await else {
+            return Vec::new();
+        };
+        let mut parts = Vec::new();
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name();
+            let is_part = name
+                .to_str()
+                .and_then(|name| name.strip_prefix(base))
+                .and_then(|rest| rest.strip_prefix('_'))
+                .and_then(|rest| rest.strip_suffix(".vpk"))
+                .is_some_and(|index| index.len() == 3 && index.bytes().all(|b| b.is_ascii_digit()));
+            if is_part && entry.file_type().await.is_ok_and(|kind| kind.is_file()) {
+                parts.push(entry.path());
+            }
+        }
+        parts.sort();
+        parts
+    }
+
     pub(super) async fn file_modified_time(
         path: &Path,
     ) -> Option<chrono::DateTime<chrono::Utc>> {
@@ -1090,9 +2327,29 @@ impl MapInstallationService {
         &self,
         path: &Path,
         relative_path: &str,
+        checksum_kind: &str,
     ) -> anyhow::Result<Option<MapEntry>> {
-        let checksum = crate::utils::calculate_file_md5(path).await.ok();
-        let checksum_kind = checksum.as_ref().map(|_| "md5".to_string());
+        let checksum = match crate::utils::calculate_file_checksum(path, checksum_kind).await {
+            Ok(checksum) => Some(checksum),
+            Err(error) if self.config().detect_require_checksum => {
+                warn!(
+                    path = %path.display(),
+                    error = %error,
+                    "Checksum computation failed, skipping map registration"
+                );
+                return Ok(None);
+            }
+            Err(error) => {
+                warn!(
+                    path = %path.display(),
+                    error = %error,
+                    "Checksum computation failed, registering map without checksum"
+                );
+                None
+            }
+        };
+        let checksum_kind = checksum.as_ref().map(|_| checksum_kind.to_string());
+        let size_bytes = Self::installed_size(path).await;
         let installed_at = Self::file_modified_time(path)
             .await
             .unwrap_or_else(chrono::Utc::now);
@@ -1116,7 +2373,7 @@ impl MapInstallationService {
                     id: 0,
                     name: fallback_name,
                     source_url: format!("detected:{}", path.display()),
-                    source_kind: SourceKind::Other,
+                    source_kind: SourceKind::Detected,
                     workshop_id: None,
                     installed_path: relative_path.to_string(),
                     installed_at,
@@ -1124,6 +2381,14 @@ impl MapInstallationService {
                     version: None,
                     checksum,
                     checksum_kind,
+                    size_bytes,
+                    author: None,
+                    content_flags: Vec::new(),
+                    updated_at: None,
+                    tags: Vec::new(),
+                    source_last_modified: None,
+                    source_etag: None,
+                    instance_paths: Vec::new(),
                 }));
             }
         };
@@ -1135,7 +2400,7 @@ impl MapInstallationService {
                 workshop_source_url(workshop_id),
             ),
             None => (
-                SourceKind::Other,
+                SourceKind::Detected,
                 None,
                 format!("detected:{}", path.display()),
             ),
@@ -1153,6 +2418,14 @@ impl MapInstallationService {
             version: Some(metadata.version),
             checksum,
             checksum_kind,
+            size_bytes,
+            author: None,
+            content_flags: metadata.content_flags,
+            updated_at: None,
+            tags: Vec::new(),
+            source_last_modified: None,
+            source_etag: None,
+            instance_paths: Vec::new(),
         }))
     }
 
@@ -1162,6 +2435,8 @@ impl MapInstallationService {
             fresh.source_kind = existing.source_kind;
             fresh.source_url = existing.source_url.clone();
         }
+        // Tags are user-assigned and never derived from the file.
+        fresh.tags = existing.tags.clone();
     }
 
     /// Check the cached Steam transport and evict it when disconnected.
@@ -1169,6 +2444,83 @@ impl MapInstallationService {
         self.workshop_downloader.health_check().await;
     }
 
+    pub fn steam_breaker_status(&self) -> CircuitBreakerStatus {
+        self.workshop_downloader.steam_breaker_status()
+    }
+
+    /// Remove download temp files and `extract-*` directories last modified more than
+    /// `older_than` ago. Anything younger may belong to an install still running.
+    pub async fn cleanup_temp(&self, older_than: Duration) -> anyhow::Result<CleanupReport> {
+        let mut report = CleanupReport::default();
+        let mut entries = match tokio::fs::read_dir(&self.temp_dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(report),
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Failed to read temp directory {}", self.temp_dir.display())
+                });
+            }
+        };
+
+        let now = std::time::SystemTime::now();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let Ok(metadata) = tokio::fs::symlink_metadata(&path).await else {
+                report.failed += 1;
+                continue;
+            };
+            let age = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .unwrap_or_default();
+            if age < older_than {
+                report.kept += 1;
+                continue;
+            }
+
+            let result = if metadata.is_dir() {
+                tokio::fs::remove_dir_all(&path).await
+            } else {
+                tokio::fs::remove_file(&path).await
+            };
+            match result {
+                Ok(()) => report.removed.push(entry.file_name().to_string_lossy().into_owned()),
+                Err(error) => {
+                    warn!(path = %path.display(), error = %error, "Failed to remove temp artifact");
+                    report.failed += 1;
+                }
+            }
+        }
+
+        if !report.removed.is_empty() {
+            info!(
+                removed = report.removed.len(),
+                kept = report.kept,
+                failed = report.failed,
+                "Cleaned leftover files from download temp directory"
+            );
+        }
+        Ok(report)
+    }
+
+    /// Resolve a current Steam download URL for a workshop item.
+    pub async fn workshop_download_url(&self, workshop_id: u64) -> anyhow::Result<String> {
+        self.workshop_downloader.fresh_download_url(workshop_id).await
+    }
+
+    /// Downloadable items in Workshop collection `collection_id`, nested collections
+    /// included up to `workshop_collection_max_depth` levels.
+    pub async fn workshop_collection_items(
+        &self,
+        collection_id: u64,
+    ) -> anyhow::Result<Vec<WorkshopFileDetails>> {
+        let max_depth = self.config().workshop_collection_max_depth;
+        self.workshop_downloader
+            .expand_collection(collection_id, max_depth)
+            .await
+    }
+
     /// Get reference to registry (for sync task)
     pub fn registry(&self) -> &Arc<dyn Registry> {
         &self.registry
@@ -1176,7 +2528,9 @@ impl MapInstallationService {
 }
 
 
+mod backend_update;
 mod discovery;
+mod instances;
 mod l4d2center;
 mod workshop_update;
 
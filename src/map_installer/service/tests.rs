@@ -57,17 +57,9 @@ async fn setup_test_service() -> (MapInstallationService, Arc<dyn Registry>, tes
         
         // Add a test map entry
         let mut map_entry = MapEntry {
-            id: 0, // Will be assigned by database
-            name: "Test Map".to_string(),
             source_url: "https://example.com/map.zip".to_string(),
-            source_kind: SourceKind::Other,
-            workshop_id: None,
             installed_path: "test_map.vpk".to_string(), // Relative path
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..test_helpers::test_map_entry("Test Map")
         };
         let assigned_id = registry.add_map(map_entry.clone()).await.unwrap();
         map_entry.id = assigned_id;
@@ -97,17 +89,9 @@ async fn setup_test_service() -> (MapInstallationService, Arc<dyn Registry>, tes
         tokio::fs::write(&vpk_path, b"vpk-bytes").await.unwrap();
 
         let map_entry = MapEntry {
-            id: 0,
-            name: "Present".to_string(),
             source_url: "https://example.com/map.zip".to_string(),
-            source_kind: SourceKind::Other,
-            workshop_id: None,
             installed_path: "present.vpk".to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..test_helpers::test_map_entry("Present")
         };
         let assigned_id = registry.add_map(map_entry).await.unwrap();
 
@@ -122,7 +106,9 @@ async fn setup_test_service() -> (MapInstallationService, Arc<dyn Registry>, tes
         let (service, _registry, _dirs) = setup_test_service().await;
         
         // This should fail because numeric strings are not valid URLs
-        let result = service.install_from_url("123456789".to_string(), None).await;
+        let result = service
+            .install_from_url("123456789".to_string(), None, InstallOptions::default())
+            .await;
         assert!(result.is_err());
         
         // Error should indicate URL validation failure
@@ -149,11 +135,16 @@ async fn setup_test_service() -> (MapInstallationService, Arc<dyn Registry>, tes
         let result = service
             .install_downloaded_file(
                 test_zip_path,
-                SourceKind::Other,
-                None,
-                Some("Test Map".to_string()),
-                Some("https://example.com/test_map.zip".to_string()),
-                None,
+                InstallTarget {
+                    source_kind: SourceKind::Other,
+                    workshop_id: None,
+                    provided_name: Some("Test Map".to_string()),
+                    source_url: Some("https://example.com/test_map.zip".to_string()),
+                    expected_installed_filename: None,
+                    force: false,
+                    expected_checksum: None,
+                    source_validators: CacheValidators::default(),
+                },
             )
             .await
             .expect("ZIP install should succeed");
@@ -167,667 +158,3067 @@ async fn setup_test_service() -> (MapInstallationService, Arc<dyn Registry>, tes
     }
 
     #[tokio::test]
-    async fn test_compact_registry_prunes_sorts_and_reindexes() {
+    async fn test_install_zip_rejects_denied_extension_before_extracting() {
         let (service, registry, dirs) = setup_test_service().await;
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.archive_extension_denylist = vec!["sh".to_string()];
+        let service = service.with_config(crate::config::init_handle(config));
 
-        tokio::fs::write(dirs.addons_path().join("alpha.vpk"), b"vpk").await.unwrap();
-        tokio::fs::write(dirs.addons_path().join("zulu.vpk"), b"vpk").await.unwrap();
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk_path = vpk_temp.path().join("scripted.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Scripted").unwrap();
+        let vpk_bytes = std::fs::read(&vpk_path).unwrap();
+        let (zip_path, _zip_temp) = create_test_zip_with_map(&[
+            ("scripted.vpk", &vpk_bytes),
+            ("install.sh", b"#!/bin/sh\nrm -rf ~\n"),
+        ]);
 
-        let now = chrono::Utc::now();
-        registry
-            .replace_all_maps(vec![
-                MapEntry {
-                    id: 5,
-                    name: "Zulu".to_string(),
-                    source_url: "https://example.com/zulu".to_string(),
-                    source_kind: SourceKind::Other,
-                    workshop_id: None,
-                    installed_path: "zulu.vpk".to_string(),
-                    installed_at: now,
-                    workshop_updated_at: None,
-                    version: None,
-                    checksum: None,
-                    checksum_kind: None,
-                },
-                MapEntry {
-                    id: 12,
-                    name: "Alpha".to_string(),
-                    source_url: "https://example.com/alpha".to_string(),
-                    source_kind: SourceKind::Other,
-                    workshop_id: None,
-                    installed_path: "alpha.vpk".to_string(),
-                    installed_at: now,
-                    workshop_updated_at: None,
-                    version: None,
-                    checksum: None,
-                    checksum_kind: None,
-                },
-                MapEntry {
-                    id: 3,
-                    name: "Missing".to_string(),
-                    source_url: "https://example.com/missing".to_string(),
-                    source_kind: SourceKind::Other,
-                    workshop_id: None,
-                    installed_path: "missing.vpk".to_string(),
-                    installed_at: now,
-                    workshop_updated_at: None,
-                    version: None,
-                    checksum: None,
-                    checksum_kind: None,
-                },
-            ])
+        let error = service
+            .install_downloaded_file(zip_path, unlabeled_target("scripted"))
             .await
-            .unwrap();
-
-        let report = service.compact_registry().await.unwrap();
-
-        assert_eq!(report.removed.len(), 1);
-        assert_eq!(report.removed[0].name, "Missing");
-        assert_eq!(report.kept.len(), 2);
-        assert_eq!(report.kept[0].name, "Alpha");
-        assert_eq!(report.kept[0].id, 1);
-        assert_eq!(report.kept[1].name, "Zulu");
-        assert_eq!(report.kept[1].id, 2);
+            .unwrap_err();
 
-        let maps = registry.list_maps().await.unwrap();
-        assert_eq!(maps.len(), 2);
-        assert_eq!(maps[0].id, 1);
-        assert_eq!(maps[0].name, "Alpha");
-        assert_eq!(maps[1].id, 2);
-        assert_eq!(maps[1].name, "Zulu");
+        assert!(format!("{error:#}").contains("install.sh"));
+        assert_install_error_code(error, axum::http::StatusCode::UNPROCESSABLE_ENTITY, "archive_entry_denied").await;
+        assert!(registry.list_maps().await.unwrap().is_empty());
+        assert!(!dirs.addons_path().join("scripted.vpk").exists());
     }
 
-    fn create_modify_test_entry() -> MapEntry {
-        MapEntry {
-            id: 0,
-            name: "Test Map".to_string(),
-            source_url: "https://example.com/map.zip".to_string(),
-            source_kind: SourceKind::Workshop,
-            workshop_id: Some(999),
-            installed_path: "test_map.vpk".to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: Some("1.0".to_string()),
-            checksum: None,
-            checksum_kind: None,
+    fn unlabeled_target(name: &str) -> InstallTarget {
+        InstallTarget {
+            source_kind: SourceKind::Other,
+            workshop_id: None,
+            provided_name: Some(name.to_string()),
+            source_url: Some(format!("https://example.com/download.php?id={name}")),
+            expected_installed_filename: None,
+            force: false,
+            expected_checksum: None,
+            source_validators: CacheValidators::default(),
         }
     }
 
     #[tokio::test]
-    async fn test_modify_map_field_workshop_id_sets_kind_and_url() {
-        let (service, registry, _dirs) = setup_test_service().await;
-        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+    async fn test_install_mislabeled_zip_by_content() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk_path = vpk_temp.path().join("hidden_zip.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Hidden Zip").unwrap();
+        let vpk_bytes = std::fs::read(&vpk_path).unwrap();
+        let (zip_path, zip_temp) = create_test_zip_with_map(&[("hidden_zip.vpk", &vpk_bytes)]);
+        let dat_path = zip_temp.path().join("download.dat");
+        std::fs::rename(&zip_path, &dat_path).unwrap();
 
-        let updated = service
-            .modify_map_field(id, "workshop_id", "3135451698")
+        let entry = service
+            .install_downloaded_file(dat_path, unlabeled_target("hidden_zip"))
             .await
-            .unwrap();
-
-        assert_eq!(updated.workshop_id, Some(3135451698));
-        assert_eq!(updated.source_kind, SourceKind::Workshop);
-        assert_eq!(
-            updated.source_url,
-            "https://steamcommunity.com/sharedfiles/filedetails/?id=3135451698"
-        );
+            .expect("ZIP saved as .dat should install");
 
-        let retrieved = registry.get_map(id).await.unwrap().unwrap();
-        assert_eq!(retrieved.workshop_id, Some(3135451698));
-        assert_eq!(retrieved.source_kind, SourceKind::Workshop);
-        assert_eq!(
-            retrieved.source_url,
-            "https://steamcommunity.com/sharedfiles/filedetails/?id=3135451698"
-        );
+        assert_eq!(entry.installed_path, "hidden_zip.vpk");
+        assert!(dirs.addons_path().join("hidden_zip.vpk").exists());
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
     }
 
     #[tokio::test]
-    async fn test_modify_map_field_source_kind_other_clears_workshop_id() {
-        let (service, registry, _dirs) = setup_test_service().await;
-        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+    async fn test_install_mislabeled_vpk_by_content() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let temp = TempDir::new().unwrap();
+        let dat_path = temp.path().join("download.dat");
+        test_helpers::write_minimal_test_vpk(&dat_path, "Hidden Vpk").unwrap();
 
-        let updated = service
-            .modify_map_field(id, "source_kind", "other")
+        let entry = service
+            .install_downloaded_file(dat_path, unlabeled_target("hidden_vpk"))
             .await
-            .unwrap();
-
-        assert_eq!(updated.source_kind, SourceKind::Other);
-        assert_eq!(updated.workshop_id, None);
+            .expect("VPK saved as .dat should install");
 
-        let retrieved = registry.get_map(id).await.unwrap().unwrap();
-        assert_eq!(retrieved.source_kind, SourceKind::Other);
-        assert_eq!(retrieved.workshop_id, None);
+        assert_eq!(entry.installed_path, "hidden_vpk.vpk");
+        assert!(dirs.addons_path().join("hidden_vpk.vpk").exists());
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
     }
 
     #[tokio::test]
-    async fn test_modify_map_field_unknown_field_errors() {
-        let (service, registry, _dirs) = setup_test_service().await;
-        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+    async fn test_install_gzipped_vpk_and_rejects_unknown_content() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let temp = TempDir::new().unwrap();
+        let vpk_path = temp.path().join("plain.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Gzipped Vpk").unwrap();
+        let gz_path = temp.path().join("download.bin");
+        let mut encoder = flate2::write::GzEncoder::new(
+            std::fs::File::create(&gz_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(&std::fs::read(&vpk_path).unwrap()).unwrap();
+        encoder.finish().unwrap();
 
-        let result = service
-            .modify_map_field(id, "checksum", "abc123")
-            .await;
-        assert!(result.is_err());
-        let err = result.unwrap_err().to_string();
-        assert!(err.contains("Unknown or read-only field"));
+        service
+            .install_downloaded_file(gz_path, unlabeled_target("gzipped_vpk"))
+            .await
+            .expect("gzipped VPK should install");
+        assert!(dirs.addons_path().join("gzipped_vpk.vpk").exists());
+
+        let junk = temp.path().join("junk.dat");
+        std::fs::write(&junk, b"not a map at all").unwrap();
+        let error = service
+            .install_downloaded_file(junk, unlabeled_target("junk"))
+            .await
+            .unwrap_err();
+        assert!(format!("{error:#}").contains("Unsupported file type: dat"), "{error:#}");
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
     }
 
     #[tokio::test]
-    async fn test_modify_installed_path_renames_file_and_updates_registry() {
-        let (service, registry, dirs) = setup_test_service().await;
-        let addons = dirs.addons_path();
-        let old_path = addons.join("test_map.vpk");
-        test_helpers::write_minimal_test_vpk(&old_path, "Test Map").unwrap();
+    async fn test_find_vpk_files_errors_on_symlink_loop() {
+        let (service, _registry, _dirs) = setup_test_service().await;
+        let extract = TempDir::new().unwrap();
+        let nested = extract.path().join("maps").join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        test_helpers::write_minimal_test_vpk(&nested.join("map.vpk"), "Loop Map").unwrap();
+        std::os::unix::fs::symlink(extract.path(), nested.join("loop")).unwrap();
+
+        let result = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            service.find_vpk_files_in_extracted(extract.path().to_path_buf()),
+        )
+        .await
+        .expect("scan must terminate");
 
-        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("loop"), "unexpected error: {err}");
+    }
 
-        let updated = service
-            .modify_map_field(id, "installed_path", "renamed.vpk")
+    #[tokio::test]
+    async fn test_install_from_zip_url_rejects_html_error_page() {
+        let http = crate::downloader::test_lock::acquire_http_test_lock().await;
+        let (mut service, registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir.clone(), 1024 * 1024)
             .await
             .unwrap();
 
-        assert_eq!(updated.installed_path, "renamed.vpk");
-        assert!(!old_path.exists());
-        assert!(addons.join("renamed.vpk").exists());
+        let err = service
+            .install_from_zip_url(
+                &http.url("/error-page.zip"),
+                None,
+                InstallOptions::default(),
+            )
+            .await
+            .unwrap_err()
+            .to_string();
 
-        let retrieved = registry.get_map(id).await.unwrap().unwrap();
-        assert_eq!(retrieved.installed_path, "renamed.vpk");
+        assert!(err.contains("not a ZIP/VPK archive"), "unexpected error: {err}");
+        assert!(err.contains("HTML"), "unexpected error: {err}");
+        assert!(registry.list_maps().await.unwrap().is_empty());
+        let leftovers: Vec<_> = std::fs::read_dir(&download_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().ends_with("error-page.zip"))
+            .collect();
+        assert!(leftovers.is_empty());
     }
 
     #[tokio::test]
-    async fn test_modify_installed_path_workshop_subdir() {
-        let (service, registry, dirs) = setup_test_service().await;
-        let addons = dirs.addons_path();
-        let old_path = addons.join("test_map.vpk");
-        test_helpers::write_minimal_test_vpk(&old_path, "Test Map").unwrap();
+    async fn test_configured_download_temp_dir_is_created_and_used() {
+        let http = crate::downloader::test_lock::acquire_http_test_lock().await;
+        let (registry, dirs) = test_helpers::setup_test_dirs().await.unwrap();
+        let base = TempDir::new().unwrap();
+        let config = Config {
+            download_temp_dir: Some(base.path().join("big-disk")),
+            ..Config::default()
+        };
+        let temp_dir = config.download_staging_dir();
+        assert_eq!(temp_dir, base.path().join("big-disk").join("kether-downloads"));
 
-        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+        crate::utils::ensure_writable_dir(&temp_dir).await.unwrap();
+        assert!(temp_dir.is_dir());
 
-        let updated = service
-            .modify_map_field(id, "installed_path", "workshop/renamed.vpk")
+        let mut service = MapInstallationService::new(
+            registry,
+            dirs.service_paths().addons_dir,
+            temp_dir.clone(),
+            1024 * 1024,
+            1024 * 1024 * 1024,
+            10000,
+        )
+        .await
+        .unwrap();
+        // Keep the downloader the service built for `temp_dir`; only allow the loopback mock.
+        service.zip_downloader = service.zip_downloader.into_insecure_for_tests();
+
+        let (downloaded, _) = service
+            .zip_downloader
+            .download_zip_with_progress(&http.url("/test.zip"), &DownloadOptions::default(), None)
             .await
             .unwrap();
-
-        assert_eq!(updated.installed_path, "workshop/renamed.vpk");
-        assert!(!old_path.exists());
-        assert!(addons.join("workshop/renamed.vpk").exists());
+        assert_eq!(downloaded.parent(), Some(temp_dir.as_path()));
+        assert_eq!(std::fs::read_to_string(&downloaded).unwrap(), "test file content");
     }
 
     #[tokio::test]
-    async fn test_modify_installed_path_registry_only_when_source_missing() {
-        let (service, registry, _dirs) = setup_test_service().await;
-        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+    async fn test_events_stream_reports_install_completed() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use futures_util::StreamExt;
+        use tower::ServiceExt;
+
+        let (mut service, registry, dirs) = setup_test_service().await;
+        service.zip_downloader =
+            ZipDownloader::new_insecure_for_tests(dirs.service_paths().download_dir, 10 * 1024 * 1024)
+                .await
+                .unwrap();
+        let installer = Arc::new(service);
+        let handlers = Arc::new(crate::api::handlers::ApiHandlers::new(
+            registry,
+            Arc::clone(&installer),
+            crate::config::init_handle(Config::default()),
+        ));
+        let response = crate::api::HttpServer::router(handlers)
+            .oneshot(Request::get("/api/events").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "text/event-stream"
+        );
+        let mut events = response.into_body().into_data_stream();
 
-        let updated = service
-            .modify_map_field(id, "installed_path", "renamed.vpk")
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk_path = vpk_temp.path().join("sse_map.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "SSE Map").unwrap();
+        let vpk_bytes = std::fs::read(&vpk_path).unwrap();
+        let (zip_path, _zip_temp) = create_test_zip_with_map(&[("sse_map.vpk", &vpk_bytes)]);
+        let zip_bytes = std::fs::read(&zip_path).unwrap();
+        let mirror = axum::Router::new().route(
+            "/sse_map.zip",
+            axum::routing::get(move || {
+                let zip_bytes = zip_bytes.clone();
+                async move { zip_bytes }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let entry = installer
+            .install_from_zip_url(
+                &format!("http://{addr}/sse_map.zip"),
+                None,
+                InstallOptions::default(),
+            )
             .await
             .unwrap();
 
-        assert_eq!(updated.installed_path, "renamed.vpk");
+        let mut received = String::new();
+        tokio::time::timeout(std::time::Duration::from_secs(10), async {
+            while !received.contains("event: install_completed") {
+                let chunk = events.next().await.expect("stream ended").unwrap();
+                received.push_str(&String::from_utf8_lossy(&chunk));
+            }
+        })
+        .await
+        .expect("install_completed event not received");
+        assert!(received.contains("event: install_started"));
+        assert!(received.contains(&format!("\"map_id\":{}", entry.id)));
+    }
 
-        let retrieved = registry.get_map(id).await.unwrap().unwrap();
-        assert_eq!(retrieved.installed_path, "renamed.vpk");
+    #[tokio::test]
+    async fn test_concurrent_installs_respect_download_limit() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let (mut service, _registry, dirs) = setup_test_service().await;
+        service.zip_downloader =
+            ZipDownloader::new_insecure_for_tests(dirs.service_paths().download_dir, 1024 * 1024)
+                .await
+                .unwrap();
+        let config = Config {
+            max_concurrent_downloads: 2,
+            ..Config::default()
+        };
+        let service = service.with_config(crate::config::init_handle(config));
+
+        // Counting hook: the mirror tracks how many downloads are in flight at once.
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let served = Arc::new(AtomicUsize::new(0));
+        let (in_flight_hook, peak_hook, served_hook) =
+            (in_flight.clone(), peak.clone(), served.clone());
+        let mirror = axum::Router::new().route(
+            "/{name}",
+            axum::routing::get(move || {
+                let (in_flight, peak, served) =
+                    (in_flight_hook.clone(), peak_hook.clone(), served_hook.clone());
+                async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    served.fetch_add(1, Ordering::SeqCst);
+                    axum::http::StatusCode::NOT_FOUND
+                }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let urls: Vec<String> = (0..6).map(|i| format!("http://{addr}/map{i}.zip")).collect();
+        let results = futures_util::future::join_all(
+            urls.iter()
+                .map(|url| service.install_from_zip_url(url, None, InstallOptions::default())),
+        )
+        .await;
+
+        assert!(results.iter().all(|r| r.is_err()));
+        assert_eq!(served.load(Ordering::SeqCst), 6);
+        let peak = peak.load(Ordering::SeqCst);
+        assert!((1..=2).contains(&peak), "peak in-flight downloads was {peak}");
     }
 
     #[tokio::test]
-    async fn test_modify_installed_path_rejects_conflict() {
-        let (service, registry, dirs) = setup_test_service().await;
-        let addons = dirs.addons_path();
-        test_helpers::write_minimal_test_vpk(&addons.join("test_map.vpk"), "Test Map").unwrap();
-        test_helpers::write_minimal_test_vpk(&addons.join("taken.vpk"), "Taken").unwrap();
+    async fn test_install_from_url_refuses_oversized_head_before_get() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
 
-        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+        let (mut service, registry, dirs) = setup_test_service().await;
+        service.zip_downloader =
+            ZipDownloader::new_insecure_for_tests(dirs.service_paths().download_dir, 1024 * 1024)
+                .await
+                .unwrap();
 
-        let result = service
-            .modify_map_field(id, "installed_path", "taken.vpk")
-            .await;
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("file already exists"));
+        let gets = Arc::new(AtomicUsize::new(0));
+        let gets_hook = gets.clone();
+        let mirror = axum::Router::new().route(
+            "/{name}",
+            axum::routing::get(move || {
+                let gets = gets_hook.clone();
+                async move {
+                    gets.fetch_add(1, Ordering::SeqCst);
+                    axum::http::StatusCode::OK
+                }
+            })
+            .head(|| async {
+                (
+                    [(axum::http::header::CONTENT_LENGTH, (64 * 1024 * 1024).to_string())],
+                    axum::http::StatusCode::OK,
+                )
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
 
-        let mut other = create_modify_test_entry();
-        other.installed_path = "other_map.vpk".to_string();
-        let other_id = registry.add_map(other).await.unwrap();
+        let error = service
+            .install_from_zip_url(&format!("http://{addr}/huge.zip"), None, InstallOptions::default())
+            .await
+            .unwrap_err();
 
-        let result = service
-            .modify_map_field(id, "installed_path", "other_map.vpk")
-            .await;
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains(&format!("already used by map #{other_id}")));
+        assert!(error.to_string().contains("exceeds maximum download size"), "{error}");
+        assert_eq!(gets.load(Ordering::SeqCst), 0);
+        assert!(registry.list_maps().await.unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_modify_map_field_missing_id_errors() {
-        let (service, _registry, _dirs) = setup_test_service().await;
+    async fn test_install_from_url_falls_back_to_next_mirror() {
+        let (mut service, registry, dirs) = setup_test_service().await;
+        service.zip_downloader =
+            ZipDownloader::new_insecure_for_tests(dirs.service_paths().download_dir, 1024 * 1024)
+                .await
+                .unwrap();
 
-        let result = service
-            .modify_map_field(99999, "name", "New Name")
-            .await;
-        assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("Map not found"));
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk_path = vpk_temp.path().join("mirrored.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Mirrored Map").unwrap();
+        let vpk_bytes = std::fs::read(&vpk_path).unwrap();
+        let mirror = axum::Router::new()
+            .route(
+                "/down/mirrored.vpk",
+                axum::routing::get(|| async { axum::http::StatusCode::INTERNAL_SERVER_ERROR }),
+            )
+            .route(
+                "/up/mirrored.vpk",
+                axum::routing::get(move || {
+                    let body = vpk_bytes.clone();
+                    async move { body }
+                }),
+            );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let primary = format!("http://{addr}/down/mirrored.vpk");
+        let fallback = format!("http://{addr}/up/mirrored.vpk");
+        let entry = service
+            .install_from_zip_url(
+                &primary,
+                None,
+                InstallOptions {
+                    mirror_urls: vec![fallback.clone()],
+                    ..InstallOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(entry.name, "mirrored_map");
+        assert_eq!(entry.source_url, fallback);
+        assert_eq!(registry.get_map(entry.id).await.unwrap().unwrap().source_url, fallback);
     }
 
-    #[test]
-    fn test_preserve_source_identity_keeps_existing_when_fresh_has_no_workshop_id() {
-        let existing = MapEntry {
-            id: 1,
-            name: "Existing".to_string(),
-            source_url: "https://steamcommunity.com/sharedfiles/filedetails/?id=12345".to_string(),
-            source_kind: SourceKind::Workshop,
-            workshop_id: Some(12345),
-            installed_path: "map.vpk".to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
+    #[tokio::test]
+    async fn test_install_from_url_tries_next_mirror_on_checksum_mismatch() {
+        let (mut service, registry, dirs) = setup_test_service().await;
+        service.zip_downloader =
+            ZipDownloader::new_insecure_for_tests(dirs.service_paths().download_dir, 1024 * 1024)
+                .await
+                .unwrap();
+
+        let mirror_dir = TempDir::new().unwrap();
+        test_helpers::write_minimal_test_vpk(&mirror_dir.path().join("wrong.vpk"), "Wrong Map").unwrap();
+        test_helpers::write_minimal_test_vpk(&mirror_dir.path().join("right.vpk"), "Right Map").unwrap();
+        let right_checksum = crate::utils::calculate_file_md5(&mirror_dir.path().join("right.vpk"))
+            .await
+            .unwrap();
+        let served = mirror_dir.path().to_path_buf();
+        let mirror = axum::Router::new().route(
+            "/{file}",
+            axum::routing::get(move |axum::extract::Path(file): axum::extract::Path<String>| {
+                let path = served.join(file);
+                async move { std::fs::read(path).unwrap() }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let fallback = format!("http://{addr}/right.vpk");
+        let entry = service
+            .install_from_zip_url(
+                &format!("http://{addr}/wrong.vpk"),
+                None,
+                InstallOptions {
+                    mirror_urls: vec![fallback.clone()],
+                    expected_checksum: Some(ExpectedChecksum::md5(right_checksum.clone())),
+                    ..InstallOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(entry.name, "right_map");
+        assert_eq!(entry.source_url, fallback);
+        assert_eq!(entry.checksum.as_deref(), Some(right_checksum.as_str()));
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+        assert!(!dirs.addons_path().join("wrong_map.vpk").exists());
+    }
+
+    #[tokio::test]
+    async fn test_install_from_url_skips_blocked_mirror() {
+        let (mut service, registry, dirs) = setup_test_service().await;
+        service.zip_downloader =
+            ZipDownloader::new_insecure_for_tests(dirs.service_paths().download_dir, 1024 * 1024)
+                .await
+                .unwrap();
+
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk_path = vpk_temp.path().join("allowed.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Allowed Map").unwrap();
+        let vpk_bytes = std::fs::read(&vpk_path).unwrap();
+        let mirror = axum::Router::new().route(
+            "/allowed.vpk",
+            axum::routing::get(move || {
+                let body = vpk_bytes.clone();
+                async move { body }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let service = service.with_config(crate::config::init_handle(Config {
+            blocked_url_patterns: vec![format!("http://{addr}/blocked*")],
+            ..Config::default()
+        }));
+        let allowed = format!("http://{addr}/allowed.vpk");
+        let entry = service
+            .install_from_url(
+                format!("http://{addr}/blocked.vpk"),
+                None,
+                InstallOptions {
+                    mirror_urls: vec![allowed.clone()],
+                    ..InstallOptions::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(entry.source_url, allowed);
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_install_from_url_installs_bare_vpk_without_extraction() {
+        let (mut service, registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir.clone(), 1024 * 1024)
+            .await
+            .unwrap();
+
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk_path = vpk_temp.path().join("raw_map.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Raw Map").unwrap();
+        let vpk_bytes = std::fs::read(&vpk_path).unwrap();
+        let mirror = axum::Router::new().route(
+            "/maps/raw_map.vpk",
+            axum::routing::get(move || {
+                let body = vpk_bytes.clone();
+                async move { body }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let entry = service
+            .install_from_zip_url(
+                &format!("http://{addr}/maps/raw_map.vpk"),
+                None,
+                InstallOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert!(entry.installed_path.ends_with(".vpk"));
+        assert_eq!(entry.source_kind, SourceKind::Url);
+        assert!(dirs.addons_path().join(&entry.installed_path).exists());
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+        let extract_dirs: Vec<_> = std::fs::read_dir(&download_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with("extract-"))
+            .collect();
+        assert!(extract_dirs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_installs_of_same_name_register_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let (mut service, registry, dirs) = setup_test_service().await;
+        service.zip_downloader =
+            ZipDownloader::new_insecure_for_tests(dirs.service_paths().download_dir, 1024 * 1024)
+                .await
+                .unwrap();
+
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk_path = vpk_temp.path().join("dup.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Duplicate").unwrap();
+        let vpk_bytes = std::fs::read(&vpk_path).unwrap();
+        let served = Arc::new(AtomicUsize::new(0));
+        let served_hook = served.clone();
+        let mirror = axum::Router::new().route(
+            "/{name}",
+            axum::routing::get(move || {
+                let (body, served) = (vpk_bytes.clone(), served_hook.clone());
+                async move {
+                    served.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                    body
+                }
+            })
+            // Only count downloads, not the HEAD precheck.
+            .head(|| async { axum::http::StatusCode::OK }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let first_url = format!("http://{addr}/a.vpk");
+        let second_url = format!("http://{addr}/b.vpk");
+        let (first, second) = tokio::join!(
+            service.install_from_zip_url(&first_url, Some("Same Name".into()), InstallOptions::default()),
+            service.install_from_zip_url(&second_url, Some("Same Name".into()), InstallOptions::default()),
+        );
+
+        let results = [first, second];
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        let error = results.iter().find_map(|r| r.as_ref().err()).unwrap();
+        assert!(error.to_string().contains("already installed"), "{error}");
+        assert_eq!(served.load(Ordering::SeqCst), 1, "the loser must not download");
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_workshop_and_url_installs_of_same_name_do_not_deadlock() {
+        let (mut service, registry, dirs) = setup_test_service().await;
+        service.zip_downloader =
+            ZipDownloader::new_insecure_for_tests(dirs.service_paths().download_dir, 1024 * 1024)
+                .await
+                .unwrap();
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.max_concurrent_downloads = 1;
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk_path = vpk_temp.path().join("dup.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Duplicate").unwrap();
+        let vpk_bytes = std::fs::read(&vpk_path).unwrap();
+        let mirror = axum::Router::new().route(
+            "/dup.vpk",
+            axum::routing::get(move || {
+                let body = vpk_bytes.clone();
+                async move { body }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+        let url = format!("http://{addr}/dup.vpk");
+
+        // Hold the name so the URL install queues on it first, then the workshop install.
+        let map_name = crate::utils::sanitize_map_name("Same Name").unwrap();
+        let held = service.install_name_locks.lock(&map_name).await;
+        let installs = async {
+            tokio::join!(
+                service.install_from_zip_url(&url, Some("Same Name".into()), InstallOptions::default()),
+                service.install_from_workshop_id(3135451698, Some("Same Name".into()), InstallOptions::default()),
+                async {
+                    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                    drop(held);
+                },
+            )
+        };
+        let (url_result, workshop_result, ()) =
+            tokio::time::timeout(std::time::Duration::from_secs(10), installs)
+                .await
+                .expect("installs of the same name deadlocked");
+
+        url_result.unwrap();
+        let error = workshop_result.unwrap_err();
+        assert!(error.to_string().contains("already installed"), "{error}");
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_vpk_without_addoninfo_installs_only_in_lenient_mode() {
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk_path = vpk_temp.path().join("content_pack.vpk");
+        test_helpers::write_test_vpk_without_addoninfo(&vpk_path).unwrap();
+        let vpk_bytes = std::fs::read(&vpk_path).unwrap();
+        let mirror = axum::Router::new().route(
+            "/maps/content_pack.vpk",
+            axum::routing::get(move || {
+                let body = vpk_bytes.clone();
+                async move { body }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+        let url = format!("http://{addr}/maps/content_pack.vpk");
+
+        for require_addoninfo in [true, false] {
+            let (mut service, registry, dirs) = setup_test_service().await;
+            service.zip_downloader =
+                ZipDownloader::new_insecure_for_tests(dirs.service_paths().download_dir, 1024 * 1024)
+                    .await
+                    .unwrap();
+            let config = Config {
+                require_addoninfo,
+                ..Config::default()
+            };
+            let service = service.with_config(crate::config::init_handle(config));
+
+            let result = service
+                .install_from_zip_url(&url, None, InstallOptions::default())
+                .await;
+
+            if require_addoninfo {
+                assert!(result.is_err());
+                assert!(registry.list_maps().await.unwrap().is_empty());
+            } else {
+                let entry = result.unwrap();
+                assert_eq!(entry.name, "content_pack");
+                assert!(dirs.addons_path().join(&entry.installed_path).exists());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_install_refuses_before_downloading_when_disk_is_full() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let (mut service, registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir.clone(), 1024 * 1024)
+            .await
+            .unwrap();
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let hits_handler = hits.clone();
+        let mirror = axum::Router::new().route(
+            "/maps/map.zip",
+            axum::routing::get(move || {
+                hits_handler.fetch_add(1, Ordering::SeqCst);
+                async { Vec::<u8>::new() }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let result = crate::utils::disk_space::with_available_space(
+            1,
+            service.install_from_zip_url(
+                &format!("http://{addr}/maps/map.zip"),
+                None,
+                InstallOptions::default(),
+            ),
+        )
+        .await;
+
+        let error = result.unwrap_err();
+        assert!(
+            error.chain().any(|e| e.is::<crate::utils::InsufficientDiskSpace>()),
+            "{error:#}"
+        );
+        assert_eq!(hits.load(Ordering::SeqCst), 0);
+        assert!(registry.list_maps().await.unwrap().is_empty());
+        assert_eq!(std::fs::read_dir(&download_dir).unwrap().count(), 0);
+
+        let response = axum::response::IntoResponse::into_response(
+            crate::api::service_error::classify_install_error(error),
+        );
+        assert_eq!(response.status(), axum::http::StatusCode::INSUFFICIENT_STORAGE);
+    }
+
+    #[tokio::test]
+    async fn test_install_times_out_on_stalled_mirror_and_cleans_up() {
+        use futures_util::StreamExt;
+
+        let (mut service, registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir.clone(), 1024 * 1024)
+            .await
+            .unwrap();
+        let config = Config {
+            install_timeout_secs: 1,
+            ..Config::default()
+        };
+        let service = service.with_config(crate::config::init_handle(config));
+
+        // Sends the first bytes of a ZIP, then never finishes the body.
+        let mirror = axum::Router::new().route(
+            "/stalled.zip",
+            axum::routing::get(|| async {
+                let stream = futures_util::stream::once(async {
+                    Ok::<_, std::io::Error>(axum::body::Bytes::from_static(b"PK\x03\x04partial"))
+                })
+                .chain(futures_util::stream::pending());
+                axum::body::Body::from_stream(stream)
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let started = std::time::Instant::now();
+        let err = service
+            .install_from_zip_url(
+                &format!("http://{addr}/stalled.zip"),
+                None,
+                InstallOptions::default(),
+            )
+            .await
+            .unwrap_err();
+
+        assert!(err.is::<crate::map_installer::InstallTimedOut>(), "unexpected error: {err}");
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        assert!(registry.list_maps().await.unwrap().is_empty());
+        let leftovers: Vec<_> = std::fs::read_dir(&download_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .collect();
+        assert!(leftovers.is_empty(), "temp files left behind: {leftovers:?}");
+
+        let response = axum::response::IntoResponse::into_response(
+            crate::api::service_error::classify_install_error(err),
+        );
+        assert_eq!(response.status(), axum::http::StatusCode::GATEWAY_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_install_cleans_up_and_registers_nothing() {
+        use futures_util::StreamExt;
+
+        let (mut service, registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir.clone(), 1024 * 1024)
+            .await
+            .unwrap();
+
+        // Sends the first bytes of a ZIP, then never finishes the body.
+        let requested = Arc::new(tokio::sync::Notify::new());
+        let mirror = axum::Router::new().route(
+            "/slow.zip",
+            axum::routing::get({
+                let requested = Arc::clone(&requested);
+                move || async move {
+                    requested.notify_one();
+                    let stream = futures_util::stream::once(async {
+                        Ok::<_, std::io::Error>(axum::body::Bytes::from_static(b"PK\x03\x04partial"))
+                    })
+                    .chain(futures_util::stream::pending());
+                    axum::body::Body::from_stream(stream)
+                }
+            })
+            .head(|| async { axum::http::StatusCode::OK }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let mut events = service.events().subscribe();
+        let url = format!("http://{addr}/slow.zip");
+        let install = service.install_from_zip_url(
+            &url,
+            None,
+            InstallOptions::default(),
+        );
+        let cancel = async {
+            let MapEvent::InstallStarted { operation_id, .. } = events.recv().await.unwrap() else {
+                panic!("expected install_started first");
+            };
+            requested.notified().await;
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            assert_eq!(service.install_operations().len(), 1);
+            assert!(service.cancel_install(operation_id));
+            operation_id
+        };
+        let (result, operation_id) = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            async { tokio::join!(install, cancel) },
+        )
+        .await
+        .expect("cancelled install should finish promptly");
+
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("cancelled"), "unexpected error: {err}");
+        assert!(service.install_operations().is_empty());
+        assert!(!service.cancel_install(operation_id));
+        assert!(registry.list_maps().await.unwrap().is_empty());
+        let leftovers: Vec<_> = std::fs::read_dir(&download_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .collect();
+        assert!(leftovers.is_empty(), "temp files left behind: {leftovers:?}");
+    }
+
+    #[tokio::test]
+    async fn test_install_and_uninstall_append_audit_entries() {
+        use crate::map_installer::audit::AuditAction;
+        use crate::map_installer::with_audit_actor;
+
+        let (service, _registry, dirs) = setup_test_service().await;
+        let audit_path = dirs.service_paths().download_dir.join("registry.audit.jsonl");
+        let mut service = service.with_audit_log(audit_path);
+        service.zip_downloader =
+            ZipDownloader::new_insecure_for_tests(dirs.service_paths().download_dir, 1024 * 1024)
+                .await
+                .unwrap();
+
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk_path = vpk_temp.path().join("audited.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Audited Map").unwrap();
+        let vpk_bytes = std::fs::read(&vpk_path).unwrap();
+        let mirror = axum::Router::new().route(
+            "/audited.vpk",
+            axum::routing::get(move || {
+                let body = vpk_bytes.clone();
+                async move { body }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+        let url = format!("http://{addr}/audited.vpk");
+
+        let entry = with_audit_actor(
+            "api",
+            service.install_from_zip_url(&url, None, InstallOptions::default()),
+        )
+        .await
+        .unwrap();
+        with_audit_actor("sync", service.uninstall_map(entry.id))
+            .await
+            .unwrap();
+
+        let history = service.audit_log().recent(Some(entry.id), 10).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].action, AuditAction::Uninstall);
+        assert_eq!(history[0].actor, "sync");
+        assert_eq!(history[1].action, AuditAction::Install);
+        assert_eq!(history[1].actor, "api");
+        assert_eq!(history[1].source.as_deref(), Some(url.as_str()));
+    }
+
+    fn named_vpk_target(installed_filename: &str, force: bool) -> InstallTarget {
+        InstallTarget {
+            source_kind: SourceKind::Other,
+            workshop_id: None,
+            provided_name: Some("dup_map".to_string()),
+            source_url: Some(format!("https://example.com/{installed_filename}.zip")),
+            expected_installed_filename: Some(installed_filename.to_string()),
+            force,
+            expected_checksum: None,
+            source_validators: CacheValidators::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_install_name_collision_without_force_fails() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let vpk_temp = TempDir::new().unwrap();
+
+        let first_vpk = vpk_temp.path().join("first.vpk");
+        test_helpers::write_minimal_test_vpk(&first_vpk, "Dup Map").unwrap();
+        let first = service
+            .install_downloaded_file(first_vpk, named_vpk_target("old_dup.vpk", false))
+            .await
+            .unwrap();
+
+        let second_vpk = vpk_temp.path().join("second.vpk");
+        test_helpers::write_minimal_test_vpk(&second_vpk, "Dup Map").unwrap();
+        let err = service
+            .install_downloaded_file(second_vpk.clone(), named_vpk_target("new_dup.vpk", false))
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("already installed"));
+        assert!(!second_vpk.exists());
+        assert!(dirs.addons_path().join("old_dup.vpk").exists());
+        assert!(!dirs.addons_path().join("new_dup.vpk").exists());
+        assert!(registry.get_map(first.id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_install_name_collision_with_force_replaces_map() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let vpk_temp = TempDir::new().unwrap();
+
+        let first_vpk = vpk_temp.path().join("first.vpk");
+        test_helpers::write_minimal_test_vpk(&first_vpk, "Dup Map").unwrap();
+        let first = service
+            .install_downloaded_file(first_vpk, named_vpk_target("old_dup.vpk", false))
+            .await
+            .unwrap();
+
+        let second_vpk = vpk_temp.path().join("second.vpk");
+        test_helpers::write_minimal_test_vpk(&second_vpk, "Dup Map").unwrap();
+        let second = service
+            .install_downloaded_file(second_vpk, named_vpk_target("new_dup.vpk", true))
+            .await
+            .unwrap();
+
+        assert_eq!(second.installed_path, "new_dup.vpk");
+        assert!(!dirs.addons_path().join("old_dup.vpk").exists());
+        assert!(dirs.addons_path().join("new_dup.vpk").exists());
+        let maps = registry.list_maps().await.unwrap();
+        assert_eq!(maps.len(), 1);
+        assert_eq!(maps[0].name, first.name);
+        assert_eq!(maps[0].installed_path, "new_dup.vpk");
+    }
+
+    #[tokio::test]
+    async fn test_install_accepts_matching_expected_checksum() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk = vpk_temp.path().join("checked.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk, "Checked Map").unwrap();
+        let md5 = crate::utils::calculate_file_md5(&vpk).await.unwrap();
+
+        let target = InstallTarget {
+            expected_checksum: Some(ExpectedChecksum::md5(md5.to_uppercase())),
+            source_validators: CacheValidators::default(),
+            ..named_vpk_target("checked.vpk", false)
+        };
+        let entry = service.install_downloaded_file(vpk, target).await.unwrap();
+
+        assert_eq!(entry.checksum.as_deref(), Some(md5.as_str()));
+        assert!(dirs.addons_path().join("checked.vpk").exists());
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_install_rejects_mismatched_expected_checksum() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk = vpk_temp.path().join("tampered.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk, "Tampered Map").unwrap();
+
+        let target = InstallTarget {
+            expected_checksum: Some(ExpectedChecksum::md5("00000000000000000000000000000000")),
+            source_validators: CacheValidators::default(),
+            ..named_vpk_target("tampered.vpk", false)
+        };
+        let err = service
+            .install_downloaded_file(vpk.clone(), target)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("Checksum mismatch"));
+        assert!(!vpk.exists());
+        assert!(!dirs.addons_path().join("tampered.vpk").exists());
+        assert!(registry.list_maps().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_checksum_mismatch_moves_download_to_quarantine() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let quarantine = TempDir::new().unwrap();
+        let service = service.with_config(crate::config::init_handle(Config {
+            quarantine_dir: Some(quarantine.path().to_path_buf()),
+            ..Config::default()
+        }));
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk = vpk_temp.path().join("tampered.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk, "Tampered Map").unwrap();
+        let original_bytes = std::fs::read(&vpk).unwrap();
+
+        let target = InstallTarget {
+            expected_checksum: Some(ExpectedChecksum::md5("00000000000000000000000000000000")),
+            source_validators: CacheValidators::default(),
+            ..named_vpk_target("tampered.vpk", false)
+        };
+        let err = service
+            .install_downloaded_file(vpk.clone(), target)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("Checksum mismatch"));
+        assert!(!vpk.exists());
+        assert!(!dirs.addons_path().join("tampered.vpk").exists());
+        assert!(registry.list_maps().await.unwrap().is_empty());
+
+        let mut quarantined: Vec<_> = std::fs::read_dir(quarantine.path())
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        quarantined.sort();
+        assert_eq!(quarantined.len(), 2, "{quarantined:?}");
+        let (file, reason) = (&quarantined[0], &quarantined[1]);
+        assert!(file.to_string_lossy().ends_with("tampered.vpk"));
+        assert_eq!(std::fs::read(file).unwrap(), original_bytes);
+        assert!(reason.to_string_lossy().ends_with("tampered.vpk.reason"));
+        assert!(std::fs::read_to_string(reason).unwrap().contains("Checksum mismatch"));
+    }
+
+    #[tokio::test]
+    async fn test_install_rejects_zip_with_mismatched_expected_checksum() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let vpk_temp = TempDir::new().unwrap();
+        let vpk = vpk_temp.path().join("zipped.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk, "Zipped Map").unwrap();
+        let vpk_bytes = std::fs::read(&vpk).unwrap();
+        let (zip_path, _zip_temp) = create_test_zip_with_map(&[("zipped.vpk", &vpk_bytes)]);
+
+        let target = InstallTarget {
+            expected_checksum: Some(ExpectedChecksum::md5("00000000000000000000000000000000")),
+            source_validators: CacheValidators::default(),
+            ..named_vpk_target("zipped.vpk", false)
+        };
+        let err = service
+            .install_downloaded_file(zip_path.clone(), target)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("Checksum mismatch"));
+        assert!(!zip_path.exists());
+        assert!(!dirs.addons_path().join("zipped.vpk").exists());
+        assert!(registry.list_maps().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_force_install_keeps_existing_map_when_download_invalid() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let vpk_temp = TempDir::new().unwrap();
+
+        let first_vpk = vpk_temp.path().join("first.vpk");
+        test_helpers::write_minimal_test_vpk(&first_vpk, "Dup Map").unwrap();
+        let first = service
+            .install_downloaded_file(first_vpk, named_vpk_target("old_dup.vpk", false))
+            .await
+            .unwrap();
+
+        let corrupt_vpk = vpk_temp.path().join("corrupt.vpk");
+        std::fs::write(&corrupt_vpk, b"not a vpk").unwrap();
+        let result = service
+            .install_downloaded_file(corrupt_vpk, named_vpk_target("new_dup.vpk", true))
+            .await;
+
+        assert!(result.is_err());
+        assert!(registry.get_map(first.id).await.unwrap().is_some());
+        assert!(dirs.addons_path().join("old_dup.vpk").exists());
+    }
+
+    #[tokio::test]
+    async fn test_reconcile_registers_orphans_and_drops_dangling_entries() {
+        let (service, registry, dirs) = setup_test_service().await;
+
+        let kept_id = registry
+            .add_map(MapEntry {
+                source_url: "https://example.com/kept".to_string(),
+                installed_path: "kept.vpk".to_string(),
+                ..test_helpers::test_map_entry("Kept")
+            })
+            .await
+            .unwrap();
+        tokio::fs::write(dirs.addons_path().join("kept.vpk"), b"vpk").await.unwrap();
+        let dangling_id = registry
+            .add_map(MapEntry {
+                source_url: "https://example.com/dangling".to_string(),
+                installed_path: "dangling.vpk".to_string(),
+                ..test_helpers::test_map_entry("Dangling")
+            })
+            .await
+            .unwrap();
+        test_helpers::write_minimal_test_vpk(&dirs.addons_path().join("orphan.vpk"), "Orphan Map")
+            .unwrap();
+
+        let report = service.reconcile().await.unwrap();
+
+        assert_eq!(report.registered.len(), 1);
+        assert_eq!(report.registered[0].installed_path, "orphan.vpk");
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(report.removed[0].id, dangling_id);
+        assert_eq!(report.unchanged, 1);
+        assert_eq!(report.failed, 0);
+
+        let maps = registry.list_maps().await.unwrap();
+        assert_eq!(maps.len(), 2);
+        assert!(maps.iter().any(|m| m.id == kept_id && m.installed_path == "kept.vpk"));
+        assert!(maps.iter().any(|m| m.installed_path == "orphan.vpk"));
+
+        let again = service.reconcile().await.unwrap();
+        assert!(again.registered.is_empty() && again.removed.is_empty());
+        assert_eq!(again.unchanged, 2);
+    }
+
+    #[tokio::test]
+    async fn test_compact_registry_prunes_sorts_and_reindexes() {
+        let (service, registry, dirs) = setup_test_service().await;
+
+        tokio::fs::write(dirs.addons_path().join("alpha.vpk"), b"vpk").await.unwrap();
+        tokio::fs::write(dirs.addons_path().join("zulu.vpk"), b"vpk").await.unwrap();
+
+        let now = chrono::Utc::now();
+        registry
+            .replace_all_maps(vec![
+                MapEntry {
+                    id: 5,
+                    source_url: "https://example.com/zulu".to_string(),
+                    installed_path: "zulu.vpk".to_string(),
+                    installed_at: now,
+                    ..test_helpers::test_map_entry("Zulu")
+                },
+                MapEntry {
+                    id: 12,
+                    source_url: "https://example.com/alpha".to_string(),
+                    installed_path: "alpha.vpk".to_string(),
+                    installed_at: now,
+                    ..test_helpers::test_map_entry("Alpha")
+                },
+                MapEntry {
+                    id: 3,
+                    source_url: "https://example.com/missing".to_string(),
+                    installed_path: "missing.vpk".to_string(),
+                    installed_at: now,
+                    ..test_helpers::test_map_entry("Missing")
+                },
+            ])
+            .await
+            .unwrap();
+
+        let report = service.compact_registry().await.unwrap();
+
+        assert_eq!(report.removed.len(), 1);
+        assert_eq!(report.removed[0].name, "Missing");
+        assert_eq!(report.kept.len(), 2);
+        assert_eq!(report.kept[0].name, "Alpha");
+        assert_eq!(report.kept[0].id, 1);
+        assert_eq!(report.kept[1].name, "Zulu");
+        assert_eq!(report.kept[1].id, 2);
+
+        let maps = registry.list_maps().await.unwrap();
+        assert_eq!(maps.len(), 2);
+        assert_eq!(maps[0].id, 1);
+        assert_eq!(maps[0].name, "Alpha");
+        assert_eq!(maps[1].id, 2);
+        assert_eq!(maps[1].name, "Zulu");
+    }
+
+    fn create_modify_test_entry() -> MapEntry {
+        MapEntry {
+            source_url: "https://example.com/map.zip".to_string(),
+            source_kind: SourceKind::Workshop,
+            workshop_id: Some(999),
+            installed_path: "test_map.vpk".to_string(),
+            version: Some("1.0".to_string()),
+            ..test_helpers::test_map_entry("Test Map")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_modify_map_field_workshop_id_sets_kind_and_url() {
+        let (service, registry, _dirs) = setup_test_service().await;
+        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+
+        let updated = service
+            .modify_map_field(id, "workshop_id", "3135451698")
+            .await
+            .unwrap();
+
+        assert_eq!(updated.workshop_id, Some(3135451698));
+        assert_eq!(updated.source_kind, SourceKind::Workshop);
+        assert_eq!(
+            updated.source_url,
+            "https://steamcommunity.com/sharedfiles/filedetails/?id=3135451698"
+        );
+
+        let retrieved = registry.get_map(id).await.unwrap().unwrap();
+        assert_eq!(retrieved.workshop_id, Some(3135451698));
+        assert_eq!(retrieved.source_kind, SourceKind::Workshop);
+        assert_eq!(
+            retrieved.source_url,
+            "https://steamcommunity.com/sharedfiles/filedetails/?id=3135451698"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_modify_map_field_source_kind_other_clears_workshop_id() {
+        let (service, registry, _dirs) = setup_test_service().await;
+        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+
+        let updated = service
+            .modify_map_field(id, "source_kind", "other")
+            .await
+            .unwrap();
+
+        assert_eq!(updated.source_kind, SourceKind::Other);
+        assert_eq!(updated.workshop_id, None);
+
+        let retrieved = registry.get_map(id).await.unwrap().unwrap();
+        assert_eq!(retrieved.source_kind, SourceKind::Other);
+        assert_eq!(retrieved.workshop_id, None);
+    }
+
+    #[tokio::test]
+    async fn test_modify_map_field_unknown_field_errors() {
+        let (service, registry, _dirs) = setup_test_service().await;
+        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+
+        let result = service
+            .modify_map_field(id, "checksum", "abc123")
+            .await;
+        assert!(result.is_err());
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("Unknown or read-only field"));
+    }
+
+    #[tokio::test]
+    async fn test_modify_installed_path_renames_file_and_updates_registry() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let addons = dirs.addons_path();
+        let old_path = addons.join("test_map.vpk");
+        test_helpers::write_minimal_test_vpk(&old_path, "Test Map").unwrap();
+
+        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+
+        let updated = service
+            .modify_map_field(id, "installed_path", "renamed.vpk")
+            .await
+            .unwrap();
+
+        assert_eq!(updated.installed_path, "renamed.vpk");
+        assert!(!old_path.exists());
+        assert!(addons.join("renamed.vpk").exists());
+
+        let retrieved = registry.get_map(id).await.unwrap().unwrap();
+        assert_eq!(retrieved.installed_path, "renamed.vpk");
+    }
+
+    #[tokio::test]
+    async fn test_rename_map_renames_vpk_and_entry() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let addons = dirs.addons_path();
+        let old_path = addons.join("test_map.vpk");
+        test_helpers::write_minimal_test_vpk(&old_path, "Test Map").unwrap();
+        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+
+        let renamed = service.rename_map(id, "  Dark Carnival Remix ").await.unwrap();
+
+        assert_eq!(renamed.name, "dark_carnival_remix");
+        assert_eq!(renamed.installed_path, "dark_carnival_remix.vpk");
+        assert!(!old_path.exists());
+        assert!(addons.join("dark_carnival_remix.vpk").exists());
+        let retrieved = registry.get_map(id).await.unwrap().unwrap();
+        assert_eq!(retrieved.name, "dark_carnival_remix");
+        assert_eq!(retrieved.installed_path, "dark_carnival_remix.vpk");
+    }
+
+    #[tokio::test]
+    async fn test_rename_map_rejects_name_of_another_map() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let addons = dirs.addons_path();
+        test_helpers::write_minimal_test_vpk(&addons.join("test_map.vpk"), "Test Map").unwrap();
+        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+        registry
+            .add_map(MapEntry {
+                name: "taken".to_string(),
+                installed_path: "taken.vpk".to_string(),
+                workshop_id: Some(1000),
+                ..create_modify_test_entry()
+            })
+            .await
+            .unwrap();
+
+        let error = service.rename_map(id, "Taken").await.unwrap_err();
+
+        assert!(error.to_string().contains("already used by map"), "{error}");
+        assert!(addons.join("test_map.vpk").exists());
+        assert_eq!(registry.get_map(id).await.unwrap().unwrap().name, "Test Map");
+    }
+
+    #[tokio::test]
+    async fn test_modify_installed_path_workshop_subdir() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let addons = dirs.addons_path();
+        let old_path = addons.join("test_map.vpk");
+        test_helpers::write_minimal_test_vpk(&old_path, "Test Map").unwrap();
+
+        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+
+        let updated = service
+            .modify_map_field(id, "installed_path", "workshop/renamed.vpk")
+            .await
+            .unwrap();
+
+        assert_eq!(updated.installed_path, "workshop/renamed.vpk");
+        assert!(!old_path.exists());
+        assert!(addons.join("workshop/renamed.vpk").exists());
+    }
+
+    #[tokio::test]
+    async fn test_modify_installed_path_registry_only_when_source_missing() {
+        let (service, registry, _dirs) = setup_test_service().await;
+        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+
+        let updated = service
+            .modify_map_field(id, "installed_path", "renamed.vpk")
+            .await
+            .unwrap();
+
+        assert_eq!(updated.installed_path, "renamed.vpk");
+
+        let retrieved = registry.get_map(id).await.unwrap().unwrap();
+        assert_eq!(retrieved.installed_path, "renamed.vpk");
+    }
+
+    #[tokio::test]
+    async fn test_modify_installed_path_rejects_conflict() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let addons = dirs.addons_path();
+        test_helpers::write_minimal_test_vpk(&addons.join("test_map.vpk"), "Test Map").unwrap();
+        test_helpers::write_minimal_test_vpk(&addons.join("taken.vpk"), "Taken").unwrap();
+
+        let id = registry.add_map(create_modify_test_entry()).await.unwrap();
+
+        let result = service
+            .modify_map_field(id, "installed_path", "taken.vpk")
+            .await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("file already exists"));
+
+        let mut other = create_modify_test_entry();
+        other.installed_path = "other_map.vpk".to_string();
+        let other_id = registry.add_map(other).await.unwrap();
+
+        let result = service
+            .modify_map_field(id, "installed_path", "other_map.vpk")
+            .await;
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains(&format!("already used by map #{other_id}")));
+    }
+
+    #[tokio::test]
+    async fn test_modify_map_field_missing_id_errors() {
+        let (service, _registry, _dirs) = setup_test_service().await;
+
+        let result = service
+            .modify_map_field(99999, "name", "New Name")
+            .await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Map not found"));
+    }
+
+    #[test]
+    fn test_preserve_source_identity_keeps_existing_when_fresh_has_no_workshop_id() {
+        let existing = MapEntry {
+            id: 1,
+            source_url: "https://steamcommunity.com/sharedfiles/filedetails/?id=12345".to_string(),
+            source_kind: SourceKind::Workshop,
+            workshop_id: Some(12345),
+            installed_path: "map.vpk".to_string(),
+            version: Some("1".to_string()),
+            checksum: Some("old".to_string()),
+            checksum_kind: Some("md5".to_string()),
+            ..test_helpers::test_map_entry("Existing")
+        };
+
+        let mut fresh = MapEntry {
+            id: 1,
+            source_url: "detected:/addons/map.vpk".to_string(),
+            installed_path: "map.vpk".to_string(),
+            version: Some("2".to_string()),
+            checksum: Some("new".to_string()),
+            checksum_kind: Some("md5".to_string()),
+            ..test_helpers::test_map_entry("Fresh")
+        };
+
+        MapInstallationService::preserve_source_identity(&mut fresh, &existing);
+
+        assert_eq!(fresh.workshop_id, Some(12345));
+        assert_eq!(fresh.source_kind, SourceKind::Workshop);
+        assert_eq!(
+            fresh.source_url,
+            "https://steamcommunity.com/sharedfiles/filedetails/?id=12345"
+        );
+        assert_eq!(fresh.name, "Fresh");
+        assert_eq!(fresh.version, Some("2".to_string()));
+        assert_eq!(fresh.checksum, Some("new".to_string()));
+    }
+
+    #[test]
+    fn test_preserve_source_identity_uses_fresh_when_workshop_id_detected() {
+        let existing = MapEntry {
+            id: 1,
+            source_url: "detected:/addons/map.vpk".to_string(),
+            installed_path: "map.vpk".to_string(),
             version: Some("1".to_string()),
             checksum: Some("old".to_string()),
             checksum_kind: Some("md5".to_string()),
+            ..test_helpers::test_map_entry("Existing")
+        };
+
+        let mut fresh = MapEntry {
+            id: 1,
+            source_url: "https://steamcommunity.com/sharedfiles/filedetails/?id=999".to_string(),
+            source_kind: SourceKind::Workshop,
+            workshop_id: Some(999),
+            installed_path: "map.vpk".to_string(),
+            version: Some("2".to_string()),
+            checksum: Some("new".to_string()),
+            checksum_kind: Some("md5".to_string()),
+            ..test_helpers::test_map_entry("Fresh")
+        };
+
+        MapInstallationService::preserve_source_identity(&mut fresh, &existing);
+
+        assert_eq!(fresh.workshop_id, Some(999));
+        assert_eq!(fresh.source_kind, SourceKind::Workshop);
+        assert_eq!(
+            fresh.source_url,
+            "https://steamcommunity.com/sharedfiles/filedetails/?id=999"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_detect_map_from_path_is_idempotent_for_installed_path() {
+        let (service, registry, dirs) = setup_test_service().await;
+        tokio::fs::write(dirs.addons_path().join("alpha.vpk"), b"vpk")
+            .await
+            .unwrap();
+
+        let id = registry
+            .add_map(MapEntry {
+                source_url: "https://example.com/alpha".to_string(),
+                installed_path: "alpha.vpk".to_string(),
+                ..test_helpers::test_map_entry("Alpha")
+            })
+            .await
+            .unwrap();
+
+        let path = dirs.addons_path().join("alpha.vpk");
+        let result = service.detect_map_from_path(path).await.unwrap().unwrap();
+        assert_eq!(result.id, id);
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_sync_map_from_path_returns_existing_when_metadata_unavailable() {
+        let (service, registry, dirs) = setup_test_service().await;
+        tokio::fs::write(dirs.addons_path().join("alpha.vpk"), b"vpk")
+            .await
+            .unwrap();
+
+        let id = registry
+            .add_map(MapEntry {
+                source_url: "https://example.com/alpha".to_string(),
+                installed_path: "alpha.vpk".to_string(),
+                checksum: Some("old".to_string()),
+                checksum_kind: Some("md5".to_string()),
+                ..test_helpers::test_map_entry("Alpha")
+            })
+            .await
+            .unwrap();
+
+        let result = service
+            .sync_map_from_path(dirs.addons_path().join("alpha.vpk"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.id, id);
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_build_map_entry_from_file_uses_filename_fallback_when_metadata_missing() {
+        let (service, _registry, dirs) = setup_test_service().await;
+        let path = dirs.addons_path().join("bts_l4d2.vpk");
+        tokio::fs::write(&path, b"not a vpk").await.unwrap();
+
+        let entry = service
+            .build_map_entry_from_file(&path, "bts_l4d2.vpk", "md5")
+            .await
+            .unwrap()
+            .expect("fallback entry");
+
+        assert_eq!(entry.name, "bts_l4d2");
+        assert_eq!(entry.installed_path, "bts_l4d2.vpk");
+        assert!(entry.version.is_none());
+        assert_eq!(entry.source_kind, SourceKind::Detected);
+    }
+
+    #[tokio::test]
+    async fn test_remove_map_by_path_prunes_when_file_gone() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let id = registry
+            .add_map(MapEntry {
+                source_url: "https://example.com/gone".to_string(),
+                installed_path: "gone.vpk".to_string(),
+                ..test_helpers::test_map_entry("Gone")
+            })
+            .await
+            .unwrap();
+
+        let removed = service
+            .remove_map_by_path(dirs.addons_path().join("gone.vpk"))
+            .await
+            .unwrap();
+        assert_eq!(removed, Some(id));
+        assert!(registry.get_map(id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_remove_map_by_path_noop_when_file_exists() {
+        let (service, registry, dirs) = setup_test_service().await;
+        tokio::fs::write(dirs.addons_path().join("exists.vpk"), b"vpk")
+            .await
+            .unwrap();
+
+        let id = registry
+            .add_map(MapEntry {
+                source_url: "https://example.com/exists".to_string(),
+                installed_path: "exists.vpk".to_string(),
+                ..test_helpers::test_map_entry("Exists")
+            })
+            .await
+            .unwrap();
+
+        let removed = service
+            .remove_map_by_path(dirs.addons_path().join("exists.vpk"))
+            .await
+            .unwrap();
+        assert_eq!(removed, None);
+        assert!(registry.get_map(id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_install_workshop_id_returns_existing_without_redownload() {
+        let (service, registry, _dirs) = setup_test_service().await;
+        let workshop_id = 3135451698u64;
+        let existing_id = registry
+            .add_map(MapEntry {
+                source_url: String::new(),
+                source_kind: SourceKind::Workshop,
+                workshop_id: Some(workshop_id),
+                installed_path: "workshop_map.vpk".to_string(),
+                ..test_helpers::test_map_entry("Workshop Map")
+            })
+            .await
+            .unwrap();
+
+        let result = service
+            .install_from_workshop_id(workshop_id, None, InstallOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(result.id, existing_id);
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_find_map_by_name_returns_existing_entry() {
+        let (service, registry, _dirs) = setup_test_service().await;
+        let id = registry
+            .add_map(MapEntry {
+                source_url: "https://example.com/map".to_string(),
+                installed_path: "existing.vpk".to_string(),
+                ..test_helpers::test_map_entry("existing_map")
+            })
+            .await
+            .unwrap();
+
+        let found = service.find_map_by_name("existing_map").await.unwrap();
+        assert_eq!(found.unwrap().id, id);
+    }
+
+    #[test]
+    fn test_needs_workshop_update_stored_timestamp() {
+        let steam = chrono::Utc.timestamp_opt(2_000, 0).single().unwrap();
+        let stored = chrono::Utc.timestamp_opt(1_000, 0).single().unwrap();
+        assert!(needs_workshop_update(steam, Some(stored), None, false));
+        assert!(!needs_workshop_update(stored, Some(steam), None, false));
+    }
+
+    #[test]
+    fn test_needs_workshop_update_mtime_fallback() {
+        let steam = chrono::Utc.timestamp_opt(2_000, 0).single().unwrap();
+        let mtime = chrono::Utc.timestamp_opt(1_000, 0).single().unwrap();
+        assert!(needs_workshop_update(steam, None, Some(mtime), false));
+        assert!(!needs_workshop_update(mtime, None, Some(steam), false));
+    }
+
+    #[test]
+    fn test_needs_workshop_update_no_signals_defaults_outdated() {
+        let steam = chrono::Utc.timestamp_opt(1_000, 0).single().unwrap();
+        assert!(needs_workshop_update(steam, None, None, false));
+    }
+
+    #[test]
+    fn test_needs_workshop_update_force() {
+        let steam = chrono::Utc.timestamp_opt(1_000, 0).single().unwrap();
+        let stored = chrono::Utc.timestamp_opt(9_000, 0).single().unwrap();
+        assert!(needs_workshop_update(steam, Some(stored), None, true));
+    }
+
+    #[tokio::test]
+    async fn test_workshop_update_file_copy_overwrites_target() {
+        let (service, _registry, dirs) = setup_test_service().await;
+        let downloaded = dirs.addons_path().join("workshop.vpk");
+        let payload = b"downloaded-workshop-bytes";
+        tokio::fs::write(&downloaded, payload).await.unwrap();
+
+        let target = dirs.addons_path().join("installed.vpk");
+        tokio::fs::write(&target, b"old-bytes").await.unwrap();
+
+        let (source_vpk, cleanup) = service
+            .prepare_vpk_from_download(downloaded.clone())
+            .await
+            .unwrap();
+        tokio::fs::copy(&source_vpk, &target).await.unwrap();
+        cleanup.cleanup().await;
+
+        let on_disk = tokio::fs::read(&target).await.unwrap();
+        assert_eq!(on_disk, payload);
+    }
+
+    #[tokio::test]
+    async fn test_workshop_check_only_skips_non_workshop_md5() {
+        let (service, registry, dirs) = setup_test_service().await;
+
+        // Large-ish file that would be expensive to MD5 if resolve ran.
+        let rel = "l4d2center/big.vpk";
+        let path = dirs.addons_path().join(rel);
+        tokio::fs::create_dir_all(path.parent().unwrap()).await.unwrap();
+        let chunk = vec![0u8; 256 * 1024];
+        let mut file = tokio::fs::File::create(&path).await.unwrap();
+        for _ in 0..8 {
+            tokio::io::AsyncWriteExt::write_all(&mut file, &chunk)
+                .await
+                .unwrap();
+        }
+        drop(file);
+
+        let map_entry = MapEntry {
+            source_url: "https://example.com/big.zip".to_string(),
+            source_kind: SourceKind::L4d2Center,
+            installed_path: rel.to_string(),
+            ..test_helpers::test_map_entry("L4D2Center Map")
         };
+        registry.add_map(map_entry).await.unwrap();
+
+        let started = std::time::Instant::now();
+        let report = service
+            .update_workshop_maps(None, false, true)
+            .await
+            .unwrap();
+        // Without the skip, MD5 of ~2MiB would still be fast locally, but we assert
+        // the map was classified as not_workshop without becoming a candidate.
+        assert_eq!(report.not_workshop, 1);
+        assert!(report.available.is_empty());
+        assert!(report.failed.is_empty());
+        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+    }
+
+    #[test]
+    fn resolve_vpk_filename_prefers_title_over_uuid_basename() {
+        let source = PathBuf::from(
+            "a247bd11-15e7-427a-990a-85964ca65bab-9661ED51C9125DAE6B9CFDDC4F22F0AC46FC335F.vpk",
+        );
+        let name = MapInstallationService::resolve_vpk_filename(
+            None,
+            Some("dark_carnival_remix"),
+            &source,
+        );
+        assert_eq!(name, "dark_carnival_remix.vpk");
+    }
+
+    #[test]
+    fn resolve_vpk_filename_expected_wins_over_title() {
+        let source = PathBuf::from("uuid-hash.vpk");
+        let name = MapInstallationService::resolve_vpk_filename(
+            Some("catalog_campaign"),
+            Some("addon_title"),
+            &source,
+        );
+        assert_eq!(name, "catalog_campaign.vpk");
+    }
+
+    #[test]
+    fn resolve_vpk_filename_falls_back_when_title_missing() {
+        let source = PathBuf::from(
+            "a247bd11-15e7-427a-990a-85964ca65bab-9661ED51C9125DAE6B9CFDDC4F22F0AC46FC335F.vpk",
+        );
+        let name = MapInstallationService::resolve_vpk_filename(None, None, &source);
+        assert_eq!(
+            name,
+            "a247bd11-15e7-427a-990a-85964ca65bab-9661ED51C9125DAE6B9CFDDC4F22F0AC46FC335F.vpk"
+        );
+    }
+
+    #[test]
+    fn preferred_vpk_stem_skips_unknown_metadata_title() {
+        assert_eq!(
+            MapInstallationService::preferred_vpk_stem(None, "Unknown"),
+            None
+        );
+        assert_eq!(
+            MapInstallationService::preferred_vpk_stem(None, "My Cool Map"),
+            Some("my_cool_map".to_string())
+        );
+        assert_eq!(
+            MapInstallationService::preferred_vpk_stem(Some("API Name"), "Unknown"),
+            Some("api_name".to_string())
+        );
+    }
+
+
+    #[tokio::test]
+    async fn test_detect_map_skips_unreadable_file_when_checksum_required() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.detect_require_checksum = true;
+        let service = service.with_config(crate::config::init_handle(config));
+
+        // A directory with a .vpk name cannot be read as a file, so MD5 fails.
+        let path = dirs.addons_path().join("unreadable.vpk");
+        tokio::fs::create_dir_all(&path).await.unwrap();
+
+        let result = service.detect_map_from_path(path).await.unwrap();
+        assert!(result.is_none());
+        assert!(registry.list_maps().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_detect_map_registers_without_checksum_by_default() {
+        let (service, registry, dirs) = setup_test_service().await;
+
+        let path = dirs.addons_path().join("unreadable.vpk");
+        tokio::fs::create_dir_all(&path).await.unwrap();
+
+        let entry = service
+            .detect_map_from_path(path)
+            .await
+            .unwrap()
+            .expect("registered without checksum");
+        assert_eq!(entry.source_kind, SourceKind::Detected);
+        assert!(entry.checksum.is_none());
+        assert!(entry.checksum_kind.is_none());
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_install_from_url_rejects_host_outside_allowlist() {
+        let (service, registry, _dirs) = setup_test_service().await;
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.allowed_download_hosts = vec!["*.l4d2center.com".to_string()];
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let error = service
+            .install_from_url(
+                "https://evil.example.com/map.zip".to_string(),
+                None,
+                InstallOptions::default(),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("allowed_download_hosts"));
+        assert!(registry.list_maps().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_install_from_local_path_vpk_and_zip() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let staging = TempDir::new().unwrap();
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.local_install_dirs = vec![staging.path().to_path_buf()];
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let vpk_path = staging.path().join("local_map.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Local Map").unwrap();
+        let vpk_entry = service
+            .install_from_local_path(vpk_path.clone(), None, InstallOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(vpk_entry.name, "local_map");
+        assert_eq!(
+            vpk_entry.source_url,
+            format!("file:{}", vpk_path.canonicalize().unwrap().display())
+        );
+        assert!(vpk_path.exists(), "original file is left in place");
+        assert!(dirs.addons_path().join(&vpk_entry.installed_path).exists());
+
+        let zipped_vpk = TempDir::new().unwrap();
+        let inner = zipped_vpk.path().join("zipped.vpk");
+        test_helpers::write_minimal_test_vpk(&inner, "Zipped Map").unwrap();
+        let (built_zip, _zip_temp) =
+            create_test_zip_with_map(&[("zipped.vpk", &std::fs::read(&inner).unwrap())]);
+        let zip_path = staging.path().join("zipped.zip");
+        std::fs::copy(&built_zip, &zip_path).unwrap();
+        let zip_entry = service
+            .install_from_local_path(zip_path.clone(), None, InstallOptions::default())
+            .await
+            .unwrap();
+        assert!(zip_entry.installed_path.ends_with(".vpk"));
+        assert!(zip_entry.source_url.starts_with("file:"));
+        assert!(zip_path.exists());
+
+        assert_eq!(registry.list_maps().await.unwrap().len(), 2);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_installed_vpk_gets_configured_file_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (service, _registry, dirs) = setup_test_service().await;
+        let staging = TempDir::new().unwrap();
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.local_install_dirs = vec![staging.path().to_path_buf()];
+        config.installed_file_mode = Some("0640".to_string());
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let vpk_path = staging.path().join("mode_map.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Mode Map").unwrap();
+        std::fs::set_permissions(&vpk_path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        let entry = service
+            .install_from_local_path(vpk_path, None, InstallOptions::default())
+            .await
+            .unwrap();
 
-        let mut fresh = MapEntry {
-            id: 1,
-            name: "Fresh".to_string(),
-            source_url: "detected:/addons/map.vpk".to_string(),
-            source_kind: SourceKind::Other,
-            workshop_id: None,
-            installed_path: "map.vpk".to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: Some("2".to_string()),
-            checksum: Some("new".to_string()),
-            checksum_kind: Some("md5".to_string()),
-        };
+        let installed = dirs.addons_path().join(&entry.installed_path);
+        let mode = std::fs::metadata(installed).unwrap().permissions().mode();
+        assert_eq!(mode & 0o7777, 0o640);
+    }
 
-        MapInstallationService::preserve_source_identity(&mut fresh, &existing);
+    /// Assert the API status and error code `error` is reported with.
+    async fn assert_install_error_code(error: anyhow::Error, status: axum::http::StatusCode, code: &str) {
+        let response = axum::response::IntoResponse::into_response(
+            crate::api::service_error::classify_install_error(error),
+        );
+        assert_eq!(response.status(), status);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(parsed["code"], code);
+    }
 
-        assert_eq!(fresh.workshop_id, Some(12345));
-        assert_eq!(fresh.source_kind, SourceKind::Workshop);
-        assert_eq!(
-            fresh.source_url,
-            "https://steamcommunity.com/sharedfiles/filedetails/?id=12345"
+    #[tokio::test]
+    async fn test_only_downloader_failures_are_reported_as_download_failed() {
+        let (mut service, _registry, dirs) = setup_test_service().await;
+        service.zip_downloader =
+            ZipDownloader::new_insecure_for_tests(dirs.service_paths().download_dir, 1024 * 1024)
+                .await
+                .unwrap();
+        let addr = test_helpers::spawn_test_server(axum::Router::new()).await;
+        let error = service
+            .install_from_zip_url(&format!("http://{addr}/missing.zip"), None, InstallOptions::default())
+            .await
+            .unwrap_err();
+        assert_install_error_code(error, axum::http::StatusCode::INTERNAL_SERVER_ERROR, "download_failed").await;
+
+        // Mentioning downloads or Steam is not enough, and the detail stays hidden.
+        let error = anyhow::anyhow!("Failed to create Download dir /srv/steam: permission denied");
+        let response = axum::response::IntoResponse::into_response(
+            crate::api::service_error::classify_install_error(error),
         );
-        assert_eq!(fresh.name, "Fresh");
-        assert_eq!(fresh.version, Some("2".to_string()));
-        assert_eq!(fresh.checksum, Some("new".to_string()));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let parsed: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_ne!(parsed["code"], "download_failed");
+        assert_eq!(parsed["error"], "Internal server error");
     }
 
-    #[test]
-    fn test_preserve_source_identity_uses_fresh_when_workshop_id_detected() {
-        let existing = MapEntry {
-            id: 1,
-            name: "Existing".to_string(),
-            source_url: "detected:/addons/map.vpk".to_string(),
-            source_kind: SourceKind::Other,
-            workshop_id: None,
-            installed_path: "map.vpk".to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: Some("1".to_string()),
-            checksum: Some("old".to_string()),
-            checksum_kind: Some("md5".to_string()),
+    #[tokio::test]
+    async fn test_require_signed_vpk_rejects_tampered_signature() {
+        use rsa::pkcs8::{EncodePublicKey, LineEnding};
+
+        let (service, registry, dirs) = setup_test_service().await;
+        let staging = TempDir::new().unwrap();
+        let key_path = staging.path().join("vpk-signing.pem");
+        let pem = test_helpers::test_vpk_signing_key()
+            .to_public_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        std::fs::write(&key_path, pem).unwrap();
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.local_install_dirs = vec![staging.path().to_path_buf()];
+        config.vpk_signing_public_key = Some(key_path);
+        config.require_signed_vpk = true;
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let tampered = staging.path().join("tampered.vpk");
+        test_helpers::write_signed_test_vpk(&tampered, "Tampered Map").unwrap();
+        let mut bytes = std::fs::read(&tampered).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        std::fs::write(&tampered, bytes).unwrap();
+        let error = service
+            .install_from_local_path(tampered, None, InstallOptions::default())
+            .await
+            .unwrap_err();
+        assert!(format!("{error:#}").contains("signature does not match"));
+        assert_install_error_code(error, axum::http::StatusCode::UNPROCESSABLE_ENTITY, "signature_invalid").await;
+        assert!(registry.list_maps().await.unwrap().is_empty());
+
+        let unsigned = staging.path().join("unsigned.vpk");
+        test_helpers::write_minimal_test_vpk(&unsigned, "Unsigned Map").unwrap();
+        let error = service
+            .install_from_local_path(unsigned, None, InstallOptions::default())
+            .await
+            .unwrap_err();
+        assert_install_error_code(error, axum::http::StatusCode::UNPROCESSABLE_ENTITY, "signature_invalid").await;
+
+        let signed = staging.path().join("signed.vpk");
+        test_helpers::write_signed_test_vpk(&signed, "Signed Map").unwrap();
+        let entry = service
+            .install_from_local_path(signed, None, InstallOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(entry.name, "signed_map");
+        assert!(dirs.addons_path().join(&entry.installed_path).exists());
+    }
+
+    #[tokio::test]
+    async fn test_install_from_local_path_rejects_paths_outside_allowed_dirs() {
+        let (service, registry, _dirs) = setup_test_service().await;
+        let staging = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        let outside_vpk = outside.path().join("outside.vpk");
+        test_helpers::write_minimal_test_vpk(&outside_vpk, "Outside").unwrap();
+
+        let disabled = service
+            .install_from_local_path(outside_vpk.clone(), None, InstallOptions::default())
+            .await
+            .unwrap_err();
+        assert!(disabled.to_string().contains("local installs are disabled"));
+
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.local_install_dirs = vec![staging.path().to_path_buf()];
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let escaped = staging.path().join("..").join(outside.path().file_name().unwrap()).join("outside.vpk");
+        for path in [outside_vpk, escaped, staging.path().to_path_buf()] {
+            let error = service
+                .install_from_local_path(path, None, InstallOptions::default())
+                .await
+                .unwrap_err();
+            assert!(error.to_string().starts_with("Invalid local path"), "{error}");
+        }
+        assert!(registry.list_maps().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_temp_removes_only_old_artifacts() {
+        let (service, _registry, _dirs) = setup_test_service().await;
+        let two_days_ago = std::time::SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60);
+
+        let old_download = service.temp_dir.join("0000-old_map.zip");
+        std::fs::write(&old_download, b"stale").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&old_download)
+            .unwrap()
+            .set_modified(two_days_ago)
+            .unwrap();
+        let old_extract = service.temp_dir.join("extract-old");
+        std::fs::create_dir(&old_extract).unwrap();
+        std::fs::write(old_extract.join("map.vpk"), b"stale").unwrap();
+        std::fs::File::open(&old_extract)
+            .unwrap()
+            .set_modified(two_days_ago)
+            .unwrap();
+
+        let recent_download = service.temp_dir.join("1111-new_map.zip");
+        std::fs::write(&recent_download, b"in progress").unwrap();
+        let recent_extract = service.temp_dir.join("extract-new");
+        std::fs::create_dir(&recent_extract).unwrap();
+
+        let mut report = service
+            .cleanup_temp(Duration::from_secs(24 * 60 * 60))
+            .await
+            .unwrap();
+        report.removed.sort();
+
+        assert_eq!(report.removed, vec!["0000-old_map.zip", "extract-old"]);
+        assert_eq!(report.kept, 2);
+        assert_eq!(report.failed, 0);
+        assert!(!old_download.exists());
+        assert!(!old_extract.exists());
+        assert!(recent_download.exists());
+        assert!(recent_extract.is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_scan_integrity_flags_corrupted_and_missing_files() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let addons = dirs.addons_path();
+        tokio::fs::create_dir_all(&addons).await.unwrap();
+
+        let mut ids = Vec::new();
+        for name in ["intact", "corrupted", "missing", "unhashed"] {
+            let installed_path = format!("{name}.vpk");
+            let file = addons.join(&installed_path);
+            std::fs::write(&file, format!("{name} contents")).unwrap();
+            let checksum = if name == "unhashed" {
+                None
+            } else {
+                Some(crate::utils::calculate_file_md5(&file).await.unwrap())
+            };
+            let entry = MapEntry {
+                source_kind: SourceKind::Url,
+                installed_path,
+                checksum_kind: checksum.as_ref().map(|_| "md5".to_string()),
+                checksum,
+                ..test_helpers::test_map_entry(name)
+            };
+            ids.push(registry.add_map(entry).await.unwrap());
+        }
+        std::fs::write(addons.join("corrupted.vpk"), b"tampered").unwrap();
+        std::fs::remove_file(addons.join("missing.vpk")).unwrap();
+
+        let report = service.scan_integrity().await.unwrap();
+
+        assert_eq!(report.verified, 1);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(report.mismatched, vec![ids[1]]);
+        assert_eq!(report.missing, vec![ids[2]]);
+    }
+
+    #[tokio::test]
+    async fn test_update_map_replaces_file_and_keeps_entry_id() {
+        let (mut service, registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir, 1024 * 1024)
+            .await
+            .unwrap();
+
+        let mirror_dir = TempDir::new().unwrap();
+        test_helpers::write_minimal_test_vpk(&mirror_dir.path().join("v1.vpk"), "Update Me").unwrap();
+        test_helpers::write_minimal_test_vpk(&mirror_dir.path().join("v2.vpk"), "Update Me v2").unwrap();
+        let v2_checksum = crate::utils::calculate_file_md5(&mirror_dir.path().join("v2.vpk"))
+            .await
+            .unwrap();
+        let served = mirror_dir.path().to_path_buf();
+        let mirror = axum::Router::new().route(
+            "/{file}",
+            axum::routing::get(move |axum::extract::Path(file): axum::extract::Path<String>| {
+                let path = served.join(file);
+                async move { std::fs::read(path).unwrap() }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let installed = service
+            .install_from_zip_url(&format!("http://{addr}/v1.vpk"), None, InstallOptions::default())
+            .await
+            .unwrap();
+        assert!(service.update_map(installed.id + 100, &installed, &[]).await.unwrap().is_none());
+
+        let target = MapEntry {
+            source_url: format!("http://{addr}/v2.vpk"),
+            version: Some("2.0".to_string()),
+            checksum: Some(v2_checksum.clone()),
+            ..installed.clone()
         };
+        let updated = service
+            .update_map_from_url(&installed, &target, &target.source_url)
+            .await
+            .unwrap();
 
-        let mut fresh = MapEntry {
-            id: 1,
-            name: "Fresh".to_string(),
-            source_url: "https://steamcommunity.com/sharedfiles/filedetails/?id=999".to_string(),
-            source_kind: SourceKind::Workshop,
-            workshop_id: Some(999),
-            installed_path: "map.vpk".to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: Some("2".to_string()),
-            checksum: Some("new".to_string()),
-            checksum_kind: Some("md5".to_string()),
+        assert_eq!(updated.id, installed.id);
+        assert_eq!(updated.installed_path, installed.installed_path);
+        assert_eq!(updated.version.as_deref(), Some("2.0"));
+        assert_eq!(updated.checksum.as_deref(), Some(v2_checksum.as_str()));
+        assert_ne!(updated.checksum, installed.checksum);
+        let stored = registry.get_map(installed.id).await.unwrap().unwrap();
+        assert_eq!(stored.checksum, updated.checksum);
+        assert_eq!(stored.source_url, target.source_url);
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_update_map_falls_back_to_mirror_on_checksum_mismatch() {
+        let (mut service, registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir, 1024 * 1024)
+            .await
+            .unwrap();
+
+        let mirror_dir = TempDir::new().unwrap();
+        test_helpers::write_minimal_test_vpk(&mirror_dir.path().join("v1.vpk"), "Update Me").unwrap();
+        test_helpers::write_minimal_test_vpk(&mirror_dir.path().join("v2.vpk"), "Update Me v2").unwrap();
+        let v2_checksum = crate::utils::calculate_file_md5(&mirror_dir.path().join("v2.vpk"))
+            .await
+            .unwrap();
+        let served = mirror_dir.path().to_path_buf();
+        let mirror = axum::Router::new().route(
+            "/{file}",
+            axum::routing::get(move |axum::extract::Path(file): axum::extract::Path<String>| {
+                let path = served.join(file);
+                async move { std::fs::read(path).unwrap() }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let installed = service
+            .install_from_zip_url(&format!("http://{addr}/v1.vpk"), None, InstallOptions::default())
+            .await
+            .unwrap();
+
+        // The source still serves v1, which does not match the target checksum.
+        let target = MapEntry {
+            version: Some("2.0".to_string()),
+            checksum: Some(v2_checksum.clone()),
+            ..installed.clone()
         };
+        let updated = service
+            .update_map(installed.id, &target, &[format!("http://{addr}/v2.vpk")])
+            .await
+            .unwrap()
+            .unwrap();
 
-        MapInstallationService::preserve_source_identity(&mut fresh, &existing);
+        assert_eq!(updated.id, installed.id);
+        assert_eq!(updated.checksum.as_deref(), Some(v2_checksum.as_str()));
+        let stored = registry.get_map(installed.id).await.unwrap().unwrap();
+        assert_eq!(stored.checksum, updated.checksum);
+    }
 
-        assert_eq!(fresh.workshop_id, Some(999));
-        assert_eq!(fresh.source_kind, SourceKind::Workshop);
+    #[tokio::test]
+    async fn test_update_map_cleans_up_download_when_backup_fails() {
+        let (mut service, registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir.clone(), 1024 * 1024)
+            .await
+            .unwrap();
+
+        let mirror_dir = TempDir::new().unwrap();
+        test_helpers::write_minimal_test_vpk(&mirror_dir.path().join("v1.vpk"), "Backup Fails").unwrap();
+        test_helpers::write_minimal_test_vpk(&mirror_dir.path().join("v2.vpk"), "Backup Fails v2").unwrap();
+        let served = mirror_dir.path().to_path_buf();
+        let mirror = axum::Router::new().route(
+            "/{file}",
+            axum::routing::get(move |axum::extract::Path(file): axum::extract::Path<String>| {
+                let path = served.join(file);
+                async move { std::fs::read(path).unwrap() }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let installed = service
+            .install_from_zip_url(&format!("http://{addr}/v1.vpk"), None, InstallOptions::default())
+            .await
+            .unwrap();
+        let install_path = dirs.addons_path().join(&installed.installed_path);
+        let original = std::fs::read(&install_path).unwrap();
+        // A directory where the backup goes makes the backup copy fail.
+        std::fs::create_dir(install_path.with_extension("vpk.bak")).unwrap();
+
+        let target = MapEntry {
+            source_url: format!("http://{addr}/v2.vpk"),
+            version: Some("2.0".to_string()),
+            checksum: None,
+            ..installed.clone()
+        };
+        let error = service
+            .update_map_from_url(&installed, &target, &target.source_url)
+            .await
+            .unwrap_err();
+
+        assert!(format!("{error:#}").contains("Failed to back up existing map"), "{error:#}");
+        assert_eq!(std::fs::read(&install_path).unwrap(), original);
+        let stored = registry.get_map(installed.id).await.unwrap().unwrap();
+        assert_eq!(stored.checksum, installed.checksum);
+        let leftovers: Vec<_> = std::fs::read_dir(&download_dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .collect();
+        assert!(leftovers.is_empty(), "temp files left behind: {leftovers:?}");
+    }
+
+    #[tokio::test]
+    async fn test_update_map_is_noop_when_mirror_answers_not_modified() {
+        use axum::http::{header, HeaderMap, Method, StatusCode};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let (mut service, registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir, 1024 * 1024)
+            .await
+            .unwrap();
+
+        let mirror_dir = TempDir::new().unwrap();
+        let vpk = mirror_dir.path().join("map.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk, "Unchanged Map").unwrap();
+        let body = std::fs::read(&vpk).unwrap();
+        let full_downloads = Arc::new(AtomicUsize::new(0));
+        let counter = full_downloads.clone();
+        let mirror = axum::Router::new().route(
+            "/map.vpk",
+            axum::routing::get(move |method: Method, headers: HeaderMap| {
+                let body = body.clone();
+                let counter = counter.clone();
+                async move {
+                    let validators = [
+                        (header::LAST_MODIFIED, "Wed, 01 Jan 2025 00:00:00 GMT"),
+                        (header::ETAG, "\"v1\""),
+                    ];
+                    if headers.get(header::IF_NONE_MATCH).is_some_and(|etag| etag == "\"v1\"") {
+                        return (StatusCode::NOT_MODIFIED, validators, Vec::new());
+                    }
+                    if method == Method::GET {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                    }
+                    (StatusCode::OK, validators, body)
+                }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let url = format!("http://{addr}/map.vpk");
+        let installed = service
+            .install_from_zip_url(&url, None, InstallOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(installed.source_etag.as_deref(), Some("\"v1\""));
         assert_eq!(
-            fresh.source_url,
-            "https://steamcommunity.com/sharedfiles/filedetails/?id=999"
+            installed.source_last_modified.as_deref(),
+            Some("Wed, 01 Jan 2025 00:00:00 GMT")
+        );
+        let installed = registry.get_map(installed.id).await.unwrap().unwrap();
+        let installed_file = dirs.addons_path().join(&installed.installed_path);
+        let installed_mtime = std::fs::metadata(&installed_file).unwrap().modified().unwrap();
+
+        let target = MapEntry {
+            version: Some("2.0".to_string()),
+            ..installed.clone()
+        };
+        let updated = service
+            .update_map_from_url(&installed, &target, &url)
+            .await
+            .unwrap();
+
+        assert_eq!(full_downloads.load(Ordering::SeqCst), 1);
+        assert_eq!(updated.version, installed.version);
+        assert_eq!(updated.updated_at, installed.updated_at);
+        let stored = registry.get_map(installed.id).await.unwrap().unwrap();
+        assert_eq!(stored.version, installed.version);
+        assert_eq!(stored.updated_at, installed.updated_at);
+        assert_eq!(
+            std::fs::metadata(&installed_file).unwrap().modified().unwrap(),
+            installed_mtime
         );
     }
 
     #[tokio::test]
-    async fn test_detect_map_from_path_is_idempotent_for_installed_path() {
+    async fn test_import_existing_maps_registers_untracked_vpks_once() {
         let (service, registry, dirs) = setup_test_service().await;
-        tokio::fs::write(dirs.addons_path().join("alpha.vpk"), b"vpk")
+        test_helpers::write_minimal_test_vpk(&dirs.addons_path().join("alpha.vpk"), "Alpha").unwrap();
+        test_helpers::write_minimal_test_vpk(&dirs.addons_path().join("beta.vpk"), "Beta").unwrap();
+
+        let report = service.import_existing_maps().await.unwrap();
+        assert_eq!(report.added.len(), 2);
+        assert_eq!(report.failed, 0);
+        let mut paths: Vec<String> = registry
+            .list_maps()
             .await
-            .unwrap();
+            .unwrap()
+            .into_iter()
+            .map(|map| map.installed_path)
+            .collect();
+        paths.sort();
+        assert_eq!(paths, vec!["alpha.vpk".to_string(), "beta.vpk".to_string()]);
+
+        let again = service.import_existing_maps().await.unwrap();
+        assert!(again.added.is_empty());
+        assert_eq!(again.skipped, 2);
+        assert_eq!(registry.list_maps().await.unwrap().len(), 2);
+    }
 
-        let id = registry
+    #[tokio::test]
+    async fn test_force_uninstall_removes_orphaned_file_and_stale_entries() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let orphan = dirs.addons_path().join("orphan.vpk");
+        test_helpers::write_minimal_test_vpk(&orphan, "Orphan").unwrap();
+
+        let report = service.force_uninstall_by_path("orphan.vpk").await.unwrap();
+        assert!(report.file_removed);
+        assert!(report.removed_map_ids.is_empty());
+        assert!(!orphan.exists());
+
+        let stale_id = registry
             .add_map(MapEntry {
-                id: 0,
-                name: "Alpha".to_string(),
-                source_url: "https://example.com/alpha".to_string(),
-                source_kind: SourceKind::Other,
-                workshop_id: None,
-                installed_path: "alpha.vpk".to_string(),
-                installed_at: chrono::Utc::now(),
-                workshop_updated_at: None,
-                version: None,
-                checksum: None,
-                checksum_kind: None,
+                installed_path: "gone.vpk".to_string(),
+                ..create_modify_test_entry()
             })
             .await
             .unwrap();
+        let report = service.force_uninstall_by_path("gone.vpk").await.unwrap();
+        assert!(!report.file_removed);
+        assert_eq!(report.removed_map_ids, vec![stale_id]);
+        assert!(registry.get_map(stale_id).await.unwrap().is_none());
 
-        let path = dirs.addons_path().join("alpha.vpk");
-        let result = service.detect_map_from_path(path).await.unwrap().unwrap();
-        assert_eq!(result.id, id);
-        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+        let error = service.force_uninstall_by_path("gone.vpk").await.unwrap_err();
+        assert!(error.to_string().contains("not found"), "{error}");
     }
 
     #[tokio::test]
-    async fn test_sync_map_from_path_returns_existing_when_metadata_unavailable() {
+    async fn test_force_uninstall_rejects_paths_outside_addons() {
+        let (service, _registry, dirs) = setup_test_service().await;
+        let outside = dirs.addons_path().parent().unwrap().join("outside.vpk");
+        test_helpers::write_minimal_test_vpk(&outside, "Outside").unwrap();
+
+        for path in ["../outside.vpk", "maps/../../outside.vpk", "/etc/passwd.vpk", "", "gameinfo.txt"] {
+            let error = service.force_uninstall_by_path(path).await.unwrap_err();
+            assert!(error.to_string().starts_with("Invalid path"), "{path}: {error}");
+        }
+        assert!(outside.exists());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(&outside, dirs.addons_path().join("link.vpk")).unwrap();
+            assert!(service.force_uninstall_by_path("link.vpk").await.is_err());
+            assert!(outside.exists());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recompute_checksums_migrates_md5_entries_to_sha256() {
         let (service, registry, dirs) = setup_test_service().await;
-        tokio::fs::write(dirs.addons_path().join("alpha.vpk"), b"vpk")
+        let alpha = dirs.addons_path().join("alpha.vpk");
+        test_helpers::write_minimal_test_vpk(&alpha, "Alpha").unwrap();
+        service.import_existing_maps().await.unwrap();
+        let alpha_id = registry.list_maps().await.unwrap()[0].id;
+
+        let center = dirs.addons_path().join("center.vpk");
+        test_helpers::write_minimal_test_vpk(&center, "Center").unwrap();
+        let center_id = registry
+            .add_map(MapEntry {
+                name: "Center".to_string(),
+                source_kind: SourceKind::L4d2Center,
+                workshop_id: None,
+                installed_path: "center.vpk".to_string(),
+                checksum: Some(crate::utils::calculate_file_md5(&center).await.unwrap()),
+                checksum_kind: Some("md5".to_string()),
+                ..create_modify_test_entry()
+            })
             .await
             .unwrap();
-
-        let id = registry
+        test_helpers::write_minimal_test_vpk(&dirs.addons_path().join("drift.vpk"), "Drift").unwrap();
+        let drift_id = registry
             .add_map(MapEntry {
-                id: 0,
-                name: "Alpha".to_string(),
-                source_url: "https://example.com/alpha".to_string(),
-                source_kind: SourceKind::Other,
-                workshop_id: None,
-                installed_path: "alpha.vpk".to_string(),
-                installed_at: chrono::Utc::now(),
-                workshop_updated_at: None,
-                version: None,
-                checksum: Some("old".to_string()),
+                installed_path: "drift.vpk".to_string(),
+                checksum: Some("00000000000000000000000000000000".to_string()),
                 checksum_kind: Some("md5".to_string()),
+                ..create_modify_test_entry()
             })
             .await
             .unwrap();
 
-        let result = service
-            .sync_map_from_path(dirs.addons_path().join("alpha.vpk"))
+        let config = Config {
+            default_checksum_kind: "sha256".to_string(),
+            recompute_checksums_on_start: true,
+            ..Config::default()
+        };
+        let service = service.with_config(crate::config::init_handle(config));
+        assert_eq!(service.recompute_checksums().await.unwrap(), 1);
+
+        let migrated = registry.get_map(alpha_id).await.unwrap().unwrap();
+        assert_eq!(migrated.checksum_kind.as_deref(), Some("sha256"));
+        assert_eq!(
+            migrated.checksum,
+            Some(crate::utils::calculate_file_checksum(&alpha, "sha256").await.unwrap())
+        );
+        let center_entry = registry.get_map(center_id).await.unwrap().unwrap();
+        assert_eq!(center_entry.checksum_kind.as_deref(), Some("md5"));
+        let drift_entry = registry.get_map(drift_id).await.unwrap().unwrap();
+        assert_eq!(drift_entry.checksum.as_deref(), Some("00000000000000000000000000000000"));
+
+        assert_eq!(service.recompute_checksums().await.unwrap(), 0);
+        test_helpers::write_minimal_test_vpk(&dirs.addons_path().join("beta.vpk"), "Beta").unwrap();
+        let report = service.import_existing_maps().await.unwrap();
+        assert_eq!(report.added[0].checksum_kind.as_deref(), Some("sha256"));
+    }
+
+    #[tokio::test]
+    async fn test_recorded_size_matches_installed_file() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let vpk_path = dirs.addons_path().join("alpha.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Alpha").unwrap();
+
+        service.import_existing_maps().await.unwrap();
+
+        let maps = registry.list_maps().await.unwrap();
+        assert_eq!(maps.len(), 1);
+        assert_eq!(maps[0].size_bytes, Some(std::fs::metadata(&vpk_path).unwrap().len()));
+    }
+
+    #[tokio::test]
+    async fn test_installed_size_sums_split_archive_parts() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("pak_dir.vpk"), vec![0u8; 100]).unwrap();
+        std::fs::write(temp_dir.path().join("pak_000.vpk"), vec![0u8; 1000]).unwrap();
+        std::fs::write(temp_dir.path().join("pak_001.vpk"), vec![0u8; 10]).unwrap();
+        std::fs::write(temp_dir.path().join("pak_002.vpk"), vec![0u8; 5]).unwrap();
+        // Same prefix, but not a numbered part of the archive.
+        std::fs::write(temp_dir.path().join("pak_0003.vpk"), vec![0u8; 7]).unwrap();
+        std::fs::write(temp_dir.path().join("pak_extra.vpk"), vec![0u8; 7]).unwrap();
+
+        let dir_path = temp_dir.path().join("pak_dir.vpk");
+        assert_eq!(MapInstallationService::installed_size(&dir_path).await, Some(1115));
+
+        // A missing first part must not hide the parts after it.
+        std::fs::remove_file(temp_dir.path().join("pak_000.vpk")).unwrap();
+        assert_eq!(MapInstallationService::installed_size(&dir_path).await, Some(115));
+        let missing = MapInstallationService::installed_size(&temp_dir.path().join("gone.vpk")).await;
+        assert_eq!(missing, None);
+    }
+
+    #[tokio::test]
+    async fn test_map_footprint_sums_sizes_by_source_kind() {
+        let (service, registry, _dirs) = setup_test_service().await;
+        for (path, kind, size) in [
+            ("a.vpk", SourceKind::Workshop, Some(100)),
+            ("b.vpk", SourceKind::Workshop, Some(50)),
+            ("c.vpk", SourceKind::Url, Some(25)),
+            ("d.vpk", SourceKind::Detected, None),
+        ] {
+            let mut entry = MapEntry::new(0, path.to_string(), String::new(), path.to_string());
+            entry.source_kind = kind;
+            entry.size_bytes = size;
+            registry.add_map(entry).await.unwrap();
+        }
+
+        let footprint = service.map_footprint().await.unwrap();
+        assert_eq!(footprint.total_bytes, 175);
+        assert_eq!(footprint.map_count, 4);
+        assert_eq!(footprint.by_source_kind.get(&SourceKind::Workshop), Some(&150));
+        assert_eq!(footprint.by_source_kind.get(&SourceKind::Url), Some(&25));
+        assert_eq!(footprint.by_source_kind.get(&SourceKind::Detected), None);
+        assert_eq!(footprint.unsized_map_ids.len(), 1);
+
+        let json = serde_json::to_value(&footprint).unwrap();
+        assert_eq!(json["by_source_kind"]["workshop"], 150);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_map_sizes_records_legacy_entries() {
+        let (service, registry, dirs) = setup_test_service().await;
+        std::fs::write(dirs.addons_path().join("legacy.vpk"), vec![0u8; 42]).unwrap();
+        let id = registry
+            .add_map(MapEntry::new(0, "Legacy".to_string(), String::new(), "legacy.vpk".to_string()))
+            .await
+            .unwrap();
+        registry
+            .add_map(MapEntry::new(0, "Gone".to_string(), String::new(), "gone.vpk".to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(service.backfill_map_sizes().await.unwrap(), 1);
+        assert_eq!(registry.get_map(id).await.unwrap().unwrap().size_bytes, Some(42));
+        assert_eq!(service.backfill_map_sizes().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_blocklist_refuses_workshop_id_and_url_before_download() {
+        let (service, registry, _dirs) = setup_test_service().await;
+        let config = Config {
+            blocked_workshop_ids: vec![381419931],
+            blocked_url_patterns: vec!["https://bad-mirror.example.com/*".to_string()],
+            ..Config::default()
+        };
+        let service = service.with_config(crate::config::init_handle(config));
+
+        // Neither request reaches Steam or the network: the check runs before any download.
+        let error = service
+            .install_from_workshop_id(381419931, None, InstallOptions::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("blocked by policy"), "{error}");
+
+        let error = service
+            .install_from_url(
+                "https://BAD-MIRROR.example.com/maps/evil.zip".to_string(),
+                None,
+                InstallOptions::default(),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("blocked by policy"), "{error}");
+
+        assert!(registry.list_maps().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_blocklist_refuses_redirect_to_blocked_url() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let (mut service, registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir.clone(), 1024 * 1024)
+            .await
+            .unwrap();
+        let config = Config {
+            blocked_url_patterns: vec!["*/blocked/*".to_string()],
+            ..Config::default()
+        };
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let blocked_hits = Arc::new(AtomicUsize::new(0));
+        let counter = blocked_hits.clone();
+        let mirror = axum::Router::new()
+            .route(
+                "/start.zip",
+                axum::routing::get(|| async {
+                    axum::response::Redirect::temporary("/%62locked/map.zip")
+                }),
+            )
+            .route(
+                "/blocked/map.zip",
+                axum::routing::get(move || {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    async { Vec::<u8>::new() }
+                }),
+            );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let error = service
+            .install_from_zip_url(
+                &format!("http://{addr}/start.zip"),
+                None,
+                InstallOptions::default(),
+            )
             .await
-            .unwrap()
-            .unwrap();
-        assert_eq!(result.id, id);
-        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+            .unwrap_err();
+
+        assert!(format!("{error:#}").contains("blocked by policy"), "{error:#}");
+        assert_eq!(blocked_hits.load(Ordering::SeqCst), 0);
+        assert!(registry.list_maps().await.unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_build_map_entry_from_file_uses_filename_fallback_when_metadata_missing() {
-        let (service, _registry, dirs) = setup_test_service().await;
-        let path = dirs.addons_path().join("bts_l4d2.vpk");
-        tokio::fs::write(&path, b"not a vpk").await.unwrap();
-
-        let entry = service
-            .build_map_entry_from_file(&path, "bts_l4d2.vpk")
+    async fn test_blocklist_refuses_share_link_resolved_to_blocked_url() {
+        let (mut service, registry, dirs) = setup_test_service().await;
+        service.zip_downloader =
+            ZipDownloader::new_insecure_for_tests(dirs.service_paths().download_dir, 1024 * 1024)
+                .await
+                .unwrap();
+        // Only the direct-download form the Dropbox resolver produces is blocked.
+        let service = service.with_config(crate::config::init_handle(Config {
+            blocked_url_patterns: vec!["https://www.dropbox.com/*dl=1".to_string()],
+            ..Config::default()
+        }));
+
+        let error = service
+            .install_from_url(
+                "https://www.dropbox.com/s/abc/map.zip?dl=0".to_string(),
+                None,
+                InstallOptions::default(),
+            )
             .await
-            .unwrap()
-            .expect("fallback entry");
+            .unwrap_err();
 
-        assert_eq!(entry.name, "bts_l4d2");
-        assert_eq!(entry.installed_path, "bts_l4d2.vpk");
-        assert!(entry.version.is_none());
-        assert_eq!(entry.source_kind, SourceKind::Other);
+        assert!(format!("{error:#}").contains("map.zip?dl=1 is blocked by policy"), "{error:#}");
+        assert!(registry.list_maps().await.unwrap().is_empty());
     }
 
+    #[cfg(unix)]
     #[tokio::test]
-    async fn test_remove_map_by_path_prunes_when_file_gone() {
-        let (service, registry, dirs) = setup_test_service().await;
-        let id = registry
-            .add_map(MapEntry {
-                id: 0,
-                name: "Gone".to_string(),
-                source_url: "https://example.com/gone".to_string(),
-                source_kind: SourceKind::Other,
-                workshop_id: None,
-                installed_path: "gone.vpk".to_string(),
-                installed_at: chrono::Utc::now(),
-                workshop_updated_at: None,
-                version: None,
-                checksum: None,
-                checksum_kind: None,
-            })
+    async fn test_post_install_hook_script_runs_for_installed_map() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (mut service, _registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir, 1024 * 1024)
             .await
             .unwrap();
 
-        let removed = service
-            .remove_map_by_path(dirs.addons_path().join("gone.vpk"))
+        let hook_dir = TempDir::new().unwrap();
+        let out = hook_dir.path().join("hook.out");
+        let script = hook_dir.path().join("installed.sh");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\nprintf '%s %s' \"$KETHER_MAP_ID\" \"$KETHER_MAP_PATH\" > '{}.tmp'\n/bin/mv '{0}.tmp' '{0}'\n",
+                out.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let config = Config {
+            post_install_command: Some(script),
+            ..Config::default()
+        };
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let mirror_dir = TempDir::new().unwrap();
+        test_helpers::write_minimal_test_vpk(&mirror_dir.path().join("hooked.vpk"), "Hooked").unwrap();
+        let served = mirror_dir.path().join("hooked.vpk");
+        let mirror = axum::Router::new().route(
+            "/hooked.vpk",
+            axum::routing::get(move || {
+                let served = served.clone();
+                async move { std::fs::read(served).unwrap() }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let entry = service
+            .install_from_zip_url(&format!("http://{addr}/hooked.vpk"), None, InstallOptions::default())
             .await
             .unwrap();
-        assert_eq!(removed, Some(id));
-        assert!(registry.get_map(id).await.unwrap().is_none());
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while !out.exists() {
+            assert!(std::time::Instant::now() < deadline, "post-install hook did not run");
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            format!(
+                "{} {}",
+                entry.id,
+                dirs.addons_path().join(&entry.installed_path).display()
+            )
+        );
     }
 
     #[tokio::test]
-    async fn test_remove_map_by_path_noop_when_file_exists() {
-        let (service, registry, dirs) = setup_test_service().await;
-        tokio::fs::write(dirs.addons_path().join("exists.vpk"), b"vpk")
-            .await
-            .unwrap();
+    async fn test_install_event_is_posted_to_webhook() {
+        let (received_tx, mut received_rx) = tokio::sync::mpsc::unbounded_channel();
+        let receiver = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+                let received_tx = received_tx.clone();
+                async move {
+                    let _ = received_tx.send(body);
+                }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(receiver).await;
 
-        let id = registry
-            .add_map(MapEntry {
-                id: 0,
-                name: "Exists".to_string(),
-                source_url: "https://example.com/exists".to_string(),
-                source_kind: SourceKind::Other,
-                workshop_id: None,
-                installed_path: "exists.vpk".to_string(),
-                installed_at: chrono::Utc::now(),
-                workshop_updated_at: None,
-                version: None,
-                checksum: None,
-                checksum_kind: None,
-            })
+        let (service, _registry, _dirs) = setup_test_service().await;
+        let staging = TempDir::new().unwrap();
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.local_install_dirs = vec![staging.path().to_path_buf()];
+        config.event_webhook_url = Some(format!("http://{addr}/hook"));
+        let service = service
+            .with_config(crate::config::init_handle(config))
+            .with_webhook_notifier(WebhookNotifier::new_insecure_for_tests());
+
+        let vpk_path = staging.path().join("hooked_map.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Hooked Map").unwrap();
+        let entry = service
+            .install_from_local_path(vpk_path, None, InstallOptions::default())
             .await
             .unwrap();
 
-        let removed = service
-            .remove_map_by_path(dirs.addons_path().join("exists.vpk"))
+        let event = tokio::time::timeout(std::time::Duration::from_secs(5), received_rx.recv())
             .await
+            .expect("webhook called")
             .unwrap();
-        assert_eq!(removed, None);
-        assert!(registry.get_map(id).await.unwrap().is_some());
+        assert_eq!(event["event"], "install");
+        assert_eq!(event["map_id"], entry.id);
+        assert_eq!(event["name"], "hooked_map");
+        assert!(event["timestamp"].is_string());
+    }
+
+    async fn install_staged_vpk(
+        template: Option<&str>,
+        maps: &[(&str, &str)],
+    ) -> (Vec<MapEntry>, Arc<dyn Registry>, test_helpers::TestDirs) {
+        let (service, registry, dirs) = setup_test_service().await;
+        let staging = TempDir::new().unwrap();
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.local_install_dirs = vec![staging.path().to_path_buf()];
+        config.install_filename_template = template.map(str::to_string);
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let mut entries = Vec::new();
+        for (file, title) in maps {
+            let vpk_path = staging.path().join(file);
+            test_helpers::write_minimal_test_vpk(&vpk_path, title).unwrap();
+            entries.push(
+                service
+                    .install_from_local_path(vpk_path, None, InstallOptions::default())
+                    .await
+                    .unwrap(),
+            );
+        }
+        (entries, registry, dirs)
     }
 
     #[tokio::test]
-    async fn test_install_workshop_id_returns_existing_without_redownload() {
-        let (service, registry, _dirs) = setup_test_service().await;
-        let workshop_id = 3135451698u64;
-        let existing_id = registry
-            .add_map(MapEntry {
-                id: 0,
-                name: "Workshop Map".to_string(),
-                source_url: String::new(),
-                source_kind: SourceKind::Workshop,
-                workshop_id: Some(workshop_id),
-                installed_path: "workshop_map.vpk".to_string(),
-                installed_at: chrono::Utc::now(),
-                workshop_updated_at: None,
-                version: None,
-                checksum: None,
-                checksum_kind: None,
-            })
+    async fn test_installed_filename_defaults_to_vpk_title() {
+        let (entries, registry, dirs) =
+            install_staged_vpk(None, &[("c1m1_download.vpk", "Dead Center Remix")]).await;
+
+        assert_eq!(entries[0].installed_path, "dead_center_remix.vpk");
+        let stored = registry.get_map(entries[0].id).await.unwrap().unwrap();
+        assert_eq!(stored.installed_path, "dead_center_remix.vpk");
+        assert!(dirs.addons_path().join("dead_center_remix.vpk").exists());
+    }
+
+    #[tokio::test]
+    async fn test_install_filename_template_prefixes_map_id() {
+        let (entries, registry, dirs) = install_staged_vpk(
+            Some("{map_id}_{original}"),
+            &[("first.vpk", "First Map"), ("second.vpk", "Second Map")],
+        )
+        .await;
+
+        for (entry, expected) in entries.iter().zip(["first_map.vpk", "second_map.vpk"]) {
+            let expected = format!("{}_{expected}", entry.id);
+            assert_eq!(entry.installed_path, expected);
+            let stored = registry.get_map(entry.id).await.unwrap().unwrap();
+            assert_eq!(stored.installed_path, expected);
+            assert!(dirs.addons_path().join(&expected).exists());
+        }
+        assert_eq!(entries[1].installed_path, "2_second_map.vpk");
+    }
+
+    #[tokio::test]
+    async fn test_generic_download_url_installs_under_vpk_title() {
+        let (mut service, registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir, 1024 * 1024)
             .await
             .unwrap();
 
-        let result = service
-            .install_from_workshop_id(workshop_id, None)
+        let mirror_dir = TempDir::new().unwrap();
+        let vpk = mirror_dir.path().join("served.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk, "Hard Rain Reloaded").unwrap();
+        let body = std::fs::read(&vpk).unwrap();
+        let mirror = axum::Router::new().route(
+            "/download",
+            axum::routing::get(move || {
+                let body = body.clone();
+                async move { body }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let entry = service
+            .install_from_zip_url(&format!("http://{addr}/download?file=x"), None, InstallOptions::default())
             .await
             .unwrap();
-        assert_eq!(result.id, existing_id);
-        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+
+        assert_eq!(entry.installed_path, "hard_rain_reloaded.vpk");
+        let stored = registry.get_map(entry.id).await.unwrap().unwrap();
+        assert_eq!(stored.installed_path, "hard_rain_reloaded.vpk");
+        assert!(dirs.addons_path().join("hard_rain_reloaded.vpk").exists());
     }
 
     #[tokio::test]
-    async fn test_find_map_by_name_returns_existing_entry() {
-        let (service, registry, _dirs) = setup_test_service().await;
-        let id = registry
-            .add_map(MapEntry {
-                id: 0,
-                name: "existing_map".to_string(),
-                source_url: "https://example.com/map".to_string(),
-                source_kind: SourceKind::Other,
-                workshop_id: None,
-                installed_path: "existing.vpk".to_string(),
-                installed_at: chrono::Utc::now(),
-                workshop_updated_at: None,
-                version: None,
-                checksum: None,
-                checksum_kind: None,
-            })
+    async fn test_untitled_vpk_in_generic_download_is_named_after_archive_entry() {
+        let (mut service, registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir, 1024 * 1024)
             .await
             .unwrap();
 
-        let found = service.find_map_by_name("existing_map").await.unwrap();
-        assert_eq!(found.unwrap().id, id);
-    }
-
-    #[test]
-    fn test_needs_workshop_update_stored_timestamp() {
-        let steam = chrono::Utc.timestamp_opt(2_000, 0).single().unwrap();
-        let stored = chrono::Utc.timestamp_opt(1_000, 0).single().unwrap();
-        assert!(needs_workshop_update(steam, Some(stored), None, false));
-        assert!(!needs_workshop_update(stored, Some(steam), None, false));
-    }
+        let mirror_dir = TempDir::new().unwrap();
+        let vpk = mirror_dir.path().join("untitled.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk, "").unwrap();
+        let vpk_bytes = std::fs::read(&vpk).unwrap();
+        let (zip_path, _zip_dir) =
+            create_test_zip_with_map(&[("maps/hard_rain_reloaded.vpk", vpk_bytes.as_slice())]);
+        let archive = std::fs::read(zip_path).unwrap();
+        let mirror = axum::Router::new().route(
+            "/download",
+            axum::routing::get(move || {
+                let archive = archive.clone();
+                async move { archive }
+            }),
+        );
+        let addr = test_helpers::spawn_test_server(mirror).await;
 
-    #[test]
-    fn test_needs_workshop_update_mtime_fallback() {
-        let steam = chrono::Utc.timestamp_opt(2_000, 0).single().unwrap();
-        let mtime = chrono::Utc.timestamp_opt(1_000, 0).single().unwrap();
-        assert!(needs_workshop_update(steam, None, Some(mtime), false));
-        assert!(!needs_workshop_update(mtime, None, Some(steam), false));
-    }
+        let entry = service
+            .install_from_zip_url(&format!("http://{addr}/download?file=x"), None, InstallOptions::default())
+            .await
+            .unwrap();
 
-    #[test]
-    fn test_needs_workshop_update_no_signals_defaults_outdated() {
-        let steam = chrono::Utc.timestamp_opt(1_000, 0).single().unwrap();
-        assert!(needs_workshop_update(steam, None, None, false));
+        assert_eq!(entry.name, "hard_rain_reloaded");
+        assert_eq!(entry.installed_path, "hard_rain_reloaded.vpk");
+        let stored = registry.get_map(entry.id).await.unwrap().unwrap();
+        assert_eq!(stored.installed_path, "hard_rain_reloaded.vpk");
     }
 
-    #[test]
-    fn test_needs_workshop_update_force() {
-        let steam = chrono::Utc.timestamp_opt(1_000, 0).single().unwrap();
-        let stored = chrono::Utc.timestamp_opt(9_000, 0).single().unwrap();
-        assert!(needs_workshop_update(steam, Some(stored), None, true));
+    #[tokio::test]
+    async fn test_remove_map_file_drops_one_part_and_keeps_entry() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let addons = dirs.addons_path();
+        test_helpers::write_minimal_test_vpk(&addons.join("split_dir.vpk"), "Split Map").unwrap();
+        std::fs::write(addons.join("split_000.vpk"), vec![0u8; 1000]).unwrap();
+        let dir_size = std::fs::metadata(addons.join("split_dir.vpk")).unwrap().len();
+        let mut entry = MapEntry::new(0, "split_map".to_string(), "file:split_dir.vpk".to_string(), "split_dir.vpk".to_string());
+        entry.size_bytes = Some(dir_size + 1000);
+        let map_id = registry.add_map(entry).await.unwrap();
+
+        assert!(service.remove_map_file(map_id, "split_dir.vpk").await.is_err());
+        assert!(service.remove_map_file(map_id, "other_000.vpk").await.is_err());
+        assert!(service.remove_map_file(map_id, "../split_000.vpk").await.is_err());
+        assert!(service.remove_map_file(map_id + 1, "split_000.vpk").await.is_err());
+
+        let updated = service.remove_map_file(map_id, "split_000.vpk").await.unwrap();
+        assert!(!addons.join("split_000.vpk").exists());
+        assert!(addons.join("split_dir.vpk").exists());
+        assert_eq!(updated.size_bytes, Some(dir_size));
+        let stored = registry.get_map(map_id).await.unwrap().unwrap();
+        assert_eq!(stored.installed_path, "split_dir.vpk");
+        assert_eq!(stored.size_bytes, Some(dir_size));
     }
 
     #[tokio::test]
-    async fn test_workshop_update_file_copy_overwrites_target() {
-        let (service, _registry, dirs) = setup_test_service().await;
-        let downloaded = dirs.addons_path().join("workshop.vpk");
-        let payload = b"downloaded-workshop-bytes";
-        tokio::fs::write(&downloaded, payload).await.unwrap();
-
-        let target = dirs.addons_path().join("installed.vpk");
-        tokio::fs::write(&target, b"old-bytes").await.unwrap();
-
-        let (source_vpk, cleanup) = service
-            .prepare_vpk_from_download(downloaded.clone())
+    async fn test_install_lands_in_every_server_dir_under_one_entry() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let staging = TempDir::new().unwrap();
+        let second_server = TempDir::new().unwrap();
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.local_install_dirs = vec![staging.path().to_path_buf()];
+        config.server_dirs = vec![second_server.path().to_path_buf()];
+        let second_addons = config.instance_addons_dirs().remove(0);
+        std::fs::create_dir_all(&second_addons).unwrap();
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let vpk_path = staging.path().join("download.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Twin Servers").unwrap();
+        let entry = service
+            .install_from_local_path(vpk_path, None, InstallOptions::default())
             .await
             .unwrap();
-        tokio::fs::copy(&source_vpk, &target).await.unwrap();
-        cleanup.cleanup().await;
 
-        let on_disk = tokio::fs::read(&target).await.unwrap();
-        assert_eq!(on_disk, payload);
+        let primary_copy = dirs.addons_path().join("twin_servers.vpk");
+        let second_copy = second_addons.join("twin_servers.vpk");
+        assert!(primary_copy.exists());
+        assert!(second_copy.exists());
+        let maps = registry.list_maps().await.unwrap();
+        assert_eq!(maps.len(), 1);
+        assert_eq!(maps[0].instance_paths, vec![second_copy.to_string_lossy().into_owned()]);
+
+        service.uninstall_map(entry.id).await.unwrap();
+        assert!(!primary_copy.exists());
+        assert!(!second_copy.exists());
     }
 
     #[tokio::test]
-    async fn test_workshop_check_only_skips_non_workshop_md5() {
+    async fn test_read_only_refuses_installs_and_removals() {
         let (service, registry, dirs) = setup_test_service().await;
+        let staging = TempDir::new().unwrap();
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.local_install_dirs = vec![staging.path().to_path_buf()];
+        let config = crate::config::init_handle(config);
+        let service = service.with_config(config.clone());
+
+        let vpk_path = staging.path().join("download.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Frozen").unwrap();
+        let entry = service
+            .install_from_local_path(vpk_path.clone(), None, InstallOptions::default())
+            .await
+            .unwrap();
+        let installed = dirs.addons_path().join(&entry.installed_path);
 
-        // Large-ish file that would be expensive to MD5 if resolve ran.
-        let rel = "l4d2center/big.vpk";
-        let path = dirs.addons_path().join(rel);
-        tokio::fs::create_dir_all(path.parent().unwrap()).await.unwrap();
-        let chunk = vec![0u8; 256 * 1024];
-        let mut file = tokio::fs::File::create(&path).await.unwrap();
-        for _ in 0..8 {
-            tokio::io::AsyncWriteExt::write_all(&mut file, &chunk)
-                .await
-                .unwrap();
-        }
-        drop(file);
-
-        let map_entry = MapEntry {
-            id: 0,
-            name: "L4D2Center Map".to_string(),
-            source_url: "https://example.com/big.zip".to_string(),
-            source_kind: SourceKind::L4d2Center,
-            workshop_id: None,
-            installed_path: rel.to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
-        };
-        registry.add_map(map_entry).await.unwrap();
+        let mut frozen = (*crate::config::read_config(&config)).clone();
+        frozen.read_only = true;
+        *config.write().unwrap() = Arc::new(frozen);
 
-        let started = std::time::Instant::now();
-        let report = service
-            .update_workshop_maps(None, false, true)
+        let other_path = staging.path().join("other.vpk");
+        test_helpers::write_minimal_test_vpk(&other_path, "Other").unwrap();
+        let error = service
+            .install_from_local_path(other_path, None, InstallOptions::default())
             .await
-            .unwrap();
-        // Without the skip, MD5 of ~2MiB would still be fast locally, but we assert
-        // the map was classified as not_workshop without becoming a candidate.
-        assert_eq!(report.not_workshop, 1);
-        assert!(report.available.is_empty());
-        assert!(report.failed.is_empty());
-        assert!(started.elapsed() < std::time::Duration::from_secs(2));
+            .unwrap_err();
+        assert!(error.to_string().contains("read-only mode"), "{error:#}");
+        assert!(service.uninstall_map(entry.id).await.is_err());
+        assert!(service.rename_map(entry.id, "Thawed").await.is_err());
+        assert!(service.update_workshop_maps(None, false, false).await.is_err());
+        assert!(service.update_workshop_maps(None, false, true).await.is_ok());
+
+        assert!(installed.exists());
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
     }
 
-    #[test]
-    fn resolve_vpk_filename_prefers_title_over_uuid_basename() {
-        let source = PathBuf::from(
-            "a247bd11-15e7-427a-990a-85964ca65bab-9661ED51C9125DAE6B9CFDDC4F22F0AC46FC335F.vpk",
-        );
-        let name = MapInstallationService::resolve_vpk_filename(
-            None,
-            Some("dark_carnival_remix"),
-            &source,
-        );
-        assert_eq!(name, "dark_carnival_remix.vpk");
+    #[tokio::test]
+    async fn test_install_rolls_back_when_a_server_dir_cannot_take_its_copy() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let staging = TempDir::new().unwrap();
+        let second_server = TempDir::new().unwrap();
+        let third_server = TempDir::new().unwrap();
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.local_install_dirs = vec![staging.path().to_path_buf()];
+        config.server_dirs = vec![second_server.path().to_path_buf(), third_server.path().to_path_buf()];
+        // Only the second server has an addons directory.
+        let second_addons = config.instance_addons_dirs().remove(0);
+        std::fs::create_dir_all(&second_addons).unwrap();
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let vpk_path = staging.path().join("download.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Half Installed").unwrap();
+        let error = service
+            .install_from_local_path(vpk_path, None, InstallOptions::default())
+            .await
+            .unwrap_err();
+
+        assert!(format!("{error:#}").contains("server instance"), "{error:#}");
+        assert!(!dirs.addons_path().join("half_installed.vpk").exists());
+        assert!(!second_addons.join("half_installed.vpk").exists());
+        assert!(registry.list_maps().await.unwrap().is_empty());
     }
 
-    #[test]
-    fn resolve_vpk_filename_expected_wins_over_title() {
-        let source = PathBuf::from("uuid-hash.vpk");
-        let name = MapInstallationService::resolve_vpk_filename(
-            Some("catalog_campaign"),
-            Some("addon_title"),
-            &source,
+    #[tokio::test]
+    async fn test_update_map_keeps_newer_installed_version() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let installed_path = dirs.addons_path().join("versioned.vpk");
+        test_helpers::write_minimal_test_vpk(&installed_path, "Versioned").unwrap();
+        let mut entry = MapEntry::new(
+            0,
+            "versioned".to_string(),
+            "http://127.0.0.1:9/versioned.vpk".to_string(),
+            "versioned.vpk".to_string(),
         );
-        assert_eq!(name, "catalog_campaign.vpk");
+        entry.version = Some("1.10".to_string());
+        let map_id = registry.add_map(entry).await.unwrap();
+        let existing = registry.get_map(map_id).await.unwrap().unwrap();
+
+        // 1.9 < 1.10, so no download is attempted (the source is unreachable anyway).
+        let target = MapEntry {
+            version: Some("1.9".to_string()),
+            ..existing.clone()
+        };
+        let kept = service.update_map(map_id, &target, &[]).await.unwrap().unwrap();
+        assert_eq!(kept.version.as_deref(), Some("1.10"));
+        assert_eq!(kept.checksum, existing.checksum);
     }
 
-    #[test]
-    fn resolve_vpk_filename_falls_back_when_title_missing() {
-        let source = PathBuf::from(
-            "a247bd11-15e7-427a-990a-85964ca65bab-9661ED51C9125DAE6B9CFDDC4F22F0AC46FC335F.vpk",
+    #[tokio::test]
+    async fn test_watch_install_streams_until_completion() {
+        use futures_util::StreamExt;
+
+        let (mut service, _registry, dirs) = setup_test_service().await;
+        let download_dir = dirs.service_paths().download_dir;
+        service.zip_downloader = ZipDownloader::new_insecure_for_tests(download_dir, 1024 * 1024)
+            .await
+            .unwrap();
+
+        let mirror_dir = TempDir::new().unwrap();
+        let vpk_path = mirror_dir.path().join("slow.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Slow Map").unwrap();
+        let vpk = std::fs::read(&vpk_path).unwrap();
+        let mirror = axum::Router::new().route(
+            "/slow.vpk",
+            axum::routing::get(move || {
+                let vpk = vpk.clone();
+                async move {
+                    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+                    vpk
+                }
+            }),
         );
-        let name = MapInstallationService::resolve_vpk_filename(None, None, &source);
+        let addr = test_helpers::spawn_test_server(mirror).await;
+
+        let url = format!("http://{addr}/slow.vpk");
+        let install = service.install_from_zip_url(&url, None, InstallOptions::default());
+        let watch = async {
+            let operation_id = loop {
+                if let Some(operation) = service.install_operations().first() {
+                    break operation.id;
+                }
+                tokio::task::yield_now().await;
+            };
+            let events: Vec<MapEvent> = service.watch_install(operation_id).unwrap().collect().await;
+            (operation_id, events)
+        };
+        let (result, (operation_id, events)) = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            async { tokio::join!(install, watch) },
+        )
+        .await
+        .expect("watched install should finish");
+
+        let entry = result.unwrap();
+        assert!(events.iter().all(|event| event.operation_id() == Some(operation_id)));
+        assert!(events.iter().any(|event| matches!(event, MapEvent::InstallProgress { .. })));
         assert_eq!(
-            name,
-            "a247bd11-15e7-427a-990a-85964ca65bab-9661ED51C9125DAE6B9CFDDC4F22F0AC46FC335F.vpk"
+            events.last(),
+            Some(&MapEvent::InstallCompleted {
+                source: url.clone(),
+                operation_id,
+                map_id: entry.id,
+                name: entry.name.clone(),
+            })
         );
+        assert!(service.watch_install(operation_id).is_none());
     }
 
-    #[test]
-    fn preferred_vpk_stem_skips_unknown_metadata_title() {
-        assert_eq!(
-            MapInstallationService::preferred_vpk_stem(None, "Unknown"),
-            None
-        );
-        assert_eq!(
-            MapInstallationService::preferred_vpk_stem(None, "My Cool Map"),
-            Some("my_cool_map".to_string())
-        );
-        assert_eq!(
-            MapInstallationService::preferred_vpk_stem(Some("API Name"), "Unknown"),
-            Some("api_name".to_string())
-        );
+    #[tokio::test]
+    async fn test_zip_with_too_many_vpks_is_refused_before_extraction() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let staging = TempDir::new().unwrap();
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.local_install_dirs = vec![staging.path().to_path_buf()];
+        config.max_vpks_per_archive = 2;
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let vpk_path = staging.path().join("tiny.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Tiny").unwrap();
+        let vpk = std::fs::read(&vpk_path).unwrap();
+        let (zip_path, _zip_dir) = create_test_zip_with_map(&[
+            ("a.vpk", vpk.as_slice()),
+            ("b.vpk", vpk.as_slice()),
+            ("c.vpk", vpk.as_slice()),
+        ]);
+        let staged_zip = staging.path().join("many.zip");
+        std::fs::copy(&zip_path, &staged_zip).unwrap();
+
+        let err = service
+            .install_from_local_path(staged_zip, None, InstallOptions::default())
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("max_vpks_per_archive"), "unexpected error: {err:#}");
+        assert_install_error_code(err, axum::http::StatusCode::UNPROCESSABLE_ENTITY, "too_many_vpks").await;
+        assert!(registry.list_maps().await.unwrap().is_empty());
+        assert_eq!(std::fs::read_dir(dirs.addons_path()).unwrap().count(), 0);
+        assert_eq!(std::fs::read_dir(dirs.service_paths().download_dir).unwrap().count(), 0);
     }
 
+    #[tokio::test]
+    async fn test_install_with_300_byte_name_fits_the_file_name_limit() {
+        let (service, registry, dirs) = setup_test_service().await;
+        let staging = TempDir::new().unwrap();
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.local_install_dirs = vec![staging.path().to_path_buf()];
+        let service = service.with_config(crate::config::init_handle(config));
+
+        let vpk_path = staging.path().join("long_map.vpk");
+        test_helpers::write_minimal_test_vpk(&vpk_path, "Long Map").unwrap();
+        let long_name = "m".repeat(300);
+        let entry = service
+            .install_from_local_path(vpk_path, Some(long_name), InstallOptions::default())
+            .await
+            .unwrap();
+
+        assert!(entry.name.len() <= crate::utils::path_sanitizer::MAX_MAP_NAME_STEM_BYTES);
+        let file_name = Path::new(&entry.installed_path).file_name().unwrap();
+        assert!(file_name.len() + ".bak".len() <= crate::utils::path_sanitizer::MAX_MAP_NAME_BYTES);
+        assert!(dirs.addons_path().join(&entry.installed_path).exists());
+        assert_eq!(registry.list_maps().await.unwrap().len(), 1);
+    }
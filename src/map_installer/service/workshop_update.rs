@@ -10,6 +10,7 @@ use super::{
 };
 use crate::downloader::steam::{steam_time_to_utc, WorkshopFileDetails};
 use crate::extractor::traits::Extractor;
+use crate::map_installer::audit::AuditAction;
 use crate::registry::models::{MapEntry, SourceKind};
 
 impl MapInstallationService {
@@ -20,6 +21,9 @@ impl MapInstallationService {
         force: bool,
         check_only: bool,
     ) -> anyhow::Result<WorkshopUpdateReport> {
+        if !check_only {
+            self.ensure_writable()?;
+        }
         // Do not hold op_lock across downloads — only around disk/registry replace.
 
         let mut report = WorkshopUpdateReport {
@@ -119,11 +123,7 @@ impl MapInstallationService {
 
             info!(map_id, workshop_id, "Updating outdated workshop map");
 
-            let _download_permit = self
-                .download_semaphore
-                .acquire()
-                .await
-                .expect("download semaphore closed");
+            let _download_permit = self.acquire_download_permit().await;
 
             let Some(_active) = crate::map_installer::ActiveUpdateGuard::try_begin(
                 self.active_updates.clone(),
@@ -241,7 +241,7 @@ impl MapInstallationService {
         }
 
         let Some(fresh) = self
-            .build_map_entry_from_file(&path, &entry.installed_path)
+            .build_map_entry_from_file(&path, &entry.installed_path, &self.checksum_kind_of(entry))
             .await?
         else {
             return Ok(None);
@@ -259,6 +259,7 @@ impl MapInstallationService {
         let install_path = self.addons_dir.join(&existing.installed_path);
         crate::utils::validate_path_within_base_new(&install_path, &self.addons_dir)
             .context("Attempted to update map outside of addons directory")?;
+        crate::utils::ensure_no_symlinks_under(&self.addons_dir, &install_path)?;
 
         self.active_updates.set_progress(
             existing.id,
@@ -284,13 +285,17 @@ impl MapInstallationService {
 
         let backup_path = install_path.with_extension("vpk.bak");
         let had_existing = install_path.exists();
+        if let Err(error) = crate::utils::check_space_for_copy(&source_vpk, &self.addons_dir).await {
+            temp_cleanup.cleanup().await;
+            return Err(error).context("Disk space check failed before updating workshop map");
+        }
         if had_existing {
             tokio::fs::copy(&install_path, &backup_path)
                 .await
                 .context("Failed to back up existing workshop map before update")?;
         }
 
-        if let Err(error) = crate::utils::atomic_replace_file(&source_vpk, &install_path).await {
+        if let Err(error) = self.place_in_addons(&source_vpk, &install_path).await {
             temp_cleanup.cleanup().await;
             let _ = tokio::fs::remove_file(&backup_path).await;
             return Err(error).context("Failed to replace installed map file");
@@ -303,58 +308,81 @@ impl MapInstallationService {
 
         temp_cleanup.cleanup().await;
 
-        let metadata = match self
-            .vpk_extractor
-            .extract_vpk_metadata(install_path.clone())
-            .await
-        {
+        let metadata = match self.read_vpk_metadata(&install_path).await {
             Ok(metadata) => metadata,
             Err(error) => {
                 Self::restore_vpk_backup(&install_path, &backup_path, had_existing).await;
                 return Err(error);
             }
         };
-        let checksum = match crate::utils::calculate_file_md5(&install_path).await {
-            Ok(value) => Some(value),
-            Err(error) => {
-                warn!(
-                    map_id = existing.id,
-                    error = %error,
-                    "Failed to checksum replaced workshop map"
-                );
-                None
-            }
-        };
-        let checksum_kind = checksum.as_ref().map(|_| "md5".to_string());
+        let (checksum, checksum_kind) =
+            match self.checksum_installed(&install_path, SourceKind::Workshop).await {
+                Ok((value, kind)) => (Some(value), Some(kind)),
+                Err(error) => {
+                    warn!(
+                        map_id = existing.id,
+                        error = %error,
+                        "Failed to checksum replaced workshop map"
+                    );
+                    (None, None)
+                }
+            };
         let installed_at = Self::file_modified_time(&install_path)
             .await
             .unwrap_or_else(chrono::Utc::now);
 
         let mut updated = existing.clone();
         updated.version = Some(metadata.version);
+        updated.content_flags = metadata.content_flags;
         updated.checksum = checksum;
         updated.checksum_kind = checksum_kind;
         updated.workshop_updated_at = Some(workshop_updated_at);
         updated.installed_at = installed_at;
+        updated.size_bytes = Self::installed_size(&install_path).await;
         if updated.workshop_id.is_none() {
             updated.workshop_id = metadata.workshop_id;
             if updated.workshop_id.is_some() {
                 updated.source_kind = SourceKind::Workshop;
             }
         }
+        updated.instance_paths = match self.copy_to_instances(&install_path, &install_path).await {
+            Ok(paths) => paths,
+            Err(error) => {
+                Self::restore_vpk_backup(&install_path, &backup_path, had_existing).await;
+                return Err(error);
+            }
+        };
 
         if let Err(error) = self.registry.update_map(updated.clone()).await {
             Self::restore_vpk_backup(&install_path, &backup_path, had_existing).await;
             return Err(error).context("Failed to persist workshop map update in registry");
         }
+        self.audit
+            .record(AuditAction::Update, updated.id, Some(updated.source_url.clone()))
+            .await;
+        self.notify_webhook(AuditAction::Update, &updated);
 
         let _ = tokio::fs::remove_file(&backup_path).await;
         Ok(updated)
     }
 
+    /// Unpack an update download to its VPK and check the VPK's signature.
     pub(super) async fn prepare_vpk_from_download(
         &self,
         downloaded: PathBuf,
+    ) -> anyhow::Result<(PathBuf, DownloadTempCleanup)> {
+        let (source_vpk, temp_cleanup) = self.unpack_vpk_from_download(downloaded).await?;
+        if let Err(error) = self.check_vpk_signature(&source_vpk).await {
+            self.reject_download(&source_vpk, &format!("{error:#}")).await;
+            temp_cleanup.cleanup().await;
+            return Err(error);
+        }
+        Ok((source_vpk, temp_cleanup))
+    }
+
+    async fn unpack_vpk_from_download(
+        &self,
+        downloaded: PathBuf,
     ) -> anyhow::Result<(PathBuf, DownloadTempCleanup)> {
         let file_ext = downloaded
             .extension()
@@ -365,11 +393,6 @@ impl MapInstallationService {
         match file_ext.as_str() {
             "vpk" => Ok((downloaded, DownloadTempCleanup::empty())),
             "zip" => {
-                if !self.zip_contains_vpk(&downloaded).await? {
-                    let _ = tokio::fs::remove_file(&downloaded).await;
-                    return Err(anyhow::anyhow!("ZIP file does not contain any .vpk files"));
-                }
-
                 let extract_temp = self.temp_dir.join(format!(
                     "update-extract-{}",
                     std::time::SystemTime::now()
@@ -381,7 +404,7 @@ impl MapInstallationService {
 
                 if let Err(error) = self
                     .zip_extractor
-                    .extract_zip(downloaded.clone(), extract_temp.clone())
+                    .extract_zip_requiring_vpk(downloaded.clone(), extract_temp.clone(), self.archive_entry_policy())
                     .await
                 {
                     let _ = tokio::fs::remove_dir_all(&extract_temp).await;
@@ -404,7 +427,11 @@ impl MapInstallationService {
                 ))
             }
             "7z" => {
-                if !self.sevenz_extractor.sevenz_contains_vpk(&downloaded).await? {
+                if !self
+                    .sevenz_extractor
+                    .sevenz_contains_vpk(&downloaded, self.archive_entry_policy())
+                    .await?
+                {
                     let _ = tokio::fs::remove_file(&downloaded).await;
                     return Err(anyhow::anyhow!("7z file does not contain any .vpk files"));
                 }
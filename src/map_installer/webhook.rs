@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Fire-and-forget HTTP callbacks for install/uninstall/update events.
+//!
+//! Each event is POSTed as JSON to `event_webhook_url` from a background task, so a
+//! slow or unreachable receiver never delays the operation that produced it. The URL
+//! gets the same SSRF checks as downloads before every delivery.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use reqwest::redirect::Policy;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::downloader::client::HttpSettings;
+use crate::map_installer::audit::AuditAction;
+use crate::registry::models::MapEntry;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+const WEBHOOK_MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry; doubled for each further attempt.
+const WEBHOOK_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Body POSTed to the webhook.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    /// `install`, `uninstall` or `update`
+    pub event: AuditAction,
+    pub map_id: u64,
+    pub name: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Clone)]
+pub struct WebhookNotifier {
+    client: Client,
+    enforce_ssrf: bool,
+}
+
+impl WebhookNotifier {
+    /// Uses the same proxy and `User-Agent` as download clients.
+    pub fn new(http: &HttpSettings) -> anyhow::Result<Self> {
+        Ok(Self {
+            client: Self::build_client(http)?,
+            enforce_ssrf: true,
+        })
+    }
+
+    /// Test helper: skip SSRF checks so loopback mock receivers work.
+    #[cfg(test)]
+    pub fn new_insecure_for_tests() -> Self {
+        Self {
+            client: Self::build_client(&HttpSettings::default()).unwrap(),
+            enforce_ssrf: false,
+        }
+    }
+
+    fn build_client(http: &HttpSettings) -> anyhow::Result<Client> {
+        Ok(http
+            .proxy
+            .apply(Client::builder())?
+            .timeout(WEBHOOK_TIMEOUT)
+            .redirect(Policy::none())
+            .user_agent(http.user_agent.as_str())
+            .build()?)
+    }
+
+    /// Deliver `action` for `entry` to `url` in the background; failures are only logged.
+    pub fn notify(&self, url: Option<&str>, action: AuditAction, entry: &MapEntry) {
+        let Some(url) = url.filter(|url| !url.trim().is_empty()) else {
+            return;
+        };
+        let notifier = self.clone();
+        let url = url.to_string();
+        let event = WebhookEvent {
+            event: action,
+            map_id: entry.id,
+            name: entry.name.clone(),
+            timestamp: Utc::now(),
+        };
+        tokio::spawn(async move {
+            if let Err(error) = notifier.deliver(&url, &event).await {
+                warn!(url = %url, map_id = event.map_id, event = ?event.event, error = %error, "Event webhook failed");
+            }
+        });
+    }
+
+    /// POST `event` to `url`, retrying connection errors and 5xx answers.
+    pub async fn deliver(&self, url: &str, event: &WebhookEvent) -> anyhow::Result<()> {
+        if self.enforce_ssrf {
+            crate::utils::validate_url_resolved(url).await?;
+        }
+        let mut attempt = 1;
+        loop {
+            let (error, retryable) = match self.client.post(url).json(event).send().await {
+                Ok(response) if response.status().is_success() => {
+                    debug!(url = %url, map_id = event.map_id, attempt, "Event webhook delivered");
+                    return Ok(());
+                }
+                Ok(response) => (
+                    anyhow::anyhow!("Webhook answered {}", response.status()),
+                    response.status().is_server_error(),
+                ),
+                Err(error) => (error.into(), true),
+            };
+            if !retryable || attempt >= WEBHOOK_MAX_ATTEMPTS {
+                return Err(error);
+            }
+            debug!(url = %url, attempt, error = %error, "Event webhook attempt failed; retrying");
+            tokio::time::sleep(WEBHOOK_RETRY_BASE_DELAY * 2_u32.pow(attempt - 1)).await;
+            attempt += 1;
+        }
+    }
+}
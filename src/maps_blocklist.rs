@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use percent_encoding::percent_decode_str;
+use url::Url;
+
+use crate::config::Config;
+
+/// Workshop IDs and URL patterns that must never be installed, whoever asks.
+#[derive(Debug, Clone, Default)]
+pub struct MapsBlocklist {
+    workshop_ids: HashSet<u64>,
+    url_patterns: Vec<String>,
+}
+
+impl MapsBlocklist {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            workshop_ids: config.blocked_workshop_ids.iter().copied().collect(),
+            url_patterns: config.blocked_url_patterns.clone(),
+        }
+    }
+
+    pub fn check_workshop_id(&self, workshop_id: u64) -> anyhow::Result<()> {
+        if self.workshop_ids.contains(&workshop_id) {
+            anyhow::bail!("Workshop item {workshop_id} is blocked by policy");
+        }
+        Ok(())
+    }
+
+    pub fn check_url(&self, url: &str) -> anyhow::Result<()> {
+        if let Some(pattern) = self
+            .url_patterns
+            .iter()
+            .find(|pattern| url_matches_pattern(url, pattern))
+        {
+            anyhow::bail!("URL {url} is blocked by policy (matches '{pattern}')");
+        }
+        Ok(())
+    }
+}
+
+/// The form URLs are matched in, so spelling variants of one URL cannot slip past a
+/// pattern: userinfo, the default port and any fragment are dropped, path and query
+/// are percent-decoded, and everything is lowercased. A URL that does not parse is
+/// only lowercased.
+fn normalize_url(url: &str) -> String {
+    let Ok(parsed) = Url::parse(url.trim()) else {
+        return url.trim().to_ascii_lowercase();
+    };
+    let mut normalized = format!("{}://{}", parsed.scheme(), parsed.host_str().unwrap_or_default());
+    if let Some(port) = parsed.port() {
+        let _ = write!(normalized, ":{port}");
+    }
+    normalized.push_str(&percent_decode_str(parsed.path()).decode_utf8_lossy());
+    if let Some(query) = parsed.query() {
+        normalized.push('?');
+        normalized.push_str(&percent_decode_str(query).decode_utf8_lossy());
+    }
+    normalized.to_ascii_lowercase()
+}
+
+/// Case-insensitive match of the whole normalized URL, where `*` stands for any run
+/// of characters. Patterns are percent-decoded and lowercased, but otherwise taken as written.
+fn url_matches_pattern(url: &str, pattern: &str) -> bool {
+    let url = normalize_url(url);
+    let pattern = percent_decode_str(pattern.trim())
+        .decode_utf8_lossy()
+        .to_ascii_lowercase();
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = url.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<&str> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // No `*` at all: exact match.
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocklist(workshop_ids: &[u64], url_patterns: &[&str]) -> MapsBlocklist {
+        MapsBlocklist {
+            workshop_ids: workshop_ids.iter().copied().collect(),
+            url_patterns: url_patterns.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn blocks_listed_workshop_ids_only() {
+        let bl = blocklist(&[381419931], &[]);
+        let error = bl.check_workshop_id(381419931).unwrap_err();
+        assert!(error.to_string().contains("blocked by policy"));
+        assert!(bl.check_workshop_id(1).is_ok());
+    }
+
+    #[test]
+    fn url_patterns_match_whole_url_with_wildcards() {
+        let bl = blocklist(&[], &["https://evil.example.com/*", "*/banned_map.zip"]);
+        assert!(bl.check_url("https://EVIL.example.com/maps/a.zip").is_err());
+        assert!(bl.check_url("https://mirror.example.org/files/banned_map.zip").is_err());
+        assert!(bl.check_url("https://mirror.example.org/files/banned_map.zip.txt").is_ok());
+        assert!(bl.check_url("https://good.example.com/evil.example.com/").is_ok());
+    }
+
+    #[test]
+    fn url_spelling_variants_still_match() {
+        let bl = blocklist(&[], &["https://evil.example.com/maps/*", "*/banned map.zip"]);
+        assert!(bl.check_url("https://user:pw@evil.example.com/maps/a.zip").is_err());
+        assert!(bl.check_url("https://evil.example.com:443/maps/a.zip").is_err());
+        assert!(bl.check_url("https://EVIL.example.com/%6Daps/a.zip").is_err());
+        assert!(bl.check_url("https://evil.example.com/other/../maps/a.zip").is_err());
+        assert!(bl.check_url("https://mirror.example.org/banned%20map.zip#top").is_err());
+        assert!(bl.check_url("https://evil.example.com:8443/maps/a.zip").is_ok());
+    }
+
+    #[test]
+    fn pattern_without_wildcard_is_exact() {
+        assert!(url_matches_pattern("https://a.example/x.zip", "https://a.example/x.zip"));
+        assert!(!url_matches_pattern("https://a.example/x.zip?v=2", "https://a.example/x.zip"));
+        assert!(url_matches_pattern("https://a.example/aba", "*ab*a"));
+        assert!(!url_matches_pattern("https://a.example/ab", "*ab*ab"));
+    }
+}
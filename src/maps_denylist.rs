@@ -38,7 +38,6 @@ impl Mapsdenylist {
 mod tests {
     use super::*;
     use crate::registry::models::SourceKind;
-    use chrono::Utc;
 
     fn sample_entry(id: u64, workshop_id: Option<u64>) -> MapEntry {
         MapEntry {
@@ -52,11 +51,7 @@ mod tests {
             },
             workshop_id,
             installed_path: format!("map_{id}.vpk"),
-            installed_at: Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..crate::test_helpers::test_map_entry("map")
         }
     }
 
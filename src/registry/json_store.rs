@@ -13,9 +13,10 @@ use tokio::sync::Mutex;
 use tracing::info;
 
 use crate::registry::{
-    models::{MapEntry, SourceKind},
+    models::{MapEntry, SourceKind, sanitize_tag},
     traits::Registry,
 };
+use crate::utils::sanitize_map_name;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct MapData {
@@ -30,6 +31,22 @@ struct MapData {
     version: Option<String>,
     checksum: Option<String>,
     checksum_kind: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size_bytes: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    content_flags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_last_modified: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source_etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    instance_paths: Vec<String>,
 }
 
 struct NumericOrderedSnapshot<'a>(&'a [(u64, &'a MapData)]);
@@ -51,6 +68,8 @@ pub struct JsonRegistry {
     inner: Arc<RwLock<HashMap<u64, MapData>>>,
     path: PathBuf,
     save_lock: Mutex<()>,
+    /// Highest ID handed out by `reserve_map_id`; new IDs are assigned above it.
+    reserved_id: AtomicU64,
 }
 
 impl JsonRegistry {
@@ -66,25 +85,65 @@ impl JsonRegistry {
             })?;
         }
 
-        let map = if path.exists() {
+        let mut map = if path.exists() {
             Self::load_from_file(path).await?
         } else {
             HashMap::new()
         };
+        let migrated = Self::backfill_updated_at(&mut map);
+        let reclassified = Self::reclassify_legacy_source_kinds(&mut map);
 
         let registry = Self {
             inner: Arc::new(RwLock::new(map)),
             path: path.clone(),
             save_lock: Mutex::new(()),
+            reserved_id: AtomicU64::new(0),
         };
 
-        if !path.exists() {
+        if !path.exists() || migrated > 0 || reclassified > 0 {
             registry.persist().await?;
         }
+        if migrated > 0 {
+            info!(count = migrated, "Backfilled updated_at for legacy registry entries");
+        }
+        if reclassified > 0 {
+            info!(count = reclassified, "Reclassified legacy 'other' registry entries as url/detected");
+        }
 
         Ok(registry)
     }
 
+    /// Migrate entries written before `updated_at` existed by seeding it from `installed_at`.
+    fn backfill_updated_at(map: &mut HashMap<u64, MapData>) -> usize {
+        let mut migrated = 0;
+        for data in map.values_mut() {
+            if data.updated_at.is_none() {
+                data.updated_at = Some(data.installed_at);
+                migrated += 1;
+            }
+        }
+        migrated
+    }
+
+    /// Entries written before `url`/`detected` existed were all `other`; recover the
+    /// real kind from the source URL the installer or detector recorded.
+    fn reclassify_legacy_source_kinds(map: &mut HashMap<u64, MapData>) -> usize {
+        let mut reclassified = 0;
+        for data in map.values_mut().filter(|data| data.source_kind == SourceKind::Other) {
+            let source = data.source_url.to_ascii_lowercase();
+            let kind = if source.starts_with("detected:") {
+                SourceKind::Detected
+            } else if source.starts_with("http://") || source.starts_with("https://") {
+                SourceKind::Url
+            } else {
+                continue;
+            };
+            data.source_kind = kind;
+            reclassified += 1;
+        }
+        reclassified
+    }
+
     async fn load_from_file(path: &PathBuf) -> anyhow::Result<HashMap<u64, MapData>> {
         let content = tokio::fs::read_to_string(path)
             .await
@@ -167,9 +226,87 @@ impl JsonRegistry {
             version: entry.version,
             checksum: entry.checksum,
             checksum_kind: entry.checksum_kind,
+            size_bytes: entry.size_bytes,
+            author: entry.author,
+            content_flags: entry.content_flags,
+            // Every registry write bumps the revision timestamp used by delta sync.
+            updated_at: Some(Utc::now()),
+            tags: Self::normalize_tags(&entry.tags),
+            source_last_modified: entry.source_last_modified,
+            source_etag: entry.source_etag,
+            instance_paths: entry.instance_paths,
         }
     }
 
+    /// Sanitized, sorted and deduplicated; unusable tags are dropped.
+    fn normalize_tags(tags: &[String]) -> Vec<String> {
+        let mut normalized = tags.iter().filter_map(|t| sanitize_tag(t)).collect::<Vec<_>>();
+        normalized.sort_unstable();
+        normalized.dedup();
+        normalized
+    }
+
+    /// Apply `edit` to a map's tag list and persist, restoring the old entry on failure.
+    async fn edit_tags(
+        &self,
+        id: u64,
+        edit: impl FnOnce(&mut Vec<String>),
+    ) -> anyhow::Result<MapEntry> {
+        let _guard = self.save_lock.lock().await;
+        let (previous, updated, snapshot) = {
+            let mut state = self
+                .inner
+                .write()
+                .map_err(|e| anyhow::anyhow!("Registry lock poisoned: {e}"))?;
+            let Some(data) = state.get_mut(&id) else {
+                return Err(anyhow::anyhow!("Map #{id} not found"));
+            };
+            let previous = data.clone();
+            edit(&mut data.tags);
+            data.tags = Self::normalize_tags(&data.tags);
+            data.updated_at = Some(Utc::now());
+            let updated = Self::map_entry_from_data(id, data);
+            (previous, updated, state.clone())
+        };
+
+        if let Err(error) = Self::save_snapshot(&self.path, &snapshot).await {
+            let mut state = self
+                .inner
+                .write()
+                .map_err(|e| anyhow::anyhow!("Registry lock poisoned: {e}"))?;
+            state.insert(id, previous);
+            return Err(error);
+        }
+        Ok(updated)
+    }
+
+    /// Lower is better; `None` when the (lowercased) needle matches no searchable field.
+    fn search_rank(data: &MapData, needle: &str) -> Option<u8> {
+        let name = data.name.to_lowercase();
+        if name == needle {
+            Some(0)
+        } else if name.starts_with(needle) {
+            Some(1)
+        } else if name.contains(needle) {
+            Some(2)
+        } else if data.installed_path.to_lowercase().contains(needle)
+            || data
+                .version
+                .as_deref()
+                .is_some_and(|v| v.to_lowercase().contains(needle))
+        {
+            Some(3)
+        } else {
+            None
+        }
+    }
+
+    /// One past the highest ID in `state` or reserved so far.
+    fn next_id(&self, state: &HashMap<u64, MapData>) -> u64 {
+        let highest = state.keys().max().copied().unwrap_or(0);
+        highest.max(self.reserved_id.load(Ordering::SeqCst)) + 1
+    }
+
     fn map_entry_from_data(id: u64, data: &MapData) -> MapEntry {
         MapEntry {
             id,
@@ -183,6 +320,14 @@ impl JsonRegistry {
             version: data.version.clone(),
             checksum: data.checksum.clone(),
             checksum_kind: data.checksum_kind.clone(),
+            size_bytes: data.size_bytes,
+            author: data.author.clone(),
+            content_flags: data.content_flags.clone(),
+            updated_at: data.updated_at,
+            tags: data.tags.clone(),
+            source_last_modified: data.source_last_modified.clone(),
+            source_etag: data.source_etag.clone(),
+            instance_paths: data.instance_paths.clone(),
         }
     }
 }
@@ -196,7 +341,7 @@ impl Registry for JsonRegistry {
                 .inner
                 .write()
                 .map_err(|e| anyhow::anyhow!("Registry lock poisoned: {e}"))?;
-            let id = state.keys().max().copied().unwrap_or(0) + 1;
+            let id = self.next_id(&state);
             entry.id = id;
             state.insert(id, Self::map_data_from_entry(entry));
             (id, state.clone())
@@ -215,6 +360,47 @@ impl Registry for JsonRegistry {
         Ok(id)
     }
 
+    async fn reserve_map_id(&self) -> anyhow::Result<u64> {
+        let state = self
+            .inner
+            .write()
+            .map_err(|e| anyhow::anyhow!("Registry lock poisoned: {e}"))?;
+        let id = self.next_id(&state);
+        self.reserved_id.store(id, Ordering::SeqCst);
+        Ok(id)
+    }
+
+    async fn add_map_with_id(&self, entry: MapEntry) -> anyhow::Result<u64> {
+        let _guard = self.save_lock.lock().await;
+        let id = entry.id;
+        let snapshot = {
+            let mut state = self
+                .inner
+                .write()
+                .map_err(|e| anyhow::anyhow!("Registry lock poisoned: {e}"))?;
+            if id == 0 || id > self.reserved_id.load(Ordering::SeqCst) {
+                anyhow::bail!("Map ID {id} was not reserved");
+            }
+            if state.contains_key(&id) {
+                anyhow::bail!("Map ID {id} is already in use");
+            }
+            state.insert(id, Self::map_data_from_entry(entry));
+            state.clone()
+        };
+
+        if let Err(error) = Self::save_snapshot(&self.path, &snapshot).await {
+            let mut state = self
+                .inner
+                .write()
+                .map_err(|e| anyhow::anyhow!("Registry lock poisoned: {e}"))?;
+            state.remove(&id);
+            return Err(error);
+        }
+
+        info!(map_id = id, "Added map to JSON registry");
+        Ok(id)
+    }
+
     async fn remove_map(&self, id: u64) -> anyhow::Result<()> {
         let _guard = self.save_lock.lock().await;
         let previous = {
@@ -387,6 +573,87 @@ impl Registry for JsonRegistry {
         }))
     }
 
+    async fn get_map_by_name(&self, name: &str) -> anyhow::Result<Vec<MapEntry>> {
+        let Ok(wanted) = sanitize_map_name(name) else {
+            return Ok(Vec::new());
+        };
+        let mut maps = {
+            let state = self
+                .inner
+                .read()
+                .map_err(|e| anyhow::anyhow!("Registry lock poisoned: {e}"))?;
+            state
+                .iter()
+                .filter(|(_, data)| sanitize_map_name(&data.name).is_ok_and(|n| n == wanted))
+                .map(|(id, data)| Self::map_entry_from_data(*id, data))
+                .collect::<Vec<_>>()
+        };
+        maps.sort_unstable_by_key(|entry| entry.id);
+        Ok(maps)
+    }
+
+    async fn search_maps(&self, query: &str) -> anyhow::Result<Vec<MapEntry>> {
+        let needle = query.trim().to_lowercase();
+        if needle.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut ranked = {
+            let state = self
+                .inner
+                .read()
+                .map_err(|e| anyhow::anyhow!("Registry lock poisoned: {e}"))?;
+            state
+                .iter()
+                .filter_map(|(id, data)| {
+                    Self::search_rank(data, &needle)
+                        .map(|rank| (rank, Self::map_entry_from_data(*id, data)))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        ranked.sort_unstable_by(|(a_rank, a), (b_rank, b)| {
+            a_rank
+                .cmp(b_rank)
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        Ok(ranked.into_iter().map(|(_, entry)| entry).collect())
+    }
+
+    async fn add_tags(&self, id: u64, tags: &[String]) -> anyhow::Result<MapEntry> {
+        let added = Self::normalize_tags(tags);
+        if added.is_empty() {
+            return Err(anyhow::anyhow!("No valid tags given"));
+        }
+        self.edit_tags(id, |current| current.extend(added)).await
+    }
+
+    async fn remove_tags(&self, id: u64, tags: &[String]) -> anyhow::Result<MapEntry> {
+        let removed = Self::normalize_tags(tags);
+        self.edit_tags(id, |current| current.retain(|t| !removed.contains(t)))
+            .await
+    }
+
+    async fn list_by_tag(&self, tag: &str) -> anyhow::Result<Vec<MapEntry>> {
+        let Some(tag) = sanitize_tag(tag) else {
+            return Ok(Vec::new());
+        };
+        let mut maps = {
+            let state = self
+                .inner
+                .read()
+                .map_err(|e| anyhow::anyhow!("Registry lock poisoned: {e}"))?;
+            state
+                .iter()
+                .filter(|(_, data)| data.tags.contains(&tag))
+                .map(|(id, data)| Self::map_entry_from_data(*id, data))
+                .collect::<Vec<_>>()
+        };
+        maps.sort_unstable_by_key(|entry| entry.id);
+        Ok(maps)
+    }
+
     async fn find_by_source_url(&self, url: &str) -> anyhow::Result<Option<MapEntry>> {
         let state = self
             .inner
@@ -411,16 +678,14 @@ mod tests {
     fn create_test_map_entry(id: u64) -> MapEntry {
         MapEntry {
             id,
-            name: "Test Map".to_string(),
             source_url: "https://example.com/map.zip".to_string(),
             source_kind: SourceKind::Workshop,
             workshop_id: Some(123456789),
             installed_path: "test_map.vpk".to_string(),
-            installed_at: Utc::now(),
-            workshop_updated_at: None,
             version: Some("1.0.0".to_string()),
             checksum: Some("abc123def456".to_string()),
             checksum_kind: Some("md5".to_string()),
+            ..crate::test_helpers::test_map_entry("Test Map")
         }
     }
 
@@ -456,21 +721,37 @@ mod tests {
         assert_eq!(retrieved.workshop_id, entry.workshop_id);
     }
 
+    #[tokio::test]
+    async fn test_reserved_map_id_is_not_handed_out_again() {
+        let (_temp_dir, path, registry) = setup_test_registry().await;
+        let reserved = registry.reserve_map_id().await.unwrap();
+        assert_eq!(reserved, 1);
+
+        // A concurrent add skips the reserved ID instead of taking it.
+        let added = registry.add_map(create_test_map_entry(0)).await.unwrap();
+        assert_eq!(added, 2);
+        assert_eq!(registry.reserve_map_id().await.unwrap(), 3);
+
+        let entry = MapEntry {
+            name: "Reserved".to_string(),
+            ..create_test_map_entry(reserved)
+        };
+        assert_eq!(registry.add_map_with_id(entry.clone()).await.unwrap(), reserved);
+        assert!(registry.add_map_with_id(entry).await.is_err());
+        assert!(registry.add_map_with_id(create_test_map_entry(9)).await.is_err());
+
+        let reloaded = JsonRegistry::new(&path).await.unwrap();
+        assert_eq!(reloaded.get_map(reserved).await.unwrap().unwrap().name, "Reserved");
+    }
+
     #[tokio::test]
     async fn test_add_map_normalizes_workshop_id_for_non_workshop() {
         let (_temp_dir, _path, registry) = setup_test_registry().await;
         let entry = MapEntry {
-            id: 0,
-            name: "Non workshop".to_string(),
             source_url: "https://example.com/map.zip".to_string(),
-            source_kind: SourceKind::Other,
             workshop_id: Some(12345),
             installed_path: "test_map.vpk".to_string(),
-            installed_at: Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..crate::test_helpers::test_map_entry("Non workshop")
         };
 
         let id = registry.add_map(entry).await.unwrap();
@@ -509,17 +790,9 @@ mod tests {
         let id2 = registry.add_map(create_test_map_entry(0)).await.unwrap();
         let id3 = registry
             .add_map(MapEntry {
-                id: 0,
-                name: "Third".to_string(),
                 source_url: "https://example.com/third.zip".to_string(),
-                source_kind: SourceKind::Other,
-                workshop_id: None,
                 installed_path: "third.vpk".to_string(),
-                installed_at: Utc::now(),
-                workshop_updated_at: None,
-                version: None,
-                checksum: None,
-                checksum_kind: None,
+                ..crate::test_helpers::test_map_entry("Third")
             })
             .await
             .unwrap();
@@ -544,6 +817,128 @@ mod tests {
         assert_eq!(retrieved.version, Some("2.0.0".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_writes_bump_updated_at() {
+        let (_temp_dir, _path, registry) = setup_test_registry().await;
+        let id = registry.add_map(create_test_map_entry(0)).await.unwrap();
+        let added = registry.get_map(id).await.unwrap().unwrap();
+        let added_at = added.updated_at.expect("stamped on add");
+
+        registry.update_map(added).await.unwrap();
+        let updated = registry.get_map(id).await.unwrap().unwrap();
+        assert!(updated.updated_at.unwrap() >= added_at);
+    }
+
+    #[tokio::test]
+    async fn test_get_map_by_name_matches_sanitized_names() {
+        let (_temp_dir, _path, registry) = setup_test_registry().await;
+        let id = registry.add_map(create_test_map_entry(0)).await.unwrap();
+
+        let found = registry.get_map_by_name("test_map").await.unwrap();
+        assert_eq!(found.iter().map(|m| m.id).collect::<Vec<_>>(), vec![id]);
+        assert_eq!(registry.get_map_by_name("  TEST MAP ").await.unwrap().len(), 1);
+        assert!(registry.get_map_by_name("other").await.unwrap().is_empty());
+        assert!(registry.get_map_by_name("../").await.unwrap().is_empty());
+
+        let duplicate = registry.add_map(create_test_map_entry(0)).await.unwrap();
+        let found = registry.get_map_by_name("Test Map").await.unwrap();
+        assert_eq!(found.iter().map(|m| m.id).collect::<Vec<_>>(), vec![id, duplicate]);
+    }
+
+    #[tokio::test]
+    async fn test_tags_are_sanitized_deduplicated_and_persisted() {
+        let (_temp_dir, path, registry) = setup_test_registry().await;
+        let id = registry.add_map(create_test_map_entry(0)).await.unwrap();
+        let other = registry.add_map(create_test_map_entry(0)).await.unwrap();
+
+        let tags = vec!["Survival".to_string(), " survival ".to_string(), "Co op".to_string()];
+        let tagged = registry.add_tags(id, &tags).await.unwrap();
+        assert_eq!(tagged.tags, vec!["co-op", "survival"]);
+        assert!(registry.add_tags(id, &["!!".to_string()]).await.is_err());
+        assert!(registry.add_tags(999, &tags).await.is_err());
+
+        let by_tag = registry.list_by_tag("SURVIVAL").await.unwrap();
+        assert_eq!(by_tag.iter().map(|m| m.id).collect::<Vec<_>>(), vec![id]);
+        assert!(registry.list_by_tag("survival").await.unwrap().iter().all(|m| m.id != other));
+
+        let untagged = registry.remove_tags(id, &["co op".to_string()]).await.unwrap();
+        assert_eq!(untagged.tags, vec!["survival"]);
+
+        let reloaded = JsonRegistry::new(&path).await.unwrap();
+        assert_eq!(reloaded.get_map(id).await.unwrap().unwrap().tags, vec!["survival"]);
+    }
+
+    #[tokio::test]
+    async fn test_legacy_entries_backfill_updated_at_from_installed_at() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("registry.json");
+        tokio::fs::write(
+            &path,
+            r#"{
+                "1": {
+                    "name": "Legacy",
+                    "source_url": "",
+                    "source_kind": "other",
+                    "workshop_id": null,
+                    "installed_path": "legacy.vpk",
+                    "installed_at": "2024-01-01T00:00:00Z",
+                    "version": null,
+                    "checksum": null,
+                    "checksum_kind": null
+                }
+            }"#,
+        )
+        .await
+        .unwrap();
+
+        let registry = JsonRegistry::new(&path).await.unwrap();
+        let entry = registry.get_map(1).await.unwrap().unwrap();
+        assert_eq!(entry.updated_at, Some(entry.installed_at));
+
+        let persisted = tokio::fs::read_to_string(&path).await.unwrap();
+        assert!(persisted.contains("updated_at"));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_other_entries_are_reclassified_by_source_url() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("registry.json");
+        let legacy = |source_url: &str, installed_path: &str| {
+            serde_json::json!({
+                "name": installed_path,
+                "source_url": source_url,
+                "source_kind": "other",
+                "workshop_id": null,
+                "installed_path": installed_path,
+                "installed_at": "2024-01-01T00:00:00Z",
+                "version": null,
+                "checksum": null,
+                "checksum_kind": null
+            })
+        };
+        let content = serde_json::json!({
+            "1": legacy("https://mirror.example/a.zip", "a.vpk"),
+            "2": legacy("detected:/srv/l4d2/left4dead2/addons/b.vpk", "b.vpk"),
+            "3": legacy("file:c.vpk", "c.vpk"),
+        });
+        tokio::fs::write(&path, content.to_string()).await.unwrap();
+
+        let registry = JsonRegistry::new(&path).await.unwrap();
+        let kinds: Vec<_> = registry
+            .list_maps()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|map| (map.id, map.source_kind))
+            .collect();
+        assert!(kinds.contains(&(1, SourceKind::Url)));
+        assert!(kinds.contains(&(2, SourceKind::Detected)));
+        assert!(kinds.contains(&(3, SourceKind::Other)));
+
+        let persisted = tokio::fs::read_to_string(&path).await.unwrap();
+        assert!(persisted.contains("\"detected\""));
+    }
+
     #[tokio::test]
     async fn test_update_non_existent_map_errors() {
         let (_temp_dir, _path, registry) = setup_test_registry().await;
@@ -554,6 +949,55 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
 
+    async fn add_named_map(registry: &JsonRegistry, name: &str, installed_path: &str) -> u64 {
+        let mut entry = create_test_map_entry(0);
+        entry.name = name.to_string();
+        entry.installed_path = installed_path.to_string();
+        entry.workshop_id = None;
+        entry.source_kind = SourceKind::Other;
+        registry.add_map(entry).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_search_maps_ranks_partial_name_matches() {
+        let (_temp_dir, _path, registry) = setup_test_registry().await;
+        add_named_map(&registry, "Dark Carnival Remix", "dc_remix.vpk").await;
+        add_named_map(&registry, "Carnival", "carnival.vpk").await;
+        add_named_map(&registry, "Carnival Nights", "nights.vpk").await;
+        add_named_map(&registry, "Swamp Fever", "swamp_carnival_edit.vpk").await;
+        add_named_map(&registry, "No Mercy", "nomercy.vpk").await;
+
+        let names: Vec<String> = registry
+            .search_maps("CARNIVAL")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Carnival", "Carnival Nights", "Dark Carnival Remix", "Swamp Fever"]
+        );
+        assert!(registry.search_maps("  ").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_maps_treats_wildcards_literally() {
+        let (_temp_dir, _path, registry) = setup_test_registry().await;
+        add_named_map(&registry, "100% Mercy", "mercy.vpk").await;
+        add_named_map(&registry, "Plain Map", "plain.vpk").await;
+        add_named_map(&registry, "dead_air", "deadair.vpk").await;
+
+        let percent = registry.search_maps("%").await.unwrap();
+        assert_eq!(percent.len(), 1);
+        assert_eq!(percent[0].name, "100% Mercy");
+
+        let underscore = registry.search_maps("d_a").await.unwrap();
+        assert_eq!(underscore.len(), 1);
+        assert_eq!(underscore[0].name, "dead_air");
+        assert_eq!(registry.search_maps("_").await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_auto_increment_ids_start_at_one() {
         let (_temp_dir, _path, registry) = setup_test_registry().await;
@@ -568,17 +1012,9 @@ mod tests {
         let (_temp_dir, _path, registry) = setup_test_registry().await;
         let long_path = "/".to_string() + &"a".repeat(500) + "/test/map";
         let entry = MapEntry {
-            id: 0,
-            name: "Long Path".to_string(),
             source_url: "https://example.com/map.zip".to_string(),
-            source_kind: SourceKind::Other,
-            workshop_id: None,
             installed_path: long_path.clone(),
-            installed_at: Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..crate::test_helpers::test_map_entry("Long Path")
         };
 
         let id = registry.add_map(entry).await.unwrap();
@@ -688,17 +1124,10 @@ mod tests {
             let reg = Arc::clone(&registry);
             handles.push(tokio::spawn(async move {
                 let entry = MapEntry {
-                    id: 0,
                     name: format!("Map {i}"),
                     source_url: format!("https://example.com/{i}"),
-                    source_kind: SourceKind::Other,
-                    workshop_id: None,
                     installed_path: format!("map_{i}.vpk"),
-                    installed_at: Utc::now(),
-                    workshop_updated_at: None,
-                    version: None,
-                    checksum: None,
-                    checksum_kind: None,
+                    ..crate::test_helpers::test_map_entry("map")
                 };
                 reg.add_map(entry).await.unwrap()
             }));
@@ -3,7 +3,7 @@ pub mod models;
 pub mod traits;
 pub mod json_store;
 
-pub use models::{MapEntry, SourceKind};
+pub use models::{MapEntry, SourceKind, sanitize_tag};
 pub use traits::Registry;
 pub use json_store::JsonRegistry;
 
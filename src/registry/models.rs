@@ -1,17 +1,22 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum SourceKind {
     Workshop,
     SirPlease,
     L4d2Center,
+    /// Installed from a direct download URL.
+    Url,
+    /// Found on disk by the watcher or discovery rather than installed by the daemon.
+    Detected,
     Other,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct MapEntry {
     /// Unique identifier for the map (auto-incrementing integer)
     pub id: u64,
@@ -22,7 +27,7 @@ pub struct MapEntry {
     /// Original download URL
     pub source_url: String,
     
-    /// Source kind: workshop or other
+    /// Source kind: workshop, a catalog, a direct URL, detected on disk or other
     pub source_kind: SourceKind,
     
     /// Steam Workshop ID (only present when source_kind is Workshop)
@@ -41,11 +46,44 @@ pub struct MapEntry {
     /// Map version if available
     pub version: Option<String>,
     
-    /// File checksum (MD5 hex string)
+    /// File checksum (hex string in the `checksum_kind` algorithm)
     pub checksum: Option<String>,
     
-    /// Checksum algorithm kind (currently only "md5")
+    /// Checksum algorithm kind ("md5" or "sha256")
     pub checksum_kind: Option<String>,
+
+    /// Bytes the installed file occupies (split-archive parts included)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+
+    /// Workshop item creator (SteamID64) as reported by Steam
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// What the addon provides per its `addoninfo.txt` `addonContent_*` flags
+    /// (lowercase, e.g. `campaign`, `map`, `survivor`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content_flags: Vec<String>,
+
+    /// Last registry modification time (drives delta sync)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+
+    /// Free-form labels (sanitized, deduplicated and sorted)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// `Last-Modified` the source URL sent with the installed file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_last_modified: Option<String>,
+
+    /// `ETag` the source URL sent with the installed file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_etag: Option<String>,
+
+    /// Absolute paths of the copies in the addons directories of `server_dirs`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub instance_paths: Vec<String>,
 }
 
 impl MapEntry {
@@ -67,8 +105,40 @@ impl MapEntry {
             version: None,
             checksum: None,
             checksum_kind: None,
+            size_bytes: None,
+            author: None,
+            content_flags: Vec::new(),
+            updated_at: None,
+            tags: Vec::new(),
+            source_last_modified: None,
+            source_etag: None,
+            instance_paths: Vec::new(),
+        }
+    }
+}
+
+/// Longest tag accepted after sanitizing.
+pub const MAX_TAG_LEN: usize = 32;
+
+/// Normalize a user-supplied tag: lowercase, whitespace runs become `-`, and only
+/// ASCII alphanumerics, `-` and `_` are kept. `None` when nothing usable remains.
+pub fn sanitize_tag(raw: &str) -> Option<String> {
+    let mut tag = String::new();
+    for word in raw.split_whitespace() {
+        if !tag.is_empty() {
+            tag.push('-');
         }
+        tag.extend(
+            word.chars()
+                .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+                .map(|c| c.to_ascii_lowercase()),
+        );
+    }
+    let tag = tag.trim_matches('-');
+    if tag.is_empty() {
+        return None;
     }
+    Some(tag.chars().take(MAX_TAG_LEN).collect())
 }
 
 #[cfg(test)]
@@ -97,20 +167,27 @@ mod tests {
         assert!(entry.installed_at <= Utc::now());
     }
 
+    #[test]
+    fn test_sanitize_tag() {
+        assert_eq!(sanitize_tag("  Survival "), Some("survival".to_string()));
+        assert_eq!(sanitize_tag("Co op  Finale!"), Some("co-op-finale".to_string()));
+        assert_eq!(sanitize_tag("<script>"), Some("script".to_string()));
+        assert_eq!(sanitize_tag(" !! "), None);
+        assert_eq!(sanitize_tag(&"a".repeat(40)).unwrap().len(), MAX_TAG_LEN);
+    }
+
     #[test]
     fn test_map_entry_serialize_json() {
         let entry = MapEntry {
             id: 42,
-            name: "Test Map".to_string(),
             source_url: "https://example.com/map.zip".to_string(),
             source_kind: SourceKind::Workshop,
             workshop_id: Some(123456789),
             installed_path: "workshop/map.vpk".to_string(),
-            installed_at: Utc::now(),
-            workshop_updated_at: None,
             version: Some("1.0.0".to_string()),
             checksum: Some("abc123def456".to_string()),
             checksum_kind: Some("md5".to_string()),
+            ..crate::test_helpers::test_map_entry("Test Map")
         };
 
         let json = serde_json::to_string(&entry).unwrap();
@@ -156,16 +233,12 @@ mod tests {
     fn test_map_entry_serialize_deserialize_roundtrip() {
         let original = MapEntry {
             id: 123,
-            name: "Test Map".to_string(),
             source_url: "https://example.com/map.zip".to_string(),
-            source_kind: SourceKind::Other,
-            workshop_id: None,
             installed_path: "map.vpk".to_string(),
-            installed_at: Utc::now(),
-            workshop_updated_at: None,
             version: Some("1.0.0".to_string()),
             checksum: Some("abc123def456".to_string()),
             checksum_kind: Some("md5".to_string()),
+            ..crate::test_helpers::test_map_entry("Test Map")
         };
 
         let json = serde_json::to_string(&original).unwrap();
@@ -186,16 +259,9 @@ mod tests {
     fn test_map_entry_with_optional_fields_none() {
         let entry = MapEntry {
             id: 1,
-            name: "Test Map".to_string(),
             source_url: "https://example.com/map.zip".to_string(),
-            source_kind: SourceKind::Other,
-            workshop_id: None,
             installed_path: "map.vpk".to_string(),
-            installed_at: Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..crate::test_helpers::test_map_entry("Test Map")
         };
 
         let json = serde_json::to_string(&entry).unwrap();
@@ -212,16 +278,12 @@ mod tests {
     fn test_workshop_updated_at_roundtrip_and_backward_compat() {
         let with_ts = MapEntry {
             id: 1,
-            name: "Workshop".to_string(),
             source_url: String::new(),
             source_kind: SourceKind::Workshop,
             workshop_id: Some(123),
             installed_path: "map.vpk".to_string(),
-            installed_at: Utc::now(),
             workshop_updated_at: Some(Utc.timestamp_opt(1_700_000_000, 0).single().unwrap()),
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..crate::test_helpers::test_map_entry("Workshop")
         };
 
         let json = serde_json::to_string(&with_ts).unwrap();
@@ -7,6 +7,13 @@ pub trait Registry: Send + Sync {
     /// Add a new map entry to the registry
     /// Returns the assigned auto-increment ID
     async fn add_map(&self, entry: MapEntry) -> anyhow::Result<u64>;
+
+    /// Set aside the next ID so it is known before the entry is added; neither
+    /// `add_map` nor another reservation hands it out. Unused reservations leave a gap.
+    async fn reserve_map_id(&self) -> anyhow::Result<u64>;
+
+    /// Add a new map entry under `entry.id`, which must come from `reserve_map_id`
+    async fn add_map_with_id(&self, entry: MapEntry) -> anyhow::Result<u64>;
     
     /// Remove a map entry from the registry
     async fn remove_map(&self, id: u64) -> anyhow::Result<()>;
@@ -27,5 +34,24 @@ pub trait Registry: Send + Sync {
     async fn find_by_installed_path(&self, path: &str) -> anyhow::Result<Option<MapEntry>>;
     async fn find_by_name(&self, name: &str) -> anyhow::Result<Option<MapEntry>>;
     async fn find_by_source_url(&self, url: &str) -> anyhow::Result<Option<MapEntry>>;
+
+    /// Maps whose sanitized name equals the sanitized `name`, ordered by ID.
+    /// More than one result means the name is ambiguous.
+    async fn get_map_by_name(&self, name: &str) -> anyhow::Result<Vec<MapEntry>>;
+
+    /// Case-insensitive substring search over name, version and installed path.
+    /// Results are ranked: exact name, name prefix, name substring, then other fields.
+    /// The query is matched literally (`%` and `_` are not wildcards).
+    async fn search_maps(&self, query: &str) -> anyhow::Result<Vec<MapEntry>>;
+
+    /// Add sanitized tags to a map, ignoring ones it already has.
+    /// Errors when the map does not exist or no tag survives sanitizing.
+    async fn add_tags(&self, id: u64, tags: &[String]) -> anyhow::Result<MapEntry>;
+
+    /// Remove tags from a map; tags it does not carry are ignored.
+    async fn remove_tags(&self, id: u64, tags: &[String]) -> anyhow::Result<MapEntry>;
+
+    /// Maps carrying `tag` (sanitized before matching), ordered by ID.
+    async fn list_by_tag(&self, tag: &str) -> anyhow::Result<Vec<MapEntry>>;
 }
 
@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use crate::catalog::{CatalogMapStatus, L4d2CenterCatalogEntry};
-use crate::map_installer::{CompactReport, DiscoveryReport, L4d2CenterUpdateReport, WorkshopUpdateReport};
+use crate::map_installer::{
+    CompactReport, DiscoveryReport, L4d2CenterUpdateReport, ReconcileReport, WorkshopUpdateReport,
+};
 use crate::registry::{MapEntry, SourceKind};
 
 pub fn source_kind_label(kind: SourceKind) -> &'static str {
@@ -8,6 +10,8 @@ pub fn source_kind_label(kind: SourceKind) -> &'static str {
         SourceKind::Workshop => "workshop",
         SourceKind::SirPlease => "sirplease",
         SourceKind::L4d2Center => "l4d2center",
+        SourceKind::Url => "url",
+        SourceKind::Detected => "detected",
         SourceKind::Other => "other",
     }
 }
@@ -47,8 +51,14 @@ pub fn print_map_detail(map: &MapEntry) {
     println!("  name:           {}", map.name);
     println!("  source_kind:    {source_kind}");
     println!("  workshop_id:    {workshop_id}");
+    if let Some(author) = &map.author {
+        println!("  author:         {author}");
+    }
     println!("  source_url:     {}", map.source_url);
     println!("  version:        {version}");
+    if !map.content_flags.is_empty() {
+        println!("  content:        {}", map.content_flags.join(", "));
+    }
     println!("  installed_path: {}", map.installed_path);
     println!("  installed_at:   {}", map.installed_at);
     if let Some(updated_at) = map.workshop_updated_at {
@@ -82,6 +92,29 @@ pub fn print_compact_report(report: CompactReport) {
     }
 }
 
+pub fn print_reconcile_report(report: ReconcileReport) {
+    println!(
+        "Reconcile complete: {} registered, {} dangling record(s) removed, {} unchanged, {} failed.",
+        report.registered.len(),
+        report.removed.len(),
+        report.unchanged,
+        report.failed
+    );
+
+    if !report.registered.is_empty() {
+        println!("Registered untracked maps:");
+        for map in &report.registered {
+            print_map_entry(map);
+        }
+    }
+    if !report.removed.is_empty() {
+        println!("Removed records with missing files:");
+        for map in &report.removed {
+            print_map_entry(map);
+        }
+    }
+}
+
 pub fn print_discovery_report(report: DiscoveryReport) {
     println!(
         "Discovery complete: {} added, {} updated, {} already current, {} failed.",
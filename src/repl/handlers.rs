@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use super::format::{
-    print_compact_report, print_discovery_report, print_l4d2center_catalog,
+    print_compact_report, print_discovery_report, print_reconcile_report, print_l4d2center_catalog,
     print_l4d2center_check_report, print_l4d2center_update_report, print_map_detail,
     print_map_entry, print_workshop_check_report, print_workshop_update_report,
 };
@@ -10,6 +10,7 @@ use super::parse::{
 };
 use super::session::Repl;
 use super::runtime::{block_on_installer, require_installer};
+use crate::map_installer::InstallOptions;
 
 impl Repl {
     pub(super) fn handle_list_maps(&self, runtime_handle: &tokio::runtime::Handle) {
@@ -40,8 +41,11 @@ impl Repl {
             return;
         };
 
+        let force = args.contains(&"--force");
+        let args: Vec<&str> = args.iter().copied().filter(|arg| *arg != "--force").collect();
+
         if args.is_empty() {
-            println!("Usage: install <url|workshop_id> [name]");
+            println!("Usage: install <url|workshop_id> [name] [--force]");
             return;
         }
 
@@ -63,12 +67,20 @@ impl Repl {
             InstallTarget::Workshop(workshop_id) => block_on_installer(
                 runtime_handle,
                 installer,
-                installer.install_from_workshop_id(workshop_id, provided_name),
+                installer.install_from_workshop_id(
+                    workshop_id,
+                    provided_name,
+                    InstallOptions { force, ..InstallOptions::default() },
+                ),
             ),
             InstallTarget::Url(url) => block_on_installer(
                 runtime_handle,
                 installer,
-                installer.install_from_url(url, provided_name),
+                installer.install_from_url(
+                    url,
+                    provided_name,
+                    InstallOptions { force, ..InstallOptions::default() },
+                ),
             ),
         };
 
@@ -147,6 +159,19 @@ impl Repl {
         }
     }
 
+    pub(super) fn handle_reconcile(&self, runtime_handle: &tokio::runtime::Handle) {
+        let Some(installer) = require_installer(&self.installer) else {
+            return;
+        };
+
+        match block_on_installer(runtime_handle, installer, installer.reconcile()) {
+            Ok(report) => print_reconcile_report(report),
+            Err(err) => {
+                eprintln!("Reconcile failed: {err}");
+            }
+        }
+    }
+
     pub(super) fn handle_update(&self, runtime_handle: &tokio::runtime::Handle, args: &[&str]) {
         let Some(installer) = require_installer(&self.installer) else {
             return;
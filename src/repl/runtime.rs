@@ -19,5 +19,5 @@ pub fn block_on_installer<T>(
     _installer: &Arc<MapInstallationService>,
     fut: impl Future<Output = T>,
 ) -> T {
-    handle.block_on(fut)
+    handle.block_on(crate::map_installer::with_audit_actor("repl", fut))
 }
@@ -111,6 +111,9 @@ impl Repl {
                             "compact" => {
                                 self.handle_compact(&runtime_handle);
                             }
+                            "reconcile" => {
+                                self.handle_reconcile(&runtime_handle);
+                            }
                             "info" => {
                                 self.handle_info(&runtime_handle, args.first().copied());
                             }
@@ -169,11 +172,12 @@ impl Repl {
         println!("Available commands:");
         println!("  h, help - Show this help message");
         println!("  ls, list, maps - List installed maps");
-        println!("  i, install <url|workshop_id> [name] - Install a map");
+        println!("  i, install <url|workshop_id> [name] [--force] - Install a map (--force replaces same-name map)");
         println!("  rm, remove, uninstall <id> - Remove map by ID");
         println!("  u, update [id] [--check] [--force] - Check or re-download outdated Steam Workshop maps");
         println!("  scan, discover, d [u|U] - Local addons scan (d u = refresh metadata only)");
         println!("  compact - Remove orphaned records, sort by name, reindex IDs from 1");
+        println!("  reconcile - Register untracked VPKs and drop records whose files are gone (keeps IDs)");
         println!("  info <id> - Show all stored fields for a map");
         println!("  modify <id> <field> <value> - Edit a field (name, source_url, version, source_kind, workshop_id, installed_path; renames file when present)");
         println!("  l4d2center, l4c list|l|ls - List L4D2Center catalog maps and install status");
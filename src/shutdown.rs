@@ -0,0 +1,47 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Stopping the daemon's background tasks on shutdown.
+
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Abort every task, then wait up to `grace` for them to unwind.
+///
+/// Returns as soon as all tasks have stopped; tasks still running when `grace`
+/// runs out are logged by name and left behind.
+pub async fn stop_tasks(mut tasks: Vec<(&'static str, JoinHandle<()>)>, grace: Duration) {
+    for (_, handle) in &tasks {
+        handle.abort();
+    }
+
+    // An aborted task ends cancelled (or with its panic); either way it has stopped.
+    let all_stopped = futures_util::future::join_all(tasks.iter_mut().map(|(_, handle)| handle));
+    if tokio::time::timeout(grace, all_stopped).await.is_ok() {
+        info!(count = tasks.len(), "Background tasks stopped");
+        return;
+    }
+    let stuck: Vec<&str> = tasks
+        .iter()
+        .filter(|(_, handle)| !handle.is_finished())
+        .map(|(name, _)| *name)
+        .collect();
+    warn!(?stuck, grace_secs = grace.as_secs(), "Background tasks did not stop within shutdown_grace_secs");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn idle_tasks_stop_without_waiting_out_the_grace_period() {
+        let tasks = (0..3)
+            .map(|_| ("idle", tokio::spawn(std::future::pending::<()>())))
+            .chain(std::iter::once(("finished", tokio::spawn(async {}))))
+            .collect();
+
+        let started = std::time::Instant::now();
+        stop_tasks(tasks, Duration::from_secs(30)).await;
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -1,28 +1,55 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use async_trait::async_trait;
-use reqwest::Client;
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use chrono::{DateTime, Utc};
+use reqwest::header::{
+    ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE, ETAG, IF_NONE_MATCH, RETRY_AFTER,
+};
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use tracing::{error, info, warn};
 use crate::config::{read_config, ConfigHandle};
+use crate::downloader::client::{user_agent, HttpTimeouts, ProxyConfig};
 use crate::registry::models::MapEntry;
-use crate::sync::traits::{MapUpdate, SyncService};
+use crate::sync::traits::{
+    BackendRetryAfter, DeltaSyncUnsupported, MapUpdate, RejectedEntry, SyncPushResult,
+    SyncService,
+};
+
+/// Attempts per registry push before the cycle gives up and reports failure.
+const PUSH_MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first push retry; doubled for each further attempt.
+const PUSH_RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
 
 #[derive(Debug, Clone)]
 pub struct BackendSyncService {
     client: Client,
     config: ConfigHandle,
+    /// `ETag` of the last successful updates fetch, sent back as `If-None-Match`.
+    updates_etag: Arc<Mutex<Option<String>>>,
 }
 
 impl BackendSyncService {
     pub fn new(config: ConfigHandle) -> anyhow::Result<Self> {
-        let client = Client::builder()
+        let snapshot = read_config(&config);
+        let client = ProxyConfig::from_config(&snapshot)
+            .apply(Client::builder())?
             .timeout(std::time::Duration::from_secs(30))
-            .user_agent(format!("{}/{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")))
+            .connect_timeout(HttpTimeouts::from_config(&snapshot).connect)
+            .user_agent(user_agent(&snapshot))
             .build()?;
         
         Ok(Self {
             client,
             config,
+            updates_etag: Arc::new(Mutex::new(None)),
         })
     }
     
@@ -35,9 +62,90 @@ impl BackendSyncService {
             request = request.header("Authorization", format!("Bearer {}", key));
         }
 
+        if snapshot.backend_sync_gzip {
+            request = request.header(ACCEPT_ENCODING, "gzip");
+        }
+
         request
     }
 
+    fn gzip_enabled(&self) -> bool {
+        read_config(&self.config).backend_sync_gzip
+    }
+
+    fn max_response_bytes(&self) -> u64 {
+        read_config(&self.config).max_sync_response_bytes
+    }
+
+    /// POST a JSON payload (gzipped when enabled), retrying transient failures.
+    ///
+    /// Connection errors, 429 and 5xx responses are retried up to
+    /// [`PUSH_MAX_ATTEMPTS`] times; other non-success statuses fail immediately, as
+    /// does a 429/503 with `Retry-After` (the sync loop waits it out instead).
+    /// Returns the (decoded) response body.
+    async fn post_json<T: Serialize>(&self, path: &str, payload: &T) -> anyhow::Result<Vec<u8>> {
+        let mut attempt = 1;
+        loop {
+            let (error, retryable) = match self.post_json_once(path, payload).await {
+                Ok(Ok(body)) => {
+                    if attempt > 1 {
+                        info!(path, attempt, "Backend sync succeeded after retry");
+                    }
+                    return Ok(body);
+                }
+                Ok(Err(failure)) => {
+                    let retryable = failure.retry_after.is_none()
+                        && (failure.status.is_server_error()
+                            || failure.status == StatusCode::TOO_MANY_REQUESTS);
+                    (failure.into_error("Backend sync failed"), retryable)
+                }
+                Err(e) => (e, true),
+            };
+            if !retryable || attempt >= PUSH_MAX_ATTEMPTS {
+                error!(path, attempt, error = %error, "Backend sync failed");
+                return Err(error);
+            }
+            warn!(path, attempt, error = %error, "Backend sync attempt failed; retrying");
+            tokio::time::sleep(PUSH_RETRY_BASE_DELAY * 2_u32.pow(attempt - 1)).await;
+            attempt += 1;
+        }
+    }
+
+    /// One POST attempt: the body on success, the failed response on an HTTP error.
+    async fn post_json_once<T: Serialize>(
+        &self,
+        path: &str,
+        payload: &T,
+    ) -> anyhow::Result<Result<Vec<u8>, HttpFailure>> {
+        let builder = self.build_post_request(path);
+        let builder = if self.gzip_enabled() {
+            let body = gzip_encode(&serde_json::to_vec(payload)?)?;
+            builder
+                .header(CONTENT_TYPE, "application/json")
+                .header(CONTENT_ENCODING, "gzip")
+                .body(body)
+        } else {
+            builder.json(payload)
+        };
+        let response = builder.send().await?;
+
+        if !response.status().is_success() {
+            return Ok(Err(HttpFailure::read(response, self.max_response_bytes()).await));
+        }
+
+        Ok(Ok(read_body(response, self.max_response_bytes()).await?))
+    }
+
+    fn cached_updates_etag(&self) -> Option<String> {
+        self.updates_etag.lock().ok().and_then(|etag| etag.clone())
+    }
+
+    fn store_updates_etag(&self, etag: Option<String>) {
+        if let Ok(mut stored) = self.updates_etag.lock() {
+            *stored = etag;
+        }
+    }
+
     fn build_get_request(&self, path: &str) -> reqwest::RequestBuilder {
         self.build_request(reqwest::Method::GET, path)
     }
@@ -47,60 +155,249 @@ impl BackendSyncService {
     }
 }
 
+/// A non-success backend response.
+struct HttpFailure {
+    status: StatusCode,
+    text: String,
+    /// Parsed `Retry-After` of a 429/503 response.
+    retry_after: Option<Duration>,
+}
+
+impl HttpFailure {
+    /// Status, `Retry-After` and at most `max_bytes` of the body of a failed response.
+    async fn read(mut response: reqwest::Response, max_bytes: u64) -> Self {
+        let status = response.status();
+        let retry_after = if matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+        ) {
+            response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| parse_retry_after(v, Utc::now()))
+        } else {
+            None
+        };
+        let limit = usize::try_from(max_bytes).unwrap_or(usize::MAX);
+        let mut body = Vec::new();
+        while let Ok(Some(chunk)) = response.chunk().await {
+            let room = limit - body.len();
+            body.extend_from_slice(&chunk[..chunk.len().min(room)]);
+            if chunk.len() >= room {
+                break;
+            }
+        }
+        let text = String::from_utf8_lossy(&body).into_owned();
+        Self {
+            status,
+            text,
+            retry_after,
+        }
+    }
+
+    fn into_error(self, what: &str) -> anyhow::Error {
+        let message = format!("{what}: {} - {}", self.status, self.text);
+        match self.retry_after {
+            Some(retry_after) => anyhow::Error::new(BackendRetryAfter { retry_after }).context(message),
+            None => anyhow::Error::new(BackendStatus(self.status)).context(message),
+        }
+    }
+}
+
+/// Status of a failed backend response, kept in the error chain.
+#[derive(thiserror::Error, Debug)]
+#[error("backend answered {0}")]
+struct BackendStatus(StatusCode);
+
+/// True when `error` comes from a response with one of `statuses`.
+fn has_backend_status(error: &anyhow::Error, statuses: &[StatusCode]) -> bool {
+    error
+        .chain()
+        .filter_map(|cause| cause.downcast_ref::<BackendStatus>())
+        .any(|BackendStatus(status)| statuses.contains(status))
+}
+
+/// `Retry-After` as delay-seconds or an HTTP-date; `None` when unparsable or not in
+/// the future.
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    let delay = match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => DateTime::parse_from_rfc2822(value)
+            .ok()?
+            .with_timezone(&Utc)
+            .signed_duration_since(now)
+            .to_std()
+            .ok()?,
+    };
+    Some(delay).filter(|delay| !delay.is_zero())
+}
+
+fn gzip_encode(data: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// Decode at most `max_bytes` of gzip data, so a small compressed body cannot
+/// expand without bound.
+fn gzip_decode(data: &[u8], max_bytes: u64) -> anyhow::Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(data)
+        .take(max_bytes.saturating_add(1))
+        .read_to_end(&mut decoded)
+        .map_err(|e| anyhow::anyhow!("Failed to decode gzip response from backend: {e}"))?;
+    if decoded.len() as u64 > max_bytes {
+        return Err(response_too_large(max_bytes));
+    }
+    Ok(decoded)
+}
+
+fn response_too_large(max_bytes: u64) -> anyhow::Error {
+    anyhow::anyhow!("Backend response exceeds max_sync_response_bytes ({max_bytes} bytes)")
+}
+
+/// Read a response body of at most `max_bytes`, transparently decoding
+/// `Content-Encoding: gzip` (the limit applies to the decoded body as well).
+async fn read_body(mut response: reqwest::Response, max_bytes: u64) -> anyhow::Result<Vec<u8>> {
+    let gzipped = response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.trim().eq_ignore_ascii_case("gzip"));
+    if response.content_length().is_some_and(|len| len > max_bytes) {
+        return Err(response_too_large(max_bytes));
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if (body.len() + chunk.len()) as u64 > max_bytes {
+            return Err(response_too_large(max_bytes));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    if gzipped {
+        gzip_decode(&body, max_bytes)
+    } else {
+        Ok(body)
+    }
+}
+
 #[derive(Serialize)]
 struct SyncRequest {
     maps: Vec<MapEntry>,
 }
 
+#[derive(Serialize)]
+struct SyncDeltaRequest {
+    changed: Vec<MapEntry>,
+    removed: Vec<String>,
+}
+
 #[derive(Deserialize)]
 struct UpdatesResponse {
     updates: Vec<MapUpdate>,
 }
 
+/// Optional push acknowledgement; backends that reply without one accept everything.
+#[derive(Deserialize, Default)]
+struct PushResponse {
+    accepted: Option<usize>,
+    #[serde(default)]
+    rejected: Vec<RejectedEntry>,
+}
+
+/// Interpret a push response body for `sent` entries.
+fn push_result(body: &[u8], sent: usize) -> SyncPushResult {
+    let response: PushResponse = serde_json::from_slice(body).unwrap_or_default();
+    let accepted = response
+        .accepted
+        .unwrap_or_else(|| sent.saturating_sub(response.rejected.len()));
+    SyncPushResult {
+        accepted,
+        rejected: response.rejected,
+    }
+}
+
 #[async_trait]
 impl SyncService for BackendSyncService {
-    async fn sync_registry(&self, entries: Vec<MapEntry>) -> anyhow::Result<()> {
+    async fn sync_registry(&self, entries: Vec<MapEntry>) -> anyhow::Result<SyncPushResult> {
         info!(count = entries.len(), "Syncing registry to backend");
-        
+
+        let sent = entries.len();
         let request = SyncRequest { maps: entries };
-        let response = self
-            .build_post_request("registry/sync")
-            .json(&request)
-            .send()
-            .await?;
-        
-        if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            error!(status = %status, body = %text, "Backend sync failed");
-            return Err(anyhow::anyhow!("Backend sync failed: {} - {}", status, text));
-        }
-        
-        info!("Registry sync completed successfully");
-        Ok(())
+        let body = self.post_json("registry/sync", &request).await?;
+        let result = push_result(&body, sent);
+
+        info!(
+            accepted = result.accepted,
+            rejected = result.rejected.len(),
+            "Registry sync completed"
+        );
+        Ok(result)
     }
-    
+
+    async fn sync_delta(
+        &self,
+        changed: Vec<MapEntry>,
+        removed: Vec<String>,
+    ) -> anyhow::Result<SyncPushResult> {
+        info!(
+            changed = changed.len(),
+            removed = removed.len(),
+            "Syncing registry delta to backend"
+        );
+
+        let sent = changed.len() + removed.len();
+        let request = SyncDeltaRequest { changed, removed };
+        let body = match self.post_json("registry/sync/delta", &request).await {
+            Ok(body) => body,
+            Err(e) if has_backend_status(&e, &[StatusCode::NOT_FOUND, StatusCode::METHOD_NOT_ALLOWED]) => {
+                return Err(e.context(DeltaSyncUnsupported));
+            }
+            Err(e) => return Err(e),
+        };
+        let result = push_result(&body, sent);
+
+        info!(
+            accepted = result.accepted,
+            rejected = result.rejected.len(),
+            "Registry delta sync completed"
+        );
+        Ok(result)
+    }
+
     async fn fetch_updates(&self) -> anyhow::Result<Vec<MapUpdate>> {
         info!("Fetching updates from backend");
         
-        let response = self
-            .build_get_request("registry/updates")
-            .send()
-            .await?;
-        
+        let mut request = self.build_get_request("registry/updates");
+        let cached_etag = self.cached_updates_etag();
+        if let Some(etag) = &cached_etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        let response = request.send().await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED && cached_etag.is_some() {
+            info!("Backend updates not modified since last fetch");
+            return Ok(Vec::new());
+        }
+
         if !response.status().is_success() {
-            let status = response.status();
-            let text = response.text().await.unwrap_or_default();
-            warn!(status = %status, body = %text, "Failed to fetch updates from backend");
-            return Err(anyhow::anyhow!(
-                "Failed to fetch updates from backend: {} - {}",
-                status,
-                text
-            ));
+            let failure = HttpFailure::read(response, self.max_response_bytes()).await;
+            warn!(status = %failure.status, body = %failure.text, "Failed to fetch updates from backend");
+            return Err(failure.into_error("Failed to fetch updates from backend"));
         }
         
-        let updates_response: UpdatesResponse = response.json().await?;
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let body = read_body(response, self.max_response_bytes()).await?;
+        let updates_response: UpdatesResponse = serde_json::from_slice(&body)?;
         info!(count = updates_response.updates.len(), "Fetched updates from backend");
+        // Only remember the tag once the body parsed, so a bad payload is fetched again.
+        self.store_updates_etag(etag);
         
         Ok(updates_response.updates)
     }
@@ -110,11 +407,12 @@ impl SyncService for BackendSyncService {
 mod tests {
     use super::*;
     use crate::config::{init_handle, Config};
-    use crate::registry::models::SourceKind;
     use crate::sync::traits::SyncService;
     use axum::{Json, Router, routing::{get, post}};
     use axum::http::StatusCode;
+    use axum::response::IntoResponse;
     use serde_json::json;
+    use serial_test::serial;
     use std::sync::Arc;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use tokio::sync::Mutex;
@@ -190,16 +488,9 @@ mod tests {
     fn sample_map_entry() -> MapEntry {
         MapEntry {
             id: 1,
-            name: "Test".to_string(),
             source_url: "https://example.com".to_string(),
-            source_kind: SourceKind::Other,
-            workshop_id: None,
             installed_path: "test.vpk".to_string(),
-            installed_at: chrono::Utc::now(),
-            workshop_updated_at: None,
-            version: None,
-            checksum: None,
-            checksum_kind: None,
+            ..crate::test_helpers::test_map_entry("Test")
         }
     }
 
@@ -210,6 +501,18 @@ mod tests {
         BackendSyncService::new(init_handle(config)).unwrap()
     }
 
+    fn gzip_service_with_url(base_url: String) -> BackendSyncService {
+        let config = Config {
+            backend_api_url: base_url,
+            backend_sync_gzip: true,
+            ..Config::default()
+        };
+        BackendSyncService::new(init_handle(config)).unwrap()
+    }
+
+    /// `(Content-Encoding header, raw body)` captured by the mock sync route.
+    type CapturedRequest = Arc<Mutex<Option<(Option<String>, Vec<u8>)>>>;
+
     #[tokio::test]
     async fn test_sync_registry_sends_bearer_token() {
         let auth_header = Arc::new(Mutex::new(None));
@@ -227,6 +530,49 @@ mod tests {
         assert_eq!(stored.as_deref(), Some("Bearer secret-token"));
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn test_reloaded_api_key_is_used_for_subsequent_syncs() {
+        let auth_header = Arc::new(Mutex::new(None));
+        let base_url = spawn_mock_backend(
+            Arc::clone(&auth_header),
+            StatusCode::OK,
+            StatusCode::OK,
+        )
+        .await;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let config_path = tmp.path().join(crate::config::CONF_FILE_NAME);
+        let write_config = |key: &str| {
+            let toml = format!(
+                r#"
+l4d2_server_dir = "/home/steam/l4d2"
+registry_path = "registry.json"
+backend_api_url = "{base_url}"
+backend_api_key = "{key}"
+local_api_bind = "127.0.0.1:8080"
+sync_interval_secs = 300
+log_level = "info"
+"#
+            );
+            std::fs::write(&config_path, toml).unwrap();
+        };
+        write_config("old-key");
+        let handle = init_handle(Config::load_from(&config_path).unwrap());
+        let service = BackendSyncService::new(handle.clone()).unwrap();
+
+        service.sync_registry(vec![sample_map_entry()]).await.unwrap();
+        assert_eq!(auth_header.lock().await.as_deref(), Some("Bearer old-key"));
+
+        write_config("new-key");
+        let change = crate::config_watch::force_reload(&handle, std::slice::from_ref(&config_path)).unwrap();
+        assert!(change.live_applied.contains(&"backend_api_key"));
+        assert!(change.requires_restart.is_empty());
+
+        service.sync_registry(vec![sample_map_entry()]).await.unwrap();
+        assert_eq!(auth_header.lock().await.as_deref(), Some("Bearer new-key"));
+    }
+
     #[tokio::test]
     async fn test_fetch_updates_returns_error_on_failure() {
         let auth_header = Arc::new(Mutex::new(None));
@@ -243,6 +589,227 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("503"));
     }
 
+    #[tokio::test]
+    async fn test_sync_registry_gzips_body_when_enabled() {
+        let captured: CapturedRequest = Arc::new(Mutex::new(None));
+        let captured_clone = Arc::clone(&captured);
+        let router = Router::new().route(
+            "/api/registry/sync",
+            post(move |headers: axum::http::HeaderMap, body: axum::body::Bytes| {
+                let captured = Arc::clone(&captured_clone);
+                async move {
+                    let encoding = headers
+                        .get("content-encoding")
+                        .and_then(|v| v.to_str().ok())
+                        .map(String::from);
+                    *captured.lock().await = Some((encoding, body.to_vec()));
+                    StatusCode::OK
+                }
+            }),
+        );
+        let addr = crate::test_helpers::spawn_test_server(router).await;
+
+        let service = gzip_service_with_url(format!("http://{addr}/api"));
+
+        let entries = vec![sample_map_entry()];
+        service.sync_registry(entries.clone()).await.unwrap();
+
+        let (encoding, body) = captured.lock().await.clone().expect("request captured");
+        assert_eq!(encoding.as_deref(), Some("gzip"));
+        assert_eq!(&body[..2], &[0x1f, 0x8b]);
+        let decoded: serde_json::Value = serde_json::from_slice(&gzip_decode(&body, u64::MAX).unwrap()).unwrap();
+        let expected = serde_json::to_value(SyncRequest { maps: entries }).unwrap();
+        assert_eq!(decoded, expected);
+    }
+
+    #[tokio::test]
+    async fn test_sync_delta_posts_changed_and_removed() {
+        let captured: CapturedRequest = Arc::new(Mutex::new(None));
+        let captured_clone = Arc::clone(&captured);
+        let router = Router::new().route(
+            "/api/registry/sync/delta",
+            post(move |body: axum::body::Bytes| {
+                let captured = Arc::clone(&captured_clone);
+                async move {
+                    *captured.lock().await = Some((None, body.to_vec()));
+                    StatusCode::OK
+                }
+            }),
+        );
+        let addr = crate::test_helpers::spawn_test_server(router).await;
+
+        let service = service_with_url(format!("http://{addr}/api"), None);
+        service
+            .sync_delta(vec![sample_map_entry()], vec!["7".to_string()])
+            .await
+            .unwrap();
+
+        let (_, body) = captured.lock().await.clone().expect("request captured");
+        let decoded: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(decoded["changed"].as_array().unwrap().len(), 1);
+        assert_eq!(decoded["removed"], json!(["7"]));
+    }
+
+    #[tokio::test]
+    async fn test_sync_delta_without_delta_route_reports_unsupported() {
+        let base_url = spawn_mock_backend(
+            Arc::new(Mutex::new(None)),
+            StatusCode::OK,
+            StatusCode::OK,
+        )
+        .await;
+
+        let service = service_with_url(base_url, None);
+        let error = service
+            .sync_delta(vec![sample_map_entry()], Vec::new())
+            .await
+            .unwrap_err();
+        assert!(error.is::<DeltaSyncUnsupported>());
+    }
+
+    #[tokio::test]
+    async fn test_sync_registry_retries_after_server_error() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_clone = Arc::clone(&calls);
+        let router = Router::new().route(
+            "/api/registry/sync",
+            post(move || {
+                let calls = Arc::clone(&calls_clone);
+                async move {
+                    if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                        (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": "blip" })))
+                    } else {
+                        (
+                            StatusCode::OK,
+                            Json(json!({
+                                "accepted": 1,
+                                "rejected": [{ "map_id": "2", "reason": "missing checksum" }]
+                            })),
+                        )
+                    }
+                }
+            }),
+        );
+        let addr = crate::test_helpers::spawn_test_server(router).await;
+
+        let service = service_with_url(format!("http://{addr}/api"), None);
+        let result = service
+            .sync_registry(vec![sample_map_entry(), MapEntry { id: 2, ..sample_map_entry() }])
+            .await
+            .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(result.accepted, 1);
+        assert_eq!(result.rejected.len(), 1);
+        assert_eq!(result.rejected[0].map_id, "2");
+    }
+
+    #[tokio::test]
+    async fn test_sync_registry_without_ack_accepts_everything() {
+        let auth_header = Arc::new(Mutex::new(None));
+        let base_url = spawn_mock_backend(auth_header, StatusCode::OK, StatusCode::OK).await;
+
+        let service = service_with_url(base_url, None);
+        let result = service.sync_registry(vec![sample_map_entry()]).await.unwrap();
+
+        assert_eq!(result.accepted, 1);
+        assert!(result.rejected.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_configured_user_agent_is_sent() {
+        let router = Router::new().route(
+            "/api/registry/sync",
+            post(|headers: axum::http::HeaderMap| async move {
+                match headers.get("user-agent").and_then(|v| v.to_str().ok()) {
+                    Some("MirrorFriendly/1.0") => StatusCode::OK,
+                    _ => StatusCode::FORBIDDEN,
+                }
+            }),
+        );
+        let addr = crate::test_helpers::spawn_test_server(router).await;
+
+        let default_agent = service_with_url(format!("http://{addr}/api"), None);
+        assert!(default_agent.sync_registry(vec![sample_map_entry()]).await.is_err());
+
+        let config = Config {
+            backend_api_url: format!("http://{addr}/api"),
+            http_user_agent: Some("MirrorFriendly/1.0".to_string()),
+            ..Config::default()
+        };
+        let service = BackendSyncService::new(init_handle(config)).unwrap();
+        service.sync_registry(vec![sample_map_entry()]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_updates_decodes_gzip_response() {
+        let payload = json!({
+            "updates": [{ "action": "uninstall", "map_id": "7", "map_entry": null }]
+        });
+        let compressed = gzip_encode(&serde_json::to_vec(&payload).unwrap()).unwrap();
+        let router = Router::new().route(
+            "/api/registry/updates",
+            get(move || {
+                let compressed = compressed.clone();
+                async move {
+                    (
+                        [("content-encoding", "gzip"), ("content-type", "application/json")],
+                        compressed,
+                    )
+                }
+            }),
+        );
+        let addr = crate::test_helpers::spawn_test_server(router).await;
+
+        let service = gzip_service_with_url(format!("http://{addr}/api"));
+
+        let updates = service.fetch_updates().await.unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].action, "uninstall");
+        assert_eq!(updates[0].map_id, "7");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_updates_sends_etag_and_treats_304_as_no_updates() {
+        let seen_if_none_match = Arc::new(Mutex::new(Vec::new()));
+        let router = Router::new().route(
+            "/api/registry/updates",
+            get({
+                let seen = Arc::clone(&seen_if_none_match);
+                move |headers: axum::http::HeaderMap| {
+                    let seen = Arc::clone(&seen);
+                    async move {
+                        let tag = headers
+                            .get("if-none-match")
+                            .and_then(|v| v.to_str().ok())
+                            .map(String::from);
+                        seen.lock().await.push(tag.clone());
+                        if tag.as_deref() == Some("\"v1\"") {
+                            return StatusCode::NOT_MODIFIED.into_response();
+                        }
+                        (
+                            [("etag", "\"v1\"")],
+                            Json(json!({
+                                "updates": [{ "action": "install", "map_id": "42", "map_entry": null }]
+                            })),
+                        )
+                            .into_response()
+                    }
+                }
+            }),
+        );
+        let addr = crate::test_helpers::spawn_test_server(router).await;
+
+        let service = service_with_url(format!("http://{addr}/api"), None);
+
+        assert_eq!(service.fetch_updates().await.unwrap().len(), 1);
+        assert!(service.fetch_updates().await.unwrap().is_empty());
+        assert_eq!(
+            *seen_if_none_match.lock().await,
+            vec![None, Some("\"v1\"".to_string())]
+        );
+    }
+
     #[tokio::test]
     async fn test_fetch_updates_parses_payload() {
         let auth_header = Arc::new(Mutex::new(None));
@@ -259,4 +826,70 @@ mod tests {
         assert_eq!(updates[0].map_id, "42");
         assert_eq!(updates[0].action, "install");
     }
+
+    #[tokio::test]
+    async fn test_fetch_updates_rejects_oversized_body() {
+        let padding = "x".repeat(4096);
+        let router = Router::new().route(
+            "/api/registry/updates",
+            get(move || {
+                let padding = padding.clone();
+                async move { Json(json!({ "updates": [], "padding": padding })) }
+            }),
+        );
+        let addr = crate::test_helpers::spawn_test_server(router).await;
+
+        let config = Config {
+            backend_api_url: format!("http://{addr}/api"),
+            max_sync_response_bytes: 1024,
+            ..Config::default()
+        };
+        let service = BackendSyncService::new(init_handle(config)).unwrap();
+        let error = service.fetch_updates().await.unwrap_err();
+        assert!(error.to_string().contains("max_sync_response_bytes"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_updates_error_body_is_capped() {
+        let router = Router::new().route(
+            "/api/registry/updates",
+            get(|| async { (StatusCode::INTERNAL_SERVER_ERROR, "x".repeat(1024 * 1024)) }),
+        );
+        let addr = crate::test_helpers::spawn_test_server(router).await;
+
+        let config = Config {
+            backend_api_url: format!("http://{addr}/api"),
+            max_sync_response_bytes: 1024,
+            ..Config::default()
+        };
+        let service = BackendSyncService::new(init_handle(config)).unwrap();
+        let error = service.fetch_updates().await.unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("500"), "{message}");
+        assert!(message.len() < 2048, "error body was not capped: {} bytes", message.len());
+    }
+
+    #[test]
+    fn gzip_decode_stops_at_the_size_limit() {
+        let compressed = gzip_encode(&vec![0u8; 64 * 1024]).unwrap();
+        assert!(compressed.len() < 1024);
+        assert!(gzip_decode(&compressed, 1024).is_err());
+        assert_eq!(gzip_decode(&compressed, 64 * 1024).unwrap().len(), 64 * 1024);
+    }
+
+    #[test]
+    fn retry_after_accepts_seconds_and_http_dates() {
+        let now = DateTime::parse_from_rfc2822("Wed, 21 Oct 2026 07:28:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_retry_after(" 5 ", now), Some(Duration::from_secs(5)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2026 07:30:00 GMT", now),
+            Some(Duration::from_secs(120))
+        );
+        // Past dates, zero and garbage leave the normal schedule in place.
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2026 07:00:00 GMT", now), None);
+        assert_eq!(parse_retry_after("0", now), None);
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
 }
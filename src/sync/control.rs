@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use tokio::sync::{oneshot, Notify};
+
+use crate::sync::runner::SyncCycleReport;
+
+/// Operator switch shared between the sync loop and the HTTP API.
+///
+/// While paused the periodic loop keeps ticking but skips its cycles, so the
+/// backend neither pushes installs/uninstalls nor receives registry uploads.
+#[derive(Debug, Clone, Default)]
+pub struct SyncPause(Arc<AtomicBool>);
+
+impl SyncPause {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pause sync; returns whether it was already paused.
+    pub fn pause(&self) -> bool {
+        self.0.swap(true, Ordering::SeqCst)
+    }
+
+    /// Resume sync; returns whether it was paused.
+    pub fn resume(&self) -> bool {
+        self.0.swap(false, Ordering::SeqCst)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Lets the HTTP API run a sync cycle now instead of waiting for the interval.
+///
+/// The periodic loop owns the cycle: a request wakes it through a shared `Notify`
+/// and waits for the report of the cycle it runs, so a manual sync never overlaps
+/// a scheduled one.
+#[derive(Clone, Default)]
+pub struct SyncTrigger(Arc<TriggerInner>);
+
+#[derive(Default)]
+struct TriggerInner {
+    notify: Notify,
+    waiters: Mutex<Vec<oneshot::Sender<SyncCycleReport>>>,
+}
+
+impl SyncTrigger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wake the sync loop and wait for the report of the cycle it runs next.
+    /// Returns `None` if the loop stopped before running it.
+    pub async fn run_now(&self) -> Option<SyncCycleReport> {
+        let (tx, rx) = oneshot::channel();
+        self.0.waiters.lock().expect("sync trigger lock poisoned").push(tx);
+        self.0.notify.notify_one();
+        rx.await.ok()
+    }
+
+    /// Resolves once a cycle has been requested (immediately if one is pending).
+    pub(crate) async fn requested(&self) {
+        self.0.notify.notified().await;
+    }
+
+    /// Hand `report` to everyone waiting in [`SyncTrigger::run_now`].
+    pub(crate) fn complete(&self, report: &SyncCycleReport) {
+        let waiters = std::mem::take(&mut *self.0.waiters.lock().expect("sync trigger lock poisoned"));
+        for waiter in waiters {
+            let _ = waiter.send(report.clone());
+        }
+    }
+}
+
+/// When the sync loop last completed a cycle with both the fetch and the push
+/// succeeding; reported by `GET /api/stats`.
+#[derive(Debug, Clone, Default)]
+pub struct SyncHealth(Arc<Mutex<Option<DateTime<Utc>>>>);
+
+impl SyncHealth {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_ok(&self, at: DateTime<Utc>) {
+        *self.0.lock().expect("sync health lock poisoned") = Some(at);
+    }
+
+    pub fn last_ok_at(&self) -> Option<DateTime<Utc>> {
+        *self.0.lock().expect("sync health lock poisoned")
+    }
+}
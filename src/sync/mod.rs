@@ -1,7 +1,11 @@
 // SPDX-License-Identifier: GPL-3.0-only
 pub mod traits;
 pub mod backend;
+pub mod runner;
+pub mod control;
 
 pub use traits::SyncService;
 pub use backend::BackendSyncService;
+pub use runner::SyncRunner;
+pub use control::{SyncHealth, SyncPause, SyncTrigger};
 
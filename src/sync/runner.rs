@@ -0,0 +1,843 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, warn};
+
+use crate::config::{read_config, ConfigHandle};
+use crate::map_installer::{
+    with_audit_actor, ExpectedChecksum, InstallOptions, MapEvent, MapInstallationService,
+};
+use crate::maps_denylist::Mapsdenylist;
+use crate::registry::models::MapEntry;
+use crate::sync::control::{SyncHealth, SyncPause, SyncTrigger};
+use crate::sync::traits::{
+    BackendRetryAfter, DeltaSyncUnsupported, MapUpdate, RejectedEntry, SyncService,
+};
+
+/// Upper bound for the periodic sync delay while the backend keeps failing.
+const MAX_SYNC_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+/// Delay before the next periodic cycle: the interval doubled per consecutive
+/// failure, capped at [`MAX_SYNC_BACKOFF`] (but never shorter than the interval).
+fn sync_backoff_delay(interval: Duration, consecutive_failures: u32) -> Duration {
+    let backed_off = interval.saturating_mul(2_u32.saturating_pow(consecutive_failures.min(16)));
+    backed_off.min(MAX_SYNC_BACKOFF.max(interval))
+}
+
+/// `Retry-After` the backend attached to a failed fetch or push, if any.
+fn backend_retry_after(error: &anyhow::Error) -> Option<Duration> {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<BackendRetryAfter>())
+        .map(|retry| retry.retry_after)
+}
+
+/// Outcome of a single fetch-apply-push sync cycle.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct SyncCycleReport {
+    pub applied: usize,
+    pub failed: usize,
+    pub backed_off: usize,
+    /// Backend updates left unapplied because `read_only` is set.
+    pub skipped_read_only: usize,
+    pub pushed: bool,
+    /// Pushed entries the backend rejected; they are re-sent next cycle.
+    pub rejected: usize,
+}
+
+/// Last successful push, persisted so delta sync survives restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SyncWatermark {
+    /// Start time of the last successful push; entries updated since are re-sent.
+    synced_at: Option<DateTime<Utc>>,
+    /// Map IDs the backend knows about, used to detect removals.
+    #[serde(default)]
+    synced_ids: HashSet<u64>,
+}
+
+impl SyncWatermark {
+    fn load(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!(error = %e, path = %path.display(), "Invalid sync watermark, doing a full sync");
+                Self::default()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                warn!(error = %e, path = %path.display(), "Failed to read sync watermark, doing a full sync");
+                Self::default()
+            }
+        }
+    }
+
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+}
+
+/// State carried between cycles; guarded so only one cycle runs at a time.
+#[derive(Default)]
+struct SyncState {
+    /// Per-update exponential backoff for repeatedly failing backend actions.
+    failure_backoff: HashMap<String, (u32, Instant)>,
+    watermark: SyncWatermark,
+}
+
+/// Runs backend sync cycles (periodic or triggered) one at a time.
+pub struct SyncRunner {
+    installer: Arc<MapInstallationService>,
+    sync_service: Arc<dyn SyncService>,
+    config: ConfigHandle,
+    state: Mutex<SyncState>,
+    state_path: Option<PathBuf>,
+    /// Cycles in a row where the backend fetch or push failed.
+    consecutive_failures: AtomicU32,
+    /// `Retry-After` (ms) the backend sent in the last cycle; 0 when none.
+    retry_after_ms: AtomicU64,
+    paused: SyncPause,
+    trigger: SyncTrigger,
+    health: SyncHealth,
+}
+
+impl SyncRunner {
+    pub fn new(
+        installer: Arc<MapInstallationService>,
+        sync_service: Arc<dyn SyncService>,
+        config: ConfigHandle,
+    ) -> Self {
+        Self {
+            installer,
+            sync_service,
+            config,
+            state: Mutex::new(SyncState::default()),
+            state_path: None,
+            consecutive_failures: AtomicU32::new(0),
+            retry_after_ms: AtomicU64::new(0),
+            paused: SyncPause::new(),
+            trigger: SyncTrigger::new(),
+            health: SyncHealth::new(),
+        }
+    }
+
+    /// Handle for pausing and resuming the periodic loop.
+    pub fn pause_control(&self) -> SyncPause {
+        self.paused.clone()
+    }
+
+    /// Handle for requesting an immediate cycle from the periodic loop.
+    pub fn trigger_control(&self) -> SyncTrigger {
+        self.trigger.clone()
+    }
+
+    /// Last fully successful cycle, for status reporting.
+    pub fn health_control(&self) -> SyncHealth {
+        self.health.clone()
+    }
+
+    /// Persist the delta sync watermark at `path`, loading any existing one.
+    pub fn with_state_path(mut self, path: PathBuf) -> Self {
+        self.state.get_mut().watermark = SyncWatermark::load(&path);
+        self.state_path = Some(path);
+        self
+    }
+
+    /// Run a sync cycle, waiting for any in-flight cycle to finish first.
+    pub async fn run_cycle(&self) -> SyncCycleReport {
+        let mut state = self.state.lock().await;
+        self.run_locked(&mut state).await
+    }
+
+    /// Run a sync cycle unless one is already in progress (returns `None` then).
+    pub async fn try_run_cycle(&self) -> Option<SyncCycleReport> {
+        let mut state = self.state.try_lock().ok()?;
+        Some(self.run_locked(&mut state).await)
+    }
+
+    /// Run cycles forever on `sync_interval_secs`, backing off while the backend fails.
+    ///
+    /// Each cycle finishes before the next delay starts, so slow cycles never pile
+    /// up. A backend `Retry-After` longer than the backoff delay is waited out
+    /// instead (see [`next_delay`](Self::next_delay)). A tick that finds a triggered
+    /// cycle still running is skipped, as is every tick while sync is paused. A
+    /// [`SyncTrigger`] request cuts the delay short and runs a cycle (waiting for any
+    /// in-flight one) whose report it receives.
+    pub async fn run_periodic(&self) {
+        let mut triggered = false;
+        loop {
+            if triggered {
+                info!("Running sync cycle requested through the API");
+                let report = self.run_cycle().await;
+                self.trigger.complete(&report);
+            } else {
+                self.periodic_tick().await;
+            }
+
+            let interval = Duration::from_secs(read_config(&self.config).sync_interval_secs);
+            triggered = tokio::select! {
+                _ = tokio::time::sleep(self.next_delay(interval)) => false,
+                _ = self.trigger.requested() => true,
+            };
+        }
+    }
+
+    async fn periodic_tick(&self) -> Option<SyncCycleReport> {
+        if self.paused.is_paused() {
+            debug!("Skipping periodic sync; sync is paused");
+            return None;
+        }
+        let report = self.try_run_cycle().await;
+        if report.is_none() {
+            debug!("Skipping periodic sync; previous cycle still in progress");
+        }
+        report
+    }
+
+    /// Delay before the next periodic cycle given the configured interval: the
+    /// interval with failure backoff, or the backend's `Retry-After` (capped at
+    /// [`MAX_SYNC_BACKOFF`]) when it sent a longer one.
+    pub fn next_delay(&self, interval: Duration) -> Duration {
+        let backoff = sync_backoff_delay(interval, self.consecutive_failures.load(Ordering::Relaxed));
+        match self.retry_after_ms.load(Ordering::Relaxed) {
+            0 => backoff,
+            ms => Duration::from_millis(ms).min(MAX_SYNC_BACKOFF).max(backoff),
+        }
+    }
+
+    async fn run_locked(&self, state: &mut SyncState) -> SyncCycleReport {
+        let mut report = SyncCycleReport::default();
+        let mut backend_ok = true;
+        let mut retry_after = None;
+
+        // Prune stale backoff entries (keep for at most 1h past expiry).
+        let backoff_cutoff = Instant::now() - Duration::from_secs(3600);
+        state
+            .failure_backoff
+            .retain(|_, (_, retry_after)| *retry_after > backoff_cutoff);
+
+        match self.sync_service.fetch_updates().await {
+            Ok(updates) => {
+                let now = Instant::now();
+                let read_only = read_config(&self.config).read_only;
+                for update in updates {
+                    if read_only {
+                        debug!(
+                            map_id = %update.map_id,
+                            action = %update.action,
+                            "Read-only mode; skipping backend update"
+                        );
+                        report.skipped_read_only += 1;
+                        continue;
+                    }
+                    let update_key = format!("{}:{}", update.action, update.map_id);
+                    if let Some((_, retry_after)) = state.failure_backoff.get(&update_key)
+                        && *retry_after > now
+                    {
+                        debug!(
+                            map_id = %update.map_id,
+                            action = %update.action,
+                            "Skipping backend update due to backoff"
+                        );
+                        report.backed_off += 1;
+                        continue;
+                    }
+
+                    match self.apply_update(&update).await {
+                        Ok(()) => {
+                            state.failure_backoff.remove(&update_key);
+                            report.applied += 1;
+                        }
+                        Err(e) => {
+                            error!(error = %e, map_id = %update.map_id, action = %update.action, "Failed to apply backend update");
+                            let failures = state
+                                .failure_backoff
+                                .get(&update_key)
+                                .map(|(count, _)| *count)
+                                .unwrap_or(0)
+                                .saturating_add(1);
+                            let delay_secs = 2_u64.saturating_pow(failures.min(6));
+                            state.failure_backoff.insert(
+                                update_key,
+                                (failures, Instant::now() + Duration::from_secs(delay_secs)),
+                            );
+                            report.failed += 1;
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to fetch updates from backend");
+                retry_after = retry_after.max(backend_retry_after(&e));
+                backend_ok = false;
+            }
+        }
+
+        // Push entries changed since the last successful sync (full push on first sync).
+        let cycle_started = Utc::now();
+        let mut push_ok = false;
+        match self.installer.registry().list_maps().await {
+            Ok(maps) => {
+                let visible = Mapsdenylist::from_config(&read_config(&self.config))
+                    .filter_visible(maps);
+                let current_ids: HashSet<u64> = visible.iter().map(|m| m.id).collect();
+                let result = match state.watermark.synced_at {
+                    None => Some(self.sync_service.sync_registry(visible).await),
+                    Some(synced_at) => {
+                        let (changed, removed) =
+                            registry_delta(&visible, &state.watermark.synced_ids, synced_at);
+                        if changed.is_empty() && removed.is_empty() {
+                            debug!("Skipping registry push; nothing changed since the last sync");
+                            None
+                        } else {
+                            match self.sync_service.sync_delta(changed, removed).await {
+                                Err(e) if e.is::<DeltaSyncUnsupported>() => {
+                                    info!("Backend does not support delta sync; pushing the full registry");
+                                    Some(self.sync_service.sync_registry(visible).await)
+                                }
+                                result => Some(result),
+                            }
+                        }
+                    }
+                };
+                match result {
+                    None => push_ok = true,
+                    Some(Ok(push)) => {
+                        for rejected in &push.rejected {
+                            warn!(
+                                map_id = %rejected.map_id,
+                                reason = rejected.reason.as_deref().unwrap_or("unspecified"),
+                                "Backend rejected synced entry; re-queueing"
+                            );
+                        }
+                        if !push.rejected.is_empty() {
+                            info!(
+                                accepted = push.accepted,
+                                rejected = push.rejected.len(),
+                                "Backend partially accepted registry push"
+                            );
+                        }
+                        report.rejected = push.rejected.len();
+                        let synced_ids = requeue_rejected(
+                            current_ids,
+                            &state.watermark.synced_ids,
+                            &push.rejected,
+                        );
+                        state.watermark = SyncWatermark {
+                            synced_at: Some(cycle_started),
+                            synced_ids,
+                        };
+                        if let Some(path) = &self.state_path
+                            && let Err(e) = state.watermark.save(path)
+                        {
+                            warn!(error = %e, path = %path.display(), "Failed to persist sync watermark");
+                        }
+                        report.pushed = true;
+                        push_ok = true;
+                    }
+                    Some(Err(e)) => {
+                        error!(error = %e, "Failed to sync registry to backend");
+                        retry_after = retry_after.max(backend_retry_after(&e));
+                        backend_ok = false;
+                    }
+                }
+            }
+            Err(e) => {
+                error!(error = %e, "Failed to list maps for sync");
+            }
+        }
+
+        self.retry_after_ms.store(
+            retry_after.map_or(0, |delay| u64::try_from(delay.as_millis()).unwrap_or(u64::MAX)),
+            Ordering::Relaxed,
+        );
+        if let Some(delay) = retry_after {
+            warn!(retry_after_secs = delay.as_secs(), "Backend asked sync to retry later");
+        }
+        if backend_ok {
+            self.consecutive_failures.store(0, Ordering::Relaxed);
+            if push_ok {
+                self.health.record_ok(Utc::now());
+            }
+        } else {
+            let failures = self.consecutive_failures.fetch_add(1, Ordering::Relaxed) + 1;
+            warn!(consecutive_failures = failures, "Backend sync failed; backing off");
+        }
+
+        self.installer.events().publish(MapEvent::SyncCompleted {
+            applied: report.applied,
+            failed: report.failed,
+            pushed: report.pushed,
+        });
+        report
+    }
+
+    async fn apply_update(&self, update: &MapUpdate) -> anyhow::Result<()> {
+        with_audit_actor("sync", self.apply_update_inner(update)).await
+    }
+
+    async fn apply_update_inner(&self, update: &MapUpdate) -> anyhow::Result<()> {
+        match update.action.as_str() {
+            "install" => {
+                info!(map_id = %update.map_id, "Backend requested map installation");
+                let Some(ref map_entry) = update.map_entry else {
+                    warn!(map_id = %update.map_id, "Backend update missing installation details");
+                    return Ok(());
+                };
+                self.install_backend_entry(map_entry, &update.mirror_urls).await
+            }
+            "update" => {
+                info!(map_id = %update.map_id, "Backend requested map update");
+                let Some(ref map_entry) = update.map_entry else {
+                    warn!(map_id = %update.map_id, "Backend update missing map details");
+                    return Ok(());
+                };
+                let map_id = match update.map_id.parse::<u64>() {
+                    Ok(map_id) => map_id,
+                    Err(e) => {
+                        error!(error = %e, map_id = %update.map_id, "Invalid map ID format from backend");
+                        return Ok(());
+                    }
+                };
+                match self.installer.update_map(map_id, map_entry, &update.mirror_urls).await? {
+                    Some(_) => Ok(()),
+                    None => {
+                        info!(map_id, "Map to update is not installed locally; installing it");
+                        self.install_backend_entry(map_entry, &update.mirror_urls).await
+                    }
+                }
+            }
+            "uninstall" => {
+                info!(map_id = %update.map_id, "Backend requested map uninstallation");
+                match update.map_id.parse::<u64>() {
+                    Ok(map_id) => match self.installer.uninstall_map(map_id).await {
+                        Ok(()) => Ok(()),
+                        Err(e) if e.to_string().contains("not found") => Ok(()),
+                        Err(e) => Err(e),
+                    },
+                    Err(e) => {
+                        error!(error = %e, map_id = %update.map_id, "Invalid map ID format from backend");
+                        Ok(())
+                    }
+                }
+            }
+            _ => {
+                warn!(action = %update.action, "Unknown sync action");
+                Ok(())
+            }
+        }
+    }
+
+    async fn install_backend_entry(
+        &self,
+        map_entry: &MapEntry,
+        mirror_urls: &[String],
+    ) -> anyhow::Result<()> {
+        // Reject mirrors serving something other than what the backend registered.
+        let options = InstallOptions {
+            expected_checksum: ExpectedChecksum::from_map_entry(map_entry),
+            mirror_urls: mirror_urls.to_vec(),
+            ..InstallOptions::default()
+        };
+        if let Some(workshop_id) = map_entry.workshop_id {
+            self.installer
+                .install_from_workshop_id(workshop_id, None, options)
+                .await
+                .map(|_| ())
+        } else {
+            self.installer
+                .install_from_url(
+                    map_entry.source_url.clone(),
+                    Some(map_entry.name.clone()),
+                    options,
+                )
+                .await
+                .map(|_| ())
+        }
+    }
+}
+
+/// IDs to record as known by the backend after a push.
+///
+/// Rejected current entries are left out so the next delta re-sends them as new;
+/// rejected removals stay in so the removal is re-sent.
+fn requeue_rejected(
+    current_ids: HashSet<u64>,
+    previous_ids: &HashSet<u64>,
+    rejected: &[RejectedEntry],
+) -> HashSet<u64> {
+    let mut synced_ids = current_ids;
+    for id in rejected.iter().filter_map(|r| r.map_id.parse::<u64>().ok()) {
+        if !synced_ids.remove(&id) && previous_ids.contains(&id) {
+            synced_ids.insert(id);
+        }
+    }
+    synced_ids
+}
+
+/// Entries new or modified since `synced_at`, plus IDs of entries that disappeared.
+fn registry_delta(
+    visible: &[MapEntry],
+    synced_ids: &HashSet<u64>,
+    synced_at: DateTime<Utc>,
+) -> (Vec<MapEntry>, Vec<String>) {
+    let changed = visible
+        .iter()
+        .filter(|m| !synced_ids.contains(&m.id) || m.updated_at.is_none_or(|t| t >= synced_at))
+        .cloned()
+        .collect();
+    let current: HashSet<u64> = visible.iter().map(|m| m.id).collect();
+    let mut removed: Vec<String> = synced_ids
+        .difference(&current)
+        .map(|id| id.to_string())
+        .collect();
+    removed.sort();
+    (changed, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::init_handle;
+    use crate::registry::traits::Registry;
+    use crate::sync::traits::SyncPushResult;
+    use crate::test_helpers;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, AtomicUsize};
+
+    type RecordedDelta = (Vec<MapEntry>, Vec<String>);
+
+    /// Backend mock that serves pending updates and records fetch concurrency and pushes.
+    struct SlowMockBackend {
+        pending: Vec<MapUpdate>,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+        fetches: AtomicUsize,
+        full_syncs: AtomicUsize,
+        deltas: std::sync::Mutex<Vec<RecordedDelta>>,
+        /// Number of upcoming fetches that fail as if the backend were down.
+        failing_fetches: AtomicUsize,
+        /// Map IDs the next push reports as rejected.
+        rejecting: std::sync::Mutex<Vec<String>>,
+        /// Answer delta pushes as a backend without the delta route would.
+        delta_unsupported: AtomicBool,
+    }
+
+    impl SlowMockBackend {
+        fn push_result(&self, sent: usize) -> SyncPushResult {
+            let rejected: Vec<RejectedEntry> = std::mem::take(&mut *self.rejecting.lock().unwrap())
+                .into_iter()
+                .map(|map_id| RejectedEntry { map_id, reason: Some("invalid".to_string()) })
+                .collect();
+            SyncPushResult {
+                accepted: sent - rejected.len(),
+                rejected,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SyncService for SlowMockBackend {
+        async fn sync_registry(&self, entries: Vec<MapEntry>) -> anyhow::Result<SyncPushResult> {
+            self.full_syncs.fetch_add(1, Ordering::SeqCst);
+            Ok(self.push_result(entries.len()))
+        }
+
+        async fn sync_delta(
+            &self,
+            changed: Vec<MapEntry>,
+            removed: Vec<String>,
+        ) -> anyhow::Result<SyncPushResult> {
+            if self.delta_unsupported.load(Ordering::SeqCst) {
+                return Err(anyhow::Error::new(DeltaSyncUnsupported));
+            }
+            let sent = changed.len() + removed.len();
+            self.deltas.lock().unwrap().push((changed, removed));
+            Ok(self.push_result(sent))
+        }
+
+        async fn fetch_updates(&self) -> anyhow::Result<Vec<MapUpdate>> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            if self
+                .failing_fetches
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok()
+            {
+                anyhow::bail!("backend unavailable");
+            }
+            Ok(self.pending.clone())
+        }
+    }
+
+    async fn setup_runner(
+        pending_uninstall: bool,
+    ) -> (
+        SyncRunner,
+        Arc<SlowMockBackend>,
+        Arc<dyn Registry>,
+        u64,
+        test_helpers::TestDirs,
+    ) {
+        let (registry, dirs) = test_helpers::setup_test_dirs().await.unwrap();
+        let paths = dirs.service_paths();
+        let installer = Arc::new(
+            MapInstallationService::new(
+                Arc::clone(&registry),
+                paths.addons_dir,
+                paths.download_dir,
+                1024 * 1024,
+                1024 * 1024,
+                100,
+            )
+            .await
+            .unwrap(),
+        );
+
+        let map_id = registry.add_map(test_helpers::test_map_entry("pending")).await.unwrap();
+
+        let pending = if pending_uninstall {
+            vec![MapUpdate {
+                action: "uninstall".to_string(),
+                map_id: map_id.to_string(),
+                map_entry: None,
+                mirror_urls: Vec::new(),
+            }]
+        } else {
+            Vec::new()
+        };
+        let backend = Arc::new(SlowMockBackend {
+            pending,
+            in_flight: AtomicUsize::new(0),
+            max_in_flight: AtomicUsize::new(0),
+            fetches: AtomicUsize::new(0),
+            full_syncs: AtomicUsize::new(0),
+            deltas: std::sync::Mutex::new(Vec::new()),
+            failing_fetches: AtomicUsize::new(0),
+            rejecting: std::sync::Mutex::new(Vec::new()),
+            delta_unsupported: AtomicBool::new(false),
+        });
+        let (config, _config_dir) = test_helpers::create_test_config();
+        let runner = SyncRunner::new(
+            installer,
+            backend.clone() as Arc<dyn SyncService>,
+            init_handle(config),
+        );
+        (runner, backend, registry, map_id, dirs)
+    }
+
+    #[tokio::test]
+    async fn concurrent_cycles_are_serialized() {
+        let (runner, backend, registry, map_id, _dirs) = setup_runner(true).await;
+
+        let (first, second) = tokio::join!(runner.run_cycle(), runner.run_cycle());
+
+        assert_eq!(backend.fetches.load(Ordering::SeqCst), 2);
+        assert_eq!(backend.max_in_flight.load(Ordering::SeqCst), 1);
+        assert!(registry.get_map(map_id).await.unwrap().is_none());
+        assert_eq!(first.failed + second.failed, 0);
+        assert!(first.pushed);
+        // First cycle does a full push; the second has nothing new to send.
+        assert!(!second.pushed);
+        assert_eq!(backend.full_syncs.load(Ordering::SeqCst), 1);
+        assert!(backend.deltas.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn read_only_cycle_skips_backend_actions() {
+        let (runner, backend, registry, map_id, _dirs) = setup_runner(true).await;
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.read_only = true;
+        let runner = SyncRunner::new(runner.installer, backend.clone() as Arc<dyn SyncService>, init_handle(config));
+
+        let report = runner.run_cycle().await;
+
+        assert_eq!(report.skipped_read_only, 1);
+        assert_eq!(report.applied, 0);
+        assert_eq!(report.failed, 0);
+        assert!(registry.get_map(map_id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn try_run_cycle_reports_in_progress() {
+        let (runner, backend, _registry, _map_id, _dirs) = setup_runner(true).await;
+
+        let (running, triggered) = tokio::join!(runner.run_cycle(), async {
+            // Let the first cycle take the lock before triggering.
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            runner.try_run_cycle().await
+        });
+
+        assert_eq!(running.failed, 0);
+        assert!(triggered.is_none());
+        assert_eq!(backend.fetches.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn paused_ticks_do_not_contact_backend() {
+        let (runner, backend, registry, map_id, _dirs) = setup_runner(true).await;
+        let pause = runner.pause_control();
+
+        assert!(!pause.pause());
+        assert!(runner.periodic_tick().await.is_none());
+        assert!(runner.periodic_tick().await.is_none());
+        assert_eq!(backend.fetches.load(Ordering::SeqCst), 0);
+        assert_eq!(backend.full_syncs.load(Ordering::SeqCst), 0);
+        assert!(backend.deltas.lock().unwrap().is_empty());
+        assert!(registry.get_map(map_id).await.unwrap().is_some());
+
+        assert!(pause.resume());
+        let report = runner.periodic_tick().await.expect("cycle runs after resume");
+        assert!(report.pushed);
+        assert_eq!(backend.fetches.load(Ordering::SeqCst), 1);
+        assert!(registry.get_map(map_id).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn second_sync_without_changes_skips_the_push() {
+        let (runner, backend, _registry, _map_id, _dirs) = setup_runner(false).await;
+
+        assert!(runner.run_cycle().await.pushed);
+        let first_ok = runner.health_control().last_ok_at().unwrap();
+        let second = runner.run_cycle().await;
+
+        assert!(!second.pushed);
+        assert_eq!(backend.full_syncs.load(Ordering::SeqCst), 1);
+        assert!(backend.deltas.lock().unwrap().is_empty());
+        // A cycle with nothing to push still counts as a healthy one.
+        assert!(runner.health_control().last_ok_at().unwrap() > first_ok);
+    }
+
+    #[tokio::test]
+    async fn delta_push_falls_back_to_full_sync_when_unsupported() {
+        let (runner, backend, registry, _map_id, _dirs) = setup_runner(false).await;
+        runner.run_cycle().await;
+
+        backend.delta_unsupported.store(true, Ordering::SeqCst);
+        registry.add_map(test_helpers::test_map_entry("added")).await.unwrap();
+        let report = runner.run_cycle().await;
+
+        assert!(report.pushed);
+        assert_eq!(backend.full_syncs.load(Ordering::SeqCst), 2);
+        assert!(backend.deltas.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn delta_contains_changed_and_removed_entries() {
+        let (runner, backend, registry, map_id, _dirs) = setup_runner(false).await;
+        runner.run_cycle().await;
+
+        let added_id = registry.add_map(test_helpers::test_map_entry("added")).await.unwrap();
+        registry.remove_map(map_id).await.unwrap();
+        runner.run_cycle().await;
+
+        let deltas = backend.deltas.lock().unwrap();
+        let (changed, removed) = &deltas[0];
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].id, added_id);
+        assert_eq!(removed, &vec![map_id.to_string()]);
+    }
+
+    #[tokio::test]
+    async fn rejected_entry_is_resent_next_cycle() {
+        let (runner, backend, _registry, map_id, _dirs) = setup_runner(false).await;
+        backend.rejecting.lock().unwrap().push(map_id.to_string());
+
+        let first = runner.run_cycle().await;
+        assert!(first.pushed);
+        assert_eq!(first.rejected, 1);
+
+        let second = runner.run_cycle().await;
+        assert_eq!(second.rejected, 0);
+        runner.run_cycle().await;
+
+        let deltas = backend.deltas.lock().unwrap();
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].0.len(), 1);
+        assert_eq!(deltas[0].0[0].id, map_id);
+    }
+
+    #[test]
+    fn backoff_delay_doubles_and_caps() {
+        let interval = Duration::from_secs(60);
+        assert_eq!(sync_backoff_delay(interval, 0), interval);
+        assert_eq!(sync_backoff_delay(interval, 1), Duration::from_secs(120));
+        assert_eq!(sync_backoff_delay(interval, 3), Duration::from_secs(480));
+        assert_eq!(sync_backoff_delay(interval, 50), MAX_SYNC_BACKOFF);
+        // An interval longer than the cap is never shortened.
+        let long = Duration::from_secs(3600);
+        assert_eq!(sync_backoff_delay(long, 5), long);
+    }
+
+    #[tokio::test]
+    async fn backoff_grows_while_backend_fails_and_resets_on_recovery() {
+        let (runner, backend, _registry, _map_id, _dirs) = setup_runner(false).await;
+        let interval = Duration::from_secs(10);
+        backend.failing_fetches.store(2, Ordering::SeqCst);
+
+        runner.run_cycle().await;
+        let after_one = runner.next_delay(interval);
+        runner.run_cycle().await;
+        let after_two = runner.next_delay(interval);
+        assert_eq!(after_one, Duration::from_secs(20));
+        assert_eq!(after_two, Duration::from_secs(40));
+
+        runner.run_cycle().await;
+        assert_eq!(runner.next_delay(interval), interval);
+    }
+
+    #[tokio::test]
+    async fn backend_retry_after_extends_the_next_delay() {
+        use axum::http::{header, StatusCode};
+        use axum::routing::{get, post};
+
+        let app = axum::Router::new()
+            .route(
+                "/api/registry/updates",
+                get(|| async { (StatusCode::TOO_MANY_REQUESTS, [(header::RETRY_AFTER, "5")], "slow down") }),
+            )
+            .route("/api/registry/sync", post(|| async { "{}" }));
+        let addr = crate::test_helpers::spawn_test_server(app).await;
+
+        let (runner, _backend, _registry, _map_id, _dirs) = setup_runner(false).await;
+        let (mut config, _config_dir) = test_helpers::create_test_config();
+        config.backend_api_url = format!("http://{addr}/api");
+        let config = init_handle(config);
+        let backend = crate::sync::BackendSyncService::new(config.clone()).unwrap();
+        let runner = SyncRunner::new(runner.installer, Arc::new(backend), config);
+
+        let report = runner.run_cycle().await;
+        assert!(report.pushed);
+        // One failure doubles the interval; a longer Retry-After wins over that backoff.
+        assert_eq!(runner.next_delay(Duration::from_secs(1)), Duration::from_secs(5));
+        assert_eq!(runner.next_delay(Duration::from_secs(60)), Duration::from_secs(120));
+    }
+
+    #[tokio::test]
+    async fn watermark_persists_across_runners() {
+        let state_dir = tempfile::TempDir::new().unwrap();
+        let state_path = state_dir.path().join("registry.sync.json");
+
+        let (runner, _backend, _registry, _map_id, _dirs) = setup_runner(false).await;
+        let runner = runner.with_state_path(state_path.clone());
+        runner.run_cycle().await;
+        assert!(state_path.exists());
+
+        let (runner, backend, _registry, _map_id, _dirs) = setup_runner(false).await;
+        let runner = runner.with_state_path(state_path);
+        runner.run_cycle().await;
+        assert_eq!(backend.full_syncs.load(Ordering::SeqCst), 0);
+        assert_eq!(backend.deltas.lock().unwrap().len(), 1);
+    }
+}
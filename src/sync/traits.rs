@@ -2,18 +2,57 @@
 use async_trait::async_trait;
 use crate::registry::models::MapEntry;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MapUpdate {
     pub action: String, // "install", "uninstall", "update"
     pub map_id: String,
     pub map_entry: Option<MapEntry>,
+    /// Fallback download URLs tried in order when the entry's `source_url` fails
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mirror_urls: Vec<String>,
 }
 
+/// An entry (or removal) the backend refused during a push.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RejectedEntry {
+    pub map_id: String,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// How many pushed entries the backend accepted, and which it rejected.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncPushResult {
+    pub accepted: usize,
+    pub rejected: Vec<RejectedEntry>,
+}
+
+/// The backend answered 429/503 with `Retry-After`; found in the error chain of a
+/// failed fetch or push so the sync loop can wait that long before the next cycle.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("backend asked to retry in {}s", retry_after.as_secs())]
+pub struct BackendRetryAfter {
+    pub retry_after: Duration,
+}
+
+/// The backend has no delta route (it answered 404/405); push the full registry instead.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("backend does not support delta sync")]
+pub struct DeltaSyncUnsupported;
+
 #[async_trait]
 pub trait SyncService: Send + Sync {
     /// Push local registry state to backend
-    async fn sync_registry(&self, entries: Vec<MapEntry>) -> anyhow::Result<()>;
+    async fn sync_registry(&self, entries: Vec<MapEntry>) -> anyhow::Result<SyncPushResult>;
+
+    /// Push only entries changed and map IDs removed since the last successful sync
+    async fn sync_delta(
+        &self,
+        changed: Vec<MapEntry>,
+        removed: Vec<String>,
+    ) -> anyhow::Result<SyncPushResult>;
     
     /// Fetch pending updates from backend
     async fn fetch_updates(&self) -> anyhow::Result<Vec<MapUpdate>>;
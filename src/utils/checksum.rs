@@ -13,8 +13,21 @@ pub async fn verify_file_md5(path: &Path, expected: &str) -> anyhow::Result<bool
     Ok(md5_matches(&actual, expected))
 }
 
+/// Checksum algorithms the registry can record in `checksum_kind`.
+pub const SUPPORTED_CHECKSUM_KINDS: &[&str] = &["md5", "sha256"];
+
 /// Calculate MD5 checksum of a file
 pub async fn calculate_file_md5(path: &Path) -> anyhow::Result<String> {
+    calculate_file_checksum(path, "md5").await
+}
+
+/// Calculate a file's lowercase hex digest with `kind` (`md5` or `sha256`, any case).
+pub async fn calculate_file_checksum(path: &Path, kind: &str) -> anyhow::Result<String> {
+    let mut hasher = match kind.to_ascii_lowercase().as_str() {
+        "md5" => FileHasher::Md5(md5::Context::new()),
+        "sha256" => FileHasher::Sha256(sha2::Sha256::default()),
+        _ => anyhow::bail!("Unsupported checksum kind '{kind}'"),
+    };
     let path = path.to_path_buf();
 
     tokio::task::spawn_blocking(move || {
@@ -25,7 +38,6 @@ pub async fn calculate_file_md5(path: &Path) -> anyhow::Result<String> {
             .with_context(|| format!("Failed to open file for checksum: {}", path.display()))?;
 
         let mut reader = BufReader::new(file);
-        let mut hasher = md5::Context::new();
         let mut buffer = [0u8; 8192];
 
         loop {
@@ -35,16 +47,36 @@ pub async fn calculate_file_md5(path: &Path) -> anyhow::Result<String> {
             if count == 0 {
                 break;
             }
-            hasher.consume(&buffer[..count]);
+            hasher.update(&buffer[..count]);
         }
 
-        let hash = hasher.compute();
-        Ok(format!("{:x}", hash))
+        Ok(hasher.finish())
     })
     .await
     .context("Checksum calculation task panicked")?
 }
 
+enum FileHasher {
+    Md5(md5::Context),
+    Sha256(sha2::Sha256),
+}
+
+impl FileHasher {
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(context) => context.consume(data),
+            Self::Sha256(hasher) => sha2::Digest::update(hasher, data),
+        }
+    }
+
+    fn finish(self) -> String {
+        match self {
+            Self::Md5(context) => format!("{:x}", context.compute()),
+            Self::Sha256(hasher) => format!("{:x}", sha2::Digest::finalize(hasher)),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,4 +86,21 @@ mod tests {
         assert!(md5_matches("ABC123", "abc123"));
         assert!(!md5_matches("abc123", "abc124"));
     }
+
+    #[tokio::test]
+    async fn calculates_md5_and_sha256_digests() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abc.txt");
+        std::fs::write(&path, b"abc").unwrap();
+
+        assert_eq!(
+            calculate_file_checksum(&path, "MD5").await.unwrap(),
+            "900150983cd24fb0d6963f7d28e17f72"
+        );
+        assert_eq!(
+            calculate_file_checksum(&path, "sha256").await.unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(calculate_file_checksum(&path, "crc32").await.is_err());
+    }
 }
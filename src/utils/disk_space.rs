@@ -10,11 +10,26 @@ use std::path::Path;
 use anyhow::{Context, Result};
 
 /// VPK file magic bytes: little-endian 0x55AA1234
-const VPK_MAGIC: [u8; 4] = [0x34, 0x12, 0xAA, 0x55];
+pub(crate) const VPK_MAGIC: [u8; 4] = [0x34, 0x12, 0xAA, 0x55];
 
 /// Minimum free disk space reserved as a safety buffer (128 MiB).
 const SAFETY_BUFFER_BYTES: u64 = 128 * 1024 * 1024;
 
+/// The partition holding a download or install target is too full to take it.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "Insufficient disk space: need {needed} bytes ({:.1} MiB) but only {free} bytes \
+     ({:.1} MiB) are available on the partition containing '{}'",
+    *.needed as f64 / 1_048_576.0,
+    *.free as f64 / 1_048_576.0,
+    .dir.display()
+)]
+pub struct InsufficientDiskSpace {
+    pub needed: u64,
+    pub free: u64,
+    pub dir: std::path::PathBuf,
+}
+
 /// Query the amount of free disk space (in bytes) available on the partition
 /// that contains `path`.
 ///
@@ -25,6 +40,11 @@ const SAFETY_BUFFER_BYTES: u64 = 128 * 1024 * 1024;
 pub fn available_space_bytes(path: &Path) -> Result<u64> {
     use std::mem::MaybeUninit;
 
+    #[cfg(test)]
+    if let Ok(bytes) = AVAILABLE_SPACE_OVERRIDE.try_with(|bytes| *bytes) {
+        return Ok(bytes);
+    }
+
     // Obtain a C-compatible path
     let c_path = std::ffi::CString::new(
         path.as_os_str()
@@ -49,6 +69,26 @@ pub fn available_space_bytes(path: &Path) -> Result<u64> {
     Ok(stat.f_bavail * stat.f_frsize)
 }
 
+#[cfg(test)]
+tokio::task_local! {
+    static AVAILABLE_SPACE_OVERRIDE: u64;
+}
+
+/// Run `fut` with every free-space query reporting `bytes`.
+#[cfg(test)]
+pub async fn with_available_space<F: std::future::Future>(bytes: u64, fut: F) -> F::Output {
+    AVAILABLE_SPACE_OVERRIDE.scope(bytes, fut).await
+}
+
+/// Check that the partition containing `dest_dir` has room for a copy of `source`.
+pub async fn check_space_for_copy(source: &Path, dest_dir: &Path) -> Result<()> {
+    let size = tokio::fs::metadata(source)
+        .await
+        .with_context(|| format!("Failed to stat {}", source.display()))?
+        .len();
+    check_sufficient_space(dest_dir, size)
+}
+
 /// Check that the partition containing `dir` has at least `required_bytes` +
 /// [`SAFETY_BUFFER_BYTES`] of free space.
 ///
@@ -59,18 +99,30 @@ pub fn check_sufficient_space(dir: &Path, required_bytes: u64) -> Result<()> {
 
     let needed = required_bytes.saturating_add(SAFETY_BUFFER_BYTES);
     if free < needed {
-        return Err(anyhow::anyhow!(
-            "Insufficient disk space: need {needed} bytes ({:.1} MiB) but only {free} bytes \
-             ({:.1} MiB) are available on the partition containing '{}'",
-            needed as f64 / 1_048_576.0,
-            free as f64 / 1_048_576.0,
-            dir.display()
-        ));
+        return Err(InsufficientDiskSpace {
+            needed,
+            free,
+            dir: dir.to_path_buf(),
+        }
+        .into());
     }
 
     Ok(())
 }
 
+/// Create `dir` if needed and confirm the daemon can write files into it.
+pub async fn ensure_writable_dir(dir: &Path) -> Result<()> {
+    tokio::fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+    let probe = dir.join(format!(".kether-write-test-{}", std::process::id()));
+    tokio::fs::write(&probe, b"ok")
+        .await
+        .with_context(|| format!("Directory {} is not writable", dir.display()))?;
+    let _ = tokio::fs::remove_file(&probe).await;
+    Ok(())
+}
+
 /// Validate the magic bytes of a VPK file to confirm it is a genuine VPK
 /// and not a corrupt or truncated download.
 ///
@@ -132,6 +184,7 @@ mod tests {
     fn check_sufficient_space_huge_required_fails() {
         // Asking for u64::MAX bytes should always fail.
         let result = check_sufficient_space(Path::new("."), u64::MAX / 2);
-        assert!(result.is_err(), "impossibly large request should fail");
+        let error = result.expect_err("impossibly large request should fail");
+        assert!(error.is::<InsufficientDiskSpace>());
     }
 }
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: GPL-3.0-only
+
+//! Magic-byte sniffing for downloaded map files.
+//!
+//! Mirrors often answer a broken link with an HTML error page and a 200 status,
+//! so downloads are checked here before any extraction is attempted.
+
+use std::path::Path;
+use anyhow::{Context, Result};
+use tokio::io::AsyncReadExt;
+
+use crate::utils::disk_space::VPK_MAGIC;
+
+/// ZIP local file header signature (`PK\x03\x04`).
+const ZIP_MAGIC: [u8; 4] = [0x50, 0x4B, 0x03, 0x04];
+
+/// 7z signature (`7z\xBC\xAF\x27\x1C`).
+const SEVENZ_MAGIC: [u8; 6] = [0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C];
+
+/// gzip member header (`\x1F\x8B`).
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// Number of leading bytes inspected when describing unrecognised content.
+const SNIFF_LEN: usize = 512;
+
+/// Container format detected from a file's leading bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadKind {
+    Zip,
+    SevenZ,
+    Vpk,
+}
+
+/// Detect whether a downloaded file is a ZIP, 7z or VPK by its magic bytes.
+///
+/// Returns a "downloaded content is not a ZIP/VPK archive" error otherwise,
+/// noting when the content looks like an HTML page.
+pub async fn detect_download_kind(path: &Path) -> Result<DownloadKind> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .with_context(|| format!("Failed to open downloaded file {}", path.display()))?;
+
+    let mut header = Vec::with_capacity(SNIFF_LEN);
+    (&mut file)
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut header)
+        .await
+        .with_context(|| format!("Failed to read downloaded file {}", path.display()))?;
+
+    if header.starts_with(&ZIP_MAGIC) {
+        return Ok(DownloadKind::Zip);
+    }
+    if header.starts_with(&SEVENZ_MAGIC) {
+        return Ok(DownloadKind::SevenZ);
+    }
+    if header.starts_with(&VPK_MAGIC) {
+        return Ok(DownloadKind::Vpk);
+    }
+
+    Err(anyhow::anyhow!(
+        "Downloaded content is not a ZIP/VPK archive ({})",
+        describe_content(&header)
+    ))
+}
+
+/// Strip one gzip layer from `path` in place, returning the decompressed size.
+///
+/// Returns `None` and leaves the file untouched when it does not start with the
+/// gzip magic. Output larger than `max_size` bytes is rejected.
+pub async fn gunzip_in_place(path: &Path, max_size: u64) -> Result<Option<u64>> {
+    let mut magic = [0u8; 2];
+    let mut file = tokio::fs::File::open(path).await?;
+    if file.read_exact(&mut magic).await.is_err() || magic != GZIP_MAGIC {
+        return Ok(None);
+    }
+    drop(file);
+
+    let source = path.to_path_buf();
+    let size = tokio::task::spawn_blocking(move || -> Result<u64> {
+        use std::io::Read;
+
+        let decoded_path = source.with_extension("gunzip");
+        let input = std::fs::File::open(&source)?;
+        let mut decoder = flate2::read::GzDecoder::new(std::io::BufReader::new(input))
+            .take(max_size + 1);
+        let mut output = std::fs::File::create(&decoded_path)?;
+        let written = std::io::copy(&mut decoder, &mut output);
+        let result = match written {
+            Ok(size) if size > max_size => Err(anyhow::anyhow!(
+                "Decompressed download exceeds maximum download size {} bytes",
+                max_size
+            )),
+            Ok(size) => Ok(size),
+            Err(e) => Err(anyhow::anyhow!("Failed to decompress gzip download: {e}")),
+        };
+        match result {
+            Ok(size) => {
+                std::fs::rename(&decoded_path, &source)?;
+                Ok(size)
+            }
+            Err(e) => {
+                let _ = std::fs::remove_file(&decoded_path);
+                Err(e)
+            }
+        }
+    })
+    .await??;
+
+    Ok(Some(size))
+}
+
+fn describe_content(header: &[u8]) -> String {
+    if header.is_empty() {
+        return "file is empty".to_string();
+    }
+    let text = String::from_utf8_lossy(header).to_ascii_lowercase();
+    let trimmed = text.trim_start();
+    if trimmed.starts_with("<!doctype html") || trimmed.starts_with("<html") || text.contains("<html") {
+        return "received an HTML page; the server likely returned an error page".to_string();
+    }
+    let prefix_len = header.len().min(8);
+    format!("unrecognised leading bytes {:02X?}", &header[..prefix_len])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn detect(bytes: &[u8]) -> Result<DownloadKind> {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("download.zip");
+        tokio::fs::write(&path, bytes).await.unwrap();
+        detect_download_kind(&path).await
+    }
+
+    #[tokio::test]
+    async fn detects_supported_formats() {
+        assert_eq!(detect(b"PK\x03\x04rest").await.unwrap(), DownloadKind::Zip);
+        assert_eq!(detect(&[0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C, 0]).await.unwrap(), DownloadKind::SevenZ);
+        assert_eq!(detect(&[0x34, 0x12, 0xAA, 0x55, 1, 0]).await.unwrap(), DownloadKind::Vpk);
+    }
+
+    #[tokio::test]
+    async fn rejects_html_error_page() {
+        let err = detect(b"\n<!DOCTYPE html><html><body>Not found</body></html>")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("not a ZIP/VPK archive"), "{err}");
+        assert!(err.contains("HTML page"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn rejects_empty_file() {
+        let err = detect(b"").await.unwrap_err().to_string();
+        assert!(err.contains("file is empty"), "{err}");
+    }
+}
@@ -3,9 +3,60 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+use crate::utils::InstalledFilePermissions;
+
+/// Removes a temp file or directory when dropped unless [`TempPathGuard::keep`] is called.
+///
+/// Installs can be cancelled mid-flight (e.g. by the install timeout), which drops
+/// the future without running its explicit cleanup; the guard covers that case.
+pub struct TempPathGuard {
+    path: Option<PathBuf>,
+}
+
+impl TempPathGuard {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path: Some(path) }
+    }
+
+    /// Leave the path in place (ownership passes to the caller).
+    pub fn keep(mut self) -> PathBuf {
+        self.path.take().unwrap_or_default()
+    }
+}
+
+impl Drop for TempPathGuard {
+    fn drop(&mut self) {
+        let Some(path) = self.path.take() else {
+            return;
+        };
+        let result = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        if let Err(e) = result
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            tracing::warn!(error = %e, path = %path.display(), "Failed to clean up temp path");
+        }
+    }
+}
 
 /// Copy `source` onto `dest` via a sibling temp file, fsync, then rename.
 pub async fn atomic_replace_file(source: &Path, dest: &Path) -> Result<()> {
+    atomic_replace_file_with_permissions(source, dest, &InstalledFilePermissions::default()).await
+}
+
+/// Like [`atomic_replace_file`], but `permissions` are set on the temp file before the
+/// rename, so `dest` never shows up with the daemon's own mode and owner. Failing to
+/// set them is logged, not fatal.
+pub async fn atomic_replace_file_with_permissions(
+    source: &Path,
+    dest: &Path,
+    permissions: &InstalledFilePermissions,
+) -> Result<()> {
     let parent = dest.parent().unwrap_or_else(|| Path::new("."));
     let file_name = dest
         .file_name()
@@ -35,6 +86,12 @@ pub async fn atomic_replace_file(source: &Path, dest: &Path) -> Result<()> {
         file.flush().await.ok();
     }
 
+    if !permissions.is_empty()
+        && let Err(error) = permissions.apply(&temp_path).await
+    {
+        warn!(error = %format!("{error:#}"), path = %dest.display(), "Failed to apply installed file permissions");
+    }
+
     if let Err(error) = tokio::fs::rename(&temp_path, dest).await {
         let _ = tokio::fs::remove_file(&temp_path).await;
         return Err(error).with_context(|| {
@@ -49,12 +106,20 @@ pub async fn atomic_replace_file(source: &Path, dest: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Bytes of the destination name kept in a temp name; the dot, timestamp and
+/// `.tmp` must still fit in a 255-byte file name.
+const TEMP_NAME_PREFIX_BYTES: usize = 200;
+
 fn unique_temp_path(parent: &Path, file_name: &str) -> PathBuf {
     let nanos = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|d| d.as_nanos())
         .unwrap_or(0);
-    parent.join(format!(".{file_name}.{nanos}.tmp"))
+    let mut end = file_name.len().min(TEMP_NAME_PREFIX_BYTES);
+    while !file_name.is_char_boundary(end) {
+        end -= 1;
+    }
+    parent.join(format!(".{}.{nanos}.tmp", &file_name[..end]))
 }
 
 #[cfg(test)]
@@ -79,4 +144,24 @@ mod tests {
             .filter_map(|e| e.ok())
             .any(|e| e.file_name().to_string_lossy().contains(".tmp")));
     }
+
+    #[tokio::test]
+    async fn atomic_replace_sets_permissions_before_the_file_appears() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new().unwrap();
+        let dest = dir.path().join("map.vpk");
+        let source = dir.path().join("new.vpk");
+        std::fs::write(&source, b"new-content").unwrap();
+        std::fs::set_permissions(&source, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+        let permissions = InstalledFilePermissions {
+            mode: Some(0o640),
+            ..InstalledFilePermissions::default()
+        };
+        atomic_replace_file_with_permissions(&source, &dest, &permissions)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::metadata(&dest).unwrap().permissions().mode() & 0o7777, 0o640);
+    }
 }
@@ -0,0 +1,127 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Mode and ownership applied to VPKs placed in the addons directory.
+//!
+//! Game servers often run as a dedicated user that cannot read files the daemon
+//! copied with its own umask and owner; operators can pin both in the config.
+
+use std::ffi::CString;
+use std::mem::MaybeUninit;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::config::Config;
+
+/// Name lookups may need more than the `sysconf` hint on hosts with large NSS entries.
+const LOOKUP_BUFFER_BYTES: usize = 16 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InstalledFilePermissions {
+    pub mode: Option<u32>,
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+}
+
+impl InstalledFilePermissions {
+    /// Parse `installed_file_mode` and resolve `installed_file_owner`/`group`.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Ok(Self {
+            mode: config.installed_file_mode.as_deref().map(parse_file_mode).transpose()?,
+            uid: config.installed_file_owner.as_deref().map(lookup_user).transpose()?,
+            gid: config.installed_file_group.as_deref().map(lookup_group).transpose()?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Set the configured owner/group, then the mode (chown may clear setuid bits).
+    pub async fn apply(&self, path: &Path) -> Result<()> {
+        if self.uid.is_some() || self.gid.is_some() {
+            std::os::unix::fs::chown(path, self.uid, self.gid)
+                .with_context(|| format!("Failed to change owner of {}", path.display()))?;
+        }
+        if let Some(mode) = self.mode {
+            tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))
+                .await
+                .with_context(|| format!("Failed to set mode of {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Octal permission bits such as `"0644"`, `"644"` or `"0o644"`.
+pub fn parse_file_mode(value: &str) -> Result<u32> {
+    let digits = value.trim();
+    let digits = digits.strip_prefix("0o").unwrap_or(digits);
+    let mode = u32::from_str_radix(digits, 8)
+        .ok()
+        .filter(|mode| *mode <= 0o7777)
+        .ok_or_else(|| anyhow::anyhow!("Invalid file mode '{value}' (expected octal, e.g. 0644)"))?;
+    Ok(mode)
+}
+
+/// UID of a user name, or the number itself when `name` is numeric.
+fn lookup_user(name: &str) -> Result<u32> {
+    if let Ok(uid) = name.parse() {
+        return Ok(uid);
+    }
+    let c_name = CString::new(name).context("User name contains a NUL byte")?;
+    let mut buf = vec![0 as libc::c_char; LOOKUP_BUFFER_BYTES];
+    let mut entry = MaybeUninit::<libc::passwd>::uninit();
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    // SAFETY: all pointers are valid for the call and `buf` outlives it; `entry` is
+    //         only read when the call succeeded and reported a match via `result`.
+    let rc = unsafe {
+        libc::getpwnam_r(c_name.as_ptr(), entry.as_mut_ptr(), buf.as_mut_ptr(), buf.len(), &mut result)
+    };
+    if rc != 0 || result.is_null() {
+        anyhow::bail!("Unknown user '{name}'");
+    }
+    Ok(unsafe { entry.assume_init() }.pw_uid)
+}
+
+/// GID of a group name, or the number itself when `name` is numeric.
+fn lookup_group(name: &str) -> Result<u32> {
+    if let Ok(gid) = name.parse() {
+        return Ok(gid);
+    }
+    let c_name = CString::new(name).context("Group name contains a NUL byte")?;
+    let mut buf = vec![0 as libc::c_char; LOOKUP_BUFFER_BYTES];
+    let mut entry = MaybeUninit::<libc::group>::uninit();
+    let mut result: *mut libc::group = std::ptr::null_mut();
+    // SAFETY: as in `lookup_user`.
+    let rc = unsafe {
+        libc::getgrnam_r(c_name.as_ptr(), entry.as_mut_ptr(), buf.as_mut_ptr(), buf.len(), &mut result)
+    };
+    if rc != 0 || result.is_null() {
+        anyhow::bail!("Unknown group '{name}'");
+    }
+    Ok(unsafe { entry.assume_init() }.gr_gid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_mode_accepts_common_octal_spellings() {
+        assert_eq!(parse_file_mode("0644").unwrap(), 0o644);
+        assert_eq!(parse_file_mode("640").unwrap(), 0o640);
+        assert_eq!(parse_file_mode("0o2775").unwrap(), 0o2775);
+        assert!(parse_file_mode("0999").is_err());
+        assert!(parse_file_mode("17777").is_err());
+        assert!(parse_file_mode("rw-r--r--").is_err());
+    }
+
+    #[test]
+    fn owner_and_group_resolve_names_and_numbers() {
+        assert_eq!(lookup_user("root").unwrap(), 0);
+        assert_eq!(lookup_user("1234").unwrap(), 1234);
+        assert_eq!(lookup_group("0").unwrap(), 0);
+        assert!(lookup_user("no-such-kether-user").is_err());
+        assert!(lookup_group("no-such-kether-group").is_err());
+    }
+}
@@ -1,18 +1,30 @@
 // SPDX-License-Identifier: GPL-3.0-only
 pub mod checksum;
 pub mod disk_space;
+pub mod file_magic;
 pub mod file_ops;
+pub mod file_permissions;
 pub mod file_stability;
 pub mod path_sanitizer;
 pub mod url_validator;
+pub mod version;
 
-pub use checksum::{calculate_file_md5, md5_matches, verify_file_md5};
-pub use file_ops::atomic_replace_file;
+pub use checksum::{
+    calculate_file_checksum, calculate_file_md5, md5_matches, verify_file_md5,
+    SUPPORTED_CHECKSUM_KINDS,
+};
+pub use file_magic::{detect_download_kind, gunzip_in_place, DownloadKind};
+pub use file_ops::{atomic_replace_file, atomic_replace_file_with_permissions, TempPathGuard};
+pub use file_permissions::InstalledFilePermissions;
 pub use file_stability::file_is_stable;
 pub use path_sanitizer::{
+    ensure_no_symlinks_under, is_symlink_mode, render_install_filename,
     resolve_archive_entry_path, sanitize_filename, sanitize_map_name,
     validate_archive_entry_name, validate_path_within_base_new,
 };
-pub use url_validator::{validate_url, validate_url_resolved};
-pub use disk_space::check_sufficient_space;
+pub use url_validator::{validate_download_host, validate_url, validate_url_resolved};
+pub use version::{compare_versions, is_known_version, UNKNOWN_VERSION};
+pub use disk_space::{
+    check_space_for_copy, check_sufficient_space, ensure_writable_dir, InsufficientDiskSpace,
+};
 
@@ -2,10 +2,24 @@
 use std::path::{Component, Path, PathBuf};
 use anyhow::{Context, Result};
 
+/// Maximum byte length of a file name in the addons directory.
+pub const MAX_MAP_NAME_BYTES: usize = 255;
+
+/// Longest suffix put after a map name on disk (`.vpk.bak` while an update runs).
+const LONGEST_MAP_FILE_SUFFIX: usize = ".vpk.bak".len();
+
+/// Maximum byte length of a sanitized map name, leaving room for any file suffix.
+pub const MAX_MAP_NAME_STEM_BYTES: usize = MAX_MAP_NAME_BYTES - LONGEST_MAP_FILE_SUFFIX;
+
+/// Hex digits of the original name's MD5 appended to truncated names.
+const MAP_NAME_HASH_LEN: usize = 8;
+
 /// Sanitize a map name by removing invalid characters and normalizing
 ///
 /// Removes path separators, parent directory references, and other unsafe characters.
-/// Normalizes to lowercase and replaces spaces with underscores.
+/// Normalizes to lowercase and replaces spaces with underscores. Names longer than
+/// [`MAX_MAP_NAME_STEM_BYTES`] are cut on a UTF-8 boundary and suffixed with a short hash
+/// of the original so distinct long titles stay distinct.
 pub fn sanitize_map_name(name: &str) -> Result<String> {
     let sanitized: String = name
         .chars()
@@ -18,9 +32,11 @@ pub fn sanitize_map_name(name: &str) -> Result<String> {
         return Err(anyhow::anyhow!("Map name cannot be empty after sanitization"));
     }
 
-    if normalized.len() > 255 {
-        return Err(anyhow::anyhow!("Map name too long (max 255 characters)"));
-    }
+    let normalized = if normalized.len() > MAX_MAP_NAME_STEM_BYTES {
+        truncate_with_hash(&normalized, name)
+    } else {
+        normalized
+    };
 
     if normalized.starts_with('.') || normalized.starts_with('-') {
         return Err(anyhow::anyhow!("Map name cannot start with '.' or '-'"));
@@ -29,6 +45,18 @@ pub fn sanitize_map_name(name: &str) -> Result<String> {
     Ok(normalized)
 }
 
+/// Cut `normalized` to fit `_<hash>` within [`MAX_MAP_NAME_STEM_BYTES`], hashing `original`.
+fn truncate_with_hash(normalized: &str, original: &str) -> String {
+    let digest = format!("{:x}", md5::compute(original.as_bytes()));
+    let suffix = &digest[..MAP_NAME_HASH_LEN];
+
+    let mut end = MAX_MAP_NAME_STEM_BYTES - MAP_NAME_HASH_LEN - 1;
+    while !normalized.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}_{}", normalized[..end].trim_end_matches('_'), suffix)
+}
+
 /// Validate that a path is within a base directory
 ///
 /// Prevents directory traversal attacks by ensuring the path doesn't escape
@@ -165,6 +193,48 @@ pub fn sanitize_filename(filename: &str) -> String {
     sanitized.trim().to_string()
 }
 
+/// Expand an `install_filename_template` into the filename of an installed VPK.
+///
+/// Understands `{map_id}`, `{original}` (the filename the daemon would otherwise
+/// pick, without `.vpk`) and `{sanitized_name}`. `.vpk` is appended when missing,
+/// and the result must already be a safe single path component.
+pub fn render_install_filename(
+    template: &str,
+    map_id: u64,
+    original: &str,
+    sanitized_name: &str,
+) -> Result<String> {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| anyhow::anyhow!("Unclosed '{{' in install filename template '{template}'"))?;
+        match &rest[start + 1..end] {
+            "map_id" => rendered.push_str(&map_id.to_string()),
+            "original" => rendered.push_str(original.strip_suffix(".vpk").unwrap_or(original)),
+            "sanitized_name" => rendered.push_str(sanitized_name),
+            other => anyhow::bail!("Unknown placeholder {{{other}}} in install filename template"),
+        }
+        rest = &rest[end + 1..];
+    }
+    rendered.push_str(rest);
+    if !rendered.ends_with(".vpk") {
+        rendered.push_str(".vpk");
+    }
+
+    if sanitize_filename(&rendered) != rendered
+        || rendered.starts_with('.')
+        || rendered.starts_with('-')
+        || rendered.len() > MAX_MAP_NAME_BYTES
+    {
+        anyhow::bail!("Install filename template '{template}' produced unsafe filename '{rendered}'");
+    }
+    Ok(rendered)
+}
+
 /// Normalize a path safely for comparison
 ///
 /// Removes redundant separators and normalizes the path without resolving
@@ -224,6 +294,45 @@ pub fn validate_archive_entry_name(entry_name: &str) -> Result<()> {
     Ok(())
 }
 
+/// True for a Unix `st_mode` describing a symbolic link.
+pub fn is_symlink_mode(mode: u32) -> bool {
+    mode & 0o170000 == 0o120000
+}
+
+/// Reject `path` if it or any existing component between `base` and it is a symlink.
+///
+/// Uses `symlink_metadata`, so a link planted inside `base` (e.g. `addons/maps ->
+/// /etc`) is caught before anything is written through it. `base` itself may be a
+/// symlink; operators sometimes mount the addons directory that way.
+pub fn ensure_no_symlinks_under(base: &Path, path: &Path) -> Result<()> {
+    let relative = path.strip_prefix(base).map_err(|_| {
+        anyhow::anyhow!(
+            "Path {} is outside base directory {}",
+            path.display(),
+            base.display()
+        )
+    })?;
+
+    let mut current = base.to_path_buf();
+    for component in relative.components() {
+        current.push(component);
+        match std::fs::symlink_metadata(&current) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                return Err(anyhow::anyhow!(
+                    "Refusing to use {}: it is a symbolic link",
+                    current.display()
+                ));
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => break,
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to inspect {}", current.display()));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Ensure `dest.join(entry)` stays under `dest` after lexical normalization.
 pub fn resolve_archive_entry_path(dest: &Path, entry_name: &str) -> Result<PathBuf> {
     validate_archive_entry_name(entry_name)?;
@@ -268,9 +377,26 @@ mod tests {
     }
 
     #[test]
-    fn test_sanitize_map_name_too_long() {
+    fn test_sanitize_map_name_truncates_long_names_with_hash() {
         let long_name = "a".repeat(300);
-        assert!(sanitize_map_name(&long_name).is_err());
+        let result = sanitize_map_name(&long_name).unwrap();
+        assert!(result.len() <= MAX_MAP_NAME_STEM_BYTES);
+        assert!(format!("{result}.vpk.bak").len() <= MAX_MAP_NAME_BYTES);
+        let digest = format!("{:x}", md5::compute(long_name.as_bytes()));
+        assert!(result.ends_with(&format!("_{}", &digest[..MAP_NAME_HASH_LEN])));
+
+        let other = format!("{}b", "a".repeat(299));
+        let other_result = sanitize_map_name(&other).unwrap();
+        assert!(other_result.len() <= MAX_MAP_NAME_BYTES);
+        assert_ne!(result, other_result);
+    }
+
+    #[test]
+    fn test_sanitize_map_name_truncates_on_char_boundary() {
+        let long_name = "ä".repeat(300);
+        let result = sanitize_map_name(&long_name).unwrap();
+        assert!(result.len() <= MAX_MAP_NAME_STEM_BYTES);
+        assert!(result.starts_with("ää"));
     }
 
     #[test]
@@ -289,6 +415,22 @@ mod tests {
         assert!(!result.contains('<') && !result.contains('>'));
     }
 
+    #[test]
+    fn test_render_install_filename_expands_placeholders() {
+        assert_eq!(
+            render_install_filename("{map_id}_{original}", 7, "carried_off.vpk", "carried_off").unwrap(),
+            "7_carried_off.vpk"
+        );
+        assert_eq!(
+            render_install_filename("{sanitized_name}.vpk", 7, "c1.vpk", "carried_off").unwrap(),
+            "carried_off.vpk"
+        );
+        assert!(render_install_filename("{map_id}/{original}", 7, "map.vpk", "map").is_err());
+        assert!(render_install_filename("../{original}", 7, "map.vpk", "map").is_err());
+        assert!(render_install_filename("{checksum}", 7, "map.vpk", "map").is_err());
+        assert!(render_install_filename("{map_id", 7, "map.vpk", "map").is_err());
+    }
+
     #[test]
     fn test_validate_path_within_base_valid() {
         let temp_dir = TempDir::new().unwrap();
@@ -377,6 +519,18 @@ mod tests {
         assert!(validate_archive_entry_name("nested/ok.vpk").is_ok());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_ensure_no_symlinks_under_rejects_symlinked_component() {
+        let base = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        std::os::unix::fs::symlink(outside.path(), base.path().join("maps")).unwrap();
+
+        assert!(ensure_no_symlinks_under(base.path(), &base.path().join("maps/foo.vpk")).is_err());
+        assert!(ensure_no_symlinks_under(base.path(), &base.path().join("other/foo.vpk")).is_ok());
+        assert!(ensure_no_symlinks_under(base.path(), Path::new("/etc/passwd")).is_err());
+    }
+
     #[test]
     fn test_resolve_archive_entry_path_stays_under_dest() {
         let dest = Path::new("/addons/extract");
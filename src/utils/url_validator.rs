@@ -91,6 +91,44 @@ pub async fn validate_url_resolved(url_str: &str) -> Result<()> {
     Ok(())
 }
 
+/// Reject URLs whose host is not in `allowed_hosts` (exact or `*.example.com` wildcard).
+///
+/// An empty allowlist permits any host; SSRF checks are applied separately.
+pub fn validate_download_host(url_str: &str, allowed_hosts: &[String]) -> Result<()> {
+    if allowed_hosts.is_empty() {
+        return Ok(());
+    }
+
+    let url = Url::parse(url_str).context("Invalid URL format")?;
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow::anyhow!("URL must have a host"))?;
+
+    if allowed_hosts
+        .iter()
+        .any(|pattern| host_matches_pattern(host, pattern))
+    {
+        return Ok(());
+    }
+
+    Err(anyhow::anyhow!(
+        "URL host '{host}' is not in allowed_download_hosts"
+    ))
+}
+
+/// Match a host against an exact name or a `*.suffix` wildcard (subdomains only).
+pub(crate) fn host_matches_pattern(host: &str, pattern: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let pattern = pattern.trim().trim_end_matches('.').to_ascii_lowercase();
+
+    match pattern.strip_prefix("*.") {
+        Some(suffix) => host
+            .strip_suffix(suffix)
+            .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+        None => host == pattern,
+    }
+}
+
 /// Check if a hostname is a localhost variant
 fn is_localhost(host: &str) -> bool {
     let host_lower = host.to_lowercase();
@@ -248,6 +286,35 @@ mod tests {
         assert!(is_private_ipv4(&Ipv4Addr::new(100, 64, 0, 1)));
     }
 
+    #[test]
+    fn validate_download_host_allows_any_host_when_empty() {
+        assert!(validate_download_host("https://anything.example.org/map.zip", &[]).is_ok());
+    }
+
+    #[test]
+    fn validate_download_host_accepts_listed_host() {
+        let allowed = vec!["l4d2center.com".to_string()];
+        assert!(validate_download_host("https://l4d2center.com/maps/a.7z", &allowed).is_ok());
+        assert!(validate_download_host("https://L4D2Center.com/maps/a.7z", &allowed).is_ok());
+    }
+
+    #[test]
+    fn validate_download_host_rejects_unlisted_host() {
+        let allowed = vec!["l4d2center.com".to_string()];
+        let err = validate_download_host("https://evil.example.com/a.zip", &allowed).unwrap_err();
+        assert!(err.to_string().contains("allowed_download_hosts"));
+        assert!(validate_download_host("https://notl4d2center.com/a.zip", &allowed).is_err());
+    }
+
+    #[test]
+    fn validate_download_host_wildcard_matches_subdomains_only() {
+        let allowed = vec!["*.example.com".to_string()];
+        assert!(validate_download_host("https://cdn.example.com/a.zip", &allowed).is_ok());
+        assert!(validate_download_host("https://a.b.example.com/a.zip", &allowed).is_ok());
+        assert!(validate_download_host("https://example.com/a.zip", &allowed).is_err());
+        assert!(validate_download_host("https://badexample.com/a.zip", &allowed).is_err());
+    }
+
     #[tokio::test]
     async fn validate_url_resolved_rejects_localhost_hostname() {
         let result = validate_url_resolved("http://localhost/file.zip").await;
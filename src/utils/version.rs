@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Ordering of map version strings from `addoninfo.txt`.
+//!
+//! Authors write anything from `1.9` and `v2.0.1` to `1.0-beta` or `final`, so
+//! versions that look like semver are compared component by component and
+//! everything else as plain strings.
+
+use std::cmp::Ordering;
+
+/// Version recorded for VPKs whose `addoninfo.txt` has none.
+pub const UNKNOWN_VERSION: &str = "Unknown";
+
+struct SemverIsh<'a> {
+    numbers: Vec<u64>,
+    pre_release: Option<&'a str>,
+}
+
+/// `1`, `1.10`, `v2.0.1`, `1.0-beta` or `1.2.3+build`; `None` for anything else.
+fn parse(version: &str) -> Option<SemverIsh<'_>> {
+    let version = version.trim();
+    let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+    let version = version.split_once('+').map_or(version, |(version, _build)| version);
+    let (core, pre_release) = match version.split_once('-') {
+        Some((core, pre_release)) => (core, Some(pre_release)),
+        None => (version, None),
+    };
+    let numbers = core
+        .split('.')
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+    Some(SemverIsh { numbers, pre_release })
+}
+
+/// Order two map versions.
+///
+/// Semver-ish versions compare numerically (`1.10 > 1.9`, `1.0 == 1.0.0`), with a
+/// pre-release before its release; if either side is not semver-ish both are
+/// compared lexically.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (Some(a), Some(b)) = (parse(a), parse(b)) else {
+        return a.trim().cmp(b.trim());
+    };
+    let component = |version: &SemverIsh, index: usize| version.numbers.get(index).copied().unwrap_or(0);
+    (0..a.numbers.len().max(b.numbers.len()))
+        .map(|index| component(&a, index).cmp(&component(&b, index)))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| match (a.pre_release, b.pre_release) {
+            (None, None) => Ordering::Equal,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(a), Some(b)) => a.cmp(b),
+        })
+}
+
+/// Whether `version` carries information, i.e. is neither empty nor [`UNKNOWN_VERSION`].
+pub fn is_known_version(version: &str) -> bool {
+    let version = version.trim();
+    !version.is_empty() && !version.eq_ignore_ascii_case(UNKNOWN_VERSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn semver_versions_compare_numerically() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("v2.0.1", "2.0"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0-beta", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.2.3+build7", "1.2.3"), Ordering::Equal);
+    }
+
+    #[test]
+    fn non_semver_versions_fall_back_to_lexical_order() {
+        assert_eq!(compare_versions("Unknown", "Unknown"), Ordering::Equal);
+        assert_eq!(compare_versions("Unknown", "1.0"), "Unknown".cmp("1.0"));
+        assert_eq!(compare_versions("final", "beta"), Ordering::Greater);
+        assert!(!is_known_version("Unknown"));
+        assert!(!is_known_version(" "));
+        assert!(is_known_version("1.0"));
+    }
+}
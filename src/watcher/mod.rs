@@ -2,8 +2,10 @@
 pub mod traits;
 pub mod inotify;
 pub mod debounce;
+pub mod status;
 
 pub use traits::{Watcher, WatcherEvent};
 pub use inotify::InotifyWatcher;
 pub use debounce::{PendingEntry, schedule_pending, should_force_sync};
+pub use status::WatcherStatus;
 
@@ -0,0 +1,21 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether the addons directory watcher is delivering events; shared with the HTTP API.
+#[derive(Debug, Clone, Default)]
+pub struct WatcherStatus(Arc<AtomicBool>);
+
+impl WatcherStatus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_running(&self, running: bool) {
+        self.0.store(running, Ordering::SeqCst);
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}